@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_thread_subsystems_subsystem;
+DROP TABLE IF EXISTS thread_subsystems_default;
+DROP TABLE IF EXISTS thread_subsystems;
+DROP TABLE IF EXISTS subsystems;
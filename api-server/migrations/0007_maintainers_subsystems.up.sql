@@ -0,0 +1,27 @@
+-- MAINTAINERS subsystems and the threads whose patches touch them
+
+CREATE TABLE subsystems (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    status TEXT,
+    maintainers JSONB NOT NULL DEFAULT '[]'::jsonb,
+    reviewers JSONB NOT NULL DEFAULT '[]'::jsonb,
+    lists TEXT[] NOT NULL DEFAULT '{}',
+    trees TEXT[] NOT NULL DEFAULT '{}',
+    file_patterns TEXT[] NOT NULL DEFAULT '{}',
+    exclude_patterns TEXT[] NOT NULL DEFAULT '{}',
+    regex_patterns TEXT[] NOT NULL DEFAULT '{}',
+    updated_at TIMESTAMPTZ DEFAULT NOW()
+);
+
+CREATE TABLE thread_subsystems (
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    subsystem_id INTEGER NOT NULL REFERENCES subsystems(id) ON DELETE CASCADE,
+    PRIMARY KEY (mailing_list_id, thread_id, subsystem_id),
+    FOREIGN KEY (mailing_list_id, thread_id) REFERENCES threads(mailing_list_id, id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE thread_subsystems_default PARTITION OF thread_subsystems DEFAULT;
+
+CREATE INDEX idx_thread_subsystems_subsystem ON thread_subsystems(subsystem_id);
@@ -0,0 +1,6 @@
+DROP INDEX IF EXISTS idx_patch_state_history_email;
+DROP TABLE IF EXISTS patch_state_history;
+DROP INDEX IF EXISTS idx_patch_states_state;
+DROP TABLE IF EXISTS patch_states_default;
+DROP TABLE IF EXISTS patch_states;
+DROP TYPE IF EXISTS patch_state;
@@ -0,0 +1,42 @@
+-- Patchwork-style review state for patch emails, plus an audit trail of changes
+
+CREATE TYPE patch_state AS ENUM (
+    'new',
+    'under_review',
+    'changes_requested',
+    'accepted',
+    'rejected',
+    'superseded',
+    'rfc'
+);
+
+CREATE TABLE patch_states (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    state patch_state NOT NULL DEFAULT 'new',
+    updated_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE patch_states_default PARTITION OF patch_states DEFAULT;
+
+CREATE INDEX idx_patch_states_state ON patch_states(mailing_list_id, state);
+
+CREATE TABLE patch_state_history (
+    id BIGSERIAL PRIMARY KEY,
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    previous_state patch_state,
+    state patch_state NOT NULL,
+    changed_by INTEGER REFERENCES users(id) ON DELETE SET NULL,
+    comment TEXT,
+    changed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_patch_state_history_email ON patch_state_history(mailing_list_id, email_id, changed_at);
+
+-- Patches imported before state tracking existed get their initial state on
+-- the next import of their list (`patches::initialize_patch_states`)
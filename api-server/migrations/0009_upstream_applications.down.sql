@@ -0,0 +1,17 @@
+DROP INDEX IF EXISTS idx_authors_email_lower;
+DROP INDEX IF EXISTS idx_upstream_applications_unreleased;
+DROP INDEX IF EXISTS idx_upstream_applications_commit;
+DROP TABLE IF EXISTS upstream_applications_default;
+DROP TABLE IF EXISTS upstream_applications;
+DROP TABLE IF EXISTS upstream_trees;
+DROP TYPE IF EXISTS upstream_match_method;
+
+-- Remove upstream_scan job type variant
+DELETE FROM jobs WHERE job_type = 'upstream_scan';
+
+ALTER TABLE jobs ALTER COLUMN job_type DROP DEFAULT;
+ALTER TYPE job_type RENAME TO job_type_old;
+CREATE TYPE job_type AS ENUM ('import', 'index_maintenance');
+ALTER TABLE jobs ALTER COLUMN job_type TYPE job_type USING job_type::text::job_type;
+ALTER TABLE jobs ALTER COLUMN job_type SET DEFAULT 'import';
+DROP TYPE job_type_old;
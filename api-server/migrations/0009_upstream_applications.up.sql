@@ -0,0 +1,37 @@
+-- Track which archived patches were applied to upstream git trees
+
+ALTER TYPE job_type ADD VALUE IF NOT EXISTS 'upstream_scan';
+
+CREATE TYPE upstream_match_method AS ENUM ('link', 'patch_id', 'subject_author');
+
+CREATE TABLE upstream_trees (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    repo_path TEXT NOT NULL,
+    reference TEXT NOT NULL DEFAULT 'HEAD',
+    last_scanned_commit TEXT,
+    last_scanned_at TIMESTAMPTZ
+);
+
+CREATE TABLE upstream_applications (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    tree_id INTEGER NOT NULL REFERENCES upstream_trees(id) ON DELETE CASCADE,
+    commit_hash TEXT NOT NULL,
+    commit_subject TEXT NOT NULL,
+    committed_at TIMESTAMPTZ NOT NULL,
+    release_tag TEXT,
+    match_method upstream_match_method NOT NULL,
+    matched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, email_id, tree_id, commit_hash),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE upstream_applications_default PARTITION OF upstream_applications DEFAULT;
+
+CREATE INDEX idx_upstream_applications_commit ON upstream_applications(tree_id, commit_hash);
+CREATE INDEX idx_upstream_applications_unreleased ON upstream_applications(tree_id)
+    WHERE release_tag IS NULL;
+
+-- Upstream commits are matched to patch authors by lowercased email
+CREATE INDEX IF NOT EXISTS idx_authors_email_lower ON authors (lower(email));
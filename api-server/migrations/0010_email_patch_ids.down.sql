@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_emails_patch_id;
+ALTER TABLE emails
+    DROP COLUMN IF EXISTS patch_id;
@@ -0,0 +1,9 @@
+-- Stable git patch-id of patch emails, used to find resends, cross-posts and backports.
+-- Existing rows are backfilled by the next import of each list.
+
+ALTER TABLE emails
+    ADD COLUMN IF NOT EXISTS patch_id TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_emails_patch_id
+    ON emails (patch_id)
+    WHERE patch_id IS NOT NULL;
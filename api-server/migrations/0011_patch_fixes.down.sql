@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_upstream_applications_commit_prefix;
+DROP TABLE IF EXISTS patch_fixes_default;
+DROP TABLE IF EXISTS patch_fixes;
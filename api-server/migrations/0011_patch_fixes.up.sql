@@ -0,0 +1,40 @@
+-- `Fixes: <sha> ("subject")` trailers of patch emails
+
+CREATE TABLE patch_fixes (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    commit_prefix TEXT NOT NULL,
+    fixed_subject TEXT,
+    fixed_normalized_subject TEXT,
+    PRIMARY KEY (mailing_list_id, email_id, commit_prefix),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE patch_fixes_default PARTITION OF patch_fixes DEFAULT;
+
+CREATE INDEX idx_patch_fixes_commit_prefix ON patch_fixes(commit_prefix);
+CREATE INDEX idx_patch_fixes_subject ON patch_fixes(fixed_normalized_subject)
+    WHERE fixed_normalized_subject IS NOT NULL;
+
+-- Allow resolving abbreviated hashes against upstream commits
+CREATE INDEX idx_upstream_applications_commit_prefix
+    ON upstream_applications(commit_hash text_pattern_ops);
+
+-- Backfill from already imported patches; new imports extract trailers from
+-- the commit message only.
+INSERT INTO patch_fixes (mailing_list_id, email_id, commit_prefix, fixed_subject, fixed_normalized_subject)
+SELECT DISTINCT ON (e.mailing_list_id, e.id, lower(m[1]))
+       e.mailing_list_id,
+       e.id,
+       lower(m[1]),
+       NULLIF(m[2], ''),
+       NULLIF(lower(regexp_replace(btrim(m[2]), '\s+', ' ', 'g')), '')
+FROM emails e
+CROSS JOIN LATERAL regexp_matches(
+    e.body,
+    '^Fixes:[ \t]*([0-9a-fA-F]{8,40})\M(?:[ \t]*\("(.*)"\))?',
+    'gn'
+) AS m
+WHERE e.patch_type <> 'none'
+  AND e.body IS NOT NULL
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS email_diffs_default;
+DROP TABLE IF EXISTS email_diffs;
@@ -0,0 +1,13 @@
+-- Cache of structured diffs parsed from patch emails
+
+CREATE TABLE email_diffs (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    parser_version INTEGER NOT NULL,
+    diff JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE email_diffs_default PARTITION OF email_diffs DEFAULT;
@@ -0,0 +1,5 @@
+-- Text written by the sender, without quoted text or signature.
+-- Existing rows are backfilled (together with their search text) by the next import of each list.
+
+ALTER TABLE emails
+    ADD COLUMN IF NOT EXISTS authored_body TEXT;
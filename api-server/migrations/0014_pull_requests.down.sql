@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS pull_requests_default;
+DROP TABLE IF EXISTS pull_requests;
+DROP TYPE IF EXISTS pull_request_status;
@@ -0,0 +1,41 @@
+-- `[GIT PULL]` requests and the fields extracted from their `git request-pull` text
+
+CREATE TYPE pull_request_status AS ENUM ('open', 'merged');
+
+CREATE TABLE pull_requests (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    repository_url TEXT,
+    reference TEXT,
+    base_commit TEXT,
+    head_commit TEXT,
+    shortlog JSONB NOT NULL DEFAULT '[]'::jsonb,
+    status pull_request_status NOT NULL DEFAULT 'open',
+    merged_tree_id INTEGER REFERENCES upstream_trees(id) ON DELETE SET NULL,
+    merged_detected_at TIMESTAMPTZ,
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE pull_requests_default PARTITION OF pull_requests DEFAULT;
+
+CREATE INDEX idx_pull_requests_open_head ON pull_requests(head_commit)
+    WHERE status = 'open';
+
+-- Backfill from already imported emails; shortlogs are only extracted by new imports.
+INSERT INTO pull_requests (
+    mailing_list_id, email_id, repository_url, reference, base_commit, head_commit
+)
+SELECT e.mailing_list_id,
+       e.id,
+       substring(e.body FROM '(?n)^are available in the [Gg]it repository at:\s*\n\s*(\S+)'),
+       substring(e.body FROM '(?n)^are available in the [Gg]it repository at:\s*\n\s*\S+[ \t]+(\S+)'),
+       lower(substring(e.body FROM '(?n)^The following changes since commit ([0-9a-fA-F]{40})')),
+       lower(substring(e.body FROM '(?n)^for you to fetch changes up to ([0-9a-fA-F]{40})'))
+FROM emails e
+WHERE e.subject !~* '^\s*(re|fwd?|aw)\s*:'
+  AND (
+      e.subject ~* '\[[^]]*\mGIT\s+PULL\M[^]]*\]'
+      OR e.body ~ '(?n)^The following changes since commit [0-9a-fA-F]{40}'
+  )
+ON CONFLICT DO NOTHING;
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS bot_reports_default;
+DROP TABLE IF EXISTS bot_reports;
+DROP TYPE IF EXISTS bot_report_kind;
+DROP TYPE IF EXISTS bot_name;
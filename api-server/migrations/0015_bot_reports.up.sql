@@ -0,0 +1,27 @@
+-- Structured reports parsed from syzbot and kernel test robot mail.
+-- Only emails imported after this migration are parsed.
+
+CREATE TYPE bot_name AS ENUM ('syzbot', 'kernel_test_robot');
+CREATE TYPE bot_report_kind AS ENUM ('crash', 'build_issue', 'regression', 'test_result');
+
+CREATE TABLE bot_reports (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    bot bot_name NOT NULL,
+    kind bot_report_kind NOT NULL,
+    title TEXT NOT NULL,
+    crash_signature TEXT,
+    config TEXT,
+    arch TEXT,
+    tested_commit TEXT,
+    patch_message_id TEXT,
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE bot_reports_default PARTITION OF bot_reports DEFAULT;
+
+CREATE INDEX idx_bot_reports_patch ON bot_reports(patch_message_id)
+    WHERE patch_message_id IS NOT NULL;
+CREATE INDEX idx_bot_reports_signature ON bot_reports(crash_signature)
+    WHERE crash_signature IS NOT NULL;
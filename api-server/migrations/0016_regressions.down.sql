@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS regression_state_history;
+DROP TABLE IF EXISTS regressions_default;
+DROP TABLE IF EXISTS regressions;
+DROP TABLE IF EXISTS regzbot_commands_default;
+DROP TABLE IF EXISTS regzbot_commands;
+DROP TYPE IF EXISTS regression_state;
+DROP TYPE IF EXISTS regzbot_command_kind;
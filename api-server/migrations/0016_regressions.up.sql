@@ -0,0 +1,74 @@
+-- Regression tracking from `#regzbot` commands
+
+CREATE TYPE regzbot_command_kind AS ENUM (
+    'introduced', 'title', 'fix', 'link', 'monitor', 'resolve', 'invalid'
+);
+CREATE TYPE regression_state AS ENUM ('open', 'fixed', 'resolved', 'invalid');
+
+CREATE TABLE regzbot_commands (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    position INTEGER NOT NULL,
+    command regzbot_command_kind NOT NULL,
+    argument TEXT NOT NULL,
+    PRIMARY KEY (mailing_list_id, email_id, position),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE regzbot_commands_default PARTITION OF regzbot_commands DEFAULT;
+
+-- Folded from regzbot_commands by each import.
+CREATE TABLE regressions (
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    title TEXT NOT NULL,
+    state regression_state NOT NULL DEFAULT 'open',
+    introduced TEXT NOT NULL,
+    fix TEXT,
+    related_threads TEXT[] NOT NULL DEFAULT '{}',
+    report_email_id INTEGER NOT NULL,
+    reported_at TIMESTAMPTZ NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL,
+    PRIMARY KEY (mailing_list_id, thread_id),
+    FOREIGN KEY (thread_id, mailing_list_id) REFERENCES threads(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE regressions_default PARTITION OF regressions DEFAULT;
+
+CREATE INDEX idx_regressions_state ON regressions(mailing_list_id, state, updated_at DESC);
+
+-- Backfill commands from already imported emails; regressions are folded by the next import.
+INSERT INTO regzbot_commands (mailing_list_id, email_id, position, command, argument)
+SELECT e.mailing_list_id,
+       e.id,
+       m.position::int,
+       (CASE lower(m.match[1]) WHEN 'fixed-by' THEN 'fix' ELSE lower(m.match[1]) END)::regzbot_command_kind,
+       btrim(m.match[2])
+FROM emails e
+CROSS JOIN LATERAL regexp_matches(
+    e.body,
+    '(?n)^[ \t]*#regzbot[ \t]+\^?(introduced|title|fixed-by|fix|link|monitor|resolve|invalid)\M:?[ \t]*([^\n]*)$',
+    'gi'
+) WITH ORDINALITY AS m(match, position)
+WHERE e.body ~* '#regzbot'
+  AND (
+      btrim(m.match[2]) <> ''
+      OR lower(m.match[1]) IN ('resolve', 'invalid')
+  );
+
+-- State transitions of tracked regressions, appended when an import changes a
+-- regression's state
+
+CREATE TABLE regression_state_history (
+    id BIGSERIAL PRIMARY KEY,
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    previous_state regression_state,
+    state regression_state NOT NULL,
+    email_id INTEGER,
+    changed_at TIMESTAMPTZ NOT NULL,
+    FOREIGN KEY (thread_id, mailing_list_id) REFERENCES threads(id, mailing_list_id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_regression_state_history_thread
+    ON regression_state_history(mailing_list_id, thread_id, changed_at);
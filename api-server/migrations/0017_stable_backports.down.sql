@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS stable_backports_default;
+DROP TABLE IF EXISTS stable_backports;
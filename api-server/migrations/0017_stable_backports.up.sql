@@ -0,0 +1,21 @@
+-- Stable backport emails and the upstream commits they carry
+
+CREATE TABLE stable_backports (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    upstream_commit TEXT NOT NULL,
+    -- Backports name at least 12 hex characters; equality on this prefix finds
+    -- the candidates for a longer or shorter spelling of the same commit
+    upstream_commit_prefix TEXT GENERATED ALWAYS AS (left(upstream_commit, 12)) STORED,
+    stable_branch TEXT,
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE stable_backports_default PARTITION OF stable_backports DEFAULT;
+
+CREATE INDEX idx_stable_backports_commit ON stable_backports(upstream_commit text_pattern_ops);
+CREATE INDEX idx_stable_backports_commit_prefix ON stable_backports(upstream_commit_prefix);
+
+-- Commits of already imported emails are written by the next import of each
+-- list, which runs the `stable_backports` email processor over its history.
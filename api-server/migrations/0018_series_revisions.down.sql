@@ -0,0 +1,5 @@
+DROP TABLE IF EXISTS series_revision_patches_default;
+DROP TABLE IF EXISTS series_revision_patches;
+DROP TABLE IF EXISTS series_revisions_default;
+DROP TABLE IF EXISTS series_revisions;
+DROP SEQUENCE IF EXISTS series_revisions_id_seq;
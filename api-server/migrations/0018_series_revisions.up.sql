@@ -0,0 +1,34 @@
+-- Patch series revisions and which of their patches arrived
+
+CREATE SEQUENCE series_revisions_id_seq AS INTEGER;
+
+CREATE TABLE series_revisions (
+    mailing_list_id INTEGER NOT NULL,
+    id INTEGER NOT NULL DEFAULT nextval('series_revisions_id_seq'),
+    author_id INTEGER NOT NULL REFERENCES authors(id) ON DELETE CASCADE,
+    -- revision tag from the subject, '' for the first posting
+    version TEXT NOT NULL,
+    total INTEGER NOT NULL,
+    first_date TIMESTAMPTZ NOT NULL,
+    has_cover_letter BOOLEAN NOT NULL,
+    complete BOOLEAN NOT NULL,
+    PRIMARY KEY (mailing_list_id, id)
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE series_revisions_default PARTITION OF series_revisions DEFAULT;
+
+CREATE INDEX idx_series_revisions_key ON series_revisions(mailing_list_id, author_id, version, total);
+
+CREATE TABLE series_revision_patches (
+    mailing_list_id INTEGER NOT NULL,
+    revision_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    number INTEGER NOT NULL,
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (mailing_list_id, revision_id) REFERENCES series_revisions(mailing_list_id, id) ON DELETE CASCADE,
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE series_revision_patches_default PARTITION OF series_revision_patches DEFAULT;
+
+CREATE INDEX idx_series_revision_patches_revision ON series_revision_patches(mailing_list_id, revision_id);
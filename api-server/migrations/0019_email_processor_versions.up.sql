@@ -0,0 +1,12 @@
+-- Version of each email processor last run over a mailing list's history
+
+CREATE TABLE email_processor_versions (
+    processor TEXT NOT NULL,
+    mailing_list_id INTEGER NOT NULL REFERENCES mailing_lists(id) ON DELETE CASCADE,
+    version INTEGER NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (processor, mailing_list_id)
+);
+
+-- No rows are seeded: the next import of each list re-runs its processors over
+-- history, replacing the SQL backfills of earlier migrations with parser output.
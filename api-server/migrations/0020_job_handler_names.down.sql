@@ -0,0 +1,8 @@
+ALTER TABLE jobs DROP COLUMN IF EXISTS progress;
+
+DELETE FROM jobs WHERE job_type NOT IN ('import', 'index_maintenance', 'upstream_scan');
+
+CREATE TYPE job_type AS ENUM ('import', 'index_maintenance', 'upstream_scan');
+ALTER TABLE jobs ALTER COLUMN job_type DROP DEFAULT;
+ALTER TABLE jobs ALTER COLUMN job_type TYPE job_type USING job_type::job_type;
+ALTER TABLE jobs ALTER COLUMN job_type SET DEFAULT 'import';
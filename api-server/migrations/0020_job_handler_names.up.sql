@@ -0,0 +1,9 @@
+-- Name job kinds after their registered handler instead of a closed enum, and
+-- let handlers report progress
+
+ALTER TABLE jobs ALTER COLUMN job_type DROP DEFAULT;
+ALTER TABLE jobs ALTER COLUMN job_type TYPE TEXT USING job_type::text;
+ALTER TABLE jobs ALTER COLUMN job_type SET DEFAULT 'import';
+DROP TYPE job_type;
+
+ALTER TABLE jobs ADD COLUMN progress JSONB;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS patch_code_default;
+DROP TABLE IF EXISTS patch_code;
@@ -0,0 +1,23 @@
+-- Diff content of patch emails, tokenized for code search
+
+CREATE TABLE patch_code (
+    mailing_list_id INTEGER NOT NULL,
+    email_id INTEGER NOT NULL,
+    files TEXT[] NOT NULL DEFAULT '{}',
+    functions TEXT[] NOT NULL DEFAULT '{}',
+    added_identifiers TEXT[] NOT NULL DEFAULT '{}',
+    removed_identifiers TEXT[] NOT NULL DEFAULT '{}',
+    added_lines TEXT NOT NULL DEFAULT '',
+    removed_lines TEXT NOT NULL DEFAULT '',
+    PRIMARY KEY (mailing_list_id, email_id),
+    FOREIGN KEY (email_id, mailing_list_id) REFERENCES emails(id, mailing_list_id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE patch_code_default PARTITION OF patch_code DEFAULT;
+
+CREATE INDEX idx_patch_code_functions ON patch_code USING GIN(functions);
+CREATE INDEX idx_patch_code_added_identifiers ON patch_code USING GIN(added_identifiers);
+CREATE INDEX idx_patch_code_removed_identifiers ON patch_code USING GIN(removed_identifiers);
+
+-- Already imported patches are tokenized by the next import of each list,
+-- which runs the `patch_code` email processor over its history.
@@ -0,0 +1,19 @@
+-- Thread embeddings reused across reindexes while the embedded text, the
+-- embedding model and the dimensions are unchanged
+
+CREATE TABLE thread_embedding_cache (
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    -- SHA-256 of the text sent to the embedding model
+    content_hash BYTEA NOT NULL,
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    embedding REAL[] NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, thread_id),
+    FOREIGN KEY (mailing_list_id, thread_id) REFERENCES threads(mailing_list_id, id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE thread_embedding_cache_default PARTITION OF thread_embedding_cache DEFAULT;
+
+CREATE INDEX idx_thread_embedding_cache_thread_id ON thread_embedding_cache(thread_id);
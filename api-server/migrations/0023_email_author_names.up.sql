@@ -0,0 +1,7 @@
+-- Display name from the From header of each email. `authors.canonical_name`
+-- follows the most recently seen name, so email processors re-running over
+-- history read this column to see the name the import saw. Rows imported
+-- before this migration keep NULL and fall back to the canonical name.
+
+ALTER TABLE emails
+    ADD COLUMN IF NOT EXISTS author_name TEXT;
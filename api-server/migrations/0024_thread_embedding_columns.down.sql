@@ -0,0 +1,34 @@
+DROP INDEX IF EXISTS idx_thread_embeddings_vchordrq_1024;
+
+CREATE TABLE thread_embedding_cache (
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    content_hash BYTEA NOT NULL,
+    model TEXT NOT NULL,
+    dimensions INTEGER NOT NULL,
+    embedding REAL[] NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, thread_id),
+    FOREIGN KEY (mailing_list_id, thread_id) REFERENCES threads(mailing_list_id, id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE thread_embedding_cache_default PARTITION OF thread_embedding_cache DEFAULT;
+
+CREATE INDEX idx_thread_embedding_cache_thread_id ON thread_embedding_cache(thread_id);
+
+INSERT INTO thread_embedding_cache (
+    mailing_list_id, thread_id, content_hash, model, dimensions, embedding, updated_at
+)
+SELECT mailing_list_id, thread_id, content_hash, model, dimensions, embedding::real[], aggregated_at
+FROM thread_embeddings;
+
+DELETE FROM thread_embeddings;
+
+ALTER TABLE thread_embeddings
+    DROP COLUMN IF EXISTS dimensions,
+    DROP COLUMN IF EXISTS model,
+    DROP COLUMN IF EXISTS content_hash,
+    ALTER COLUMN embedding DROP NOT NULL,
+    ALTER COLUMN embedding TYPE VECTOR(768);
+
+CREATE INDEX idx_thread_embeddings_hnsw ON thread_embeddings USING vchordrq (embedding vector_cosine_ops);
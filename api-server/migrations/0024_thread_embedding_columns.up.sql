@@ -0,0 +1,32 @@
+-- Thread embeddings written by the indexer, one row per thread. A row is
+-- reused across reindexes while the embedded text (SHA-256 in content_hash),
+-- the embedding model and the dimensions are unchanged, and backs the
+-- similar-threads lookups of the PostgreSQL search backend. Replaces the
+-- separate thread_embedding_cache table.
+
+-- The legacy aggregate rows were never written by the server
+DELETE FROM thread_embeddings;
+
+DROP INDEX IF EXISTS idx_thread_embeddings_hnsw;
+
+-- The dimension count follows SEARCH_EMBEDDING_DIM, so the column is untyped
+ALTER TABLE thread_embeddings
+    ALTER COLUMN embedding TYPE VECTOR,
+    ALTER COLUMN embedding SET NOT NULL,
+    ADD COLUMN content_hash BYTEA NOT NULL,
+    ADD COLUMN model TEXT NOT NULL,
+    ADD COLUMN dimensions INTEGER NOT NULL;
+
+INSERT INTO thread_embeddings (
+    mailing_list_id, thread_id, embedding, content_hash, model, dimensions, aggregated_at
+)
+SELECT mailing_list_id, thread_id, embedding::vector, content_hash, model, dimensions, updated_at
+FROM thread_embedding_cache;
+
+DROP TABLE IF EXISTS thread_embedding_cache_default;
+DROP TABLE IF EXISTS thread_embedding_cache;
+
+-- Vector indexes need a fixed dimension count: index the default one
+CREATE INDEX idx_thread_embeddings_vchordrq_1024 ON thread_embeddings
+    USING vchordrq ((embedding::vector(1024)) vector_cosine_ops)
+    WHERE dimensions = 1024;
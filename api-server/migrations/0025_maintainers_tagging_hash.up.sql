@@ -0,0 +1,6 @@
+-- SHA-256 of the MAINTAINERS file a list's threads were last tagged against.
+-- Imports only tag the threads they touch while it matches the current file;
+-- NULL or a different hash makes the next import retag every thread.
+
+ALTER TABLE mailing_lists
+    ADD COLUMN IF NOT EXISTS maintainers_hash BYTEA;
@@ -0,0 +1,2 @@
+ALTER TABLE mailing_lists
+    DROP COLUMN IF EXISTS series_grouped_email_id;
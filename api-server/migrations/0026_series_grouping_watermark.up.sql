@@ -0,0 +1,6 @@
+-- Highest email ID whose series revision was already grouped. Each import
+-- only groups the numbered patches above it; 0 makes the next import group
+-- the list's whole history once.
+
+ALTER TABLE mailing_lists
+    ADD COLUMN IF NOT EXISTS series_grouped_email_id INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS thread_embedding_failures_default;
+DROP TABLE IF EXISTS thread_embedding_failures;
@@ -0,0 +1,13 @@
+-- Threads indexed with a fallback zero vector because their embedding
+-- request failed. The next incremental reindex of the list embeds them again;
+-- a stored vector removes the row.
+
+CREATE TABLE thread_embedding_failures (
+    mailing_list_id INTEGER NOT NULL,
+    thread_id INTEGER NOT NULL,
+    failed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (mailing_list_id, thread_id),
+    FOREIGN KEY (mailing_list_id, thread_id) REFERENCES threads(mailing_list_id, id) ON DELETE CASCADE
+) PARTITION BY LIST (mailing_list_id);
+
+CREATE TABLE thread_embedding_failures_default PARTITION OF thread_embedding_failures DEFAULT;
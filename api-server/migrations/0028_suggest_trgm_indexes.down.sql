@@ -0,0 +1,3 @@
+DROP INDEX IF EXISTS idx_authors_canonical_name_trgm;
+DROP INDEX IF EXISTS idx_authors_email_trgm;
+DROP INDEX IF EXISTS idx_threads_subject_trgm;
@@ -0,0 +1,11 @@
+-- Substring and prefix ILIKE lookups behind the Postgres backend's
+-- suggestions. pg_trgm is created by 0001.
+
+CREATE INDEX IF NOT EXISTS idx_threads_subject_trgm
+    ON threads USING GIN (subject gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_authors_email_trgm
+    ON authors USING GIN (email gin_trgm_ops);
+
+CREATE INDEX IF NOT EXISTS idx_authors_canonical_name_trgm
+    ON authors USING GIN (canonical_name gin_trgm_ops);
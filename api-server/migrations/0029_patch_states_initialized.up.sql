@@ -0,0 +1,6 @@
+-- Whether every patch of a list was given a review state and checked for
+-- newer series versions. Imports only revisit the threads they touch once it
+-- is set; FALSE makes the next import go over the list's whole history once.
+
+ALTER TABLE mailing_lists
+    ADD COLUMN IF NOT EXISTS patch_states_initialized BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,23 @@
+//! Stable backport tracking.
+//!
+//! Patches on the stable lists name the mainline commit they backport, as
+//! `commit <sha> upstream.` or `[ Upstream commit <sha> ]`, and review series
+//! carry the target branch in their subject prefix (`[PATCH 6.6 012/150]`).
+//! Imports record both for every backport email. Queries link each backport
+//! to the original mainline patch email, through the upstream commit it was
+//! applied as or an identical patch-id, and answer which stable branches
+//! received a given upstream commit or patch.
+//!
+//! ## Module Structure
+//!
+//! - `parser`: Upstream commit and stable branch extraction
+//! - `processor`: Import-time email processor writing `stable_backports`
+//! - `store`: Backport queries by email, upstream commit and mainline patch
+
+mod parser;
+mod processor;
+mod store;
+
+pub use parser::{BackportInfo, parse_backport};
+pub use processor::StableBackportProcessor;
+pub use store::{load_backport, load_commit_backports, load_patch_backports, summarize_backports};
@@ -0,0 +1,180 @@
+//! Recognize stable backport emails and the upstream commit they carry.
+
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Fields of a stable backport email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackportInfo {
+    /// Upstream (mainline) commit hash, lowercase, possibly abbreviated.
+    pub upstream_commit: String,
+    /// Stable branch the patch targets, as `major.minor` (e.g. `6.6`).
+    pub stable_branch: Option<String>,
+}
+
+fn reply_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(re|fwd?|aw)\s*:").expect("valid reply regex"))
+}
+
+fn upstream_commit_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(?:commit ([0-9a-f]{12,40}) upstream\.?|\[\s*upstream commit ([0-9a-f]{12,40})\s*\]|\(cherry picked from commit ([0-9a-f]{12,40})\))$",
+        )
+        .expect("valid upstream commit regex")
+    })
+}
+
+fn subject_tag_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\[([^\]]*)\]").expect("valid subject tag regex"))
+}
+
+fn branch_token_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^(?:linux-)?(\d+\.\d+)(?:\.y|\.\d+)?(?:-stable)?$")
+            .expect("valid stable branch regex")
+    })
+}
+
+fn stable_tree_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\b(\d+\.\d+)(?:\.y)?-stable tree\b").expect("valid stable tree regex")
+    })
+}
+
+/// Recognize a backport and extract its upstream commit and stable branch.
+///
+/// An email is a backport when an unquoted line names its upstream commit as
+/// `commit <sha> upstream.`, `[ Upstream commit <sha> ]` or
+/// `(cherry picked from commit <sha>)`. The branch comes from the subject
+/// prefix (`[PATCH 6.6 012/150]`, `[PATCH 5.15.y]`) or, for the stable
+/// maintainers' notification mails, from the `6.6-stable tree` they name.
+pub fn parse_backport(subject: &str, body: &str) -> Option<BackportInfo> {
+    if reply_regex().is_match(subject) {
+        return None;
+    }
+
+    let upstream_commit = body
+        .lines()
+        .filter(|line| !line.starts_with('>'))
+        .find_map(|line| {
+            let caps = upstream_commit_regex().captures(line.trim())?;
+            let hash = caps.get(1).or(caps.get(2)).or(caps.get(3))?;
+            Some(hash.as_str().to_ascii_lowercase())
+        })?;
+
+    let stable_branch = subject_branch(subject).or_else(|| {
+        stable_tree_regex()
+            .captures(body)
+            .map(|caps| caps[1].to_string())
+    });
+
+    Some(BackportInfo {
+        upstream_commit,
+        stable_branch,
+    })
+}
+
+/// Stable branch named in a `[PATCH ...]` subject tag.
+fn subject_branch(subject: &str) -> Option<String> {
+    subject_tag_regex()
+        .captures_iter(subject)
+        .map(|caps| caps.get(1).map_or("", |tag| tag.as_str()))
+        .filter(|tag| {
+            tag.split_whitespace()
+                .any(|token| token.eq_ignore_ascii_case("patch"))
+        })
+        .find_map(|tag| {
+            tag.split_whitespace().find_map(|token| {
+                branch_token_regex()
+                    .captures(token)
+                    .map(|caps| caps[1].to_string())
+            })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn backport(commit: &str, branch: Option<&str>) -> Option<BackportInfo> {
+        Some(BackportInfo {
+            upstream_commit: commit.to_string(),
+            stable_branch: branch.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn parses_stable_review_patches() {
+        let body = "6.6-stable review patch.  If anyone has any objections, please let me know.
+
+------------------
+
+From: Jane Doe <jane@example.com>
+
+commit 0A5BD0FFE790511D802E7F40898429A89E2487DF upstream.
+
+Fix a leak.
+";
+        assert_eq!(
+            parse_backport("[PATCH 6.6 012/150] net: fix a leak", body),
+            backport("0a5bd0ffe790511d802e7f40898429a89e2487df", Some("6.6"))
+        );
+    }
+
+    #[test]
+    fn parses_upstream_commit_tags_and_branch_suffixes() {
+        let body = "[ Upstream commit 0a5bd0ffe790511d802e7f40898429a89e2487df ]\n\nFix a leak.\n";
+        assert_eq!(
+            parse_backport("[PATCH 5.15.y] net: fix a leak", body),
+            backport("0a5bd0ffe790511d802e7f40898429a89e2487df", Some("5.15"))
+        );
+
+        let body = "Fix a leak.\n\n(cherry picked from commit 0a5bd0ffe790)\n";
+        assert_eq!(
+            parse_backport("[PATCH v2 stable linux-6.1.y 1/3] net: fix a leak", body),
+            backport("0a5bd0ffe790", Some("6.1"))
+        );
+    }
+
+    #[test]
+    fn reads_branch_from_stable_tree_notifications() {
+        let body = "This is a note to let you know that I've just added the patch titled
+
+    net: fix a leak
+
+to the 6.1-stable tree which can be found at:
+
+commit 0a5bd0ffe790511d802e7f40898429a89e2487df upstream.
+";
+        assert_eq!(
+            parse_backport(
+                "Patch \"net: fix a leak\" has been added to the 6.1-stable tree",
+                body
+            ),
+            backport("0a5bd0ffe790511d802e7f40898429a89e2487df", Some("6.1"))
+        );
+    }
+
+    #[test]
+    fn ignores_replies_quotes_and_mainline_patches() {
+        let body = "commit 0a5bd0ffe790511d802e7f40898429a89e2487df upstream.\n";
+        assert_eq!(
+            parse_backport("Re: [PATCH 6.6 012/150] net: fix", body),
+            None
+        );
+        assert_eq!(
+            parse_backport("[PATCH 6.6 012/150] net: fix", &format!("> {body}")),
+            None
+        );
+        assert_eq!(
+            parse_backport("[PATCH v2 1/3] net: fix a leak", "Fix a leak.\n"),
+            None
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! Email processor writing stable backports to `stable_backports`.
+
+use crate::backports::parse_backport;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Parses stable backport emails into `stable_backports` rows.
+pub struct StableBackportProcessor;
+
+impl EmailProcessor for StableBackportProcessor {
+    fn name(&self) -> &'static str {
+        "stable_backports"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut upstream_commits = Vec::new();
+            let mut stable_branches = Vec::new();
+
+            for email in emails {
+                let Some(backport) = parse_backport(&email.subject, &email.body) else {
+                    continue;
+                };
+                email_ids.push(email.email_id);
+                upstream_commits.push(backport.upstream_commit);
+                stable_branches.push(backport.stable_branch);
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO stable_backports (mailing_list_id, email_id, upstream_commit, stable_branch)
+                   SELECT $1, * FROM UNNEST($2::int[], $3::text[], $4::text[])
+                   ON CONFLICT (mailing_list_id, email_id) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&upstream_commits)
+            .bind(&stable_branches)
+            .execute(pool)
+            .await?;
+
+            log::trace!("bulk inserted {} stable backports", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM stable_backports WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
@@ -0,0 +1,217 @@
+//! Backport queries.
+
+use crate::models::{StableBackport, StableBackports};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Backport columns plus the mainline patch, resolved through the upstream
+/// commit it was applied as or, failing that, an identical patch-id.
+const BACKPORT_SELECT: &str = r#"
+    SELECT ml.slug AS mailing_list_slug,
+           e.id AS email_id,
+           tm.thread_id,
+           e.subject,
+           a.canonical_name AS author_name,
+           a.email AS author_email,
+           e.date,
+           sb.upstream_commit,
+           sb.stable_branch,
+           mml.slug AS mainline_mailing_list_slug,
+           me.id AS mainline_email_id,
+           me.subject AS mainline_subject,
+           ma.canonical_name AS mainline_author_name,
+           ma.email AS mainline_author_email,
+           me.date AS mainline_date,
+           mtm.thread_id AS mainline_thread_id
+    FROM stable_backports sb
+    JOIN emails e ON e.mailing_list_id = sb.mailing_list_id AND e.id = sb.email_id
+    JOIN mailing_lists ml ON ml.id = sb.mailing_list_id
+    JOIN authors a ON a.id = e.author_id
+    LEFT JOIN thread_memberships tm ON tm.mailing_list_id = sb.mailing_list_id
+        AND tm.email_id = sb.email_id
+    LEFT JOIN LATERAL (
+        SELECT c.mailing_list_id, c.email_id
+        FROM (
+            SELECT ua.mailing_list_id, ua.email_id, 0 AS rank, ua.committed_at AS date
+            FROM upstream_applications ua
+            -- prefix range so idx_upstream_applications_commit_prefix applies
+            WHERE ua.commit_hash ~>=~ sb.upstream_commit
+              AND ua.commit_hash ~<~ (sb.upstream_commit || 'g')
+            UNION ALL
+            SELECT p.mailing_list_id, p.id, 1, p.date
+            FROM emails p
+            WHERE e.patch_id IS NOT NULL
+              AND p.patch_id = e.patch_id
+              AND NOT EXISTS (
+                  SELECT 1
+                  FROM stable_backports other
+                  WHERE other.mailing_list_id = p.mailing_list_id AND other.email_id = p.id
+              )
+        ) c
+        ORDER BY c.rank, c.date
+        LIMIT 1
+    ) mc ON TRUE
+    LEFT JOIN emails me ON me.mailing_list_id = mc.mailing_list_id AND me.id = mc.email_id
+    LEFT JOIN mailing_lists mml ON mml.id = me.mailing_list_id
+    LEFT JOIN authors ma ON ma.id = me.author_id
+    LEFT JOIN thread_memberships mtm ON mtm.mailing_list_id = me.mailing_list_id
+        AND mtm.email_id = me.id
+"#;
+
+/// Backport details of an email, or `None` if it isn't a backport.
+pub async fn load_backport(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<StableBackport>, sqlx::Error> {
+    let query = format!(
+        r#"{BACKPORT_SELECT}
+           WHERE sb.mailing_list_id = $1 AND sb.email_id = $2"#
+    );
+    sqlx::query_as::<_, StableBackport>(&query)
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Backports, from any list, of an upstream commit.
+///
+/// `commit` may be abbreviated, and so may the hashes written in backports;
+/// either being a prefix of the other counts as a match.
+pub async fn load_commit_backports(
+    pool: &PgPool,
+    commit: &str,
+) -> Result<StableBackports, sqlx::Error> {
+    // Backports name at least 12 characters, so a stored hash can only be a
+    // prefix of a commit sharing its first 12 characters
+    let query = format!(
+        r#"{BACKPORT_SELECT}
+           -- prefix range so idx_stable_backports_commit applies
+           WHERE (sb.upstream_commit ~>=~ $1 AND sb.upstream_commit ~<~ ($1 || 'g'))
+              OR (
+                  length($1) >= 12
+                  AND sb.upstream_commit_prefix = left($1, 12)
+                  AND $1 LIKE sb.upstream_commit || '%'
+              )
+           ORDER BY e.date, sb.mailing_list_id, sb.email_id"#
+    );
+    let backports = sqlx::query_as::<_, StableBackport>(&query)
+        .bind(commit)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(summarize_backports(vec![commit.to_string()], backports))
+}
+
+/// Backports, from any list, of a mainline patch email.
+///
+/// Matches backports naming an upstream commit the patch was applied as, and
+/// backports carrying the same patch-id.
+pub async fn load_patch_backports(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<StableBackports, sqlx::Error> {
+    let commits: Vec<String> = sqlx::query_scalar(
+        r#"SELECT DISTINCT commit_hash
+           FROM upstream_applications
+           WHERE mailing_list_id = $1 AND email_id = $2
+           ORDER BY commit_hash"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(pool)
+    .await?;
+
+    let query = format!(
+        r#"{BACKPORT_SELECT}
+           WHERE EXISTS (
+                     SELECT 1
+                     FROM unnest($3::text[]) AS commit(hash)
+                     WHERE sb.upstream_commit_prefix = left(commit.hash, 12)
+                       AND commit.hash LIKE sb.upstream_commit || '%'
+                 )
+              OR (
+                  e.patch_id IS NOT NULL
+                  AND e.patch_id = (
+                      SELECT patch_id FROM emails WHERE mailing_list_id = $1 AND id = $2
+                  )
+                  AND NOT (sb.mailing_list_id = $1 AND sb.email_id = $2)
+              )
+           ORDER BY e.date, sb.mailing_list_id, sb.email_id"#
+    );
+    let backports = sqlx::query_as::<_, StableBackport>(&query)
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .bind(&commits)
+        .fetch_all(pool)
+        .await?;
+
+    Ok(summarize_backports(commits, backports))
+}
+
+/// Collect the distinct stable branches of `backports`, oldest branch first.
+pub fn summarize_backports(
+    upstream_commits: Vec<String>,
+    backports: Vec<StableBackport>,
+) -> StableBackports {
+    let mut branches: Vec<String> = backports
+        .iter()
+        .filter_map(|backport| backport.stable_branch.clone())
+        .collect();
+    branches.sort_by_key(|branch| branch_version(branch));
+    branches.dedup();
+
+    StableBackports {
+        upstream_commits,
+        branches,
+        backports,
+    }
+}
+
+/// Numeric `(major, minor)` of a `major.minor` branch name.
+fn branch_version(branch: &str) -> (u32, u32) {
+    let mut parts = branch
+        .split('.')
+        .map(|part| part.parse::<u32>().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn backport(branch: Option<&str>) -> StableBackport {
+        StableBackport {
+            mailing_list_slug: "stable".to_string(),
+            email_id: 1,
+            thread_id: None,
+            subject: "[PATCH] net: fix a leak".to_string(),
+            author_name: None,
+            author_email: "jane@example.com".to_string(),
+            date: Utc::now(),
+            upstream_commit: "0a5bd0ffe790".to_string(),
+            stable_branch: branch.map(str::to_string),
+            mainline_patch: None,
+        }
+    }
+
+    #[test]
+    fn orders_branches_by_version() {
+        let summary = summarize_backports(
+            Vec::new(),
+            vec![
+                backport(Some("6.1")),
+                backport(Some("5.15")),
+                backport(None),
+                backport(Some("6.10")),
+                backport(Some("6.1")),
+                backport(Some("6.6")),
+            ],
+        );
+
+        assert_eq!(summary.branches, vec!["5.15", "6.1", "6.6", "6.10"]);
+        assert_eq!(summary.backports.len(), 6);
+    }
+}
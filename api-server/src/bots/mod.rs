@@ -0,0 +1,21 @@
+//! Bot report ingestion.
+//!
+//! Recognizes mail from syzbot and the kernel test robot (0-day CI / LKP) by
+//! sender, and parses crash reports, build failures and regression reports into
+//! structured records: bug title, crash signature, kernel config and arch, and
+//! the tested commit. Reports replying to a patch, or naming one through a
+//! `patch link:`, are linked back to that patch.
+//!
+//! ## Module Structure
+//!
+//! - `parser`: Sender detection and per-bot report parsing
+//! - `processor`: Import-time email processor writing `bot_reports`
+//! - `store`: Report queries, including reports about a given patch
+
+mod parser;
+mod processor;
+mod store;
+
+pub use parser::{BotReportInfo, detect_bot, parse_bot_report};
+pub use processor::BotReportProcessor;
+pub use store::{list_bot_reports, load_bot_report, load_patch_bot_reports};
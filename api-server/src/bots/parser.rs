@@ -0,0 +1,386 @@
+//! Recognize bot emails by sender and parse their report format.
+
+use crate::models::{BotName, BotReportKind};
+use crate::upstream::message_id_from_url;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Fields of a bot report email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BotReportInfo {
+    pub bot: BotName,
+    pub kind: BotReportKind,
+    pub title: String,
+    pub crash_signature: Option<String>,
+    pub config: Option<String>,
+    pub arch: Option<String>,
+    pub tested_commit: Option<String>,
+    /// Message-ID of the patch the report is about.
+    pub patch_message_id: Option<String>,
+}
+
+/// Crash report headers, in the form the kernel prints them.
+const CRASH_PREFIXES: &[&str] = &[
+    "BUG: ",
+    "KASAN: ",
+    "KCSAN: ",
+    "KMSAN: ",
+    "UBSAN: ",
+    "WARNING: ",
+    "INFO: ",
+    "general protection fault",
+    "kernel BUG at ",
+    "Oops: ",
+    "unregister_netdevice: ",
+];
+
+fn tag_prefix_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^\s*(?:(?:re|fwd?|aw)\s*:\s*|\[[^\]]*\]\s*)*").expect("valid tag regex")
+    })
+}
+
+fn field_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([A-Za-z][A-Za-z ]*?):\s+(\S.*)$").expect("valid field regex"))
+}
+
+fn commit_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^([0-9a-fA-F]{12,40})\b").expect("valid commit regex"))
+}
+
+fn symbol_offset_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"\+0x[0-9a-f]+/0x[0-9a-f]+").expect("valid offset regex"))
+}
+
+/// Identify the bot that sent an email, if any.
+pub fn detect_bot(author_name: &str, author_email: &str) -> Option<BotName> {
+    let email = author_email.trim().to_ascii_lowercase();
+    if email.ends_with("@syzkaller.appspotmail.com") {
+        Some(BotName::Syzbot)
+    } else if email == "lkp@intel.com"
+        || author_name.trim().eq_ignore_ascii_case("kernel test robot")
+    {
+        Some(BotName::KernelTestRobot)
+    } else {
+        None
+    }
+}
+
+/// Parse a bot email into a structured report.
+///
+/// Returns `None` for human senders and for bot mail that isn't a report
+/// (e.g. syzbot's monthly summaries).
+pub fn parse_bot_report(
+    author_name: &str,
+    author_email: &str,
+    subject: &str,
+    body: &str,
+    in_reply_to: Option<&str>,
+) -> Option<BotReportInfo> {
+    let bot = detect_bot(author_name, author_email)?;
+    let fields = Fields::parse(body);
+    let title = strip_subject_tags(subject);
+
+    let mut report = match bot {
+        BotName::Syzbot => parse_syzbot(body, &fields, title)?,
+        BotName::KernelTestRobot => parse_test_robot(body, &fields, title)?,
+    };
+
+    report.patch_message_id = report
+        .patch_message_id
+        .or_else(|| in_reply_to.map(str::to_string));
+    Some(report)
+}
+
+fn parse_syzbot(body: &str, fields: &Fields<'_>, title: String) -> Option<BotReportInfo> {
+    let kind = if contains_ci(body, "syzbot found the following") {
+        BotReportKind::Crash
+    } else if contains_ci(body, "syzbot has tested the proposed patch")
+        || contains_ci(body, "syzbot tried to test the proposed patch")
+    {
+        BotReportKind::TestResult
+    } else {
+        return None;
+    };
+
+    // syzbot titles are the crash signatures it deduplicates bugs by.
+    let crash_signature = match kind {
+        BotReportKind::Crash => Some(title.clone()).filter(|t| !t.is_empty()),
+        _ => first_crash_line(body),
+    };
+
+    Some(BotReportInfo {
+        bot: BotName::Syzbot,
+        kind,
+        title,
+        crash_signature,
+        config: fields.get("kernel config").map(str::to_string),
+        arch: fields.get("userspace arch").map(str::to_string),
+        tested_commit: fields.get("HEAD commit").and_then(leading_commit),
+        patch_message_id: None,
+    })
+}
+
+fn parse_test_robot(body: &str, fields: &Fields<'_>, title: String) -> Option<BotReportInfo> {
+    let lower = body.to_ascii_lowercase();
+    let (kind, crash_signature) = if lower.contains("kernel test robot noticed the following build")
+        || lower.contains("[auto build test ")
+    {
+        (BotReportKind::BuildIssue, first_build_error(body))
+    } else if let Some(noticed) = noticed_regression(body) {
+        (BotReportKind::Regression, Some(noticed))
+    } else {
+        return None;
+    };
+
+    let config = fields
+        .get("config")
+        .and_then(|value| value.split_whitespace().next())
+        .map(str::to_string);
+    let arch = config
+        .as_deref()
+        .and_then(|config| config.split_once('-'))
+        .map(|(arch, _)| arch.to_string());
+    let tested_commit = fields
+        .get("commit")
+        .or_else(|| fields.get("head"))
+        .and_then(leading_commit);
+
+    Some(BotReportInfo {
+        bot: BotName::KernelTestRobot,
+        kind,
+        title,
+        crash_signature,
+        config,
+        arch,
+        tested_commit,
+        patch_message_id: fields.get("patch link").and_then(message_id_from_url),
+    })
+}
+
+/// `key: value` header lines at the start of a line, first occurrence wins.
+struct Fields<'a>(Vec<(String, &'a str)>);
+
+impl<'a> Fields<'a> {
+    fn parse(body: &'a str) -> Self {
+        let fields = body
+            .lines()
+            .filter_map(|line| {
+                let caps = field_regex().captures(line.trim_end())?;
+                Some((
+                    caps.get(1)?.as_str().to_ascii_lowercase(),
+                    caps.get(2)?.as_str(),
+                ))
+            })
+            .collect();
+        Self(fields)
+    }
+
+    fn get(&self, key: &str) -> Option<&'a str> {
+        self.0
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(key))
+            .map(|(_, value)| *value)
+    }
+}
+
+/// Subject without reply prefixes and bracketed tags (`[syzbot] [net?]`).
+fn strip_subject_tags(subject: &str) -> String {
+    let stripped = tag_prefix_regex().replace(subject, "");
+    stripped.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn leading_commit(value: &str) -> Option<String> {
+    commit_regex()
+        .captures(value.trim())
+        .map(|caps| caps[1].to_ascii_lowercase())
+}
+
+fn contains_ci(haystack: &str, needle: &str) -> bool {
+    haystack.to_ascii_lowercase().contains(needle)
+}
+
+/// First crash header in a console log, without symbol offsets.
+fn first_crash_line(body: &str) -> Option<String> {
+    body.lines()
+        .map(str::trim)
+        .find(|line| CRASH_PREFIXES.iter().any(|prefix| line.starts_with(prefix)))
+        .map(|line| symbol_offset_regex().replace_all(line, "").into_owned())
+}
+
+/// First new (`>>`-marked) compiler error or warning, without line numbers.
+fn first_build_error(body: &str) -> Option<String> {
+    body.lines()
+        .filter_map(|line| line.strip_prefix(">>"))
+        .map(str::trim)
+        .find_map(|line| {
+            let index = line
+                .find(": error: ")
+                .or_else(|| line.find(": warning: "))?;
+            let location = &line[..index];
+            let path = location.split(':').next().unwrap_or(location);
+            Some(format!("{}:{}", path, &line[index + 1..]))
+        })
+}
+
+/// What LKP noticed: `-12.3% regression of will-it-scale.per_process_ops` or
+/// a functional failure such as `BUG:kernel_NULL_pointer_dereference`.
+fn noticed_regression(body: &str) -> Option<String> {
+    body.lines().find_map(|line| {
+        let rest = line.trim().strip_prefix("kernel test robot noticed ")?;
+        let rest = rest.strip_suffix(':').unwrap_or(rest);
+        let rest = rest.strip_suffix(" on").unwrap_or(rest);
+        if let Some(quoted) = rest.strip_prefix('"') {
+            return quoted.split('"').next().map(str::to_string);
+        }
+        let regression = rest.strip_prefix("a ")?;
+        regression
+            .contains("regression")
+            .then(|| regression.to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_syzbot_crash() {
+        let body = "Hello,
+
+syzbot found the following issue on:
+
+HEAD commit:    6613476e225e Linux 6.8-rc1
+git tree:       upstream
+console output: https://syzkaller.appspot.com/x/log.txt?x=1
+kernel config:  https://syzkaller.appspot.com/x/.config?x=2
+dashboard link: https://syzkaller.appspot.com/bug?extid=3
+userspace arch: i386
+
+general protection fault, probably for non-canonical address 0xdffffc0000000000
+RIP: 0010:foo_bar+0x12/0x40 net/foo.c:123
+";
+        let report = parse_bot_report(
+            "syzbot",
+            "syzbot+3@syzkaller.appspotmail.com",
+            "[syzbot] [net?] KASAN: use-after-free Read in foo_bar",
+            body,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(report.bot, BotName::Syzbot);
+        assert_eq!(report.kind, BotReportKind::Crash);
+        assert_eq!(report.title, "KASAN: use-after-free Read in foo_bar");
+        assert_eq!(
+            report.crash_signature.as_deref(),
+            Some("KASAN: use-after-free Read in foo_bar")
+        );
+        assert_eq!(
+            report.config.as_deref(),
+            Some("https://syzkaller.appspot.com/x/.config?x=2")
+        );
+        assert_eq!(report.arch.as_deref(), Some("i386"));
+        assert_eq!(report.tested_commit.as_deref(), Some("6613476e225e"));
+        assert_eq!(report.patch_message_id, None);
+    }
+
+    #[test]
+    fn parses_build_failure_reply() {
+        let body = "Hi Jane,
+
+kernel test robot noticed the following build errors:
+
+[auto build test ERROR on net-next/main]
+
+url:    https://github.com/intel-lab-lkp/linux/commits/Jane-Doe/foo/20240101-000000
+base:   https://git.kernel.org/pub/scm/linux/kernel/git/netdev/net-next.git main
+patch link:    https://lore.kernel.org/r/20240101.1234-1-jane%40example.org
+patch subject: [PATCH net-next] net: foo: add bar
+config: x86_64-allyesconfig (https://download.01.org/0day-ci/archive/config)
+compiler: gcc-12 (Debian 12.2.0-14) 12.2.0
+
+All errors (new ones prefixed by >>):
+
+>> drivers/net/foo.c:12:5: error: 'bar' undeclared (first use in this function)
+";
+        let report = parse_bot_report(
+            "kernel test robot",
+            "lkp@intel.com",
+            "Re: [PATCH net-next] net: foo: add bar",
+            body,
+            Some("parent@example.org"),
+        )
+        .unwrap();
+
+        assert_eq!(report.bot, BotName::KernelTestRobot);
+        assert_eq!(report.kind, BotReportKind::BuildIssue);
+        assert_eq!(report.title, "net: foo: add bar");
+        assert_eq!(
+            report.crash_signature.as_deref(),
+            Some("drivers/net/foo.c: error: 'bar' undeclared (first use in this function)")
+        );
+        assert_eq!(report.config.as_deref(), Some("x86_64-allyesconfig"));
+        assert_eq!(report.arch.as_deref(), Some("x86_64"));
+        assert_eq!(
+            report.patch_message_id.as_deref(),
+            Some("20240101.1234-1-jane@example.org")
+        );
+    }
+
+    #[test]
+    fn parses_lkp_regression() {
+        let body = "Hello,
+
+kernel test robot noticed a -12.3% regression of will-it-scale.per_process_ops on:
+
+commit: 0a5bd0ffe790511d802e7f40898429a89e2487df (\"mm: foo\")
+https://git.kernel.org/cgit/linux/kernel/git/next/linux-next.git master
+
+testcase: will-it-scale
+config: x86_64-rhel-8.3
+";
+        let report = parse_bot_report(
+            "kernel test robot",
+            "oliver.sang@intel.com",
+            "[linux-next:master] [mm]  0a5bd0ffe7: will-it-scale.per_process_ops -12.3% regression",
+            body,
+            None,
+        )
+        .unwrap();
+
+        assert_eq!(report.kind, BotReportKind::Regression);
+        assert_eq!(
+            report.title,
+            "0a5bd0ffe7: will-it-scale.per_process_ops -12.3% regression"
+        );
+        assert_eq!(
+            report.crash_signature.as_deref(),
+            Some("-12.3% regression of will-it-scale.per_process_ops")
+        );
+        assert_eq!(
+            report.tested_commit.as_deref(),
+            Some("0a5bd0ffe790511d802e7f40898429a89e2487df")
+        );
+        assert_eq!(report.arch.as_deref(), Some("x86_64"));
+    }
+
+    #[test]
+    fn ignores_humans_and_non_reports() {
+        assert!(parse_bot_report("Jane", "jane@example.org", "[syzbot] x", "", None).is_none());
+        assert!(
+            parse_bot_report(
+                "syzbot",
+                "syzbot@syzkaller.appspotmail.com",
+                "[syzbot] Monthly net report (Jan 2024)",
+                "Hello net maintainers/developers,",
+                None,
+            )
+            .is_none()
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! Email processor writing syzbot and kernel test robot reports to `bot_reports`.
+
+use crate::bots::parse_bot_report;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Parses bot mail into `bot_reports` rows.
+pub struct BotReportProcessor;
+
+impl EmailProcessor for BotReportProcessor {
+    fn name(&self) -> &'static str {
+        "bot_reports"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut bots = Vec::new();
+            let mut kinds = Vec::new();
+            let mut titles = Vec::new();
+            let mut crash_signatures = Vec::new();
+            let mut configs = Vec::new();
+            let mut arches = Vec::new();
+            let mut tested_commits = Vec::new();
+            let mut patch_message_ids = Vec::new();
+
+            for email in emails {
+                let Some(report) = parse_bot_report(
+                    &email.author_name,
+                    &email.author_email,
+                    &email.subject,
+                    &email.body,
+                    email.in_reply_to.as_deref(),
+                ) else {
+                    continue;
+                };
+                email_ids.push(email.email_id);
+                bots.push(report.bot);
+                kinds.push(report.kind);
+                titles.push(report.title);
+                crash_signatures.push(report.crash_signature);
+                configs.push(report.config);
+                arches.push(report.arch);
+                tested_commits.push(report.tested_commit);
+                patch_message_ids.push(report.patch_message_id);
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO bot_reports (
+                       mailing_list_id, email_id, bot, kind, title, crash_signature,
+                       config, arch, tested_commit, patch_message_id
+                   )
+                   SELECT $1, * FROM UNNEST(
+                       $2::int[], $3::bot_name[], $4::bot_report_kind[], $5::text[],
+                       $6::text[], $7::text[], $8::text[], $9::text[], $10::text[]
+                   )
+                   ON CONFLICT (mailing_list_id, email_id) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&bots)
+            .bind(&kinds)
+            .bind(&titles)
+            .bind(&crash_signatures)
+            .bind(&configs)
+            .bind(&arches)
+            .bind(&tested_commits)
+            .bind(&patch_message_ids)
+            .execute(pool)
+            .await?;
+
+            log::trace!("bulk inserted {} bot reports", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM bot_reports WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
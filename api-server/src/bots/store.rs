@@ -0,0 +1,122 @@
+//! Bot report queries.
+
+use crate::models::{BotName, BotReport, BotReportKind};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Report columns plus the linked patch, resolved by Message-ID across lists.
+const BOT_REPORT_SELECT: &str = r#"
+    SELECT br.email_id,
+           tm.thread_id,
+           br.bot,
+           br.kind,
+           e.date,
+           br.title,
+           br.crash_signature,
+           br.config,
+           br.arch,
+           br.tested_commit,
+           pe.id AS patch_email_id,
+           pml.slug AS patch_mailing_list_slug,
+           pe.subject AS patch_subject,
+           pa.canonical_name AS patch_author_name,
+           pa.email AS patch_author_email,
+           pe.date AS patch_date,
+           ptm.thread_id AS patch_thread_id
+    FROM bot_reports br
+    JOIN emails e ON e.mailing_list_id = br.mailing_list_id AND e.id = br.email_id
+    LEFT JOIN thread_memberships tm ON tm.mailing_list_id = br.mailing_list_id
+        AND tm.email_id = br.email_id
+    LEFT JOIN LATERAL (
+        SELECT p.mailing_list_id, p.id, p.subject, p.date, p.author_id
+        FROM emails p
+        WHERE br.patch_message_id IS NOT NULL
+          AND p.message_id = br.patch_message_id
+          AND p.patch_type <> 'none'
+        -- prefer the report's own list
+        ORDER BY (p.mailing_list_id = br.mailing_list_id) DESC, p.mailing_list_id
+        LIMIT 1
+    ) pe ON TRUE
+    LEFT JOIN mailing_lists pml ON pml.id = pe.mailing_list_id
+    LEFT JOIN authors pa ON pa.id = pe.author_id
+    LEFT JOIN thread_memberships ptm ON ptm.mailing_list_id = pe.mailing_list_id
+        AND ptm.email_id = pe.id
+"#;
+
+/// Bot reports of a list, newest first, with the total count.
+pub async fn list_bot_reports(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    bot: Option<BotName>,
+    kind: Option<BotReportKind>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<BotReport>, i64), sqlx::Error> {
+    let total: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*)
+           FROM bot_reports
+           WHERE mailing_list_id = $1
+             AND ($2::bot_name IS NULL OR bot = $2)
+             AND ($3::bot_report_kind IS NULL OR kind = $3)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(bot)
+    .bind(kind)
+    .fetch_one(pool)
+    .await?;
+
+    let query = format!(
+        r#"{BOT_REPORT_SELECT}
+           WHERE br.mailing_list_id = $1
+             AND ($2::bot_name IS NULL OR br.bot = $2)
+             AND ($3::bot_report_kind IS NULL OR br.kind = $3)
+           ORDER BY e.date DESC, br.email_id DESC
+           LIMIT $4 OFFSET $5"#
+    );
+    let reports = sqlx::query_as::<_, BotReport>(&query)
+        .bind(mailing_list_id)
+        .bind(bot)
+        .bind(kind)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok((reports, total))
+}
+
+/// Bot report parsed from an email, or `None` if it isn't one.
+pub async fn load_bot_report(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<BotReport>, sqlx::Error> {
+    let query = format!(
+        r#"{BOT_REPORT_SELECT}
+           WHERE br.mailing_list_id = $1 AND br.email_id = $2"#
+    );
+    sqlx::query_as::<_, BotReport>(&query)
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Bot reports, from any list, about a patch email (matched by Message-ID).
+pub async fn load_patch_bot_reports(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Vec<BotReport>, sqlx::Error> {
+    let query = format!(
+        r#"{BOT_REPORT_SELECT}
+           WHERE br.patch_message_id = (
+                     SELECT message_id FROM emails WHERE mailing_list_id = $1 AND id = $2
+                 )
+           ORDER BY e.date"#
+    );
+    sqlx::query_as::<_, BotReport>(&query)
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .fetch_all(pool)
+        .await
+}
@@ -1,4 +1,4 @@
-use crate::search::SearchError;
+use crate::search::{QueryError, SearchError};
 use chrono::Utc;
 use rocket::http::Status;
 use rocket::response::{self, Responder};
@@ -16,6 +16,7 @@ pub enum ApiError {
     DatabaseError(sqlx::Error),
     NotFound(String),
     BadRequest(String),
+    Forbidden(String),
     InternalError(String),
 }
 
@@ -63,6 +64,15 @@ impl<'r> Responder<'r, 'static> for ApiError {
                     "https://docs.nexus/errors/bad-request",
                 )
             }
+            ApiError::Forbidden(msg) => {
+                log::debug!("forbidden: {}", msg);
+                (
+                    Status::Forbidden,
+                    "Forbidden",
+                    msg,
+                    "https://docs.nexus/errors/forbidden",
+                )
+            }
             ApiError::InternalError(msg) => {
                 log::error!("internal error: {}", msg);
                 (
@@ -111,6 +121,13 @@ impl From<SearchError> for ApiError {
         ApiError::InternalError("Search service error".to_string())
     }
 }
+
+impl From<QueryError> for ApiError {
+    fn from(err: QueryError) -> Self {
+        ApiError::BadRequest(format!("Invalid search query: {}", err))
+    }
+}
+
 impl OpenApiResponderInner for ApiError {
     fn responses(_generator: &mut OpenApiGenerator) -> Result<Responses, OpenApiError> {
         use rocket_okapi::okapi::openapi3::*;
@@ -121,6 +138,10 @@ impl OpenApiResponderInner for ApiError {
                     description: "Bad Request - Invalid input parameters".to_string(),
                     ..Default::default()
                 }),
+                "403".to_string() => RefOr::Object(Response {
+                    description: "Forbidden - The user may not perform this action".to_string(),
+                    ..Default::default()
+                }),
                 "404".to_string() => RefOr::Object(Response {
                     description: "Not Found - The requested resource was not found".to_string(),
                     ..Default::default()
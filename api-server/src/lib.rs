@@ -2,20 +2,31 @@
 extern crate rocket;
 
 pub mod auth;
+pub mod backports;
+pub mod bots;
 pub mod db;
 pub mod error;
+pub mod maintainers;
 pub mod models;
+pub mod patches;
+pub mod pull_requests;
+pub mod regressions;
 pub mod request_logger;
 pub mod routes;
 pub mod search;
 pub mod sync;
 pub mod threading;
+pub mod upstream;
 
 use crate::auth::{AuthConfig, AuthState, JwtService, PasswordService, RefreshTokenStore};
 use crate::db::NexusDb;
 use crate::request_logger::RequestLogger;
-use crate::search::SearchService;
+use crate::search::{
+    EmbeddingProviderKind, EmbeddingsClient, EmbeddingsConfig, SearchBackend, SearchBackendKind,
+    SearchService,
+};
 use crate::sync::dispatcher::SyncDispatcher;
+use crate::sync::jobs::JobRegistry;
 use crate::sync::queue::JobQueue;
 use env_logger::Env;
 use rocket::fairing::AdHoc;
@@ -57,17 +68,12 @@ pub fn rocket() -> Rocket<Build> {
     let meili_key = std::env::var("MEILISEARCH_MASTER_KEY")
         .or_else(|_| std::env::var("MEILI_MASTER_KEY"))
         .ok();
-    let embeddings_url =
-        std::env::var("EMBEDDINGS_URL").unwrap_or_else(|_| "http://100.65.8.15:8080".to_string());
     let default_semantic_ratio = std::env::var("SEARCH_DEFAULT_SEMANTIC_RATIO")
         .ok()
         .and_then(|value| value.parse::<f32>().ok())
         .unwrap_or(0.35);
 
-    let thread_embedding_dim = std::env::var("SEARCH_EMBEDDING_DIM")
-        .ok()
-        .and_then(|value| value.parse::<usize>().ok())
-        .unwrap_or(1024);
+    let embeddings_config = EmbeddingsConfig::from_env().unwrap_or_else(|err| panic!("{err}"));
 
     let allow_global_thread_search = std::env::var("SEARCH_ENABLE_GLOBAL_THREADS")
         .ok()
@@ -77,14 +83,42 @@ pub fn rocket() -> Rocket<Build> {
         })
         .unwrap_or(false);
 
+    let search_backend = std::env::var("SEARCH_BACKEND")
+        .ok()
+        .and_then(|value| {
+            let kind = SearchBackendKind::parse(&value);
+            if kind.is_none() {
+                log::warn!(
+                    "unknown SEARCH_BACKEND '{value}' (expected meilisearch or postgres); using {}",
+                    SearchBackendKind::default().as_str()
+                );
+            }
+            kind
+        })
+        .unwrap_or_default();
+    log::info!("search backend: {}", search_backend.as_str());
+    log::info!(
+        "embeddings provider: {} ({}, {} dims)",
+        embeddings_config.provider.as_str(),
+        embeddings_config.model,
+        embeddings_config.dimensions
+    );
+    if search_backend.uses_document_index()
+        && embeddings_config.provider == EmbeddingProviderKind::Hash
+    {
+        log::warn!(
+            "semantic search uses local hashing embeddings; set EMBEDDINGS_URL to use a model"
+        );
+    }
+
     let search_service = SearchService::new(
         meili_url,
         meili_key,
-        embeddings_url,
+        EmbeddingsClient::new(embeddings_config),
         default_semantic_ratio,
-        thread_embedding_dim,
         allow_global_thread_search,
-    );
+    )
+    .with_backend(search_backend);
 
     // Configure CORS
     let cors = CorsOptions::default()
@@ -145,8 +179,18 @@ pub fn rocket() -> Rocket<Build> {
 
                         // Initialize job queue with database pool
                         let job_queue = Arc::new(Mutex::new(JobQueue::new(pool.clone())));
+                        let job_handlers = Arc::new(JobRegistry::builtin());
 
-                        Ok(rocket.manage(pool).manage(job_queue))
+                        // Searches go through the configured backend
+                        let search_backend: Option<Arc<dyn SearchBackend>> = rocket
+                            .state::<SearchService>()
+                            .map(|search| search.backend().build(search, pool.clone()));
+
+                        let rocket = rocket.manage(pool).manage(job_queue).manage(job_handlers);
+                        match search_backend {
+                            Some(backend) => Ok(rocket.manage(backend)),
+                            None => Err(rocket),
+                        }
                     }
                     None => Err(rocket),
                 }
@@ -222,13 +266,18 @@ pub fn rocket() -> Rocket<Build> {
             Box::pin(async move {
                 let pool_state = rocket.state::<rocket_db_pools::sqlx::PgPool>();
                 let search_state = rocket.state::<SearchService>();
+                let handlers = rocket
+                    .state::<Arc<JobRegistry>>()
+                    .cloned()
+                    .unwrap_or_else(|| Arc::new(JobRegistry::builtin()));
 
                 if let (Some(pool), Some(search)) = (pool_state, search_state) {
                     let dispatcher_pool = pool.clone();
                     let dispatcher_search = search.clone();
                     tokio::spawn(async move {
                         log::info!("starting sync dispatcher");
-                        let dispatcher = SyncDispatcher::new(dispatcher_pool, dispatcher_search);
+                        let dispatcher = SyncDispatcher::new(dispatcher_pool, dispatcher_search)
+                            .with_handlers(handlers);
                         dispatcher.run().await
                     });
                 } else {
@@ -261,6 +310,40 @@ pub fn rocket() -> Rocket<Build> {
                 routes::threads::get_thread,
                 routes::emails::list_emails,
                 routes::emails::get_email,
+                // Maintainers
+                routes::maintainers::list_subsystems,
+                routes::maintainers::get_subsystem,
+                routes::maintainers::get_email_maintainers,
+                // Patches
+                routes::patches::get_patch_state,
+                routes::patches::update_patch_state,
+                routes::patches::get_patch_state_history,
+                routes::patches::update_series_state,
+                routes::patches::get_email_diff,
+                routes::patches::list_patch_id_emails,
+                routes::patches::list_same_patch_emails,
+                routes::patches::list_fixed_commits,
+                routes::patches::list_fixing_patches,
+                routes::patches::list_email_review_comments,
+                routes::patches::list_thread_review_comments,
+                routes::patches::list_thread_series,
+                routes::patches::get_email_series,
+                // Bot reports
+                routes::bots::list_list_bot_reports,
+                routes::bots::get_email_bot_report,
+                routes::bots::list_patch_bot_reports,
+                // Pull requests
+                routes::pull_requests::list_list_pull_requests,
+                routes::pull_requests::get_email_pull_request,
+                // Regressions
+                routes::regressions::list_list_regressions,
+                routes::regressions::get_regression,
+                // Stable backports
+                routes::backports::list_commit_backports,
+                routes::backports::get_email_backport,
+                routes::backports::list_patch_backports,
+                // Upstream
+                routes::upstream::get_email_upstream,
                 // Authors
                 routes::authors::list_authors,
                 routes::authors::get_author,
@@ -269,6 +352,10 @@ pub fn rocket() -> Rocket<Build> {
                 routes::authors::get_author_threads_participated,
                 routes::search::search_threads_for_list,
                 routes::search::search_threads_global,
+                routes::search::search_emails,
+                routes::search::search_patches,
+                routes::search::search_suggest,
+                routes::search::similar_threads,
                 routes::search::search_authors,
             ],
         )
@@ -283,6 +370,7 @@ pub fn rocket() -> Rocket<Build> {
                 routes::mailing_lists::admin_seed_lists,
                 // Jobs
                 routes::admin::list_jobs,
+                routes::admin::list_job_types,
                 routes::admin::create_job,
                 routes::admin::get_job,
                 routes::admin::patch_job,
@@ -342,6 +430,9 @@ pub mod test_support {
     use rocket::local::blocking::Client;
     use rocket::{Build, Rocket, Route};
     use rocket_db_pools::sqlx::{self, PgPool};
+    use std::sync::Arc;
+
+    use crate::sync::jobs::JobRegistry;
 
     pub use database::{TestDatabase, TestDatabaseError};
 
@@ -686,7 +777,7 @@ pub mod test_support {
                 rocket = rocket.manage(pool);
             }
 
-            rocket
+            rocket.manage(Arc::new(JobRegistry::builtin()))
         }
 
         /// Convenience helper to produce a blocking local client.
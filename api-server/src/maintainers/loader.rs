@@ -0,0 +1,83 @@
+//! Locate and read the MAINTAINERS file configured for this deployment.
+//!
+//! Two sources are supported:
+//! - `MAINTAINERS_PATH`: a plain file on disk (e.g. a kernel checkout)
+//! - `MAINTAINERS_GIT_PATH` (+ optional `MAINTAINERS_GIT_REF`, default `HEAD`):
+//!   a git mirror of the kernel tree; the file is read from the tree at that ref
+//!
+//! When both are set the local path wins.
+
+use std::env;
+use std::path::PathBuf;
+
+/// Where the MAINTAINERS file is read from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MaintainersSource {
+    Path(PathBuf),
+    Git { repo: PathBuf, reference: String },
+}
+
+impl MaintainersSource {
+    /// Build the source from environment variables, or `None` if not configured.
+    pub fn from_env() -> Option<Self> {
+        if let Some(path) = non_empty_env("MAINTAINERS_PATH") {
+            return Some(MaintainersSource::Path(PathBuf::from(path)));
+        }
+
+        non_empty_env("MAINTAINERS_GIT_PATH").map(|repo| MaintainersSource::Git {
+            repo: PathBuf::from(repo),
+            reference: non_empty_env("MAINTAINERS_GIT_REF").unwrap_or_else(|| "HEAD".to_string()),
+        })
+    }
+
+    /// Read the MAINTAINERS file contents.
+    ///
+    /// Git access is blocking; call from `spawn_blocking` in async contexts.
+    pub fn read(&self) -> Result<String, String> {
+        match self {
+            MaintainersSource::Path(path) => std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read MAINTAINERS from {:?}: {}", path, e)),
+            MaintainersSource::Git { repo, reference } => read_from_git(repo, reference),
+        }
+    }
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn read_from_git(repo_path: &PathBuf, reference: &str) -> Result<String, String> {
+    let repo = gix::open(repo_path)
+        .map_err(|e| format!("Failed to open git repository {:?}: {}", repo_path, e))?;
+
+    let commit = repo
+        .rev_parse_single(reference)
+        .map_err(|e| format!("Failed to resolve '{}': {}", reference, e))?
+        .object()
+        .map_err(|e| format!("Failed to find object for '{}': {}", reference, e))?
+        .peel_to_commit()
+        .map_err(|e| format!("'{}' does not point to a commit: {}", reference, e))?;
+
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree: {}", e))?;
+
+    let entry = tree
+        .lookup_entry_by_path("MAINTAINERS")
+        .map_err(|e| format!("Failed to look up MAINTAINERS: {}", e))?
+        .ok_or_else(|| {
+            format!(
+                "MAINTAINERS not found at '{}' in {:?}",
+                reference, repo_path
+            )
+        })?;
+
+    let blob = entry
+        .object()
+        .map_err(|e| format!("Failed to read MAINTAINERS blob: {}", e))?;
+
+    Ok(String::from_utf8_lossy(&blob.data).into_owned())
+}
@@ -0,0 +1,22 @@
+//! Kernel MAINTAINERS integration.
+//!
+//! Loads the MAINTAINERS file from a local path or a git mirror, parses the
+//! subsystem entries, and maps the paths touched by patches onto subsystems so
+//! threads can be tagged and filtered by subsystem.
+//!
+//! ## Module Structure
+//!
+//! - `loader`: Resolves the configured source and reads the file contents
+//! - `parser`: Section parser and `get_maintainer.pl`-style path matching
+//! - `store`: Persists subsystems and recomputes per-thread subsystem tags
+
+mod loader;
+mod parser;
+mod store;
+
+pub use loader::MaintainersSource;
+pub use parser::{MaintainerEntry, MaintainersIndex, parse_maintainers};
+pub use store::{
+    index_from_subsystems, load_maintainers_hash, load_subsystems, maintainers_hash,
+    record_maintainers_hash, sync_subsystems, tag_threads, tag_threads_for_list,
+};
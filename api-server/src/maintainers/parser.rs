@@ -0,0 +1,424 @@
+//! Parser and path matcher for the kernel `MAINTAINERS` file.
+//!
+//! The file is a sequence of blank-line separated sections. Each section starts
+//! with a title line followed by `X:\tvalue` field lines. Only the fields Nexus
+//! uses are retained; everything else (`W:`, `Q:`, `B:`, `C:`, `P:`, `K:`) is
+//! ignored. The free-form preamble at the top of the file is skipped because its
+//! paragraphs never contain field lines.
+//!
+//! Path matching mirrors `scripts/get_maintainer.pl`:
+//! - `F:` patterns ending in `/` match everything below that directory
+//! - `*` and `?` do not cross directory boundaries
+//! - a literal pattern matches the exact file or, when it names a directory
+//!   without a trailing slash, anything below it
+//! - `N:` values are regular expressions matched anywhere in the path
+//! - `X:` patterns exclude files using the same rules as `F:`
+
+use crate::models::MaintainerContact;
+use regex::Regex;
+
+/// A single subsystem section from the MAINTAINERS file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaintainerEntry {
+    pub name: String,
+    pub status: Option<String>,
+    pub maintainers: Vec<MaintainerContact>,
+    pub reviewers: Vec<MaintainerContact>,
+    pub lists: Vec<String>,
+    pub trees: Vec<String>,
+    pub file_patterns: Vec<String>,
+    pub exclude_patterns: Vec<String>,
+    pub regex_patterns: Vec<String>,
+}
+
+impl MaintainerEntry {
+    fn has_fields(&self) -> bool {
+        self.status.is_some()
+            || !self.maintainers.is_empty()
+            || !self.reviewers.is_empty()
+            || !self.lists.is_empty()
+            || !self.trees.is_empty()
+            || !self.file_patterns.is_empty()
+            || !self.exclude_patterns.is_empty()
+            || !self.regex_patterns.is_empty()
+    }
+
+    /// Whether the entry only exists to catch otherwise unowned files
+    /// (`THE REST` uses `F: *` and `F: */`).
+    pub fn is_catch_all(&self) -> bool {
+        !self.file_patterns.is_empty()
+            && self
+                .file_patterns
+                .iter()
+                .all(|pattern| pattern == "*" || pattern == "*/")
+    }
+}
+
+/// Parse the contents of a MAINTAINERS file into subsystem entries.
+///
+/// Entries without any recognised field lines are dropped. Duplicate titles keep
+/// the first occurrence, matching how `get_maintainer.pl` reports them.
+pub fn parse_maintainers(contents: &str) -> Vec<MaintainerEntry> {
+    let mut entries: Vec<MaintainerEntry> = Vec::new();
+    let mut current: Option<MaintainerEntry> = None;
+
+    for line in contents.lines() {
+        let trimmed_end = line.trim_end();
+        if trimmed_end.is_empty() {
+            finish(current.take(), &mut entries);
+            continue;
+        }
+
+        if let Some((tag, value)) = split_field(trimmed_end) {
+            let Some(entry) = current.as_mut() else {
+                continue;
+            };
+            apply_field(entry, tag, value);
+            continue;
+        }
+
+        if line.starts_with(char::is_whitespace) {
+            continue;
+        }
+
+        match current.as_mut() {
+            Some(entry) if !entry.has_fields() => {
+                entry.name = trimmed_end.trim().to_string();
+            }
+            _ => {
+                finish(current.take(), &mut entries);
+                current = Some(MaintainerEntry {
+                    name: trimmed_end.trim().to_string(),
+                    ..Default::default()
+                });
+            }
+        }
+    }
+
+    finish(current.take(), &mut entries);
+    entries
+}
+
+fn finish(entry: Option<MaintainerEntry>, entries: &mut Vec<MaintainerEntry>) {
+    if let Some(entry) = entry
+        && entry.has_fields()
+        && !entries.iter().any(|existing| existing.name == entry.name)
+    {
+        entries.push(entry);
+    }
+}
+
+fn split_field(line: &str) -> Option<(char, &str)> {
+    let mut chars = line.chars();
+    let tag = chars.next()?;
+    if !tag.is_ascii_uppercase() || chars.next()? != ':' {
+        return None;
+    }
+    let value = line[2..].trim();
+    if value.is_empty() {
+        return None;
+    }
+    Some((tag, value))
+}
+
+fn apply_field(entry: &mut MaintainerEntry, tag: char, value: &str) {
+    match tag {
+        'M' => entry.maintainers.push(parse_contact(value)),
+        'R' => entry.reviewers.push(parse_contact(value)),
+        'L' => {
+            if let Some(list) = value.split_whitespace().next() {
+                entry.lists.push(list.to_string());
+            }
+        }
+        'S' => entry.status = Some(value.to_string()),
+        'T' => entry.trees.push(value.to_string()),
+        'F' => entry.file_patterns.push(value.to_string()),
+        'X' => entry.exclude_patterns.push(value.to_string()),
+        'N' => entry.regex_patterns.push(value.to_string()),
+        _ => {}
+    }
+}
+
+/// Parse `Full Name <address@domain>` (or a bare address) into a contact.
+fn parse_contact(value: &str) -> MaintainerContact {
+    if let (Some(start), Some(end)) = (value.find('<'), value.rfind('>'))
+        && start < end
+    {
+        let name = value[..start].trim().trim_matches('"').trim();
+        return MaintainerContact {
+            name: (!name.is_empty()).then(|| name.to_string()),
+            email: value[start + 1..end].trim().to_lowercase(),
+        };
+    }
+
+    MaintainerContact {
+        name: None,
+        email: value.trim().to_lowercase(),
+    }
+}
+
+/// Compiled `F:`/`X:` pattern.
+#[derive(Debug)]
+struct FilePattern {
+    raw: String,
+    regex: Option<Regex>,
+    directory: bool,
+    slashes: usize,
+}
+
+impl FilePattern {
+    fn new(raw: &str) -> Self {
+        let directory = raw.ends_with('/');
+        let has_wildcard = raw.contains('*') || raw.contains('?');
+        let regex = if has_wildcard {
+            let mut expr = String::from("^");
+            for ch in raw.chars() {
+                match ch {
+                    '*' => expr.push_str("[^/]*"),
+                    '?' => expr.push_str("[^/]"),
+                    other => expr.push_str(&regex::escape(&other.to_string())),
+                }
+            }
+            Regex::new(&expr).ok()
+        } else {
+            None
+        };
+
+        Self {
+            raw: raw.to_string(),
+            regex,
+            directory,
+            slashes: raw.matches('/').count(),
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        match &self.regex {
+            Some(regex) => {
+                if !regex.is_match(path) {
+                    return false;
+                }
+                self.directory || path.matches('/').count() == self.slashes
+            }
+            None if self.directory => path.starts_with(&self.raw),
+            None => {
+                path == self.raw
+                    || path
+                        .strip_prefix(self.raw.as_str())
+                        .is_some_and(|rest| rest.starts_with('/'))
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CompiledEntry {
+    files: Vec<FilePattern>,
+    excludes: Vec<FilePattern>,
+    regexes: Vec<Regex>,
+    catch_all: bool,
+}
+
+impl CompiledEntry {
+    fn new(entry: &MaintainerEntry) -> Self {
+        Self {
+            files: entry
+                .file_patterns
+                .iter()
+                .map(|p| FilePattern::new(p))
+                .collect(),
+            excludes: entry
+                .exclude_patterns
+                .iter()
+                .map(|p| FilePattern::new(p))
+                .collect(),
+            regexes: entry
+                .regex_patterns
+                .iter()
+                .filter_map(|p| Regex::new(p).ok())
+                .collect(),
+            catch_all: entry.is_catch_all(),
+        }
+    }
+
+    fn matches(&self, path: &str) -> bool {
+        if self.excludes.iter().any(|pattern| pattern.matches(path)) {
+            return false;
+        }
+        self.files.iter().any(|pattern| pattern.matches(path))
+            || self.regexes.iter().any(|regex| regex.is_match(path))
+    }
+}
+
+/// Parsed MAINTAINERS entries with precompiled path matchers.
+#[derive(Debug)]
+pub struct MaintainersIndex {
+    entries: Vec<MaintainerEntry>,
+    compiled: Vec<CompiledEntry>,
+}
+
+impl MaintainersIndex {
+    pub fn new(entries: Vec<MaintainerEntry>) -> Self {
+        let compiled = entries.iter().map(CompiledEntry::new).collect();
+        Self { entries, compiled }
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        Self::new(parse_maintainers(contents))
+    }
+
+    pub fn entries(&self) -> &[MaintainerEntry] {
+        &self.entries
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Subsystems responsible for any of the given paths.
+    ///
+    /// Catch-all entries are only returned for paths that no other subsystem
+    /// claims. Results keep MAINTAINERS file order and contain no duplicates.
+    pub fn subsystems_for_paths<S: AsRef<str>>(&self, paths: &[S]) -> Vec<&MaintainerEntry> {
+        let mut matched = vec![false; self.entries.len()];
+
+        for path in paths {
+            let path = path.as_ref();
+            let mut claimed = false;
+            for (idx, compiled) in self.compiled.iter().enumerate() {
+                if !compiled.catch_all && compiled.matches(path) {
+                    matched[idx] = true;
+                    claimed = true;
+                }
+            }
+            if !claimed {
+                for (idx, compiled) in self.compiled.iter().enumerate() {
+                    if compiled.catch_all && compiled.matches(path) {
+                        matched[idx] = true;
+                    }
+                }
+            }
+        }
+
+        self.entries
+            .iter()
+            .zip(matched)
+            .filter_map(|(entry, hit)| hit.then_some(entry))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE: &str = "\
+List of maintainers
+===================
+
+Descriptions of section entries and preferred order
+---------------------------------------------------
+
+\tM: *Mail* patch to: FullName <address@domain>
+\tF: *Files* and directories wildcard patterns.
+
+Maintainers List
+----------------
+
+BPF [CORE]
+M:\tAlexei Starovoitov <ast@kernel.org>
+R:\tJohn Fastabend <john.fastabend@gmail.com>
+L:\tbpf@vger.kernel.org
+S:\tMaintained
+T:\tgit git://git.kernel.org/pub/scm/linux/kernel/git/bpf/bpf.git
+F:\tkernel/bpf/
+X:\tkernel/bpf/btf.c
+N:\tbpf_test
+
+BPF [BTF]
+M:\tMartin KaFai Lau <MARTIN.LAU@linux.dev>
+L:\tbpf@vger.kernel.org (open list)
+S:\tSupported
+F:\tkernel/bpf/btf.c
+F:\tinclude/linux/btf*.h
+
+NETWORKING DRIVERS
+M:\tnetdev-maint@example.org
+L:\tnetdev@vger.kernel.org
+S:\tOdd Fixes
+F:\tdrivers/net/*
+F:\tDocumentation/networking
+
+THE REST
+M:\tLinus Torvalds <torvalds@linux-foundation.org>
+L:\tlinux-kernel@vger.kernel.org
+S:\tBuried alive in reporters
+F:\t*
+F:\t*/
+";
+
+    #[test]
+    fn parses_entries_and_fields() {
+        let entries = parse_maintainers(SAMPLE);
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["BPF [CORE]", "BPF [BTF]", "NETWORKING DRIVERS", "THE REST"]
+        );
+
+        let core = &entries[0];
+        assert_eq!(core.status.as_deref(), Some("Maintained"));
+        assert_eq!(
+            core.maintainers,
+            vec![MaintainerContact {
+                name: Some("Alexei Starovoitov".to_string()),
+                email: "ast@kernel.org".to_string(),
+            }]
+        );
+        assert_eq!(core.reviewers.len(), 1);
+        assert_eq!(core.lists, vec!["bpf@vger.kernel.org".to_string()]);
+        assert_eq!(core.trees.len(), 1);
+        assert_eq!(core.file_patterns, vec!["kernel/bpf/".to_string()]);
+        assert_eq!(core.exclude_patterns, vec!["kernel/bpf/btf.c".to_string()]);
+        assert_eq!(core.regex_patterns, vec!["bpf_test".to_string()]);
+
+        let btf = &entries[1];
+        assert_eq!(btf.maintainers[0].email, "martin.lau@linux.dev");
+        assert_eq!(btf.lists, vec!["bpf@vger.kernel.org".to_string()]);
+
+        let net = &entries[2];
+        assert_eq!(net.maintainers[0].name, None);
+        assert!(entries[3].is_catch_all());
+    }
+
+    #[test]
+    fn matches_paths_like_get_maintainer() {
+        let index = MaintainersIndex::parse(SAMPLE);
+        let names = |paths: &[&str]| -> Vec<String> {
+            index
+                .subsystems_for_paths(paths)
+                .into_iter()
+                .map(|e| e.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(&["kernel/bpf/verifier.c"]), vec!["BPF [CORE]"]);
+        assert_eq!(names(&["kernel/bpf/btf.c"]), vec!["BPF [BTF]"]);
+        assert_eq!(names(&["include/linux/btf_ids.h"]), vec!["BPF [BTF]"]);
+        assert_eq!(
+            names(&["tools/testing/selftests/bpf_test_run.c"]),
+            vec!["BPF [CORE]"]
+        );
+        assert_eq!(names(&["drivers/net/dummy.c"]), vec!["NETWORKING DRIVERS"]);
+        assert_eq!(names(&["drivers/net/ethernet/foo.c"]), vec!["THE REST"]);
+        assert_eq!(
+            names(&["Documentation/networking/ip-sysctl.rst"]),
+            vec!["NETWORKING DRIVERS"]
+        );
+        assert_eq!(
+            names(&["kernel/bpf/verifier.c", "mm/slab.c"]),
+            vec!["BPF [CORE]", "THE REST"]
+        );
+        assert!(names(&[] as &[&str]).is_empty());
+    }
+}
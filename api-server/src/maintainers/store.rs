@@ -0,0 +1,304 @@
+//! Persistence for MAINTAINERS subsystems and per-thread subsystem tags.
+
+use crate::maintainers::parser::{MaintainerEntry, MaintainersIndex};
+use crate::models::Subsystem;
+use crate::sync::parser::extract_touched_paths;
+use rocket_db_pools::sqlx::{self, PgPool, types::Json};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+
+const TAG_THREAD_BATCH_SIZE: i64 = 500;
+
+/// Replace the stored subsystems with the entries from a freshly parsed file.
+///
+/// Entries are upserted by name; subsystems that disappeared from the file are
+/// removed along with their thread tags.
+///
+/// # Returns
+/// Number of subsystems stored
+pub async fn sync_subsystems(
+    pool: &PgPool,
+    index: &MaintainersIndex,
+) -> Result<usize, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    for entry in index.entries() {
+        sqlx::query(
+            r#"INSERT INTO subsystems (
+                   name, status, maintainers, reviewers, lists, trees,
+                   file_patterns, exclude_patterns, regex_patterns, updated_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, NOW())
+               ON CONFLICT (name) DO UPDATE
+               SET status = EXCLUDED.status,
+                   maintainers = EXCLUDED.maintainers,
+                   reviewers = EXCLUDED.reviewers,
+                   lists = EXCLUDED.lists,
+                   trees = EXCLUDED.trees,
+                   file_patterns = EXCLUDED.file_patterns,
+                   exclude_patterns = EXCLUDED.exclude_patterns,
+                   regex_patterns = EXCLUDED.regex_patterns,
+                   updated_at = NOW()"#,
+        )
+        .bind(&entry.name)
+        .bind(&entry.status)
+        .bind(Json(&entry.maintainers))
+        .bind(Json(&entry.reviewers))
+        .bind(&entry.lists)
+        .bind(&entry.trees)
+        .bind(&entry.file_patterns)
+        .bind(&entry.exclude_patterns)
+        .bind(&entry.regex_patterns)
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    let names: Vec<&str> = index.entries().iter().map(|e| e.name.as_str()).collect();
+    sqlx::query("DELETE FROM subsystems WHERE name <> ALL($1)")
+        .bind(&names)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+    Ok(names.len())
+}
+
+/// Load every stored subsystem ordered by name.
+pub async fn load_subsystems(pool: &PgPool) -> Result<Vec<Subsystem>, sqlx::Error> {
+    sqlx::query_as::<_, Subsystem>(
+        r#"SELECT id, name, status, maintainers, reviewers, lists, trees,
+                  file_patterns, exclude_patterns, regex_patterns, updated_at
+           FROM subsystems
+           ORDER BY name"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Rebuild a matcher from stored subsystem rows.
+pub fn index_from_subsystems(subsystems: &[Subsystem]) -> MaintainersIndex {
+    MaintainersIndex::new(
+        subsystems
+            .iter()
+            .map(|s| MaintainerEntry {
+                name: s.name.clone(),
+                status: s.status.clone(),
+                maintainers: s.maintainers.clone(),
+                reviewers: s.reviewers.clone(),
+                lists: s.lists.clone(),
+                trees: s.trees.clone(),
+                file_patterns: s.file_patterns.clone(),
+                exclude_patterns: s.exclude_patterns.clone(),
+                regex_patterns: s.regex_patterns.clone(),
+            })
+            .collect(),
+    )
+}
+
+/// SHA-256 of a MAINTAINERS file, recorded per list once its threads are
+/// tagged against it.
+pub fn maintainers_hash(contents: &str) -> Vec<u8> {
+    Sha256::digest(contents.as_bytes()).to_vec()
+}
+
+/// Hash of the MAINTAINERS file the list's threads were last tagged against.
+pub async fn load_maintainers_hash(
+    pool: &PgPool,
+    mailing_list_id: i32,
+) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    sqlx::query_scalar("SELECT maintainers_hash FROM mailing_lists WHERE id = $1")
+        .bind(mailing_list_id)
+        .fetch_optional(pool)
+        .await
+        .map(Option::flatten)
+}
+
+/// Record that every thread of the list was tagged against a MAINTAINERS file.
+pub async fn record_maintainers_hash(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    hash: &[u8],
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE mailing_lists SET maintainers_hash = $2 WHERE id = $1")
+        .bind(mailing_list_id)
+        .bind(hash)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Recompute subsystem tags for every thread in a mailing list.
+///
+/// Used when the MAINTAINERS file changed; imports otherwise only tag the
+/// threads they touched with [`tag_threads`].
+///
+/// # Returns
+/// Threads whose subsystem tags changed
+pub async fn tag_threads_for_list(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    index: &MaintainersIndex,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let subsystem_ids = load_subsystem_ids(pool).await?;
+
+    let mut last_id: i32 = 0;
+    let mut changed_threads = Vec::new();
+
+    loop {
+        let thread_ids: Vec<i32> = sqlx::query_scalar(
+            r#"SELECT id FROM threads
+               WHERE mailing_list_id = $1 AND id > $2
+               ORDER BY id
+               LIMIT $3"#,
+        )
+        .bind(mailing_list_id)
+        .bind(last_id)
+        .bind(TAG_THREAD_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        let Some(&batch_last) = thread_ids.last() else {
+            break;
+        };
+
+        changed_threads.extend(
+            tag_thread_batch(pool, mailing_list_id, index, &subsystem_ids, &thread_ids).await?,
+        );
+        last_id = batch_last;
+    }
+
+    Ok(changed_threads)
+}
+
+/// Recompute subsystem tags for the given threads of a mailing list.
+///
+/// # Returns
+/// Threads whose subsystem tags changed
+pub async fn tag_threads(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    index: &MaintainersIndex,
+    thread_ids: &[i32],
+) -> Result<Vec<i32>, sqlx::Error> {
+    if thread_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let subsystem_ids = load_subsystem_ids(pool).await?;
+    let mut changed_threads = Vec::new();
+    for batch in thread_ids.chunks(TAG_THREAD_BATCH_SIZE as usize) {
+        changed_threads
+            .extend(tag_thread_batch(pool, mailing_list_id, index, &subsystem_ids, batch).await?);
+    }
+
+    Ok(changed_threads)
+}
+
+async fn load_subsystem_ids(pool: &PgPool) -> Result<HashMap<String, i32>, sqlx::Error> {
+    let id_rows: Vec<(i32, String)> = sqlx::query_as("SELECT id, name FROM subsystems")
+        .fetch_all(pool)
+        .await?;
+    Ok(id_rows.into_iter().map(|(id, name)| (name, id)).collect())
+}
+
+/// Replace the tags of one batch of threads.
+///
+/// Touched paths are extracted from the inline diffs of each patch email in the
+/// thread and matched against the index. Existing tags of the batch are
+/// replaced atomically.
+///
+/// # Returns
+/// Threads of the batch whose tags changed
+async fn tag_thread_batch(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    index: &MaintainersIndex,
+    subsystem_ids: &HashMap<String, i32>,
+    thread_ids: &[i32],
+) -> Result<Vec<i32>, sqlx::Error> {
+    let bodies: Vec<(i32, Option<String>)> = sqlx::query_as(
+        r#"SELECT tm.thread_id, e.body
+           FROM thread_memberships tm
+           JOIN emails e ON e.id = tm.email_id AND e.mailing_list_id = tm.mailing_list_id
+           WHERE tm.mailing_list_id = $1
+             AND tm.thread_id = ANY($2)
+             AND e.patch_type <> 'none'"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut paths_by_thread: HashMap<i32, Vec<String>> = HashMap::new();
+    for (thread_id, body) in bodies {
+        let Some(body) = body else { continue };
+        let paths = paths_by_thread.entry(thread_id).or_default();
+        for path in extract_touched_paths(&body) {
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+        }
+    }
+
+    let mut tags_by_thread: HashMap<i32, BTreeSet<i32>> = HashMap::new();
+    let mut tag_thread_ids = Vec::new();
+    let mut tag_subsystem_ids = Vec::new();
+    for (thread_id, paths) in &paths_by_thread {
+        let matched: BTreeSet<i32> = index
+            .subsystems_for_paths(paths)
+            .into_iter()
+            .filter_map(|entry| subsystem_ids.get(&entry.name).copied())
+            .collect();
+        for &subsystem_id in &matched {
+            tag_thread_ids.push(*thread_id);
+            tag_subsystem_ids.push(subsystem_id);
+        }
+        if !matched.is_empty() {
+            tags_by_thread.insert(*thread_id, matched);
+        }
+    }
+
+    let mut tx = pool.begin().await?;
+    let previous: Vec<(i32, i32)> = sqlx::query_as(
+        r#"SELECT thread_id, subsystem_id FROM thread_subsystems
+           WHERE mailing_list_id = $1 AND thread_id = ANY($2)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+    let mut previous_by_thread: HashMap<i32, BTreeSet<i32>> = HashMap::new();
+    for (thread_id, subsystem_id) in previous {
+        previous_by_thread
+            .entry(thread_id)
+            .or_default()
+            .insert(subsystem_id);
+    }
+
+    sqlx::query("DELETE FROM thread_subsystems WHERE mailing_list_id = $1 AND thread_id = ANY($2)")
+        .bind(mailing_list_id)
+        .bind(thread_ids)
+        .execute(&mut *tx)
+        .await?;
+
+    if !tag_thread_ids.is_empty() {
+        sqlx::query(
+            r#"INSERT INTO thread_subsystems (mailing_list_id, thread_id, subsystem_id)
+               SELECT $1, thread_id, subsystem_id
+               FROM UNNEST($2::int[], $3::int[]) AS t(thread_id, subsystem_id)
+               ON CONFLICT DO NOTHING"#,
+        )
+        .bind(mailing_list_id)
+        .bind(&tag_thread_ids)
+        .bind(&tag_subsystem_ids)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(thread_ids
+        .iter()
+        .copied()
+        .filter(|thread_id| tags_by_thread.get(thread_id) != previous_by_thread.get(thread_id))
+        .collect())
+}
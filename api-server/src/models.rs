@@ -55,6 +55,136 @@ pub struct PatchMetadata {
     pub trailer_count: usize,
 }
 
+/// Structured diff parsed from a patch email.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchDiff {
+    /// Files touched by the patch, in diff order.
+    pub files: Vec<DiffFile>,
+    /// Totals across all files.
+    pub diffstat: DiffStat,
+}
+
+/// Per-patch change totals, as printed by `git diff --stat`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffStat {
+    pub files_changed: usize,
+    pub insertions: usize,
+    pub deletions: usize,
+}
+
+/// Kind of change made to a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffFileStatus {
+    Added,
+    Deleted,
+    Modified,
+    Renamed,
+    Copied,
+}
+
+/// One file section of a diff.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffFile {
+    /// Path before the change (`None` for added files).
+    pub old_path: Option<String>,
+    /// Path after the change (`None` for deleted files).
+    pub new_path: Option<String>,
+    pub status: DiffFileStatus,
+    /// File mode before the change (e.g. `100644`), when the diff states it.
+    pub old_mode: Option<String>,
+    /// File mode after the change, when the diff states it.
+    pub new_mode: Option<String>,
+    /// Similarity percentage of renames and copies.
+    pub similarity: Option<u8>,
+    /// Whether the change is binary (no textual hunks).
+    pub binary: bool,
+    pub additions: usize,
+    pub deletions: usize,
+    pub hunks: Vec<DiffHunk>,
+}
+
+/// A `@@ -a,b +c,d @@` hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffHunk {
+    /// Full hunk header line.
+    pub header: String,
+    pub old_start: u32,
+    pub old_lines: u32,
+    pub new_start: u32,
+    pub new_lines: u32,
+    /// Function or section context after the second `@@`, if any.
+    pub section: Option<String>,
+    pub lines: Vec<DiffLine>,
+}
+
+/// Kind of a line inside a hunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffLineKind {
+    Context,
+    Addition,
+    Deletion,
+    /// `\ No newline at end of file` marker.
+    NoNewline,
+}
+
+/// One line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub kind: DiffLineKind,
+    /// Line content without the leading `+`/`-`/space marker.
+    pub content: String,
+    /// Line number in the old file (context and deletions).
+    pub old_line: Option<u32>,
+    /// Line number in the new file (context and additions).
+    pub new_line: Option<u32>,
+    /// 0-based line index within the email body.
+    pub body_line: usize,
+}
+
+/// Side of a diff a line belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffSide {
+    /// Pre-image (deleted lines).
+    Old,
+    /// Post-image (added and context lines).
+    New,
+}
+
+/// Inline review comment from a reply, anchored to a line of the patch diff.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewComment {
+    /// Patch email the comment refers to.
+    pub patch_email_id: i32,
+    /// Reply email containing the comment.
+    pub reply_email_id: i32,
+    /// Reviewer name, if known.
+    pub author_name: Option<String>,
+    /// Reviewer email address.
+    pub author_email: String,
+    /// Reply timestamp.
+    pub date: DateTime<Utc>,
+    /// File the comment is anchored to.
+    pub path: String,
+    pub side: DiffSide,
+    /// Line number on `side`.
+    pub line: u32,
+    /// Header of the hunk containing the line.
+    pub hunk_header: String,
+    /// Quoted diff lines preceding the comment, ending with the anchor line.
+    pub quoted: Vec<String>,
+    /// Comment text.
+    pub comment: String,
+}
+
 /// Metadata for a mailing list managed by the service.
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
 pub struct MailingList {
@@ -122,6 +252,34 @@ pub struct Thread {
     pub message_count: Option<i32>,
 }
 
+/// Kind of a reply body segment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BodySegmentKind {
+    /// Text written by the sender.
+    Text,
+    /// Text quoted from an earlier message.
+    Quote,
+    /// Signature below the `-- ` delimiter.
+    Signature,
+}
+
+/// Contiguous part of an email body, so clients can collapse quotes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BodySegment {
+    pub kind: BodySegmentKind,
+    /// Attribution line(s) introducing a quote, e.g. `On Mon, Jane wrote:`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attribution: Option<String>,
+    /// First line (0-based index) of the segment, including any attribution.
+    pub start_line: usize,
+    /// Last line (0-based index) of the segment.
+    pub end_line: usize,
+    /// Segment text, without the attribution.
+    pub content: String,
+}
+
 /// Email row enriched with author metadata for API responses.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct EmailWithAuthor {
@@ -156,6 +314,12 @@ pub struct EmailWithAuthor {
     /// Inline patch metadata (diff sections, trailers, diffstat).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub patch_metadata: Option<PatchMetadata>,
+    /// Stable `git patch-id` of the diff, for patch emails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch_id: Option<String>,
+    /// Body split into new text, quotes and signature (single email and thread views).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<BodySegment>,
 }
 
 /// Thread details including the threaded list of emails.
@@ -165,6 +329,8 @@ pub struct ThreadDetail {
     pub thread: Thread,
     /// Emails that belong to the thread ordered depth-first.
     pub emails: Vec<EmailHierarchy>,
+    /// Patch series revisions with patches in the thread.
+    pub series: Vec<SeriesRevision>,
 }
 
 /// Email node enriched with depth information for thread rendering.
@@ -203,6 +369,12 @@ pub struct EmailHierarchy {
     /// Inline patch metadata (diff sections, trailers, diffstat).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub patch_metadata: Option<PatchMetadata>,
+    /// Stable `git patch-id` of the diff, for patch emails.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub patch_id: Option<String>,
+    /// Body split into new text, quotes and signature (single email and thread views).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<BodySegment>,
 }
 
 /// Aggregated author statistics used in list and detail endpoints.
@@ -251,6 +423,54 @@ impl<'r> FromRow<'r, PgRow> for EmailWithAuthor {
             patch_type: row.try_get("patch_type")?,
             is_patch_only: row.try_get("is_patch_only")?,
             patch_metadata: patch_metadata.map(|json| json.0),
+            patch_id: row.try_get("patch_id")?,
+            segments: Vec::new(),
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for Subsystem {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let maintainers: Json<Vec<MaintainerContact>> = row.try_get("maintainers")?;
+        let reviewers: Json<Vec<MaintainerContact>> = row.try_get("reviewers")?;
+        Ok(Self {
+            id: row.try_get("id")?,
+            name: row.try_get("name")?,
+            status: row.try_get("status")?,
+            maintainers: maintainers.0,
+            reviewers: reviewers.0,
+            lists: row.try_get("lists")?,
+            trees: row.try_get("trees")?,
+            file_patterns: row.try_get("file_patterns")?,
+            exclude_patterns: row.try_get("exclude_patterns")?,
+            regex_patterns: row.try_get("regex_patterns")?,
+            updated_at: row.try_get("updated_at")?,
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for FixedCommit {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let resolution: Option<String> = row.try_get("resolution")?;
+        Ok(Self {
+            commit: row.try_get("commit")?,
+            subject: row.try_get("subject")?,
+            resolution: resolution.as_deref().and_then(FixResolution::parse),
+            introduced_by: LinkedPatch::from_row_opt(row)?,
+        })
+    }
+}
+
+impl<'r> FromRow<'r, PgRow> for FixingPatch {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let resolution: String = row.try_get("resolution")?;
+        Ok(Self {
+            commit: row.try_get("commit")?,
+            resolution: FixResolution::parse(&resolution).ok_or_else(|| {
+                sqlx::Error::Decode(format!("unknown fix resolution '{resolution}'").into())
+            })?,
+            patch: LinkedPatch::from_row_opt(row)?
+                .ok_or_else(|| sqlx::Error::Decode("fixing patch without email".into()))?,
         })
     }
 }
@@ -275,6 +495,8 @@ impl<'r> FromRow<'r, PgRow> for EmailHierarchy {
             patch_type: row.try_get("patch_type")?,
             is_patch_only: row.try_get("is_patch_only")?,
             patch_metadata: patch_metadata.map(|json| json.0),
+            patch_id: row.try_get("patch_id")?,
+            segments: Vec::new(),
         })
     }
 }
@@ -335,6 +557,769 @@ pub struct ThreadWithStarter {
     pub starter_name: Option<String>,
     /// Email of the thread starter.
     pub starter_email: String,
+    /// MAINTAINERS subsystems touched by patches in the thread.
+    #[sqlx(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subsystems: Vec<String>,
+    /// Distinct review states of the patches in the thread.
+    #[sqlx(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub patch_states: Vec<PatchState>,
+}
+
+/// Person listed in a MAINTAINERS entry (`M:` or `R:` line).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct MaintainerContact {
+    /// Display name, if the entry provides one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Email address.
+    pub email: String,
+}
+
+/// Subsystem parsed from the MAINTAINERS file.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Subsystem {
+    /// Database identifier.
+    pub id: i32,
+    /// Subsystem title (e.g. `BPF [CORE]`).
+    pub name: String,
+    /// Support status (`S:` line), if present.
+    pub status: Option<String>,
+    /// Maintainers (`M:` lines).
+    pub maintainers: Vec<MaintainerContact>,
+    /// Designated reviewers (`R:` lines).
+    pub reviewers: Vec<MaintainerContact>,
+    /// Mailing lists patches should be sent to (`L:` lines).
+    pub lists: Vec<String>,
+    /// Source trees (`T:` lines).
+    pub trees: Vec<String>,
+    /// File patterns owned by the subsystem (`F:` lines).
+    pub file_patterns: Vec<String>,
+    /// File patterns excluded from the subsystem (`X:` lines).
+    pub exclude_patterns: Vec<String>,
+    /// Regex patterns matched against file paths (`N:` lines).
+    pub regex_patterns: Vec<String>,
+    /// When the entry was last refreshed from the MAINTAINERS file.
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+/// Maintainers and reviewers responsible for the files touched by a patch.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchMaintainers {
+    /// Email identifier.
+    pub email_id: i32,
+    /// Paths touched by the diff, in order of appearance.
+    pub touched_paths: Vec<String>,
+    /// Matching subsystems ordered by name.
+    pub subsystems: Vec<Subsystem>,
+}
+
+/// Review state of a patch email, modelled after Patchwork.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "patch_state", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PatchState {
+    /// Posted and awaiting review.
+    New,
+    /// Picked up by a reviewer or maintainer.
+    UnderReview,
+    /// Review asked for a respin.
+    ChangesRequested,
+    /// Applied by a maintainer.
+    Accepted,
+    /// Will not be applied.
+    Rejected,
+    /// Replaced by a newer version of the patch or series.
+    Superseded,
+    /// Posted as a Request For Comments rather than for merging.
+    Rfc,
+}
+
+impl PatchState {
+    /// All states in workflow order.
+    pub const ALL: [PatchState; 7] = [
+        PatchState::New,
+        PatchState::UnderReview,
+        PatchState::ChangesRequested,
+        PatchState::Accepted,
+        PatchState::Rejected,
+        PatchState::Superseded,
+        PatchState::Rfc,
+    ];
+
+    /// Wire/database representation of the state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PatchState::New => "new",
+            PatchState::UnderReview => "under_review",
+            PatchState::ChangesRequested => "changes_requested",
+            PatchState::Accepted => "accepted",
+            PatchState::Rejected => "rejected",
+            PatchState::Superseded => "superseded",
+            PatchState::Rfc => "rfc",
+        }
+    }
+
+    /// Parse the wire representation (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|state| state.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Whether the patch is still awaiting a final decision.
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self,
+            PatchState::New
+                | PatchState::UnderReview
+                | PatchState::ChangesRequested
+                | PatchState::Rfc
+        )
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for PatchState {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_patch_state")
+    }
+}
+
+/// Current review state of a patch email.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchStateRecord {
+    /// Email identifier.
+    pub email_id: i32,
+    /// Current state.
+    pub state: PatchState,
+    /// User who last changed the state (`None` for automatic changes).
+    pub updated_by: Option<i32>,
+    /// Display name or email of the user who last changed the state.
+    pub updated_by_name: Option<String>,
+    /// When the state last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Single entry in a patch's state history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchStateChange {
+    /// History entry identifier.
+    pub id: i64,
+    /// Email identifier.
+    pub email_id: i32,
+    /// State before the change (`None` for the initial state).
+    pub previous_state: Option<PatchState>,
+    /// State after the change.
+    pub state: PatchState,
+    /// User who made the change (`None` for automatic changes).
+    pub changed_by: Option<i32>,
+    /// Display name or email of the user who made the change.
+    pub changed_by_name: Option<String>,
+    /// Optional free-form comment attached to the change.
+    pub comment: Option<String>,
+    /// When the change was made.
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Email carrying a given patch-id, possibly on another mailing list.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchIdMatch {
+    /// Slug of the mailing list the email was posted to.
+    pub mailing_list_slug: String,
+    /// Email identifier within that list.
+    pub email_id: i32,
+    /// Message-ID header value.
+    pub message_id: String,
+    /// Subject line.
+    pub subject: String,
+    /// Canonical author name, if known.
+    pub author_name: Option<String>,
+    /// Author email address.
+    pub author_email: String,
+    /// Email timestamp.
+    pub date: DateTime<Utc>,
+    /// Thread containing the email, if threaded yet.
+    pub thread_id: Option<i32>,
+    /// Current review state of the patch, if tracked.
+    pub patch_state: Option<PatchState>,
+}
+
+/// How a `Fixes:` trailer was linked to an archived patch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum FixResolution {
+    /// The trailer's hash prefixes an upstream commit matched to the patch.
+    UpstreamCommit,
+    /// The trailer's quoted subject matches the patch subject.
+    Subject,
+}
+
+impl FixResolution {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "upstream_commit" => Some(Self::UpstreamCommit),
+            "subject" => Some(Self::Subject),
+            _ => None,
+        }
+    }
+}
+
+/// Archived patch email linked through the `Fixes:` graph.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkedPatch {
+    /// Slug of the mailing list the email was posted to.
+    pub mailing_list_slug: String,
+    /// Email identifier within that list.
+    pub email_id: i32,
+    /// Subject line.
+    pub subject: String,
+    /// Canonical author name, if known.
+    pub author_name: Option<String>,
+    /// Author email address.
+    pub author_email: String,
+    /// Email timestamp.
+    pub date: DateTime<Utc>,
+    /// Thread containing the email, if threaded yet.
+    pub thread_id: Option<i32>,
+}
+
+impl LinkedPatch {
+    fn from_row_opt(row: &PgRow) -> Result<Option<Self>, sqlx::Error> {
+        let Some(email_id) = row.try_get::<Option<i32>, _>("email_id")? else {
+            return Ok(None);
+        };
+        Ok(Some(Self {
+            mailing_list_slug: row.try_get("mailing_list_slug")?,
+            email_id,
+            subject: row.try_get("email_subject")?,
+            author_name: row.try_get("author_name")?,
+            author_email: row.try_get("author_email")?,
+            date: row.try_get("date")?,
+            thread_id: row.try_get("thread_id")?,
+        }))
+    }
+}
+
+/// Commit named by a patch's `Fixes:` trailer.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FixedCommit {
+    /// Commit hash as written in the trailer (usually abbreviated).
+    pub commit: String,
+    /// Commit subject quoted in the trailer.
+    pub subject: Option<String>,
+    /// How the introducing patch was found, if it was.
+    pub resolution: Option<FixResolution>,
+    /// Archived patch that introduced the commit, if it could be resolved.
+    pub introduced_by: Option<LinkedPatch>,
+}
+
+/// Patch whose `Fixes:` trailer points at another patch.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FixingPatch {
+    /// Commit hash as written in the trailer.
+    pub commit: String,
+    /// How the trailer was linked to the fixed patch.
+    pub resolution: FixResolution,
+    /// The fixing patch email.
+    pub patch: LinkedPatch,
+}
+
+/// How an upstream commit was matched to an archived patch email.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "upstream_match_method", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum UpstreamMatchMethod {
+    /// The commit's `Link:` trailer points at the email's Message-ID.
+    Link,
+    /// The commit diff and the email diff share a `git patch-id`.
+    PatchId,
+    /// Same subject line and author email.
+    SubjectAuthor,
+}
+
+/// Upstream commit that applied a patch email.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamApplication {
+    /// Name of the upstream tree (e.g. `mainline`, `net-next`).
+    pub tree: String,
+    /// Commit hash in the upstream tree.
+    pub commit_hash: String,
+    /// Commit subject line.
+    pub commit_subject: String,
+    /// Committer timestamp.
+    pub committed_at: DateTime<Utc>,
+    /// First release tag containing the commit, if it has been released.
+    pub release_tag: Option<String>,
+    /// How the commit was matched to the email.
+    pub match_method: UpstreamMatchMethod,
+    /// When the match was recorded.
+    pub matched_at: DateTime<Utc>,
+}
+
+/// Merge status of a `[GIT PULL]` request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "pull_request_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum PullRequestStatus {
+    /// Not seen in any scanned upstream tree yet.
+    Open,
+    /// The pulled commit is contained in a scanned upstream tree.
+    Merged,
+}
+
+impl PullRequestStatus {
+    /// Wire/database representation of the status.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PullRequestStatus::Open => "open",
+            PullRequestStatus::Merged => "merged",
+        }
+    }
+
+    /// Parse the wire representation (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        [PullRequestStatus::Open, PullRequestStatus::Merged]
+            .into_iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+/// Commits of one author in a pull request's shortlog.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PullShortlogEntry {
+    pub author: String,
+    /// Commit subjects in shortlog order.
+    pub subjects: Vec<String>,
+}
+
+/// Pull request email with the fields extracted from its `git request-pull` text.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PullRequest {
+    /// Email identifier.
+    pub email_id: i32,
+    /// Thread containing the email, if threaded yet.
+    pub thread_id: Option<i32>,
+    pub subject: String,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    pub date: DateTime<Utc>,
+    /// Repository to pull from.
+    pub repository_url: Option<String>,
+    /// Tag or branch to pull, as written (e.g. `tags/net-6.9-rc1`).
+    pub reference: Option<String>,
+    /// Commit the changes are based on.
+    pub base_commit: Option<String>,
+    /// Last commit of the pulled changes.
+    pub head_commit: Option<String>,
+    pub shortlog: Vec<PullShortlogEntry>,
+    pub status: PullRequestStatus,
+    /// Upstream tree the pulled commit was found in.
+    pub merged_tree: Option<String>,
+    /// When the pulled commit was first seen upstream.
+    pub merged_detected_at: Option<DateTime<Utc>>,
+}
+
+impl<'r> FromRow<'r, PgRow> for PullRequest {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let shortlog: Json<Vec<PullShortlogEntry>> = row.try_get("shortlog")?;
+        Ok(Self {
+            email_id: row.try_get("email_id")?,
+            thread_id: row.try_get("thread_id")?,
+            subject: row.try_get("subject")?,
+            author_name: row.try_get("author_name")?,
+            author_email: row.try_get("author_email")?,
+            date: row.try_get("date")?,
+            repository_url: row.try_get("repository_url")?,
+            reference: row.try_get("reference")?,
+            base_commit: row.try_get("base_commit")?,
+            head_commit: row.try_get("head_commit")?,
+            shortlog: shortlog.0,
+            status: row.try_get("status")?,
+            merged_tree: row.try_get("merged_tree")?,
+            merged_detected_at: row.try_get("merged_detected_at")?,
+        })
+    }
+}
+
+/// Automated sender of a bot report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "bot_name", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BotName {
+    /// syzkaller's continuous fuzzing bot.
+    Syzbot,
+    /// Intel's 0-day CI / LKP (kernel test robot).
+    KernelTestRobot,
+}
+
+/// What a bot report is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "bot_report_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum BotReportKind {
+    /// Kernel crash found by fuzzing.
+    Crash,
+    /// Build errors or warnings.
+    BuildIssue,
+    /// Performance or functional regression found by a test suite.
+    Regression,
+    /// Result of testing a proposed fix.
+    TestResult,
+}
+
+impl BotName {
+    /// Wire/database representation of the bot.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotName::Syzbot => "syzbot",
+            BotName::KernelTestRobot => "kernel_test_robot",
+        }
+    }
+
+    /// Parse the wire representation (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        [BotName::Syzbot, BotName::KernelTestRobot]
+            .into_iter()
+            .find(|bot| bot.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+impl BotReportKind {
+    /// Wire/database representation of the kind.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BotReportKind::Crash => "crash",
+            BotReportKind::BuildIssue => "build_issue",
+            BotReportKind::Regression => "regression",
+            BotReportKind::TestResult => "test_result",
+        }
+    }
+
+    /// Parse the wire representation (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        [
+            BotReportKind::Crash,
+            BotReportKind::BuildIssue,
+            BotReportKind::Regression,
+            BotReportKind::TestResult,
+        ]
+        .into_iter()
+        .find(|kind| kind.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for BotName {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_bot_name")
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for BotReportKind {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_bot_report_kind")
+    }
+}
+
+/// Structured report parsed from a bot email.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BotReport {
+    /// Email identifier.
+    pub email_id: i32,
+    /// Thread containing the email, if threaded yet.
+    pub thread_id: Option<i32>,
+    pub bot: BotName,
+    pub kind: BotReportKind,
+    pub date: DateTime<Utc>,
+    /// Bug title without bot and subsystem tags.
+    pub title: String,
+    /// Crash, error or regression line identifying the bug.
+    pub crash_signature: Option<String>,
+    /// Kernel config (name, or URL for syzbot).
+    pub config: Option<String>,
+    pub arch: Option<String>,
+    /// Commit the bot tested.
+    pub tested_commit: Option<String>,
+    /// Patch email the report is about, if archived.
+    pub patch: Option<LinkedPatch>,
+}
+
+impl<'r> FromRow<'r, PgRow> for BotReport {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let patch = match row.try_get::<Option<i32>, _>("patch_email_id")? {
+            Some(email_id) => Some(LinkedPatch {
+                mailing_list_slug: row.try_get("patch_mailing_list_slug")?,
+                email_id,
+                subject: row.try_get("patch_subject")?,
+                author_name: row.try_get("patch_author_name")?,
+                author_email: row.try_get("patch_author_email")?,
+                date: row.try_get("patch_date")?,
+                thread_id: row.try_get("patch_thread_id")?,
+            }),
+            None => None,
+        };
+        Ok(Self {
+            email_id: row.try_get("email_id")?,
+            thread_id: row.try_get("thread_id")?,
+            bot: row.try_get("bot")?,
+            kind: row.try_get("kind")?,
+            date: row.try_get("date")?,
+            title: row.try_get("title")?,
+            crash_signature: row.try_get("crash_signature")?,
+            config: row.try_get("config")?,
+            arch: row.try_get("arch")?,
+            tested_commit: row.try_get("tested_commit")?,
+            patch,
+        })
+    }
+}
+
+/// Patch of a series revision and the thread it landed in.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesPatch {
+    /// Index within the series (`0` for the cover letter).
+    pub number: i32,
+    pub email_id: i32,
+    pub subject: String,
+    pub date: DateTime<Utc>,
+    /// Thread containing the patch, if threaded yet.
+    pub thread_id: Option<i32>,
+    /// Whether the patch landed outside the series' main thread.
+    pub misthreaded: bool,
+}
+
+/// One posted revision of a patch series and which of its patches arrived.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SeriesRevision {
+    pub id: i32,
+    pub author_id: i32,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    /// Revision tag from the subject (e.g. `v2`); `None` for the first posting.
+    pub version: Option<String>,
+    /// Number of patches announced by the `n/total` prefix.
+    pub total: i32,
+    /// Main thread of the series: the cover letter's, or the one holding most patches.
+    pub thread_id: Option<i32>,
+    pub has_cover_letter: bool,
+    /// Whether every index from 1 to `total` arrived exactly once.
+    pub complete: bool,
+    /// Indices that arrived, ascending.
+    pub present: Vec<i32>,
+    /// Indices that never arrived.
+    pub missing: Vec<i32>,
+    /// Indices that arrived more than once.
+    pub duplicated: Vec<i32>,
+    /// Patches ordered by index, then date.
+    pub patches: Vec<SeriesPatch>,
+}
+
+/// Stable backport email and the upstream commit it carries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StableBackport {
+    /// Slug of the mailing list the backport was posted to.
+    pub mailing_list_slug: String,
+    pub email_id: i32,
+    /// Thread containing the email, if threaded yet.
+    pub thread_id: Option<i32>,
+    pub subject: String,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    pub date: DateTime<Utc>,
+    /// Upstream commit hash as written in the email.
+    pub upstream_commit: String,
+    /// Stable branch the backport targets (e.g. `6.6`), if named.
+    pub stable_branch: Option<String>,
+    /// Original mainline patch email, when archived.
+    pub mainline_patch: Option<LinkedPatch>,
+}
+
+impl<'r> FromRow<'r, PgRow> for StableBackport {
+    fn from_row(row: &'r PgRow) -> Result<Self, sqlx::Error> {
+        let mainline_patch = match row.try_get::<Option<i32>, _>("mainline_email_id")? {
+            Some(email_id) => Some(LinkedPatch {
+                mailing_list_slug: row.try_get("mainline_mailing_list_slug")?,
+                email_id,
+                subject: row.try_get("mainline_subject")?,
+                author_name: row.try_get("mainline_author_name")?,
+                author_email: row.try_get("mainline_author_email")?,
+                date: row.try_get("mainline_date")?,
+                thread_id: row.try_get("mainline_thread_id")?,
+            }),
+            None => None,
+        };
+        Ok(Self {
+            mailing_list_slug: row.try_get("mailing_list_slug")?,
+            email_id: row.try_get("email_id")?,
+            thread_id: row.try_get("thread_id")?,
+            subject: row.try_get("subject")?,
+            author_name: row.try_get("author_name")?,
+            author_email: row.try_get("author_email")?,
+            date: row.try_get("date")?,
+            upstream_commit: row.try_get("upstream_commit")?,
+            stable_branch: row.try_get("stable_branch")?,
+            mainline_patch,
+        })
+    }
+}
+
+/// Stable branches that received an upstream change, and through which emails.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct StableBackports {
+    /// Upstream commits the backports were matched against.
+    pub upstream_commits: Vec<String>,
+    /// Distinct stable branches, oldest first.
+    pub branches: Vec<String>,
+    /// Backport emails, oldest first.
+    pub backports: Vec<StableBackport>,
+}
+
+/// Regression tracking command from a `#regzbot` line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "regzbot_command_kind", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RegzbotCommandKind {
+    /// Starts tracking; names the commit or range that introduced the regression.
+    Introduced,
+    /// Sets the regression's title.
+    Title,
+    /// Names the commit fixing the regression.
+    Fix,
+    /// Links a related discussion.
+    Link,
+    /// Adds a thread to monitor, usually a proposed fix.
+    Monitor,
+    /// Marks the regression resolved without a fix commit.
+    Resolve,
+    /// Marks the report as not being a regression.
+    Invalid,
+}
+
+impl sqlx::postgres::PgHasArrayType for RegzbotCommandKind {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_regzbot_command_kind")
+    }
+}
+
+/// Tracking state of a regression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Type)]
+#[sqlx(type_name = "regression_state", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum RegressionState {
+    Open,
+    /// A fix commit was named.
+    Fixed,
+    Resolved,
+    Invalid,
+}
+
+impl RegressionState {
+    pub const ALL: [RegressionState; 4] = [
+        RegressionState::Open,
+        RegressionState::Fixed,
+        RegressionState::Resolved,
+        RegressionState::Invalid,
+    ];
+
+    /// Wire/database representation of the state.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RegressionState::Open => "open",
+            RegressionState::Fixed => "fixed",
+            RegressionState::Resolved => "resolved",
+            RegressionState::Invalid => "invalid",
+        }
+    }
+
+    /// Parse the wire representation (case-insensitive).
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|state| state.as_str().eq_ignore_ascii_case(value.trim()))
+    }
+}
+
+impl sqlx::postgres::PgHasArrayType for RegressionState {
+    fn array_type_info() -> PgTypeInfo {
+        PgTypeInfo::with_name("_regression_state")
+    }
+}
+
+/// Regression tracked through `#regzbot` commands in its report thread.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Regression {
+    /// Report thread identifier.
+    pub thread_id: i32,
+    /// `#regzbot title:`, or the thread subject when none was given.
+    pub title: String,
+    pub state: RegressionState,
+    /// Commit or range named by `#regzbot introduced:`.
+    pub introduced: String,
+    /// Commit named by `#regzbot fix:`.
+    pub fix: Option<String>,
+    /// Threads linked with `#regzbot link:` or `#regzbot monitor:`.
+    pub related_threads: Vec<String>,
+    /// Email carrying the `introduced` command.
+    pub report_email_id: i32,
+    pub reported_at: DateTime<Utc>,
+    /// Date of the latest command.
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One `#regzbot` command in a regression's history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegzbotCommandEntry {
+    pub email_id: i32,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    pub date: DateTime<Utc>,
+    pub command: RegzbotCommandKind,
+    pub argument: String,
+}
+
+/// Single entry in a regression's state history.
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressionStateChange {
+    /// History entry identifier.
+    pub id: i64,
+    /// State before the change (`None` when tracking started).
+    pub previous_state: Option<RegressionState>,
+    /// State after the change.
+    pub state: RegressionState,
+    /// Email carrying the command that caused the change.
+    pub email_id: Option<i32>,
+    /// Date of that email.
+    pub changed_at: DateTime<Utc>,
+}
+
+/// Regression together with the commands that shaped it and its state
+/// changes, oldest first.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RegressionDetail {
+    #[serde(flatten)]
+    pub regression: Regression,
+    pub history: Vec<RegzbotCommandEntry>,
+    pub state_history: Vec<RegressionStateChange>,
 }
 
 /// Lightweight thread summary returned by the search endpoints.
@@ -410,6 +1395,12 @@ pub struct ThreadSearchHit {
     pub series_number: Option<i32>,
     #[serde(rename = "seriesTotal", skip_serializing_if = "Option::is_none")]
     pub series_total: Option<i32>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub subsystems: Vec<String>,
+    #[serde(rename = "patchStates", default, skip_serializing_if = "Vec::is_empty")]
+    pub patch_states: Vec<PatchState>,
+    #[serde(rename = "incompleteSeries", default)]
+    pub incomplete_series: bool,
     #[serde(rename = "firstPostExcerpt", skip_serializing_if = "Option::is_none")]
     pub first_post_excerpt: Option<String>,
     pub score: ThreadSearchScore,
@@ -423,6 +1414,225 @@ pub struct ThreadSearchHit {
 pub struct ThreadSearchPage {
     pub hits: Vec<ThreadSearchHit>,
     pub total: i64,
+    /// Counts for the facets requested with `facet`, in request order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub facets: Vec<ThreadSearchFacetCounts>,
+}
+
+/// Dimension thread search results can be counted by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub enum ThreadSearchFacet {
+    /// Mailing list slug.
+    MailingList,
+    /// Year the thread started (UTC).
+    Year,
+    /// Month the thread started (UTC), as `YYYY-MM`.
+    Month,
+    /// Whether the thread carries patches (`true`/`false`).
+    HasPatches,
+    /// Email address of a participant.
+    Participant,
+    /// Version of the patch series started by the thread (`v1`, `v2`, ...).
+    SeriesVersion,
+}
+
+impl ThreadSearchFacet {
+    /// All facets in display order.
+    pub const ALL: [ThreadSearchFacet; 6] = [
+        ThreadSearchFacet::MailingList,
+        ThreadSearchFacet::Year,
+        ThreadSearchFacet::Month,
+        ThreadSearchFacet::HasPatches,
+        ThreadSearchFacet::Participant,
+        ThreadSearchFacet::SeriesVersion,
+    ];
+
+    /// Query parameter representation of the facet.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThreadSearchFacet::MailingList => "mailingList",
+            ThreadSearchFacet::Year => "year",
+            ThreadSearchFacet::Month => "month",
+            ThreadSearchFacet::HasPatches => "hasPatches",
+            ThreadSearchFacet::Participant => "participant",
+            ThreadSearchFacet::SeriesVersion => "seriesVersion",
+        }
+    }
+
+    /// Parse the query parameter representation, ignoring case.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        Self::ALL
+            .into_iter()
+            .find(|facet| facet.as_str().eq_ignore_ascii_case(value))
+    }
+
+    /// Whether values are dates, listed chronologically rather than by count.
+    pub fn is_chronological(&self) -> bool {
+        matches!(self, ThreadSearchFacet::Year | ThreadSearchFacet::Month)
+    }
+}
+
+/// Value counts of one facet over all threads matching a search.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSearchFacetCounts {
+    pub facet: ThreadSearchFacet,
+    pub values: Vec<ThreadSearchFacetValue>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ThreadSearchFacetValue {
+    pub value: String,
+    pub count: i64,
+}
+
+/// Thread a message search hit belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSearchThreadContext {
+    pub thread_id: i32,
+    pub subject: String,
+    pub root_message_id: String,
+    pub message_count: i32,
+}
+
+/// Highlight snippets for message search hits.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSearchHighlights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subject_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body_text: Option<String>,
+}
+
+/// Search hit for message-level queries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSearchHit {
+    pub email_id: i32,
+    pub mailing_list_id: i32,
+    pub mailing_list_slug: String,
+    pub message_id: String,
+    pub subject: String,
+    pub date: DateTime<Utc>,
+    pub author: ThreadSearchParticipant,
+    #[serde(default)]
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    pub is_patch: bool,
+    /// Paths touched by the patch carried by the message.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<String>,
+    pub thread: EmailSearchThreadContext,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranking_score: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<EmailSearchHighlights>,
+}
+
+/// Response payload for message search queries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSearchPage {
+    pub hits: Vec<EmailSearchHit>,
+    pub total: i64,
+}
+
+/// Highlight snippets for patch search hits.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSearchHighlights {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added_text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed_html: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removed_text: Option<String>,
+}
+
+/// Search hit for code-aware patch queries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSearchHit {
+    pub email_id: i32,
+    pub mailing_list_id: i32,
+    pub mailing_list_slug: String,
+    pub message_id: String,
+    pub subject: String,
+    pub date: DateTime<Utc>,
+    pub author: ThreadSearchParticipant,
+    pub thread_id: i32,
+    /// Paths touched by the patch.
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Functions or sections named in the patch's hunk headers.
+    #[serde(default)]
+    pub functions: Vec<String>,
+    /// Identifiers from the query that the patch adds, removes or names in a
+    /// hunk header.
+    #[serde(default)]
+    pub matched_symbols: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ranking_score: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<PatchSearchHighlights>,
+}
+
+/// Response payload for patch search queries.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSearchPage {
+    pub hits: Vec<PatchSearchHit>,
+    pub total: i64,
+}
+
+/// Completions for a partially typed search query, grouped by kind.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSuggestions {
+    pub authors: Vec<SuggestedAuthor>,
+    pub mailing_lists: Vec<SuggestedMailingList>,
+    /// Recent threads whose subject matches.
+    pub threads: Vec<SuggestedThread>,
+    /// MAINTAINERS subsystem names.
+    pub subsystems: Vec<String>,
+    /// MAINTAINERS file patterns starting with the query.
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedAuthor {
+    pub id: i32,
+    pub name: Option<String>,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedMailingList {
+    pub slug: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedThread {
+    pub thread_id: i32,
+    pub mailing_list: String,
+    pub subject: String,
+    pub last_activity: Option<DateTime<Utc>>,
 }
 
 /// Per-mailing-list activity breakdown returned with author search hits.
@@ -0,0 +1,590 @@
+//! Parse patch emails into structured diffs (files, hunks, lines).
+
+use crate::models::{
+    DiffFile, DiffFileStatus, DiffHunk, DiffLine, DiffLineKind, DiffStat, PatchDiff, PatchMetadata,
+};
+use rocket_db_pools::sqlx::{self, PgPool, types::Json};
+use std::collections::HashMap;
+
+/// Bump when the parser output changes so cached diffs are rebuilt.
+pub const DIFF_PARSER_VERSION: i32 = 1;
+
+/// Patch email to parse: `(id, body, patch_metadata)`.
+type PatchBodyRow = (i32, Option<String>, Option<Json<PatchMetadata>>);
+
+/// Parse the diff carried by a patch email body.
+///
+/// Parsing starts at the first diff section from `metadata` (or the first
+/// `diff `/`---`+`+++` pair when there is none) and stops at the signature
+/// marker or the first line that can't belong to a diff.
+///
+/// # Returns
+/// The structured diff, or `None` if the body contains no file changes
+pub fn parse_patch_diff(body: &str, metadata: Option<&PatchMetadata>) -> Option<PatchDiff> {
+    let lines: Vec<&str> = body.lines().collect();
+    let start = metadata
+        .and_then(|meta| meta.diff_sections.first().map(|s| s.start_line))
+        .or_else(|| find_diff_start(&lines))?;
+
+    let mut parser = DiffParser::default();
+    for (index, line) in lines.iter().enumerate().skip(start) {
+        if !parser.feed(index, line) {
+            break;
+        }
+    }
+
+    let files = parser.finish();
+    if files.is_empty() {
+        return None;
+    }
+
+    let diffstat = DiffStat {
+        files_changed: files.len(),
+        insertions: files.iter().map(|f| f.additions).sum(),
+        deletions: files.iter().map(|f| f.deletions).sum(),
+    };
+
+    Some(PatchDiff { files, diffstat })
+}
+
+/// Load the structured diff of an email, parsing and caching it on first use.
+///
+/// # Returns
+/// The diff, or `None` if the email carries no patch
+pub async fn load_patch_diff(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<PatchDiff>, sqlx::Error> {
+    let mut diffs = load_patch_diffs(pool, mailing_list_id, &[email_id]).await?;
+    Ok(diffs.remove(&email_id))
+}
+
+/// Load the structured diffs of several emails of a list, parsing and caching
+/// the ones not cached yet.
+///
+/// # Returns
+/// Diffs keyed by email ID; emails without a patch are absent
+pub async fn load_patch_diffs(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_ids: &[i32],
+) -> Result<HashMap<i32, PatchDiff>, sqlx::Error> {
+    if email_ids.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let cached: Vec<(i32, Json<PatchDiff>)> = sqlx::query_as(
+        r#"SELECT email_id, diff FROM email_diffs
+           WHERE mailing_list_id = $1 AND email_id = ANY($2) AND parser_version = $3"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_ids)
+    .bind(DIFF_PARSER_VERSION)
+    .fetch_all(pool)
+    .await?;
+    let mut diffs: HashMap<i32, PatchDiff> = cached
+        .into_iter()
+        .map(|(email_id, Json(diff))| (email_id, diff))
+        .collect();
+
+    let missing: Vec<i32> = email_ids
+        .iter()
+        .copied()
+        .filter(|email_id| !diffs.contains_key(email_id))
+        .collect();
+    if missing.is_empty() {
+        return Ok(diffs);
+    }
+
+    let rows: Vec<PatchBodyRow> = sqlx::query_as(
+        r#"SELECT id, body, patch_metadata
+           FROM emails
+           WHERE mailing_list_id = $1 AND id = ANY($2) AND patch_type <> 'none'"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&missing)
+    .fetch_all(pool)
+    .await?;
+
+    let parsed: Vec<(i32, PatchDiff)> = rows
+        .into_iter()
+        .filter_map(|(email_id, body, metadata)| {
+            let diff = parse_patch_diff(
+                body.as_deref().unwrap_or_default(),
+                metadata.as_ref().map(|json| &json.0),
+            )?;
+            Some((email_id, diff))
+        })
+        .collect();
+    if parsed.is_empty() {
+        return Ok(diffs);
+    }
+
+    let ids: Vec<i32> = parsed.iter().map(|(email_id, _)| *email_id).collect();
+    let payloads: Vec<Json<&PatchDiff>> = parsed.iter().map(|(_, diff)| Json(diff)).collect();
+    sqlx::query(
+        r#"INSERT INTO email_diffs (mailing_list_id, email_id, parser_version, diff)
+           SELECT $1, d.email_id, $3, d.diff
+           FROM UNNEST($2::int[], $4::jsonb[]) AS d(email_id, diff)
+           ON CONFLICT (mailing_list_id, email_id) DO UPDATE
+           SET parser_version = EXCLUDED.parser_version,
+               diff = EXCLUDED.diff,
+               created_at = NOW()"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&ids)
+    .bind(DIFF_PARSER_VERSION)
+    .bind(&payloads)
+    .execute(pool)
+    .await?;
+
+    diffs.extend(parsed);
+    Ok(diffs)
+}
+
+fn find_diff_start(lines: &[&str]) -> Option<usize> {
+    lines.iter().enumerate().position(|(index, line)| {
+        line.starts_with("diff ")
+            || (line.starts_with("--- ")
+                && lines
+                    .get(index + 1)
+                    .is_some_and(|next| next.starts_with("+++ ")))
+    })
+}
+
+#[derive(Default)]
+struct DiffParser {
+    files: Vec<DiffFile>,
+    current: Option<DiffFile>,
+    /// Old/new lines still expected in the current hunk.
+    remaining: Option<(u32, u32)>,
+    old_line: u32,
+    new_line: u32,
+}
+
+impl DiffParser {
+    /// Consume one body line; returns `false` once the diff has ended.
+    fn feed(&mut self, index: usize, line: &str) -> bool {
+        if let Some((old_left, new_left)) = self.remaining {
+            if old_left > 0 || new_left > 0 {
+                return self.feed_hunk_line(index, line, old_left, new_left);
+            }
+            if let Some(rest) = line.strip_prefix('\\') {
+                self.push_line(
+                    index,
+                    DiffLineKind::NoNewline,
+                    rest.trim_start(),
+                    None,
+                    None,
+                );
+                return true;
+            }
+        }
+
+        if line.starts_with("diff ") {
+            self.start_file(line);
+            return true;
+        }
+
+        if let Some(header) = line.strip_prefix("@@ -") {
+            if self.current.is_none() {
+                return false;
+            }
+            return self.start_hunk(line, header);
+        }
+
+        if let Some(path) = line.strip_prefix("--- ") {
+            let next_file = self
+                .current
+                .as_ref()
+                .is_none_or(|file| !file.hunks.is_empty() || file.binary);
+            if next_file {
+                self.flush_file();
+                self.current = Some(new_file(None, None));
+            }
+            if let Some(file) = self.current.as_mut() {
+                file.old_path = parse_header_path(path);
+            }
+            return true;
+        }
+
+        let Some(file) = self.current.as_mut() else {
+            return false;
+        };
+
+        if let Some(path) = line.strip_prefix("+++ ") {
+            file.new_path = parse_header_path(path);
+        } else if let Some(mode) = line.strip_prefix("old mode ") {
+            file.old_mode = Some(mode.trim().to_string());
+        } else if let Some(mode) = line.strip_prefix("new mode ") {
+            file.new_mode = Some(mode.trim().to_string());
+        } else if let Some(mode) = line.strip_prefix("new file mode ") {
+            file.status = DiffFileStatus::Added;
+            file.new_mode = Some(mode.trim().to_string());
+        } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+            file.status = DiffFileStatus::Deleted;
+            file.old_mode = Some(mode.trim().to_string());
+        } else if let Some(path) = line.strip_prefix("rename from ") {
+            file.status = DiffFileStatus::Renamed;
+            file.old_path = Some(path.trim().to_string());
+        } else if let Some(path) = line.strip_prefix("rename to ") {
+            file.status = DiffFileStatus::Renamed;
+            file.new_path = Some(path.trim().to_string());
+        } else if let Some(path) = line.strip_prefix("copy from ") {
+            file.status = DiffFileStatus::Copied;
+            file.old_path = Some(path.trim().to_string());
+        } else if let Some(path) = line.strip_prefix("copy to ") {
+            file.status = DiffFileStatus::Copied;
+            file.new_path = Some(path.trim().to_string());
+        } else if let Some(value) = line
+            .strip_prefix("similarity index ")
+            .or_else(|| line.strip_prefix("dissimilarity index "))
+        {
+            file.similarity = value.trim().trim_end_matches('%').parse().ok();
+        } else if let Some(value) = line.strip_prefix("index ") {
+            // `index abc..def 100644` carries the mode of unchanged-mode files
+            if let Some(mode) = value.split_whitespace().nth(1) {
+                file.old_mode.get_or_insert_with(|| mode.to_string());
+                file.new_mode.get_or_insert_with(|| mode.to_string());
+            }
+        } else if line.starts_with("Binary files ") || line == "GIT binary patch" {
+            file.binary = true;
+        } else if file.binary
+            && (line.starts_with("literal ")
+                || line.starts_with("delta ")
+                || line.is_empty()
+                || line.starts_with(|c: char| c.is_ascii_alphabetic()))
+        {
+            // Base85 payload of a binary patch
+        } else if !file.hunks.is_empty()
+            || line.starts_with("-- ")
+            || !line.starts_with(|c: char| c.is_ascii_alphabetic())
+        {
+            // Unknown extended headers are skipped until the first hunk;
+            // anything else ends the diff
+            return false;
+        }
+
+        true
+    }
+
+    fn feed_hunk_line(&mut self, index: usize, line: &str, old_left: u32, new_left: u32) -> bool {
+        let (kind, content) = match line.chars().next() {
+            Some(' ') => (DiffLineKind::Context, &line[1..]),
+            // Some mailers strip the trailing space of empty context lines
+            None => (DiffLineKind::Context, ""),
+            Some('+') if new_left > 0 => (DiffLineKind::Addition, &line[1..]),
+            Some('-') if old_left > 0 => (DiffLineKind::Deletion, &line[1..]),
+            Some('\\') => {
+                self.push_line(
+                    index,
+                    DiffLineKind::NoNewline,
+                    line[1..].trim_start(),
+                    None,
+                    None,
+                );
+                return true;
+            }
+            _ => {
+                // Truncated hunk: end it and let the line start the next section
+                self.remaining = None;
+                return self.feed(index, line);
+            }
+        };
+
+        match kind {
+            DiffLineKind::Context => {
+                let (old, new) = (self.old_line, self.new_line);
+                self.old_line += 1;
+                self.new_line += 1;
+                self.remaining = Some((old_left.saturating_sub(1), new_left.saturating_sub(1)));
+                self.push_line(index, kind, content, Some(old), Some(new));
+            }
+            DiffLineKind::Addition => {
+                let new = self.new_line;
+                self.new_line += 1;
+                self.remaining = Some((old_left, new_left - 1));
+                if let Some(file) = self.current.as_mut() {
+                    file.additions += 1;
+                }
+                self.push_line(index, kind, content, None, Some(new));
+            }
+            DiffLineKind::Deletion => {
+                let old = self.old_line;
+                self.old_line += 1;
+                self.remaining = Some((old_left - 1, new_left));
+                if let Some(file) = self.current.as_mut() {
+                    file.deletions += 1;
+                }
+                self.push_line(index, kind, content, Some(old), None);
+            }
+            DiffLineKind::NoNewline => {}
+        }
+
+        true
+    }
+
+    fn start_file(&mut self, line: &str) {
+        self.flush_file();
+        let (old_path, new_path) = line
+            .strip_prefix("diff --git ")
+            .and_then(split_git_paths)
+            .map(|(old, new)| (Some(old), Some(new)))
+            .unwrap_or((None, None));
+        self.current = Some(new_file(old_path, new_path));
+    }
+
+    fn start_hunk(&mut self, line: &str, header: &str) -> bool {
+        let Some((ranges, section)) = header.split_once(" @@") else {
+            return false;
+        };
+        let mut parts = ranges.split_whitespace();
+        let (Some(old), Some(new)) = (
+            parts.next().and_then(parse_range),
+            parts
+                .next()
+                .and_then(|r| r.strip_prefix('+'))
+                .and_then(parse_range),
+        ) else {
+            return false;
+        };
+
+        let section = section.trim();
+        let hunk = DiffHunk {
+            header: line.to_string(),
+            old_start: old.0,
+            old_lines: old.1,
+            new_start: new.0,
+            new_lines: new.1,
+            section: (!section.is_empty()).then(|| section.to_string()),
+            lines: Vec::new(),
+        };
+
+        self.old_line = old.0;
+        self.new_line = new.0;
+        self.remaining = Some((old.1, new.1));
+        if let Some(file) = self.current.as_mut() {
+            file.hunks.push(hunk);
+        }
+        true
+    }
+
+    fn push_line(
+        &mut self,
+        body_line: usize,
+        kind: DiffLineKind,
+        content: &str,
+        old_line: Option<u32>,
+        new_line: Option<u32>,
+    ) {
+        if let Some(hunk) = self.current.as_mut().and_then(|f| f.hunks.last_mut()) {
+            hunk.lines.push(DiffLine {
+                kind,
+                content: content.to_string(),
+                old_line,
+                new_line,
+                body_line,
+            });
+        }
+    }
+
+    fn flush_file(&mut self) {
+        self.remaining = None;
+        if let Some(mut file) = self.current.take() {
+            if file.status == DiffFileStatus::Modified {
+                if file.old_path.is_none() && file.new_path.is_some() {
+                    file.status = DiffFileStatus::Added;
+                } else if file.new_path.is_none() && file.old_path.is_some() {
+                    file.status = DiffFileStatus::Deleted;
+                }
+            }
+            match file.status {
+                DiffFileStatus::Added => file.old_path = None,
+                DiffFileStatus::Deleted => file.new_path = None,
+                _ => {}
+            }
+            if file.old_path.is_some() || file.new_path.is_some() {
+                self.files.push(file);
+            }
+        }
+    }
+
+    fn finish(mut self) -> Vec<DiffFile> {
+        self.flush_file();
+        self.files
+    }
+}
+
+fn new_file(old_path: Option<String>, new_path: Option<String>) -> DiffFile {
+    DiffFile {
+        old_path,
+        new_path,
+        status: DiffFileStatus::Modified,
+        old_mode: None,
+        new_mode: None,
+        similarity: None,
+        binary: false,
+        additions: 0,
+        deletions: 0,
+        hunks: Vec::new(),
+    }
+}
+
+/// Split `a/<path> b/<path>`, preferring the split where both paths are equal
+/// so paths containing ` b/` are handled.
+fn split_git_paths(paths: &str) -> Option<(String, String)> {
+    let rest = paths.strip_prefix("a/")?;
+    let splits: Vec<usize> = rest.match_indices(" b/").map(|(i, _)| i).collect();
+    let index = splits
+        .iter()
+        .copied()
+        .find(|&i| rest[..i] == rest[i + 3..])
+        .or_else(|| splits.last().copied())?;
+    Some((rest[..index].to_string(), rest[index + 3..].to_string()))
+}
+
+/// Path from a `---`/`+++` header, without the `a/`/`b/` prefix and timestamp.
+fn parse_header_path(value: &str) -> Option<String> {
+    let path = value.split('\t').next().unwrap_or(value).trim_end();
+    if path == "/dev/null" {
+        return None;
+    }
+    let path = path
+        .strip_prefix("a/")
+        .or_else(|| path.strip_prefix("b/"))
+        .unwrap_or(path);
+    Some(path.to_string())
+}
+
+/// Parse `start[,count]` (count defaults to 1).
+fn parse_range(range: &str) -> Option<(u32, u32)> {
+    match range.split_once(',') {
+        Some((start, count)) => Some((start.parse().ok()?, count.parse().ok()?)),
+        None => Some((range.parse().ok()?, 1)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "Subject: [PATCH] net: fix things\n\
+\n\
+Signed-off-by: A <a@example.com>\n\
+---\n \
+net/core.c | 3 ++-\n\
+\n\
+diff --git a/net/core.c b/net/core.c\n\
+index 1111111..2222222 100644\n\
+--- a/net/core.c\n\
++++ b/net/core.c\n\
+@@ -10,4 +10,5 @@ static int setup(void)\n \
+int a;\n\
+-int b;\n\
++int c;\n\
++int d;\n\
+\n \
+return 0;\n\
+diff --git a/old.h b/new.h\n\
+old mode 100644\n\
+new mode 100755\n\
+similarity index 90%\n\
+rename from old.h\n\
+rename to new.h\n\
+diff --git a/logo.png b/logo.png\n\
+new file mode 100644\n\
+index 0000000..3333333\n\
+Binary files /dev/null and b/logo.png differ\n\
+diff --git a/gone.c b/gone.c\n\
+deleted file mode 100644\n\
+index 4444444..0000000\n\
+--- a/gone.c\n\
++++ /dev/null\n\
+@@ -1 +0,0 @@\n\
+-int gone;\n\
+\\ No newline at end of file\n\
+-- \n\
+2.39.5\n";
+
+    #[test]
+    fn parses_files_hunks_and_lines() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+
+        assert_eq!(diff.files.len(), 4);
+        assert_eq!(
+            diff.diffstat,
+            DiffStat {
+                files_changed: 4,
+                insertions: 2,
+                deletions: 2,
+            }
+        );
+
+        let core = &diff.files[0];
+        assert_eq!(core.status, DiffFileStatus::Modified);
+        assert_eq!(core.new_path.as_deref(), Some("net/core.c"));
+        assert_eq!(core.old_mode.as_deref(), Some("100644"));
+        let hunk = &core.hunks[0];
+        assert_eq!(
+            (
+                hunk.old_start,
+                hunk.old_lines,
+                hunk.new_start,
+                hunk.new_lines
+            ),
+            (10, 4, 10, 5)
+        );
+        assert_eq!(hunk.section.as_deref(), Some("static int setup(void)"));
+        let kinds: Vec<_> = hunk
+            .lines
+            .iter()
+            .map(|l| (l.kind, l.old_line, l.new_line))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (DiffLineKind::Context, Some(10), Some(10)),
+                (DiffLineKind::Deletion, Some(11), None),
+                (DiffLineKind::Addition, None, Some(11)),
+                (DiffLineKind::Addition, None, Some(12)),
+                (DiffLineKind::Context, Some(12), Some(13)),
+                (DiffLineKind::Context, Some(13), Some(14)),
+            ]
+        );
+        assert_eq!(hunk.lines[1].content, "int b;");
+        assert_eq!(PATCH.lines().nth(hunk.lines[1].body_line), Some("-int b;"));
+    }
+
+    #[test]
+    fn parses_renames_binaries_and_deletions() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+
+        let rename = &diff.files[1];
+        assert_eq!(rename.status, DiffFileStatus::Renamed);
+        assert_eq!(rename.old_path.as_deref(), Some("old.h"));
+        assert_eq!(rename.new_path.as_deref(), Some("new.h"));
+        assert_eq!(rename.old_mode.as_deref(), Some("100644"));
+        assert_eq!(rename.new_mode.as_deref(), Some("100755"));
+        assert_eq!(rename.similarity, Some(90));
+
+        let binary = &diff.files[2];
+        assert_eq!(binary.status, DiffFileStatus::Added);
+        assert!(binary.binary);
+        assert_eq!(binary.old_path, None);
+
+        let deleted = &diff.files[3];
+        assert_eq!(deleted.status, DiffFileStatus::Deleted);
+        assert_eq!(deleted.new_path, None);
+        assert_eq!(deleted.deletions, 1);
+        assert_eq!(
+            deleted.hunks[0].lines.last().map(|l| l.kind),
+            Some(DiffLineKind::NoNewline)
+        );
+    }
+
+    #[test]
+    fn returns_none_without_diff() {
+        assert_eq!(parse_patch_diff("Thanks, applied.\n", None), None);
+    }
+}
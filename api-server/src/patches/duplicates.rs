@@ -0,0 +1,37 @@
+//! Lookup of every email carrying the same stored patch-id.
+
+use crate::models::PatchIdMatch;
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Find every email, across all mailing lists, carrying `patch_id`.
+///
+/// # Returns
+/// Matching emails, oldest first
+pub async fn find_emails_by_patch_id(
+    pool: &PgPool,
+    patch_id: &str,
+) -> Result<Vec<PatchIdMatch>, sqlx::Error> {
+    sqlx::query_as::<_, PatchIdMatch>(
+        r#"SELECT ml.slug AS mailing_list_slug,
+                  e.id AS email_id,
+                  e.message_id,
+                  e.subject,
+                  a.canonical_name AS author_name,
+                  a.email AS author_email,
+                  e.date,
+                  tm.thread_id,
+                  ps.state AS patch_state
+           FROM emails e
+           JOIN mailing_lists ml ON ml.id = e.mailing_list_id
+           JOIN authors a ON a.id = e.author_id
+           LEFT JOIN thread_memberships tm ON tm.mailing_list_id = e.mailing_list_id
+               AND tm.email_id = e.id
+           LEFT JOIN patch_states ps ON ps.mailing_list_id = e.mailing_list_id
+               AND ps.email_id = e.id
+           WHERE e.patch_id = $1
+           ORDER BY e.date, ml.slug, e.id"#,
+    )
+    .bind(patch_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,263 @@
+//! `Fixes:` trailers and the regression graph they form.
+//!
+//! A fix names the commit it repairs by abbreviated hash and subject. The hash
+//! is resolved through upstream applications first; when the introducing patch
+//! was never matched upstream, the subject is matched against archived patches.
+
+use crate::models::{FixedCommit, FixingPatch, PatchMetadata, PatchType};
+use crate::sync::parser::normalize_subject;
+use regex::Regex;
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+
+static FIXES_RE: OnceLock<Regex> = OnceLock::new();
+
+fn fixes_regex() -> &'static Regex {
+    FIXES_RE.get_or_init(|| {
+        Regex::new(r#"^Fixes:[ \t]*([0-9a-fA-F]{8,40})\b(?:[ \t]*\("(.*)"\))?"#)
+            .expect("valid Fixes regex")
+    })
+}
+
+/// Commit referenced by a `Fixes:` trailer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixesTrailer {
+    /// Lowercase abbreviated (or full) commit hash.
+    pub commit: String,
+    /// Subject quoted after the hash, if present.
+    pub subject: Option<String>,
+}
+
+impl FixesTrailer {
+    /// Subject normalized the same way as email subjects, for matching.
+    pub fn normalized_subject(&self) -> Option<String> {
+        self.subject
+            .as_deref()
+            .map(normalize_subject)
+            .filter(|s| !s.is_empty())
+    }
+}
+
+/// Parse `Fixes:` trailers from commit message text.
+///
+/// Quoted or indented lines are ignored; each commit is returned once.
+pub fn parse_fixes_trailers(text: &str) -> Vec<FixesTrailer> {
+    let mut seen = HashSet::new();
+    text.lines()
+        .filter_map(|line| {
+            let caps = fixes_regex().captures(line.trim_end())?;
+            let commit = caps.get(1)?.as_str().to_ascii_lowercase();
+            let subject = caps
+                .get(2)
+                .map(|m| m.as_str().trim().to_string())
+                .filter(|s| !s.is_empty());
+            seen.insert(commit.clone())
+                .then_some(FixesTrailer { commit, subject })
+        })
+        .collect()
+}
+
+/// Extract `Fixes:` trailers from the commit message of a patch email.
+///
+/// Only the part before the `---` separator (or the first diff) is considered,
+/// so trailers inside the diff or below it don't count.
+pub fn extract_fixes(
+    body: &str,
+    patch_type: PatchType,
+    metadata: Option<&PatchMetadata>,
+) -> Vec<FixesTrailer> {
+    if patch_type == PatchType::None {
+        return Vec::new();
+    }
+
+    let lines: Vec<&str> = body.lines().collect();
+    let end = metadata
+        .and_then(|meta| {
+            meta.separator_line
+                .or_else(|| meta.diff_sections.first().map(|s| s.start_line))
+        })
+        .or_else(|| {
+            lines
+                .iter()
+                .position(|line| line.trim_end() == "---" || line.starts_with("diff "))
+        })
+        .unwrap_or(lines.len())
+        .min(lines.len());
+
+    parse_fixes_trailers(&lines[..end].join("\n"))
+}
+
+/// Commits a patch email claims to fix, each with the archived patch that
+/// introduced it when it can be resolved.
+pub async fn load_fixed_commits(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Vec<FixedCommit>, sqlx::Error> {
+    sqlx::query_as::<_, FixedCommit>(
+        r#"SELECT pf.commit_prefix AS commit,
+                  pf.fixed_subject AS subject,
+                  intro.resolution,
+                  ml.slug AS mailing_list_slug,
+                  ie.id AS email_id,
+                  ie.subject AS email_subject,
+                  a.canonical_name AS author_name,
+                  a.email AS author_email,
+                  ie.date,
+                  tm.thread_id
+           FROM patch_fixes pf
+           JOIN emails fe ON fe.mailing_list_id = pf.mailing_list_id AND fe.id = pf.email_id
+           LEFT JOIN LATERAL (
+               SELECT c.mailing_list_id, c.email_id, c.resolution
+               FROM (
+                   SELECT ua.mailing_list_id, ua.email_id, 'upstream_commit' AS resolution,
+                          0 AS rank, ua.committed_at AS date
+                   FROM upstream_applications ua
+                   -- prefix range so idx_upstream_applications_commit_prefix applies
+                   WHERE ua.commit_hash ~>=~ pf.commit_prefix
+                     AND ua.commit_hash ~<~ (pf.commit_prefix || 'g')
+                   UNION ALL
+                   SELECT e.mailing_list_id, e.id, 'subject', 1, e.date
+                   FROM emails e
+                   WHERE pf.fixed_normalized_subject IS NOT NULL
+                     AND e.normalized_subject = pf.fixed_normalized_subject
+                     AND e.patch_type <> 'none'
+                     AND e.subject !~* '^\s*(re|fwd?)\s*:'
+                     AND e.date <= fe.date
+                     AND NOT (e.mailing_list_id = fe.mailing_list_id AND e.id = fe.id)
+               ) c
+               ORDER BY c.rank, c.date DESC
+               LIMIT 1
+           ) intro ON TRUE
+           LEFT JOIN emails ie ON ie.mailing_list_id = intro.mailing_list_id
+               AND ie.id = intro.email_id
+           LEFT JOIN mailing_lists ml ON ml.id = ie.mailing_list_id
+           LEFT JOIN authors a ON a.id = ie.author_id
+           LEFT JOIN thread_memberships tm ON tm.mailing_list_id = ie.mailing_list_id
+               AND tm.email_id = ie.id
+           WHERE pf.mailing_list_id = $1 AND pf.email_id = $2
+           ORDER BY pf.commit_prefix"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Patch emails whose `Fixes:` trailers point at this patch.
+///
+/// A trailer points at the patch when its hash prefixes an upstream commit the
+/// patch was applied as, or when its subject matches the patch and no later
+/// revision of the patch was posted before the fix.
+pub async fn load_fixing_patches(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Vec<FixingPatch>, sqlx::Error> {
+    sqlx::query_as::<_, FixingPatch>(
+        r#"WITH target AS (
+               SELECT e.mailing_list_id, e.id, e.normalized_subject, e.date
+               FROM emails e
+               WHERE e.mailing_list_id = $1 AND e.id = $2
+           ),
+           by_commit AS (
+               SELECT pf.mailing_list_id, pf.email_id, pf.commit_prefix,
+                      'upstream_commit' AS resolution, 0 AS rank
+               FROM upstream_applications ua
+               CROSS JOIN generate_series(8, 40) AS len
+               JOIN patch_fixes pf ON pf.commit_prefix = left(ua.commit_hash, len)
+               WHERE ua.mailing_list_id = $1 AND ua.email_id = $2
+           ),
+           by_subject AS (
+               SELECT pf.mailing_list_id, pf.email_id, pf.commit_prefix,
+                      'subject' AS resolution, 1 AS rank
+               FROM target t
+               JOIN patch_fixes pf ON pf.fixed_normalized_subject = t.normalized_subject
+               JOIN emails fe ON fe.mailing_list_id = pf.mailing_list_id AND fe.id = pf.email_id
+               WHERE fe.date >= t.date
+                 AND NOT (fe.mailing_list_id = t.mailing_list_id AND fe.id = t.id)
+                 AND NOT EXISTS (
+                     SELECT 1
+                     FROM emails later
+                     WHERE later.normalized_subject = t.normalized_subject
+                       AND later.patch_type <> 'none'
+                       AND later.subject !~* '^\s*(re|fwd?)\s*:'
+                       AND later.date > t.date
+                       AND later.date <= fe.date
+                 )
+           ),
+           matches AS (
+               SELECT DISTINCT ON (mailing_list_id, email_id, commit_prefix) *
+               FROM (SELECT * FROM by_commit UNION ALL SELECT * FROM by_subject) m
+               ORDER BY mailing_list_id, email_id, commit_prefix, rank
+           )
+           SELECT m.commit_prefix AS commit,
+                  m.resolution,
+                  ml.slug AS mailing_list_slug,
+                  fe.id AS email_id,
+                  fe.subject AS email_subject,
+                  a.canonical_name AS author_name,
+                  a.email AS author_email,
+                  fe.date,
+                  tm.thread_id
+           FROM matches m
+           JOIN emails fe ON fe.mailing_list_id = m.mailing_list_id AND fe.id = m.email_id
+           JOIN mailing_lists ml ON ml.id = fe.mailing_list_id
+           JOIN authors a ON a.id = fe.author_id
+           LEFT JOIN thread_memberships tm ON tm.mailing_list_id = fe.mailing_list_id
+               AND tm.email_id = fe.id
+           ORDER BY fe.date, ml.slug, fe.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_fixes_trailers() {
+        let text = "net: fix leak\n\
+\n\
+Fixes: 1234567890AB (\"net: add \"fancy\" feature\")\n\
+Fixes: deadbeefcafe\n\
+Fixes: 1234567890ab (\"duplicate\")\n\
+> Fixes: abcdef012345 (\"quoted\")\n\
+Fixes: xyz (\"not a hash\")\n\
+Signed-off-by: Jane <jane@example.com>\n";
+
+        assert_eq!(
+            parse_fixes_trailers(text),
+            vec![
+                FixesTrailer {
+                    commit: "1234567890ab".to_string(),
+                    subject: Some("net: add \"fancy\" feature".to_string()),
+                },
+                FixesTrailer {
+                    commit: "deadbeefcafe".to_string(),
+                    subject: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn extract_fixes_ignores_diff_content() {
+        let body = "mm: fix\n\
+\n\
+Fixes: aaaaaaaaaaaa (\"mm: break\")\n\
+---\n\
+diff --git a/Documentation/x.rst b/Documentation/x.rst\n\
++Fixes: bbbbbbbbbbbb (\"example\")\n";
+
+        let fixes = extract_fixes(body, PatchType::Inline, None);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].commit, "aaaaaaaaaaaa");
+        assert_eq!(fixes[0].normalized_subject().as_deref(), Some("mm: break"));
+        assert!(extract_fixes(body, PatchType::None, None).is_empty());
+    }
+}
@@ -0,0 +1,50 @@
+//! Patch review workflow.
+//!
+//! Tracks a Patchwork-style review state for every patch email (new, under
+//! review, changes requested, accepted, rejected, superseded, RFC) together with
+//! an audit trail of who changed it and when. Imports seed the initial state and
+//! automatically supersede older revisions of a series once a newer one appears.
+//! Every patch also carries a stable `git patch-id`, which links resends,
+//! cross-posts and backports of the same change, and `Fixes:` trailers link
+//! fixes to the patches that introduced the bug. Inline replies are mapped to
+//! the diff lines they comment on. Numbered patches are grouped into series
+//! revisions that report missing, duplicated and misthreaded patches.
+//!
+//! ## Module Structure
+//!
+//! - `diff`: Structured file/hunk/line diffs parsed from patch emails (cached)
+//! - `duplicates`: Lookup of resent/cross-posted patches by patch-id
+//! - `fixes`: `Fixes:` trailer parsing and the regression graph between patches
+//! - `patch_id`: `git patch-id --stable` compatible diff fingerprints
+//! - `processor`: Import-time email processors writing patch-ids and `Fixes:` trailers
+//! - `review`: Anchoring of inline review replies to the diff lines they quote
+//! - `series`: Series revision grouping and completeness
+//! - `state`: State persistence, history, and series supersede detection
+
+mod diff;
+mod duplicates;
+mod fixes;
+mod patch_id;
+mod processor;
+mod review;
+mod series;
+mod state;
+
+pub use diff::{DIFF_PARSER_VERSION, load_patch_diff, load_patch_diffs, parse_patch_diff};
+pub use duplicates::find_emails_by_patch_id;
+pub use fixes::{
+    FixesTrailer, extract_fixes, load_fixed_commits, load_fixing_patches, parse_fixes_trailers,
+};
+pub use patch_id::{compute_patch_id, email_patch_id};
+pub use processor::{PatchFixesProcessor, PatchIdProcessor};
+pub use review::{InlineComment, extract_inline_comments, load_review_comments};
+pub use series::{
+    SeriesCompleteness, SeriesEmail, group_series_revisions, load_email_series, load_thread_series,
+    refresh_series_revisions, series_completeness,
+};
+pub use state::{
+    PATCH_EMAIL_SQL, SeriesRoot, find_superseded_threads, initialize_patch_states,
+    load_patch_state, load_state_history, patch_states_initialized,
+    record_patch_states_initialized, set_patch_states, supersede_older_versions,
+    thread_patch_states,
+};
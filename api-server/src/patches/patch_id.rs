@@ -0,0 +1,233 @@
+//! `git patch-id --stable` compatible patch identifiers.
+//!
+//! A patch-id is a hash of a diff with whitespace and line numbers removed, so
+//! the same change keeps its id across rebases, resends, and cherry-picks. The
+//! stable variant hashes each file separately and sums the hashes, which makes
+//! the id independent of file order.
+
+use crate::models::{PatchMetadata, PatchType};
+use sha1::{Digest, Sha1};
+
+/// Compute the patch-id of a patch email.
+///
+/// Inline patches are hashed over the diff sections located by the parser so
+/// quoted diffs and signatures can't leak in; attachments (and inline patches
+/// without metadata) fall back to scanning the whole body.
+///
+/// # Returns
+/// Patch-id, or `None` for non-patch emails and diffs that can't be parsed
+pub fn email_patch_id(
+    body: &str,
+    patch_type: PatchType,
+    metadata: Option<&PatchMetadata>,
+) -> Option<String> {
+    if patch_type == PatchType::None {
+        return None;
+    }
+
+    let sections = metadata
+        .map(|meta| meta.diff_sections.as_slice())
+        .filter(|sections| !sections.is_empty());
+
+    match sections {
+        Some(sections) => {
+            let lines: Vec<&str> = body.lines().collect();
+            let mut diff = String::new();
+            for section in sections {
+                let end = section.end_line.min(lines.len().saturating_sub(1));
+                for line in lines.get(section.start_line..=end).unwrap_or_default() {
+                    diff.push_str(line);
+                    diff.push('\n');
+                }
+            }
+            compute_patch_id(&diff)
+        }
+        None => compute_patch_id(body),
+    }
+}
+
+/// Compute the stable patch-id of the first patch found in `text`.
+///
+/// `text` can be a bare diff or a whole email: everything before the first
+/// `diff ` line (commit message, diffstat) is skipped and parsing stops at the
+/// first line that can't belong to the diff (e.g. the `-- ` signature marker).
+///
+/// # Returns
+/// Lowercase hex patch-id, or `None` if the text contains no diff
+pub fn compute_patch_id(text: &str) -> Option<String> {
+    let mut result = [0u8; 20];
+    let mut hasher = Sha1::new();
+    let mut patch_len = 0usize;
+    // Remaining old/new lines in the current hunk; -1 while parsing a file header.
+    let mut before: i64 = -1;
+    let mut after: i64 = -1;
+
+    for line in text.lines() {
+        if patch_len == 0 && !line.starts_with("diff ") {
+            continue;
+        }
+
+        if before == -1 {
+            if line.starts_with("index ") {
+                continue;
+            } else if line.starts_with("--- ") {
+                before = 1;
+                after = 1;
+            } else if !line.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                break;
+            }
+        }
+
+        if before == 0 && after == 0 {
+            if line.starts_with("@@ -") {
+                match parse_hunk_header(line) {
+                    Some((old, new)) => {
+                        before = old;
+                        after = new;
+                    }
+                    None => break,
+                }
+                continue;
+            }
+
+            if !line.starts_with("diff ") {
+                break;
+            }
+
+            flush_file_hash(&mut result, &mut hasher);
+            before = -1;
+            after = -1;
+        }
+
+        if line.starts_with('-') || line.starts_with(' ') {
+            before -= 1;
+        }
+        if line.starts_with('+') || line.starts_with(' ') {
+            after -= 1;
+        }
+
+        let stripped: Vec<u8> = line.bytes().filter(|b| !b.is_ascii_whitespace()).collect();
+        patch_len += stripped.len();
+        hasher.update(&stripped);
+    }
+
+    if patch_len == 0 {
+        return None;
+    }
+
+    flush_file_hash(&mut result, &mut hasher);
+    Some(result.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Add the current file's hash into the running sum (little-endian with carry)
+/// and reset the hasher for the next file.
+fn flush_file_hash(result: &mut [u8; 20], hasher: &mut Sha1) {
+    let hash = std::mem::take(hasher).finalize();
+    let mut carry: u16 = 0;
+    for (sum, byte) in result.iter_mut().zip(hash.iter()) {
+        carry += *sum as u16 + *byte as u16;
+        *sum = carry as u8;
+        carry >>= 8;
+    }
+}
+
+/// Parse the old/new line counts from `@@ -a[,b] +c[,d] @@`.
+fn parse_hunk_header(line: &str) -> Option<(i64, i64)> {
+    let mut ranges = line.strip_prefix("@@ -")?.split_whitespace();
+    let old = ranges.next()?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    Some((range_len(old)?, range_len(new)?))
+}
+
+fn range_len(range: &str) -> Option<i64> {
+    match range.split_once(',') {
+        Some((_, len)) => len.parse().ok(),
+        None => range.parse::<i64>().ok().map(|_| 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "Subject: [PATCH] change\n\
+\n\
+---\n \
+f.txt | 3 ++-\n \
+n.h   | 1 +\n\
+\n\
+diff --git a/f.txt b/f.txt\n\
+index f9d9a01..81a6bcc 100644\n\
+--- a/f.txt\n\
++++ b/f.txt\n\
+@@ -1,7 +1,8 @@\n \
+a\n \
+b\n\
+-c\n\
++C2\n \
+d\n \
+e\n \
+f\n \
+g\n\
++h\n\
+diff --git a/n.h b/n.h\n\
+new file mode 100644\n\
+index 0000000..3e75765\n\
+--- /dev/null\n\
++++ b/n.h\n\
+@@ -0,0 +1 @@\n\
++new\n\
+-- \n\
+2.39.5\n";
+
+    #[test]
+    fn matches_git_patch_id_stable() {
+        // Expected value produced by `git patch-id --stable`.
+        assert_eq!(
+            compute_patch_id(PATCH).as_deref(),
+            Some("c49d8f7b85a3524c661e496c3d651fbff6c15e9d")
+        );
+    }
+
+    #[test]
+    fn ignores_file_order_and_whitespace() {
+        let (head, files) = PATCH.split_once("diff --git a/f.txt").unwrap();
+        let (first, second) = files.split_once("diff --git a/n.h").unwrap();
+        let second = second.replace("-- \n2.39.5\n", "");
+        let reordered = format!(
+            "{head}diff --git a/n.h{second}diff --git a/f.txt{}",
+            first.replace("+C2", "+C 2")
+        );
+
+        assert_eq!(compute_patch_id(&reordered), compute_patch_id(PATCH));
+    }
+
+    #[test]
+    fn email_patch_id_uses_diff_sections() {
+        use crate::models::PatchSection;
+
+        let start = PATCH.lines().position(|l| l.starts_with("diff ")).unwrap();
+        let end = PATCH.lines().position(|l| l == "+new").unwrap();
+        let metadata = PatchMetadata {
+            diff_sections: vec![PatchSection {
+                start_line: start,
+                end_line: end,
+            }],
+            diffstat_section: None,
+            trailer_sections: Vec::new(),
+            separator_line: None,
+            trailer_count: 0,
+        };
+
+        assert_eq!(
+            email_patch_id(PATCH, PatchType::Inline, Some(&metadata)),
+            compute_patch_id(PATCH)
+        );
+        assert_eq!(email_patch_id(PATCH, PatchType::None, None), None);
+    }
+
+    #[test]
+    fn returns_none_without_diff() {
+        assert_eq!(compute_patch_id("Just a reply\n\n> quoted\n"), None);
+    }
+}
@@ -0,0 +1,147 @@
+//! Email processors writing patch-ids to `emails` and `Fixes:` trailers to
+//! `patch_fixes`.
+
+use crate::models::PatchType;
+use crate::patches::{email_patch_id, extract_fixes};
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Stores the stable `git patch-id` of patch emails in `emails.patch_id`.
+///
+/// Emails whose diff can't be parsed are stored without a patch-id; since the
+/// processor's version is recorded per list, they are only looked at again
+/// when the version changes.
+pub struct PatchIdProcessor;
+
+impl EmailProcessor for PatchIdProcessor {
+    fn name(&self) -> &'static str {
+        "patch_ids"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut patch_ids = Vec::new();
+
+            for email in emails {
+                if email.patch_type == PatchType::None {
+                    continue;
+                }
+                email_ids.push(email.email_id);
+                patch_ids.push(email_patch_id(
+                    &email.body,
+                    email.patch_type,
+                    email.patch_metadata.as_ref().map(|json| &json.0),
+                ));
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            let result = sqlx::query(
+                r#"UPDATE emails e
+                   SET patch_id = p.patch_id
+                   FROM UNNEST($2::int[], $3::text[]) AS p(id, patch_id)
+                   WHERE e.mailing_list_id = $1
+                     AND e.id = p.id
+                     AND e.patch_id IS DISTINCT FROM p.patch_id"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&patch_ids)
+            .execute(pool)
+            .await?;
+
+            log::trace!("stored {} patch-ids", result.rows_affected());
+            Ok(result.rows_affected() as usize)
+        })
+    }
+
+    fn reset<'a>(&'a self, _pool: &'a PgPool, _mailing_list_id: i32) -> ProcessorFuture<'a> {
+        // Every patch email's patch-id is overwritten when the processor re-runs
+        Box::pin(async { Ok(0) })
+    }
+}
+
+/// Parses `Fixes:` trailers of patch emails into `patch_fixes` rows.
+pub struct PatchFixesProcessor;
+
+impl EmailProcessor for PatchFixesProcessor {
+    fn name(&self) -> &'static str {
+        "patch_fixes"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut commit_prefixes = Vec::new();
+            let mut subjects = Vec::new();
+            let mut normalized_subjects = Vec::new();
+
+            for email in emails {
+                let fixes = extract_fixes(
+                    &email.body,
+                    email.patch_type,
+                    email.patch_metadata.as_ref().map(|json| &json.0),
+                );
+                for fix in fixes {
+                    email_ids.push(email.email_id);
+                    normalized_subjects.push(fix.normalized_subject());
+                    commit_prefixes.push(fix.commit);
+                    subjects.push(fix.subject);
+                }
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO patch_fixes (
+                       mailing_list_id, email_id, commit_prefix, fixed_subject, fixed_normalized_subject
+                   )
+                   SELECT $1, * FROM UNNEST($2::int[], $3::text[], $4::text[], $5::text[])
+                   ON CONFLICT (mailing_list_id, email_id, commit_prefix) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&commit_prefixes)
+            .bind(&subjects)
+            .bind(&normalized_subjects)
+            .execute(pool)
+            .await?;
+
+            log::trace!("bulk inserted {} fixes trailers", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM patch_fixes WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
@@ -0,0 +1,362 @@
+//! Anchor inline review replies to the diff lines they quote.
+//!
+//! Reviewers reply to a patch by quoting parts of it and writing comments
+//! below the quoted lines. Each quoted `+`/`-`/context line is matched against
+//! the parent patch's structured diff; the text that follows a quote becomes a
+//! comment on the last matched line.
+
+use crate::models::{DiffLineKind, DiffSide, PatchDiff, ReviewComment};
+use crate::patches::diff::load_patch_diffs;
+use chrono::{DateTime, Utc};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Number of quoted diff lines kept as context for a comment.
+const MAX_QUOTED_CONTEXT: usize = 10;
+
+/// Reply to a patch email:
+/// `(patch_email_id, reply_email_id, author_name, author_email, date, body)`.
+type ReplyRow = (
+    i32,
+    i32,
+    Option<String>,
+    String,
+    DateTime<Utc>,
+    Option<String>,
+);
+
+/// A reply comment anchored to a line of the parent patch's diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InlineComment {
+    /// File path (new path, or old path for deleted files).
+    pub path: String,
+    pub side: DiffSide,
+    /// Line number on `side`.
+    pub line: u32,
+    /// Header of the hunk containing the line.
+    pub hunk_header: String,
+    /// Quoted diff lines the comment follows, ending with the anchor line.
+    pub quoted: Vec<String>,
+    pub comment: String,
+}
+
+/// A diff line a quote can match.
+struct Target<'a> {
+    text: String,
+    path: &'a str,
+    side: DiffSide,
+    line: u32,
+    hunk_header: &'a str,
+}
+
+/// Match a reply's quoted diff lines against `diff` and collect the comments
+/// written below them.
+///
+/// Only first-level quotes are considered; text before the first quote, text
+/// after quoted commit-message lines, and comments consisting only of trailers
+/// (`Reviewed-by:` and friends) are dropped.
+pub fn extract_inline_comments(reply: &str, diff: &PatchDiff) -> Vec<InlineComment> {
+    let targets = diff_targets(diff);
+    if targets.is_empty() {
+        return Vec::new();
+    }
+
+    let mut comments = Vec::new();
+    let mut cursor = 0;
+    let mut anchor: Option<usize> = None;
+    let mut quoted: Vec<String> = Vec::new();
+    let mut pending: Vec<&str> = Vec::new();
+
+    for line in reply.lines() {
+        if line == "-- " {
+            break;
+        }
+
+        if line.starts_with(">>") {
+            continue;
+        }
+
+        if let Some(rest) = line.strip_prefix('>') {
+            // A quote after comment text starts a new quote block.
+            if pending.iter().any(|l| !l.trim().is_empty()) {
+                flush_comment(&mut comments, &targets, anchor, &quoted, &mut pending);
+                quoted.clear();
+            }
+            pending.clear();
+
+            let text = rest.strip_prefix(' ').unwrap_or(rest).trim_end();
+            if text.is_empty() {
+                continue;
+            }
+
+            match find_target(&targets, text, cursor) {
+                Some(index) => {
+                    anchor = Some(index);
+                    cursor = index + 1;
+                    quoted.push(targets[index].text.clone());
+                    if quoted.len() > MAX_QUOTED_CONTEXT {
+                        quoted.remove(0);
+                    }
+                }
+                None if is_diff_header(text) => {}
+                None => {
+                    anchor = None;
+                    quoted.clear();
+                }
+            }
+            continue;
+        }
+
+        pending.push(line);
+    }
+
+    flush_comment(&mut comments, &targets, anchor, &quoted, &mut pending);
+    comments
+}
+
+fn flush_comment(
+    comments: &mut Vec<InlineComment>,
+    targets: &[Target<'_>],
+    anchor: Option<usize>,
+    quoted: &[String],
+    pending: &mut Vec<&str>,
+) {
+    let lines = std::mem::take(pending);
+    let Some(target) = anchor.map(|index| &targets[index]) else {
+        return;
+    };
+
+    let text = lines.join("\n");
+    let text = text.trim();
+    if text.is_empty() || text.lines().all(is_trailer_or_blank) {
+        return;
+    }
+
+    // Several comments after the same quote become one.
+    if let Some(last) = comments.last_mut()
+        && last.path == target.path
+        && last.side == target.side
+        && last.line == target.line
+        && last.quoted == quoted
+    {
+        last.comment.push_str("\n\n");
+        last.comment.push_str(text);
+        return;
+    }
+
+    comments.push(InlineComment {
+        path: target.path.to_string(),
+        side: target.side,
+        line: target.line,
+        hunk_header: target.hunk_header.to_string(),
+        quoted: quoted.to_vec(),
+        comment: text.to_string(),
+    });
+}
+
+fn diff_targets(diff: &PatchDiff) -> Vec<Target<'_>> {
+    let mut targets = Vec::new();
+    for file in &diff.files {
+        let Some(path) = file.new_path.as_deref().or(file.old_path.as_deref()) else {
+            continue;
+        };
+        for hunk in &file.hunks {
+            for line in &hunk.lines {
+                let (marker, side, number) = match line.kind {
+                    DiffLineKind::Addition => ('+', DiffSide::New, line.new_line),
+                    DiffLineKind::Deletion => ('-', DiffSide::Old, line.old_line),
+                    DiffLineKind::Context => (' ', DiffSide::New, line.new_line),
+                    DiffLineKind::NoNewline => continue,
+                };
+                let Some(number) = number else {
+                    continue;
+                };
+                targets.push(Target {
+                    text: format!("{marker}{}", line.content).trim_end().to_string(),
+                    path,
+                    side,
+                    line: number,
+                    hunk_header: &hunk.header,
+                });
+            }
+        }
+    }
+    targets
+}
+
+/// Find the diff line matching a quoted line, preferring the first match at or
+/// after `cursor` since reviewers quote in order.
+fn find_target(targets: &[Target<'_>], text: &str, cursor: usize) -> Option<usize> {
+    let matches = |candidate: &str| {
+        let position = |range: std::ops::Range<usize>| {
+            range
+                .into_iter()
+                .find(|&index| targets[index].text == candidate)
+        };
+        position(cursor.min(targets.len())..targets.len()).or_else(|| position(0..cursor))
+    };
+
+    if text.starts_with(['+', '-', ' '])
+        && let Some(index) = matches(text)
+    {
+        return Some(index);
+    }
+
+    // Mailers that trim `>  ` down to `> ` turn context lines into bare text.
+    if !text.starts_with(['+', '-']) {
+        return matches(format!(" {text}").trim_end());
+    }
+
+    None
+}
+
+fn is_diff_header(text: &str) -> bool {
+    text.starts_with("diff ")
+        || text.starts_with("@@ ")
+        || text.starts_with("index ")
+        || text.starts_with("--- ")
+        || text.starts_with("+++ ")
+}
+
+fn is_trailer_or_blank(line: &str) -> bool {
+    let line = line.trim();
+    if line.is_empty() {
+        return true;
+    }
+    line.split_once(':').is_some_and(|(key, _)| {
+        key.ends_with("-by") && key.chars().all(|c| c.is_ascii_alphabetic() || c == '-')
+    })
+}
+
+/// Review comments left as inline replies to the given patch emails.
+///
+/// Only direct replies are considered; each reply is matched against the diff
+/// of the patch it answers.
+///
+/// # Returns
+/// Comments ordered by patch, reply date, and position in the reply
+pub async fn load_review_comments(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    patch_email_ids: &[i32],
+) -> Result<Vec<ReviewComment>, sqlx::Error> {
+    let diffs = load_patch_diffs(pool, mailing_list_id, patch_email_ids).await?;
+    if diffs.is_empty() {
+        return Ok(Vec::new());
+    }
+    let patch_ids: Vec<i32> = diffs.keys().copied().collect();
+
+    let replies: Vec<ReplyRow> = sqlx::query_as(
+        r#"SELECT p.id, r.id, a.canonical_name, a.email, r.date, r.body
+           FROM emails p
+           JOIN emails r ON r.mailing_list_id = p.mailing_list_id
+               AND r.in_reply_to = p.message_id
+           JOIN authors a ON a.id = r.author_id
+           WHERE p.mailing_list_id = $1 AND p.id = ANY($2)
+             AND r.body IS NOT NULL
+           ORDER BY p.id, r.date, r.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&patch_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut comments = Vec::new();
+    for (patch_email_id, reply_email_id, author_name, author_email, date, body) in replies {
+        let (Some(diff), Some(body)) = (diffs.get(&patch_email_id), body) else {
+            continue;
+        };
+        for inline in extract_inline_comments(&body, diff) {
+            comments.push(ReviewComment {
+                patch_email_id,
+                reply_email_id,
+                author_name: author_name.clone(),
+                author_email: author_email.clone(),
+                date,
+                path: inline.path,
+                side: inline.side,
+                line: inline.line,
+                hunk_header: inline.hunk_header,
+                quoted: inline.quoted,
+                comment: inline.comment,
+            });
+        }
+    }
+
+    Ok(comments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::patches::diff::parse_patch_diff;
+
+    const PATCH: &str = "diff --git a/net/core.c b/net/core.c\n\
+index 1111111..2222222 100644\n\
+--- a/net/core.c\n\
++++ b/net/core.c\n\
+@@ -10,4 +10,5 @@ static int setup(void)\n \
+int a;\n\
+-int b;\n\
++int c;\n\
++int d;\n \
+\n \
+return 0;\n";
+
+    #[test]
+    fn anchors_comments_to_quoted_lines() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+        let reply = "On Mon, Jane wrote:\n\
+> diff --git a/net/core.c b/net/core.c\n\
+> @@ -10,4 +10,5 @@ static int setup(void)\n\
+>  int a;\n\
+> -int b;\n\
+\n\
+Why drop b?\n\
+\n\
+> +int c;\n\
+> +int d;\n\
+\n\
+d is unused.\n\
+>\n\
+>  return 0;\n\
+\n\
+Reviewed-by: Rev <rev@example.com>\n\
+-- \n\
+sig\n";
+
+        let comments = extract_inline_comments(reply, &diff);
+        assert_eq!(comments.len(), 2);
+
+        assert_eq!(comments[0].path, "net/core.c");
+        assert_eq!(comments[0].side, DiffSide::Old);
+        assert_eq!(comments[0].line, 11);
+        assert_eq!(comments[0].quoted, vec![" int a;", "-int b;"]);
+        assert_eq!(comments[0].comment, "Why drop b?");
+
+        assert_eq!(comments[1].side, DiffSide::New);
+        assert_eq!(comments[1].line, 12);
+        assert_eq!(comments[1].quoted, vec!["+int c;", "+int d;"]);
+        assert_eq!(comments[1].comment, "d is unused.");
+    }
+
+    #[test]
+    fn ignores_comments_on_commit_message() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+        let reply = "> +int c;\n\
+> This patch also reworks the setup.\n\
+\n\
+Please split this.\n";
+
+        assert!(extract_inline_comments(reply, &diff).is_empty());
+    }
+
+    #[test]
+    fn matches_context_lines_with_trimmed_quote_prefix() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+        let reply = "> int a;\n\nfine\n";
+
+        let comments = extract_inline_comments(reply, &diff);
+        assert_eq!(comments.len(), 1);
+        assert_eq!((comments[0].side, comments[0].line), (DiffSide::New, 10));
+    }
+}
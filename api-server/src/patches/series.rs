@@ -0,0 +1,772 @@
+//! Series revision grouping and completeness.
+//!
+//! A revision is the set of `[PATCH vN k/total]` emails one author sent for
+//! one posting of a series. Within an author, revision tag and total, patches
+//! belong to the posting whose cover letter (or first patch) their
+//! `In-Reply-To` chain leads to; patches replying to a cover letter that never
+//! reached the list share that letter's Message-ID. Patches sent outside any
+//! chain join a posting sent around the same time under the same subject
+//! prefix, so patches that never reached the cover letter's thread still count
+//! towards their series.
+
+use crate::models::{SeriesPatch, SeriesRevision};
+use chrono::{DateTime, Duration, Utc};
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Unthreaded patches join postings sent within this long of their first email.
+const SERIES_WINDOW: Duration = Duration::hours(24);
+
+/// Numbered patch email, as grouped into revisions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesEmail {
+    pub email_id: i32,
+    pub author_id: i32,
+    /// Revision tag (`v2`), empty for the first posting.
+    pub version: String,
+    pub total: i32,
+    pub number: i32,
+    pub date: DateTime<Utc>,
+    pub message_id: String,
+    pub in_reply_to: Option<String>,
+    pub normalized_subject: String,
+}
+
+/// Which indices of a revision arrived.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SeriesCompleteness {
+    pub has_cover_letter: bool,
+    pub present: Vec<i32>,
+    pub missing: Vec<i32>,
+    pub duplicated: Vec<i32>,
+}
+
+impl SeriesCompleteness {
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty() && self.duplicated.is_empty()
+    }
+}
+
+/// Split numbered patch emails into series revisions.
+///
+/// Emails sharing author, revision tag and total are grouped by the root of
+/// their `In-Reply-To` chain among those emails: a cover letter, a first patch
+/// the others reply to, or the missing message they all reply to. A second
+/// cover letter under the same root starts a new posting. Emails outside any
+/// chain join the chained posting sent within [`SERIES_WINDOW`] that has a
+/// patch with the same subject prefix, or else form revisions by subject
+/// prefix and time.
+///
+/// # Returns
+/// Revisions as groups of `emails` indices, each ordered by date
+pub fn group_series_revisions(emails: &[SeriesEmail]) -> Vec<Vec<usize>> {
+    let mut series: BTreeMap<(i32, &str, i32), Vec<usize>> = BTreeMap::new();
+    for (index, email) in emails.iter().enumerate() {
+        series
+            .entry((email.author_id, email.version.as_str(), email.total))
+            .or_default()
+            .push(index);
+    }
+
+    let mut revisions = Vec::new();
+    for mut members in series.into_values() {
+        members.sort_by_key(|&index| (emails[index].date, emails[index].email_id));
+        revisions.extend(group_one_series(emails, &members));
+    }
+
+    revisions.sort_by_key(|revision| {
+        let first = &emails[revision[0]];
+        (
+            first.author_id,
+            first.version.clone(),
+            first.total,
+            first.date,
+            first.email_id,
+        )
+    });
+    revisions
+}
+
+/// Group the emails of one author, revision tag and total, given in date order.
+fn group_one_series(emails: &[SeriesEmail], members: &[usize]) -> Vec<Vec<usize>> {
+    let by_message_id: HashMap<&str, usize> = members
+        .iter()
+        .map(|&index| (emails[index].message_id.as_str(), index))
+        .collect();
+    let parent = |index: usize| -> Option<usize> {
+        emails[index]
+            .in_reply_to
+            .as_deref()
+            .and_then(|reply_to| by_message_id.get(reply_to).copied())
+            .filter(|&parent| parent != index)
+    };
+    let has_replies: HashSet<usize> = members.iter().filter_map(|&index| parent(index)).collect();
+
+    // Postings keyed by the root of their chain, in date order of their first
+    // email; emails outside any chain are placed afterwards
+    let mut chained: Vec<(&str, Vec<usize>)> = Vec::new();
+    let mut unthreaded = Vec::new();
+    for &index in members {
+        let mut current = index;
+        // Bounded walk in case of a reply cycle
+        for _ in 0..members.len() {
+            match parent(current) {
+                Some(next) => current = next,
+                None => break,
+            }
+        }
+
+        let root = &emails[current];
+        let key = match root.in_reply_to.as_deref() {
+            // Replies to a message outside the series, such as a cover letter
+            // that never reached the list
+            Some(reply_to) if root.number != 0 && parent(current).is_none() => reply_to,
+            _ if current != index || root.number == 0 || has_replies.contains(&current) => {
+                root.message_id.as_str()
+            }
+            _ => {
+                unthreaded.push(index);
+                continue;
+            }
+        };
+
+        // A second cover letter under the same root starts a new posting
+        let is_cover = emails[index].number == 0;
+        match chained.iter_mut().rev().find(|(root, _)| *root == key) {
+            Some((_, revision)) if !(is_cover && has_cover_letter(emails, revision)) => {
+                revision.push(index);
+            }
+            _ => chained.push((key, vec![index])),
+        }
+    }
+
+    // A lone patch replying outside the series, e.g. a resend in reply to its
+    // review, is placed like an unthreaded one
+    chained.retain(|(key, revision)| {
+        let lone = revision.len() == 1
+            && emails[revision[0]].number != 0
+            && emails[revision[0]].in_reply_to.as_deref() == Some(*key);
+        if lone {
+            unthreaded.push(revision[0]);
+        }
+        !lone
+    });
+    unthreaded.sort_by_key(|&index| (emails[index].date, emails[index].email_id));
+
+    let mut revisions: Vec<Vec<usize>> =
+        chained.into_iter().map(|(_, revision)| revision).collect();
+    let chained_count = revisions.len();
+    for index in unthreaded {
+        let email = &emails[index];
+        let prefix = subject_prefix(&email.normalized_subject);
+        let joins = |revision: &Vec<usize>| {
+            let first = &emails[revision[0]];
+            (email.date - first.date).abs() <= SERIES_WINDOW
+                && revision
+                    .iter()
+                    .any(|&member| subject_prefix(&emails[member].normalized_subject) == prefix)
+        };
+
+        let target = revisions[..chained_count]
+            .iter()
+            .position(joins)
+            .or_else(|| {
+                revisions[chained_count..]
+                    .iter()
+                    .position(|revision| {
+                        // Two unthreaded patches with the same index are more
+                        // likely separate postings than a resend
+                        joins(revision)
+                            && revision
+                                .iter()
+                                .all(|&member| emails[member].number != email.number)
+                    })
+                    .map(|position| chained_count + position)
+            });
+        match target {
+            Some(target) => revisions[target].push(index),
+            None => revisions.push(vec![index]),
+        }
+    }
+
+    for revision in &mut revisions {
+        revision.sort_by_key(|&index| (emails[index].date, emails[index].email_id));
+    }
+    revisions
+}
+
+fn has_cover_letter(emails: &[SeriesEmail], revision: &[usize]) -> bool {
+    revision.iter().any(|&index| emails[index].number == 0)
+}
+
+/// Subsystem prefix of a normalized subject (`mm/slab` for
+/// `mm/slab: fix leak`), or the whole subject when it has none.
+fn subject_prefix(normalized_subject: &str) -> &str {
+    normalized_subject
+        .split_once(':')
+        .map(|(prefix, _)| prefix.trim())
+        .filter(|prefix| !prefix.is_empty())
+        .unwrap_or(normalized_subject.trim())
+}
+
+/// Compare the indices that arrived against `1..=total`.
+///
+/// The cover letter (`0`) is reported separately and never counts as missing;
+/// indices above `total` are ignored.
+pub fn series_completeness(total: i32, numbers: &[i32]) -> SeriesCompleteness {
+    let mut counts: BTreeMap<i32, usize> = BTreeMap::new();
+    for &number in numbers {
+        if (0..=total).contains(&number) {
+            *counts.entry(number).or_default() += 1;
+        }
+    }
+
+    SeriesCompleteness {
+        has_cover_letter: counts.contains_key(&0),
+        present: counts.keys().copied().filter(|&n| n > 0).collect(),
+        missing: (1..=total).filter(|n| !counts.contains_key(n)).collect(),
+        duplicated: counts
+            .iter()
+            .filter(|&(_, &count)| count > 1)
+            .map(|(&number, _)| number)
+            .collect(),
+    }
+}
+
+/// Series emails that are numbered patches rather than replies to one.
+const SERIES_EMAIL_SQL: &str = r#"e.series_number IS NOT NULL
+    AND e.series_total > 0
+    AND e.subject !~* '^\s*(re|fwd?)\s*:'"#;
+
+/// Regroup the revisions of every series with patches imported since the
+/// last run.
+///
+/// Only numbered patches above the list's `series_grouped_email_id` watermark
+/// are looked up; all emails sharing an author, revision tag and total with
+/// one of them are regrouped, so late or resent patches join the revision they
+/// belong to. The watermark then moves to the list's newest email.
+///
+/// # Returns
+/// Threads holding a regrouped patch, whose series completeness may have
+/// changed
+pub async fn refresh_series_revisions(
+    pool: &PgPool,
+    mailing_list_id: i32,
+) -> Result<Vec<i32>, sqlx::Error> {
+    let (watermark, newest): (i32, Option<i32>) = sqlx::query_as(
+        r#"SELECT ml.series_grouped_email_id,
+                  (SELECT MAX(e.id) FROM emails e WHERE e.mailing_list_id = ml.id)
+           FROM mailing_lists ml
+           WHERE ml.id = $1"#,
+    )
+    .bind(mailing_list_id)
+    .fetch_one(pool)
+    .await?;
+    let Some(newest) = newest.filter(|&newest| newest > watermark) else {
+        return Ok(Vec::new());
+    };
+
+    let keys: Vec<(i32, String, i32)> = sqlx::query_as(&format!(
+        r#"SELECT DISTINCT e.author_id, COALESCE(e.series_id, ''), e.series_total
+           FROM emails e
+           WHERE e.mailing_list_id = $1
+             AND e.id > $2
+             AND e.id <= $3
+             AND {SERIES_EMAIL_SQL}"#
+    ))
+    .bind(mailing_list_id)
+    .bind(watermark)
+    .bind(newest)
+    .fetch_all(pool)
+    .await?;
+
+    if keys.is_empty() {
+        record_series_watermark(pool, mailing_list_id, newest).await?;
+        return Ok(Vec::new());
+    }
+
+    let mut author_ids = Vec::with_capacity(keys.len());
+    let mut versions = Vec::with_capacity(keys.len());
+    let mut totals = Vec::with_capacity(keys.len());
+    for (author_id, version, total) in keys {
+        author_ids.push(author_id);
+        versions.push(version);
+        totals.push(total);
+    }
+
+    let rows: Vec<SeriesEmailRow> = sqlx::query_as(&format!(
+        r#"SELECT e.id, e.author_id, COALESCE(e.series_id, '') AS version, e.series_total,
+                  e.series_number, e.date, e.message_id, e.in_reply_to,
+                  COALESCE(e.normalized_subject, '') AS normalized_subject
+           FROM emails e
+           JOIN UNNEST($2::int[], $3::text[], $4::int[]) AS k(author_id, version, total)
+               ON e.author_id = k.author_id
+               AND COALESCE(e.series_id, '') = k.version
+               AND e.series_total = k.total
+           WHERE e.mailing_list_id = $1
+             AND e.id <= $5
+             AND {SERIES_EMAIL_SQL}"#
+    ))
+    .bind(mailing_list_id)
+    .bind(&author_ids)
+    .bind(&versions)
+    .bind(&totals)
+    .bind(newest)
+    .fetch_all(pool)
+    .await?;
+
+    let emails: Vec<SeriesEmail> = rows.into_iter().map(SeriesEmail::from).collect();
+    let revisions = group_series_revisions(&emails);
+
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"DELETE FROM series_revisions sr
+           USING UNNEST($2::int[], $3::text[], $4::int[]) AS k(author_id, version, total)
+           WHERE sr.mailing_list_id = $1
+             AND sr.author_id = k.author_id
+             AND sr.version = k.version
+             AND sr.total = k.total"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&author_ids)
+    .bind(&versions)
+    .bind(&totals)
+    .execute(&mut *tx)
+    .await?;
+
+    let ids: Vec<i32> = sqlx::query_scalar(
+        "SELECT nextval('series_revisions_id_seq')::int FROM generate_series(1, $1)",
+    )
+    .bind(revisions.len() as i32)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    let mut revision_author_ids = Vec::with_capacity(revisions.len());
+    let mut revision_versions = Vec::with_capacity(revisions.len());
+    let mut revision_totals = Vec::with_capacity(revisions.len());
+    let mut first_dates = Vec::with_capacity(revisions.len());
+    let mut cover_letters = Vec::with_capacity(revisions.len());
+    let mut completes = Vec::with_capacity(revisions.len());
+    let mut patch_revision_ids = Vec::new();
+    let mut patch_email_ids = Vec::new();
+    let mut patch_numbers = Vec::new();
+
+    for (revision, &id) in revisions.iter().zip(&ids) {
+        let first = &emails[revision[0]];
+        let numbers: Vec<i32> = revision.iter().map(|&i| emails[i].number).collect();
+        let completeness = series_completeness(first.total, &numbers);
+
+        revision_author_ids.push(first.author_id);
+        revision_versions.push(first.version.clone());
+        revision_totals.push(first.total);
+        first_dates.push(first.date);
+        cover_letters.push(completeness.has_cover_letter);
+        completes.push(completeness.is_complete());
+        for &index in revision {
+            patch_revision_ids.push(id);
+            patch_email_ids.push(emails[index].email_id);
+            patch_numbers.push(emails[index].number);
+        }
+    }
+
+    sqlx::query(
+        r#"INSERT INTO series_revisions
+               (mailing_list_id, id, author_id, version, total, first_date, has_cover_letter, complete)
+           SELECT $1, * FROM UNNEST(
+               $2::int[], $3::int[], $4::text[], $5::int[], $6::timestamptz[], $7::bool[], $8::bool[]
+           )"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&ids)
+    .bind(&revision_author_ids)
+    .bind(&revision_versions)
+    .bind(&revision_totals)
+    .bind(&first_dates)
+    .bind(&cover_letters)
+    .bind(&completes)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"INSERT INTO series_revision_patches (mailing_list_id, revision_id, email_id, number)
+           SELECT $1, * FROM UNNEST($2::int[], $3::int[], $4::int[])"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&patch_revision_ids)
+    .bind(&patch_email_ids)
+    .bind(&patch_numbers)
+    .execute(&mut *tx)
+    .await?;
+
+    let thread_ids: Vec<i32> = sqlx::query_scalar(
+        r#"SELECT DISTINCT thread_id FROM thread_memberships
+           WHERE mailing_list_id = $1 AND email_id = ANY($2)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&patch_email_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    record_series_watermark(&mut *tx, mailing_list_id, newest).await?;
+
+    tx.commit().await?;
+    Ok(thread_ids)
+}
+
+async fn record_series_watermark<'e, E>(
+    executor: E,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query("UPDATE mailing_lists SET series_grouped_email_id = $2 WHERE id = $1")
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+#[derive(sqlx::FromRow)]
+struct SeriesEmailRow {
+    id: i32,
+    author_id: i32,
+    version: String,
+    series_total: i32,
+    series_number: i32,
+    date: DateTime<Utc>,
+    message_id: String,
+    in_reply_to: Option<String>,
+    normalized_subject: String,
+}
+
+impl From<SeriesEmailRow> for SeriesEmail {
+    fn from(row: SeriesEmailRow) -> Self {
+        Self {
+            email_id: row.id,
+            author_id: row.author_id,
+            version: row.version,
+            total: row.series_total,
+            number: row.series_number,
+            date: row.date,
+            message_id: row.message_id,
+            in_reply_to: row.in_reply_to,
+            normalized_subject: row.normalized_subject,
+        }
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct RevisionRow {
+    id: i32,
+    author_id: i32,
+    author_name: Option<String>,
+    author_email: String,
+    version: String,
+    total: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct RevisionPatchRow {
+    revision_id: i32,
+    number: i32,
+    email_id: i32,
+    subject: String,
+    date: DateTime<Utc>,
+    thread_id: Option<i32>,
+}
+
+/// Series revisions with at least one patch in a thread.
+pub async fn load_thread_series(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_id: i32,
+) -> Result<Vec<SeriesRevision>, sqlx::Error> {
+    let ids: Vec<i32> = sqlx::query_scalar(
+        r#"SELECT DISTINCT srp.revision_id
+           FROM thread_memberships tm
+           JOIN series_revision_patches srp ON srp.mailing_list_id = tm.mailing_list_id
+               AND srp.email_id = tm.email_id
+           WHERE tm.mailing_list_id = $1 AND tm.thread_id = $2"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await?;
+
+    load_revisions(pool, mailing_list_id, &ids).await
+}
+
+/// Series revision a patch email belongs to, if it is a numbered patch.
+pub async fn load_email_series(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<SeriesRevision>, sqlx::Error> {
+    let ids: Vec<i32> = sqlx::query_scalar(
+        r#"SELECT revision_id
+           FROM series_revision_patches
+           WHERE mailing_list_id = $1 AND email_id = $2"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(pool)
+    .await?;
+
+    Ok(load_revisions(pool, mailing_list_id, &ids)
+        .await?
+        .into_iter()
+        .next())
+}
+
+async fn load_revisions(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    ids: &[i32],
+) -> Result<Vec<SeriesRevision>, sqlx::Error> {
+    if ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let revisions: Vec<RevisionRow> = sqlx::query_as(
+        r#"SELECT sr.id, sr.author_id, a.canonical_name AS author_name, a.email AS author_email,
+                  sr.version, sr.total
+           FROM series_revisions sr
+           JOIN authors a ON a.id = sr.author_id
+           WHERE sr.mailing_list_id = $1 AND sr.id = ANY($2)
+           ORDER BY sr.first_date, sr.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    let patches: Vec<RevisionPatchRow> = sqlx::query_as(
+        r#"SELECT srp.revision_id, srp.number, e.id AS email_id, e.subject, e.date, tm.thread_id
+           FROM series_revision_patches srp
+           JOIN emails e ON e.mailing_list_id = srp.mailing_list_id AND e.id = srp.email_id
+           LEFT JOIN thread_memberships tm ON tm.mailing_list_id = srp.mailing_list_id
+               AND tm.email_id = srp.email_id
+           WHERE srp.mailing_list_id = $1 AND srp.revision_id = ANY($2)
+           ORDER BY srp.revision_id, srp.number, e.date, e.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut patches_by_revision: HashMap<i32, Vec<RevisionPatchRow>> = HashMap::new();
+    for patch in patches {
+        patches_by_revision
+            .entry(patch.revision_id)
+            .or_default()
+            .push(patch);
+    }
+
+    Ok(revisions
+        .into_iter()
+        .map(|revision| {
+            let rows = patches_by_revision.remove(&revision.id).unwrap_or_default();
+            build_revision(revision, rows)
+        })
+        .collect())
+}
+
+fn build_revision(revision: RevisionRow, rows: Vec<RevisionPatchRow>) -> SeriesRevision {
+    let numbers: Vec<i32> = rows.iter().map(|row| row.number).collect();
+    let completeness = series_completeness(revision.total, &numbers);
+    let thread_id = main_thread(&rows);
+
+    let patches = rows
+        .into_iter()
+        .map(|row| SeriesPatch {
+            number: row.number,
+            email_id: row.email_id,
+            subject: row.subject,
+            date: row.date,
+            misthreaded: thread_id.is_some() && row.thread_id != thread_id,
+            thread_id: row.thread_id,
+        })
+        .collect();
+
+    SeriesRevision {
+        id: revision.id,
+        author_id: revision.author_id,
+        author_name: revision.author_name,
+        author_email: revision.author_email,
+        version: Some(revision.version).filter(|version| !version.is_empty()),
+        total: revision.total,
+        thread_id,
+        complete: completeness.is_complete(),
+        has_cover_letter: completeness.has_cover_letter,
+        present: completeness.present,
+        missing: completeness.missing,
+        duplicated: completeness.duplicated,
+        patches,
+    }
+}
+
+/// The cover letter's thread, else the thread holding the most patches
+/// (the lowest-numbered patch's on a tie).
+fn main_thread(rows: &[RevisionPatchRow]) -> Option<i32> {
+    if let Some(cover) = rows
+        .iter()
+        .find(|row| row.number == 0 && row.thread_id.is_some())
+    {
+        return cover.thread_id;
+    }
+
+    let mut counts: HashMap<i32, usize> = HashMap::new();
+    for thread_id in rows.iter().filter_map(|row| row.thread_id) {
+        *counts.entry(thread_id).or_default() += 1;
+    }
+    // rows are ordered by number, so the first maximum wins ties
+    rows.iter().filter_map(|row| row.thread_id).fold(
+        None,
+        |best: Option<i32>, thread_id| match best {
+            Some(best) if counts[&best] >= counts[&thread_id] => Some(best),
+            _ => Some(thread_id),
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn email(email_id: i32, version: &str, number: i32, hours: i64) -> SeriesEmail {
+        SeriesEmail {
+            email_id,
+            author_id: 7,
+            version: version.to_string(),
+            total: 3,
+            number,
+            date: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap() + Duration::hours(hours),
+            message_id: format!("{email_id}@example.org"),
+            in_reply_to: None,
+            normalized_subject: "mm/slab: fix leak".to_string(),
+        }
+    }
+
+    fn reply(mut email: SeriesEmail, parent_id: i32) -> SeriesEmail {
+        email.in_reply_to = Some(format!("{parent_id}@example.org"));
+        email
+    }
+
+    fn subject(mut email: SeriesEmail, subject: &str) -> SeriesEmail {
+        email.normalized_subject = subject.to_string();
+        email
+    }
+
+    fn email_ids(emails: &[SeriesEmail]) -> Vec<Vec<i32>> {
+        group_series_revisions(emails)
+            .into_iter()
+            .map(|revision| revision.into_iter().map(|i| emails[i].email_id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn groups_revisions_by_version_and_time() {
+        let emails = vec![
+            email(1, "", 0, 0),
+            reply(email(2, "", 1, 0), 1),
+            reply(email(3, "", 3, 0), 1),
+            // v1's 2/3 resent as its own thread an hour later
+            email(4, "", 2, 1),
+            email(5, "v2", 0, 48),
+            reply(email(6, "v2", 1, 48), 5),
+            // same tag and total much later: a separate posting
+            email(7, "", 1, 72),
+        ];
+
+        assert_eq!(
+            email_ids(&emails),
+            vec![vec![1, 2, 3, 4], vec![7], vec![5, 6]]
+        );
+    }
+
+    #[test]
+    fn second_cover_letter_starts_a_new_revision() {
+        let emails = vec![
+            email(1, "", 0, 0),
+            reply(email(2, "", 1, 0), 1),
+            email(3, "", 0, 2),
+        ];
+
+        assert_eq!(group_series_revisions(&emails), vec![vec![0, 1], vec![2]]);
+    }
+
+    #[test]
+    fn unrelated_series_sent_together_stay_apart() {
+        let emails = vec![
+            // two series with the same tag and total, sent minutes apart
+            email(1, "", 0, 0),
+            reply(email(2, "", 1, 0), 1),
+            subject(email(3, "", 0, 0), "usb: gadget: rename descriptors"),
+            subject(
+                reply(email(4, "", 1, 0), 3),
+                "usb: gadget: rename descriptors",
+            ),
+            reply(email(5, "", 2, 0), 1),
+            subject(
+                reply(email(6, "", 2, 0), 3),
+                "usb: gadget: rename descriptors",
+            ),
+            // patches replying to a cover letter that never reached the list
+            subject(reply(email(7, "", 1, 1), 100), "net: fix refcount"),
+            subject(reply(email(8, "", 2, 1), 100), "net: fix refcount"),
+            // unthreaded patches only join postings with the same prefix
+            subject(email(9, "", 3, 2), "usb: gadget: rename descriptors"),
+            subject(email(10, "", 1, 2), "ext4: fix journal"),
+        ];
+
+        assert_eq!(
+            email_ids(&emails),
+            vec![vec![1, 2, 5], vec![3, 4, 6, 9], vec![7, 8], vec![10]]
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_duplicated_indices() {
+        let completeness = series_completeness(12, &[0, 1, 2, 3, 4, 5, 6, 8, 9, 10, 11, 12, 3, 14]);
+
+        assert_eq!(
+            completeness,
+            SeriesCompleteness {
+                has_cover_letter: true,
+                present: vec![1, 2, 3, 4, 5, 6, 8, 9, 10, 11, 12],
+                missing: vec![7],
+                duplicated: vec![3],
+            }
+        );
+        assert!(!completeness.is_complete());
+        assert!(series_completeness(2, &[1, 2]).is_complete());
+    }
+
+    #[test]
+    fn main_thread_prefers_cover_letter_then_majority() {
+        let row = |number: i32, thread_id: Option<i32>| RevisionPatchRow {
+            revision_id: 1,
+            number,
+            email_id: number,
+            subject: String::new(),
+            date: Utc::now(),
+            thread_id,
+        };
+
+        assert_eq!(
+            main_thread(&[row(0, Some(9)), row(1, Some(4)), row(2, Some(4))]),
+            Some(9)
+        );
+        assert_eq!(
+            main_thread(&[row(1, Some(5)), row(2, Some(4)), row(3, Some(4))]),
+            Some(4)
+        );
+        assert_eq!(main_thread(&[row(1, Some(5)), row(2, Some(4))]), Some(5));
+        assert_eq!(main_thread(&[row(1, None)]), None);
+    }
+}
@@ -0,0 +1,452 @@
+//! Persistence for patch review states and their change history.
+
+use crate::models::{PatchState, PatchStateChange, PatchStateRecord};
+use crate::threading::patch_series::{extract_patch_version, is_rfc_subject};
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::HashMap;
+
+/// Emails that start a patch (inline/attached diff or a numbered series entry,
+/// including cover letters) rather than reply to one.
+pub const PATCH_EMAIL_SQL: &str = r#"(e.patch_type <> 'none' OR e.series_number IS NOT NULL)
+    AND e.subject !~* '^\s*(re|fwd?)\s*:'"#;
+
+const STATE_RECORD_SELECT: &str = r#"
+    SELECT ps.email_id, ps.state, ps.updated_by,
+           COALESCE(u.display_name, u.email) AS updated_by_name,
+           ps.updated_at
+    FROM patch_states ps
+    LEFT JOIN users u ON u.id = ps.updated_by
+"#;
+
+/// Root email of a patch thread, used to detect newer revisions of a series.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesRoot {
+    pub thread_id: i32,
+    pub author_id: i32,
+    pub normalized_subject: String,
+    pub version: i32,
+}
+
+/// Create `new`/`rfc` states for patch emails in a list that don't have one yet.
+/// RFC postings are recognized by [`is_rfc_subject`].
+///
+/// `thread_ids` limits the check to the members of those threads; `None`
+/// checks every email of the list.
+///
+/// # Returns
+/// Number of patch emails that received an initial state
+pub async fn initialize_patch_states(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_ids: Option<&[i32]>,
+) -> Result<u64, sqlx::Error> {
+    if thread_ids.is_some_and(<[i32]>::is_empty) {
+        return Ok(0);
+    }
+    let scope = if thread_ids.is_some() {
+        r#"AND e.id IN (
+                 SELECT tm.email_id
+                 FROM thread_memberships tm
+                 WHERE tm.mailing_list_id = $1 AND tm.thread_id = ANY($2)
+             )"#
+    } else {
+        ""
+    };
+    let sql = format!(
+        r#"SELECT e.id, e.subject
+           FROM emails e
+           WHERE e.mailing_list_id = $1
+             AND {PATCH_EMAIL_SQL}
+             {scope}
+             AND NOT EXISTS (
+                 SELECT 1
+                 FROM patch_states ps
+                 WHERE ps.mailing_list_id = e.mailing_list_id AND ps.email_id = e.id
+             )"#
+    );
+    let mut query = sqlx::query_as(&sql).bind(mailing_list_id);
+    if let Some(thread_ids) = thread_ids {
+        query = query.bind(thread_ids);
+    }
+    let pending: Vec<(i32, String)> = query.fetch_all(pool).await?;
+
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let (email_ids, states): (Vec<i32>, Vec<PatchState>) = pending
+        .into_iter()
+        .map(|(email_id, subject)| (email_id, initial_state(&subject)))
+        .unzip();
+
+    let result = sqlx::query(
+        r#"INSERT INTO patch_states (mailing_list_id, email_id, state)
+           SELECT $1, ids.email_id, ids.state
+           FROM UNNEST($2::int[], $3::patch_state[]) AS ids(email_id, state)
+           ON CONFLICT DO NOTHING"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&email_ids)
+    .bind(&states)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
+
+/// State a newly seen patch starts in.
+fn initial_state(subject: &str) -> PatchState {
+    if is_rfc_subject(subject) {
+        PatchState::Rfc
+    } else {
+        PatchState::New
+    }
+}
+
+/// Pair every series root with the newest revision posted by the same author
+/// under the same normalized subject.
+///
+/// # Returns
+/// `(older_thread_id, newer_thread_id)` for each root that has a newer version
+pub fn find_superseded_threads(roots: &[SeriesRoot]) -> Vec<(i32, i32)> {
+    let mut latest: HashMap<(i32, &str), &SeriesRoot> = HashMap::new();
+    for root in roots {
+        let key = (root.author_id, root.normalized_subject.as_str());
+        latest
+            .entry(key)
+            .and_modify(|current| {
+                if root.version > current.version
+                    || (root.version == current.version && root.thread_id > current.thread_id)
+                {
+                    *current = root;
+                }
+            })
+            .or_insert(root);
+    }
+
+    let mut superseded: Vec<(i32, i32)> = roots
+        .iter()
+        .filter_map(|root| {
+            let newest = latest.get(&(root.author_id, root.normalized_subject.as_str()))?;
+            (newest.version > root.version).then_some((root.thread_id, newest.thread_id))
+        })
+        .collect();
+    superseded.sort_unstable();
+    superseded
+}
+
+/// Mark still-open patches as superseded when a newer version of the same series
+/// has been posted to the list.
+///
+/// `thread_ids` limits the comparison to the series whose roots are among those
+/// threads, against every other version of them on the list; `None` compares
+/// every series of the list.
+///
+/// # Returns
+/// Thread identifiers whose patches changed state
+pub async fn supersede_older_versions(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_ids: Option<&[i32]>,
+) -> Result<Vec<i32>, sqlx::Error> {
+    if thread_ids.is_some_and(<[i32]>::is_empty) {
+        return Ok(Vec::new());
+    }
+    let scope = if thread_ids.is_some() {
+        r#"AND (e.author_id, e.normalized_subject) IN (
+                 SELECT ce.author_id, ce.normalized_subject
+                 FROM threads ct
+                 JOIN emails ce ON ce.mailing_list_id = ct.mailing_list_id
+                     AND ce.message_id = ct.root_message_id
+                 WHERE ct.mailing_list_id = $1 AND ct.id = ANY($2)
+             )"#
+    } else {
+        ""
+    };
+    let sql = format!(
+        r#"SELECT t.id, e.author_id, e.normalized_subject, e.subject
+           FROM threads t
+           JOIN emails e ON e.mailing_list_id = t.mailing_list_id
+               AND e.message_id = t.root_message_id
+           JOIN patch_states ps ON ps.mailing_list_id = e.mailing_list_id
+               AND ps.email_id = e.id
+           WHERE t.mailing_list_id = $1
+             {scope}"#
+    );
+    let mut query = sqlx::query_as(&sql).bind(mailing_list_id);
+    if let Some(thread_ids) = thread_ids {
+        query = query.bind(thread_ids);
+    }
+    let rows: Vec<(i32, i32, Option<String>, String)> = query.fetch_all(pool).await?;
+
+    let roots: Vec<SeriesRoot> = rows
+        .into_iter()
+        .filter_map(|(thread_id, author_id, normalized_subject, subject)| {
+            let normalized_subject = normalized_subject.filter(|s| !s.is_empty())?;
+            Some(SeriesRoot {
+                thread_id,
+                author_id,
+                normalized_subject,
+                version: extract_patch_version(&subject)?,
+            })
+        })
+        .collect();
+
+    let superseded = find_superseded_threads(&roots);
+    if superseded.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let (thread_ids, newer_ids): (Vec<i32>, Vec<i32>) = superseded.into_iter().unzip();
+    let open_states: Vec<PatchState> = PatchState::ALL
+        .into_iter()
+        .filter(PatchState::is_open)
+        .collect();
+
+    let mut tx = pool.begin().await?;
+
+    let changed: Vec<(i32,)> = sqlx::query_as(
+        r#"WITH targets AS (
+               SELECT tm.email_id, tg.newer_thread_id
+               FROM UNNEST($2::int[], $3::int[]) AS tg(thread_id, newer_thread_id)
+               JOIN thread_memberships tm ON tm.mailing_list_id = $1
+                   AND tm.thread_id = tg.thread_id
+           ),
+           history AS (
+               INSERT INTO patch_state_history
+                   (mailing_list_id, email_id, previous_state, state, changed_by, comment)
+               SELECT ps.mailing_list_id, ps.email_id, ps.state, 'superseded', NULL,
+                      'Superseded by newer version in thread ' || tg.newer_thread_id
+               FROM patch_states ps
+               JOIN targets tg ON tg.email_id = ps.email_id
+               WHERE ps.mailing_list_id = $1
+                 AND ps.state = ANY($4)
+           )
+           UPDATE patch_states ps
+           SET state = 'superseded', updated_by = NULL, updated_at = NOW()
+           FROM thread_memberships tm
+           WHERE ps.mailing_list_id = $1
+             AND tm.mailing_list_id = $1
+             AND tm.email_id = ps.email_id
+             AND tm.thread_id = ANY($2)
+             AND ps.state = ANY($4)
+           RETURNING tm.thread_id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(&thread_ids)
+    .bind(&newer_ids)
+    .bind(&open_states)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    let mut changed_threads: Vec<i32> = changed.into_iter().map(|(id,)| id).collect();
+    changed_threads.sort_unstable();
+    changed_threads.dedup();
+    Ok(changed_threads)
+}
+
+/// Whether every patch of the list already went through
+/// [`initialize_patch_states`] and [`supersede_older_versions`].
+pub async fn patch_states_initialized(
+    pool: &PgPool,
+    mailing_list_id: i32,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar("SELECT patch_states_initialized FROM mailing_lists WHERE id = $1")
+        .bind(mailing_list_id)
+        .fetch_optional(pool)
+        .await
+        .map(|initialized| initialized.unwrap_or(false))
+}
+
+/// Record that every patch of the list has a state and was checked for newer
+/// series versions.
+pub async fn record_patch_states_initialized(
+    pool: &PgPool,
+    mailing_list_id: i32,
+) -> Result<(), sqlx::Error> {
+    sqlx::query("UPDATE mailing_lists SET patch_states_initialized = TRUE WHERE id = $1")
+        .bind(mailing_list_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Load the current state of a single patch email.
+pub async fn load_patch_state(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<PatchStateRecord>, sqlx::Error> {
+    sqlx::query_as::<_, PatchStateRecord>(&format!(
+        "{STATE_RECORD_SELECT} WHERE ps.mailing_list_id = $1 AND ps.email_id = $2"
+    ))
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_optional(pool)
+    .await
+}
+
+/// Set the state of one or more patch emails, recording a history entry for every
+/// email whose state actually changes.
+///
+/// Emails without a state yet (e.g. patches the importer didn't recognise) are
+/// given one.
+///
+/// # Returns
+/// The resulting state of each email, ordered by email id
+pub async fn set_patch_states(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_ids: &[i32],
+    state: PatchState,
+    changed_by: Option<i32>,
+    comment: Option<&str>,
+) -> Result<Vec<PatchStateRecord>, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query(
+        r#"INSERT INTO patch_state_history
+               (mailing_list_id, email_id, previous_state, state, changed_by, comment)
+           SELECT $1, ids.email_id, ps.state, $3, $4, $5
+           FROM UNNEST($2::int[]) AS ids(email_id)
+           LEFT JOIN patch_states ps ON ps.mailing_list_id = $1
+               AND ps.email_id = ids.email_id
+           WHERE ps.state IS DISTINCT FROM $3"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_ids)
+    .bind(state)
+    .bind(changed_by)
+    .bind(comment)
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query(
+        r#"INSERT INTO patch_states (mailing_list_id, email_id, state, updated_by, updated_at)
+           SELECT $1, email_id, $3, $4, NOW()
+           FROM UNNEST($2::int[]) AS ids(email_id)
+           ON CONFLICT (mailing_list_id, email_id) DO UPDATE
+           SET state = EXCLUDED.state,
+               updated_by = EXCLUDED.updated_by,
+               updated_at = EXCLUDED.updated_at
+           WHERE patch_states.state IS DISTINCT FROM EXCLUDED.state"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_ids)
+    .bind(state)
+    .bind(changed_by)
+    .execute(&mut *tx)
+    .await?;
+
+    let records = sqlx::query_as::<_, PatchStateRecord>(&format!(
+        "{STATE_RECORD_SELECT} WHERE ps.mailing_list_id = $1 AND ps.email_id = ANY($2) ORDER BY ps.email_id"
+    ))
+    .bind(mailing_list_id)
+    .bind(email_ids)
+    .fetch_all(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(records)
+}
+
+/// Load the state history of a patch email, oldest change first.
+pub async fn load_state_history(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Vec<PatchStateChange>, sqlx::Error> {
+    sqlx::query_as::<_, PatchStateChange>(
+        r#"SELECT h.id, h.email_id, h.previous_state, h.state, h.changed_by,
+                  COALESCE(u.display_name, u.email) AS changed_by_name,
+                  h.comment, h.changed_at
+           FROM patch_state_history h
+           LEFT JOIN users u ON u.id = h.changed_by
+           WHERE h.mailing_list_id = $1 AND h.email_id = $2
+           ORDER BY h.changed_at, h.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Distinct patch states per thread, for refreshing search documents.
+pub async fn thread_patch_states(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_ids: &[i32],
+) -> Result<Vec<(i32, Vec<PatchState>)>, sqlx::Error> {
+    sqlx::query_as(
+        r#"SELECT ids.thread_id,
+                  COALESCE(
+                      (SELECT array_agg(DISTINCT ps.state)
+                       FROM thread_memberships tm
+                       JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id
+                           AND ps.email_id = tm.email_id
+                       WHERE tm.mailing_list_id = $1 AND tm.thread_id = ids.thread_id),
+                      '{}'
+                  ) AS patch_states
+           FROM UNNEST($2::int[]) AS ids(thread_id)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn root(thread_id: i32, author_id: i32, subject: &str, version: i32) -> SeriesRoot {
+        SeriesRoot {
+            thread_id,
+            author_id,
+            normalized_subject: subject.to_string(),
+            version,
+        }
+    }
+
+    #[test]
+    fn starts_rfc_postings_in_rfc_state() {
+        assert_eq!(
+            initial_state("[RFC PATCH 1/2] mm: try this"),
+            PatchState::Rfc
+        );
+        assert_eq!(
+            initial_state("[PATCH RFC v2] mm: try this"),
+            PatchState::Rfc
+        );
+        assert_eq!(
+            initial_state("[PATCH v2] mm: RFC in the subject"),
+            PatchState::New
+        );
+    }
+
+    #[test]
+    fn supersedes_older_versions_of_same_series() {
+        let roots = vec![
+            root(1, 10, "net: fix leak", 1),
+            root(5, 10, "net: fix leak", 2),
+            root(9, 10, "net: fix leak", 3),
+            root(3, 10, "mm: unrelated", 1),
+        ];
+
+        assert_eq!(find_superseded_threads(&roots), vec![(1, 9), (5, 9)]);
+    }
+
+    #[test]
+    fn ignores_other_authors_and_resends() {
+        let roots = vec![
+            root(1, 10, "net: fix leak", 1),
+            root(2, 11, "net: fix leak", 2),
+            root(3, 12, "mm: resend", 2),
+            root(4, 12, "mm: resend", 2),
+        ];
+
+        assert!(find_superseded_threads(&roots).is_empty());
+    }
+}
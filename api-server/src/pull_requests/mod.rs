@@ -0,0 +1,20 @@
+//! `[GIT PULL]` request tracking.
+//!
+//! Recognizes pull request emails at import time and extracts the repository,
+//! tag or branch, base and head commits, and shortlog from the
+//! `git request-pull` text. Upstream scans mark a request merged once its head
+//! commit shows up in the scanned tree.
+//!
+//! ## Module Structure
+//!
+//! - `parser`: Pull request detection and `git request-pull` field extraction
+//! - `processor`: Import-time email processor writing `pull_requests`
+//! - `store`: Pull request queries and merge status updates
+
+mod parser;
+mod processor;
+mod store;
+
+pub use parser::{PullRequestInfo, parse_pull_request};
+pub use processor::PullRequestProcessor;
+pub use store::{list_pull_requests, load_pull_request, mark_pulls_merged, open_pull_heads};
@@ -0,0 +1,233 @@
+//! Recognize pull request emails and extract their `git request-pull` fields.
+
+use crate::models::PullShortlogEntry;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// Fields of a pull request email.
+///
+/// Any field can be missing when the sender edited the generated text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PullRequestInfo {
+    pub repository_url: Option<String>,
+    /// Tag or branch to pull, as written (e.g. `tags/net-6.9-rc1`).
+    pub reference: Option<String>,
+    pub base_commit: Option<String>,
+    pub head_commit: Option<String>,
+    pub shortlog: Vec<PullShortlogEntry>,
+}
+
+fn subject_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)\[[^\]]*\bGIT\s+PULL\b[^\]]*\]").expect("valid pull subject regex")
+    })
+}
+
+fn reply_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^\s*(re|fwd?|aw)\s*:").expect("valid reply regex"))
+}
+
+fn commit_line_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(
+            r"(?i)^(the following changes since commit|for you to fetch changes up to)\s+([0-9a-f]{40})\b",
+        )
+        .expect("valid request-pull commit regex")
+    })
+}
+
+fn shortlog_author_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"^(\S.*?) \(\d+\):$").expect("valid shortlog author regex"))
+}
+
+/// Recognize a pull request and extract its fields.
+///
+/// An email is a pull request when its subject carries a `[GIT PULL]` tag or
+/// its body contains `git request-pull` output. Replies never are, even
+/// though they usually quote the request.
+pub fn parse_pull_request(subject: &str, body: &str) -> Option<PullRequestInfo> {
+    if reply_regex().is_match(subject) {
+        return None;
+    }
+
+    let lines: Vec<&str> = body.lines().map(str::trim_end).collect();
+    let mut info = PullRequestInfo::default();
+    let mut has_request_pull_text = false;
+
+    for (index, line) in lines.iter().enumerate() {
+        if let Some(caps) = commit_line_regex().captures(line) {
+            has_request_pull_text = true;
+            let commit = caps[2].to_ascii_lowercase();
+            if caps[1].eq_ignore_ascii_case("the following changes since commit") {
+                info.base_commit.get_or_insert(commit);
+            } else {
+                info.head_commit.get_or_insert(commit);
+            }
+        } else if line
+            .to_ascii_lowercase()
+            .starts_with("are available in the git repository at")
+        {
+            has_request_pull_text = true;
+            if let Some(location) = lines[index + 1..].iter().find(|l| !l.trim().is_empty()) {
+                let mut parts = location.split_whitespace();
+                info.repository_url = parts.next().map(str::to_string);
+                info.reference = parts.next().map(str::to_string);
+            }
+        }
+    }
+
+    if !has_request_pull_text && !subject_regex().is_match(subject) {
+        return None;
+    }
+
+    info.shortlog = parse_shortlog(&lines);
+    Some(info)
+}
+
+/// Parse the shortlog that follows the last dashed separator line.
+///
+/// Stops at the diffstat.
+fn parse_shortlog(lines: &[&str]) -> Vec<PullShortlogEntry> {
+    let Some(separator) = lines
+        .iter()
+        .rposition(|line| line.len() >= 20 && line.bytes().all(|b| b == b'-'))
+    else {
+        return Vec::new();
+    };
+
+    let mut entries: Vec<PullShortlogEntry> = Vec::new();
+    for line in &lines[separator + 1..] {
+        if is_diffstat_line(line) {
+            break;
+        }
+
+        if let Some(caps) = shortlog_author_regex().captures(line) {
+            entries.push(PullShortlogEntry {
+                author: caps[1].to_string(),
+                subjects: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(entry) = entries.last_mut() else {
+            continue;
+        };
+        let indent = line.len() - line.trim_start().len();
+        let text = line.trim();
+        if text.is_empty() {
+            continue;
+        }
+        match entry.subjects.last_mut() {
+            // `git shortlog -w` indents wrapped subject lines deeper.
+            Some(subject) if indent > 6 => {
+                subject.push(' ');
+                subject.push_str(text);
+            }
+            _ => entry.subjects.push(text.to_string()),
+        }
+    }
+
+    entries.retain(|entry| !entry.subjects.is_empty());
+    entries
+}
+
+fn is_diffstat_line(line: &str) -> bool {
+    let trimmed = line.trim();
+    (line.starts_with(' ') && trimmed.contains(" | "))
+        || (trimmed.contains(" changed,") && trimmed.contains("file"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REQUEST: &str = "Hi Linus,
+
+Please pull the networking fixes.
+
+The following changes since commit 6613476e225e090cc9aad49be7fa504e290dd33d:
+
+  Linux 6.8-rc1 (2024-01-21 14:11:32 -0800)
+
+are available in the Git repository at:
+
+  git://git.kernel.org/pub/scm/linux/kernel/git/netdev/net.git tags/net-6.8-rc2
+
+for you to fetch changes up to 0a5bd0ffe790511d802e7f40898429a89e2487df:
+
+  net: fix a leak (2024-01-25 10:00:00 -0800)
+
+----------------------------------------------------------------
+Networking fixes for 6.8-rc2.
+
+----------------------------------------------------------------
+Jane Doe (2):
+      net: fix a leak
+      net: fix a very long subject line that git shortlog wrapped onto
+         a second line
+
+John Smith (1):
+      tcp: fix a race
+
+ net/core/dev.c | 4 ++--
+ 1 file changed, 2 insertions(+), 2 deletions(-)
+";
+
+    #[test]
+    fn extracts_request_pull_fields() {
+        let info = parse_pull_request("[GIT PULL] Networking for v6.8-rc2", REQUEST).unwrap();
+
+        assert_eq!(
+            info.repository_url.as_deref(),
+            Some("git://git.kernel.org/pub/scm/linux/kernel/git/netdev/net.git")
+        );
+        assert_eq!(info.reference.as_deref(), Some("tags/net-6.8-rc2"));
+        assert_eq!(
+            info.base_commit.as_deref(),
+            Some("6613476e225e090cc9aad49be7fa504e290dd33d")
+        );
+        assert_eq!(
+            info.head_commit.as_deref(),
+            Some("0a5bd0ffe790511d802e7f40898429a89e2487df")
+        );
+        assert_eq!(
+            info.shortlog,
+            vec![
+                PullShortlogEntry {
+                    author: "Jane Doe".to_string(),
+                    subjects: vec![
+                        "net: fix a leak".to_string(),
+                        "net: fix a very long subject line that git shortlog wrapped onto a second line"
+                            .to_string(),
+                    ],
+                },
+                PullShortlogEntry {
+                    author: "John Smith".to_string(),
+                    subjects: vec!["tcp: fix a race".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn detects_request_pull_text_without_subject_tag() {
+        let info = parse_pull_request("Networking for 6.8-rc2", REQUEST).unwrap();
+        assert_eq!(info.reference.as_deref(), Some("tags/net-6.8-rc2"));
+    }
+
+    #[test]
+    fn subject_tag_alone_is_a_pull_request() {
+        let info = parse_pull_request("[GIT PULL v2] drm fixes", "Please pull.").unwrap();
+        assert_eq!(info, PullRequestInfo::default());
+    }
+
+    #[test]
+    fn ignores_replies_and_plain_mail() {
+        assert!(parse_pull_request("Re: [GIT PULL] Networking for v6.8-rc2", REQUEST).is_none());
+        assert!(parse_pull_request("[PATCH] net: fix a leak", "Plain patch.").is_none());
+    }
+}
@@ -0,0 +1,83 @@
+//! Email processor writing `[GIT PULL]` requests to `pull_requests`.
+
+use crate::pull_requests::parse_pull_request;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Parses pull request emails into `pull_requests` rows.
+pub struct PullRequestProcessor;
+
+impl EmailProcessor for PullRequestProcessor {
+    fn name(&self) -> &'static str {
+        "pull_requests"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut repository_urls = Vec::new();
+            let mut references = Vec::new();
+            let mut base_commits = Vec::new();
+            let mut head_commits = Vec::new();
+            let mut shortlogs = Vec::new();
+
+            for email in emails {
+                let Some(pull) = parse_pull_request(&email.subject, &email.body) else {
+                    continue;
+                };
+                email_ids.push(email.email_id);
+                repository_urls.push(pull.repository_url);
+                references.push(pull.reference);
+                base_commits.push(pull.base_commit);
+                head_commits.push(pull.head_commit);
+                shortlogs.push(serde_json::to_value(&pull.shortlog).unwrap_or_default());
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO pull_requests (
+                       mailing_list_id, email_id, repository_url, reference,
+                       base_commit, head_commit, shortlog
+                   )
+                   SELECT $1, * FROM UNNEST(
+                       $2::int[], $3::text[], $4::text[], $5::text[], $6::text[], $7::jsonb[]
+                   )
+                   ON CONFLICT (mailing_list_id, email_id) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&repository_urls)
+            .bind(&references)
+            .bind(&base_commits)
+            .bind(&head_commits)
+            .bind(&shortlogs)
+            .execute(pool)
+            .await?;
+
+            log::trace!("bulk inserted {} pull requests", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM pull_requests WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
@@ -0,0 +1,123 @@
+//! Pull request queries and upstream merge status updates.
+
+use crate::models::{PullRequest, PullRequestStatus};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+const PULL_REQUEST_COLUMNS: &str = r#"
+    pr.email_id,
+    tm.thread_id,
+    e.subject,
+    a.canonical_name AS author_name,
+    a.email AS author_email,
+    e.date,
+    pr.repository_url,
+    pr.reference,
+    pr.base_commit,
+    pr.head_commit,
+    pr.shortlog,
+    pr.status,
+    t.name AS merged_tree,
+    pr.merged_detected_at
+"#;
+
+const PULL_REQUEST_JOINS: &str = r#"
+    FROM pull_requests pr
+    JOIN emails e ON e.mailing_list_id = pr.mailing_list_id AND e.id = pr.email_id
+    JOIN authors a ON a.id = e.author_id
+    LEFT JOIN thread_memberships tm ON tm.mailing_list_id = pr.mailing_list_id
+        AND tm.email_id = pr.email_id
+    LEFT JOIN upstream_trees t ON t.id = pr.merged_tree_id
+"#;
+
+/// Pull requests of a list, newest first, with the total count.
+pub async fn list_pull_requests(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    status: Option<PullRequestStatus>,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<PullRequest>, i64), sqlx::Error> {
+    let total: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*)
+           FROM pull_requests
+           WHERE mailing_list_id = $1
+             AND ($2::pull_request_status IS NULL OR status = $2)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(status)
+    .fetch_one(pool)
+    .await?;
+
+    let query = format!(
+        r#"SELECT {PULL_REQUEST_COLUMNS}
+           {PULL_REQUEST_JOINS}
+           WHERE pr.mailing_list_id = $1
+             AND ($2::pull_request_status IS NULL OR pr.status = $2)
+           ORDER BY e.date DESC, pr.email_id DESC
+           LIMIT $3 OFFSET $4"#
+    );
+    let pulls = sqlx::query_as::<_, PullRequest>(&query)
+        .bind(mailing_list_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok((pulls, total))
+}
+
+/// Pull request details of an email, or `None` if it isn't a pull request.
+pub async fn load_pull_request(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<Option<PullRequest>, sqlx::Error> {
+    let query = format!(
+        r#"SELECT {PULL_REQUEST_COLUMNS}
+           {PULL_REQUEST_JOINS}
+           WHERE pr.mailing_list_id = $1 AND pr.email_id = $2"#
+    );
+    sqlx::query_as::<_, PullRequest>(&query)
+        .bind(mailing_list_id)
+        .bind(email_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Distinct head commits of open pull requests, to check against an upstream tree.
+pub async fn open_pull_heads(pool: &PgPool) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"SELECT DISTINCT head_commit
+           FROM pull_requests
+           WHERE status = 'open' AND head_commit IS NOT NULL"#,
+    )
+    .fetch_all(pool)
+    .await
+}
+
+/// Mark open pull requests whose head commit is in `tree_id` as merged.
+///
+/// # Returns
+/// Number of pull requests marked merged
+pub async fn mark_pulls_merged(
+    pool: &PgPool,
+    tree_id: i32,
+    head_commits: &[String],
+) -> Result<u64, sqlx::Error> {
+    if head_commits.is_empty() {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        r#"UPDATE pull_requests
+           SET status = 'merged', merged_tree_id = $1, merged_detected_at = NOW()
+           WHERE status = 'open' AND head_commit = ANY($2)"#,
+    )
+    .bind(tree_id)
+    .bind(head_commits)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -0,0 +1,28 @@
+//! Regression tracking from `#regzbot` commands.
+//!
+//! Regression reports carry regzbot commands such as
+//! `#regzbot introduced: <commit>`, `#regzbot title:` and `#regzbot fix:`.
+//! Imports extract the commands of every email; after threading, each report
+//! thread's commands are folded into one regression with its state,
+//! introducing commit, fix and related threads. The commands themselves are
+//! kept as the regression's history, and every state change is recorded with
+//! the email that caused it.
+//!
+//! ## Module Structure
+//!
+//! - `parser`: Extraction of `#regzbot` commands from email bodies
+//! - `processor`: Import-time email processor writing `regzbot_commands`
+//! - `store`: Regression and command history queries
+//! - `tracker`: Folding commands into per-thread regressions
+
+mod parser;
+mod processor;
+mod store;
+mod tracker;
+
+pub use parser::{RegzbotCommand, parse_regzbot_commands};
+pub use processor::RegzbotCommandProcessor;
+pub use store::{
+    list_regressions, load_regression, load_regression_history, load_regression_state_history,
+};
+pub use tracker::{RegressionSummary, refresh_regressions, summarize_commands};
@@ -0,0 +1,125 @@
+//! Extract `#regzbot` commands from email bodies.
+
+use crate::models::RegzbotCommandKind;
+use regex::Regex;
+use std::sync::OnceLock;
+
+/// A `#regzbot` command and its argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegzbotCommand {
+    pub kind: RegzbotCommandKind,
+    pub argument: String,
+}
+
+fn command_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| {
+        Regex::new(r"(?i)^#regzbot\s+\^?([a-z-]+)\b:?\s*(.*)$").expect("valid regzbot regex")
+    })
+}
+
+/// Parse the `#regzbot` commands of an email, in body order.
+///
+/// Commands must start a line; quoted lines are skipped so replies don't
+/// repeat the commands they quote. Unknown commands and commands missing a
+/// required argument are ignored.
+pub fn parse_regzbot_commands(body: &str) -> Vec<RegzbotCommand> {
+    body.lines()
+        .filter(|line| !line.starts_with('>'))
+        .filter_map(|line| {
+            let caps = command_regex().captures(line.trim())?;
+            let kind = command_kind(&caps[1])?;
+            let argument = caps[2].trim().to_string();
+            let needs_argument = !matches!(
+                kind,
+                RegzbotCommandKind::Resolve | RegzbotCommandKind::Invalid
+            );
+            if needs_argument && argument.is_empty() {
+                return None;
+            }
+            Some(RegzbotCommand { kind, argument })
+        })
+        .collect()
+}
+
+fn command_kind(keyword: &str) -> Option<RegzbotCommandKind> {
+    let kind = match keyword.to_ascii_lowercase().as_str() {
+        "introduced" => RegzbotCommandKind::Introduced,
+        "title" => RegzbotCommandKind::Title,
+        "fix" | "fixed-by" => RegzbotCommandKind::Fix,
+        "link" => RegzbotCommandKind::Link,
+        "monitor" => RegzbotCommandKind::Monitor,
+        "resolve" => RegzbotCommandKind::Resolve,
+        "invalid" => RegzbotCommandKind::Invalid,
+        _ => return None,
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(kind: RegzbotCommandKind, argument: &str) -> RegzbotCommand {
+        RegzbotCommand {
+            kind,
+            argument: argument.to_string(),
+        }
+    }
+
+    #[test]
+    fn parses_commands_in_body_order() {
+        let body = "Hi,
+
+suspend broke on my laptop after updating to 6.8.
+
+#regzbot introduced: v6.7..v6.8
+#regzbot title: thinkpad: resume hangs with black screen
+#regzbot ^introduced 1a2b3c4d5e6f
+#regzbot link: https://bugzilla.kernel.org/show_bug.cgi?id=218000
+";
+
+        assert_eq!(
+            parse_regzbot_commands(body),
+            vec![
+                command(RegzbotCommandKind::Introduced, "v6.7..v6.8"),
+                command(
+                    RegzbotCommandKind::Title,
+                    "thinkpad: resume hangs with black screen"
+                ),
+                command(RegzbotCommandKind::Introduced, "1a2b3c4d5e6f"),
+                command(
+                    RegzbotCommandKind::Link,
+                    "https://bugzilla.kernel.org/show_bug.cgi?id=218000"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn accepts_aliases_and_argumentless_commands() {
+        let body = "#REGZBOT fixed-by: 0123456789ab (\"drm/i915: fix resume\")\n#regzbot resolve\n";
+
+        assert_eq!(
+            parse_regzbot_commands(body),
+            vec![
+                command(
+                    RegzbotCommandKind::Fix,
+                    "0123456789ab (\"drm/i915: fix resume\")"
+                ),
+                command(RegzbotCommandKind::Resolve, ""),
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_quoted_unknown_and_incomplete_commands() {
+        let body = "> #regzbot introduced: v6.7..v6.8
+#regzbot dup-of: https://lore.kernel.org/all/abc@example.com/
+#regzbot fix:
+see #regzbot title: not at line start
+";
+
+        assert!(parse_regzbot_commands(body).is_empty());
+    }
+}
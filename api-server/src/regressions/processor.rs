@@ -0,0 +1,76 @@
+//! Email processor writing `#regzbot` commands to `regzbot_commands`.
+
+use crate::regressions::parse_regzbot_commands;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Parses `#regzbot` commands into `regzbot_commands` rows, one per command
+/// in body order.
+pub struct RegzbotCommandProcessor;
+
+impl EmailProcessor for RegzbotCommandProcessor {
+    fn name(&self) -> &'static str {
+        "regzbot_commands"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut positions = Vec::new();
+            let mut commands = Vec::new();
+            let mut arguments = Vec::new();
+
+            for email in emails {
+                for (position, command) in
+                    parse_regzbot_commands(&email.body).into_iter().enumerate()
+                {
+                    email_ids.push(email.email_id);
+                    positions.push(position as i32 + 1);
+                    commands.push(command.kind);
+                    arguments.push(command.argument);
+                }
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO regzbot_commands (mailing_list_id, email_id, position, command, argument)
+                   SELECT $1, * FROM UNNEST(
+                       $2::int[], $3::int[], $4::regzbot_command_kind[], $5::text[]
+                   )
+                   ON CONFLICT (mailing_list_id, email_id, position) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&positions)
+            .bind(&commands)
+            .bind(&arguments)
+            .execute(pool)
+            .await?;
+
+            log::trace!("bulk inserted {} regzbot commands", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM regzbot_commands WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
@@ -0,0 +1,109 @@
+//! Regression queries.
+
+use crate::models::{Regression, RegressionState, RegressionStateChange, RegzbotCommandEntry};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+const REGRESSION_COLUMNS: &str = r#"
+    thread_id, title, state, introduced, fix, related_threads,
+    report_email_id, reported_at, updated_at
+"#;
+
+/// Regressions of a list in any of `states`, most recently updated first,
+/// with the total count.
+pub async fn list_regressions(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    states: &[RegressionState],
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<Regression>, i64), sqlx::Error> {
+    let total: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*)
+           FROM regressions
+           WHERE mailing_list_id = $1 AND state = ANY($2::regression_state[])"#,
+    )
+    .bind(mailing_list_id)
+    .bind(states)
+    .fetch_one(pool)
+    .await?;
+
+    let query = format!(
+        r#"SELECT {REGRESSION_COLUMNS}
+           FROM regressions
+           WHERE mailing_list_id = $1 AND state = ANY($2::regression_state[])
+           ORDER BY updated_at DESC, thread_id DESC
+           LIMIT $3 OFFSET $4"#
+    );
+    let regressions = sqlx::query_as::<_, Regression>(&query)
+        .bind(mailing_list_id)
+        .bind(states)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(pool)
+        .await?;
+
+    Ok((regressions, total))
+}
+
+/// Regression tracked in a thread, or `None` if the thread doesn't track one.
+pub async fn load_regression(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_id: i32,
+) -> Result<Option<Regression>, sqlx::Error> {
+    let query = format!(
+        r#"SELECT {REGRESSION_COLUMNS}
+           FROM regressions
+           WHERE mailing_list_id = $1 AND thread_id = $2"#
+    );
+    sqlx::query_as::<_, Regression>(&query)
+        .bind(mailing_list_id)
+        .bind(thread_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// `#regzbot` commands posted in a thread, oldest first.
+pub async fn load_regression_history(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_id: i32,
+) -> Result<Vec<RegzbotCommandEntry>, sqlx::Error> {
+    sqlx::query_as::<_, RegzbotCommandEntry>(
+        r#"SELECT rc.email_id,
+                  a.canonical_name AS author_name,
+                  a.email AS author_email,
+                  e.date,
+                  rc.command,
+                  rc.argument
+           FROM regzbot_commands rc
+           JOIN thread_memberships tm ON tm.mailing_list_id = rc.mailing_list_id
+               AND tm.email_id = rc.email_id
+           JOIN emails e ON e.mailing_list_id = rc.mailing_list_id AND e.id = rc.email_id
+           JOIN authors a ON a.id = e.author_id
+           WHERE rc.mailing_list_id = $1 AND tm.thread_id = $2
+           ORDER BY e.date, rc.email_id, rc.position"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// State changes of the regression tracked in a thread, oldest first.
+pub async fn load_regression_state_history(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_id: i32,
+) -> Result<Vec<RegressionStateChange>, sqlx::Error> {
+    sqlx::query_as::<_, RegressionStateChange>(
+        r#"SELECT id, previous_state, state, email_id, changed_at
+           FROM regression_state_history
+           WHERE mailing_list_id = $1 AND thread_id = $2
+           ORDER BY changed_at, id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_all(pool)
+    .await
+}
@@ -0,0 +1,292 @@
+//! Fold `#regzbot` commands into per-thread regressions.
+
+use crate::models::{RegressionState, RegzbotCommandKind};
+use crate::regressions::parser::RegzbotCommand;
+use chrono::{DateTime, Utc};
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::HashMap;
+
+/// Regression fields derived from a thread's commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RegressionSummary {
+    pub title: Option<String>,
+    pub state: RegressionState,
+    pub introduced: String,
+    pub fix: Option<String>,
+    pub related_threads: Vec<String>,
+}
+
+/// Apply a thread's commands in chronological order.
+///
+/// A thread only tracks a regression once an `introduced` command names the
+/// culprit; later commands override earlier ones, so a corrected range or
+/// title replaces the first one.
+pub fn summarize_commands(commands: &[RegzbotCommand]) -> Option<RegressionSummary> {
+    let mut introduced = None;
+    let mut summary = RegressionSummary {
+        title: None,
+        state: RegressionState::Open,
+        introduced: String::new(),
+        fix: None,
+        related_threads: Vec::new(),
+    };
+
+    for command in commands {
+        let argument = command.argument.clone();
+        match command.kind {
+            RegzbotCommandKind::Introduced => introduced = Some(argument),
+            RegzbotCommandKind::Title => summary.title = Some(argument),
+            RegzbotCommandKind::Fix => {
+                summary.fix = Some(argument);
+                summary.state = RegressionState::Fixed;
+            }
+            RegzbotCommandKind::Link | RegzbotCommandKind::Monitor => {
+                if !summary.related_threads.contains(&argument) {
+                    summary.related_threads.push(argument);
+                }
+            }
+            RegzbotCommandKind::Resolve => summary.state = RegressionState::Resolved,
+            RegzbotCommandKind::Invalid => summary.state = RegressionState::Invalid,
+        }
+    }
+
+    summary.introduced = introduced?;
+    Some(summary)
+}
+
+#[derive(sqlx::FromRow)]
+struct ThreadCommandRow {
+    thread_id: i32,
+    thread_subject: String,
+    email_id: i32,
+    date: DateTime<Utc>,
+    command: RegzbotCommandKind,
+    argument: String,
+}
+
+/// Fold the threaded `#regzbot` commands of a list into regressions.
+///
+/// Only `thread_ids` are refreshed when given, otherwise every thread of the
+/// list. Regressions are updated in place and a history entry is appended
+/// whenever a regression starts being tracked or its state changes; threads
+/// that no longer track a regression lose their row. Must run after threading
+/// since regressions are keyed by report thread.
+///
+/// # Returns
+/// Number of regressions tracked in the refreshed threads
+pub async fn refresh_regressions(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    thread_ids: Option<&[i32]>,
+) -> Result<usize, sqlx::Error> {
+    let rows: Vec<ThreadCommandRow> = sqlx::query_as(
+        r#"SELECT tm.thread_id,
+                  t.subject AS thread_subject,
+                  rc.email_id,
+                  e.date,
+                  rc.command,
+                  rc.argument
+           FROM regzbot_commands rc
+           JOIN emails e ON e.mailing_list_id = rc.mailing_list_id AND e.id = rc.email_id
+           JOIN thread_memberships tm ON tm.mailing_list_id = rc.mailing_list_id
+               AND tm.email_id = rc.email_id
+           JOIN threads t ON t.mailing_list_id = tm.mailing_list_id AND t.id = tm.thread_id
+           WHERE rc.mailing_list_id = $1
+             AND ($2::int[] IS NULL OR tm.thread_id = ANY($2))
+           ORDER BY tm.thread_id, e.date, rc.email_id, rc.position"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = pool.begin().await?;
+
+    let previous: HashMap<i32, RegressionState> = sqlx::query_as(
+        r#"SELECT thread_id, state
+           FROM regressions
+           WHERE mailing_list_id = $1
+             AND ($2::int[] IS NULL OR thread_id = ANY($2))
+           FOR UPDATE"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .fetch_all(&mut *tx)
+    .await?
+    .into_iter()
+    .collect();
+
+    let mut tracked = Vec::new();
+    for thread_rows in rows.chunk_by(|a, b| a.thread_id == b.thread_id) {
+        let commands: Vec<RegzbotCommand> = thread_rows
+            .iter()
+            .map(|row| RegzbotCommand {
+                kind: row.command,
+                argument: row.argument.clone(),
+            })
+            .collect();
+        let Some(summary) = summarize_commands(&commands) else {
+            continue;
+        };
+        let Some(report) = thread_rows
+            .iter()
+            .find(|row| row.command == RegzbotCommandKind::Introduced)
+        else {
+            continue;
+        };
+        let first = &thread_rows[0];
+        let updated_at = thread_rows
+            .iter()
+            .map(|row| row.date)
+            .max()
+            .unwrap_or(first.date);
+
+        let previous_state = previous.get(&first.thread_id).copied();
+        if previous_state != Some(summary.state) {
+            // The change is attributed to the last command that set the state
+            let cause = thread_rows
+                .iter()
+                .rev()
+                .find(|row| sets_state(row.command))
+                .filter(|_| summary.state != RegressionState::Open)
+                .unwrap_or(report);
+            sqlx::query(
+                r#"INSERT INTO regression_state_history
+                       (mailing_list_id, thread_id, previous_state, state, email_id, changed_at)
+                   VALUES ($1, $2, $3, $4, $5, $6)"#,
+            )
+            .bind(mailing_list_id)
+            .bind(first.thread_id)
+            .bind(previous_state)
+            .bind(summary.state)
+            .bind(cause.email_id)
+            .bind(cause.date)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"INSERT INTO regressions (
+                   mailing_list_id, thread_id, title, state, introduced, fix,
+                   related_threads, report_email_id, reported_at, updated_at
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+               ON CONFLICT (mailing_list_id, thread_id) DO UPDATE
+               SET title = EXCLUDED.title,
+                   state = EXCLUDED.state,
+                   introduced = EXCLUDED.introduced,
+                   fix = EXCLUDED.fix,
+                   related_threads = EXCLUDED.related_threads,
+                   report_email_id = EXCLUDED.report_email_id,
+                   reported_at = EXCLUDED.reported_at,
+                   updated_at = EXCLUDED.updated_at"#,
+        )
+        .bind(mailing_list_id)
+        .bind(first.thread_id)
+        .bind(
+            summary
+                .title
+                .unwrap_or_else(|| first.thread_subject.clone()),
+        )
+        .bind(summary.state)
+        .bind(&summary.introduced)
+        .bind(&summary.fix)
+        .bind(&summary.related_threads)
+        .bind(report.email_id)
+        .bind(report.date)
+        .bind(updated_at)
+        .execute(&mut *tx)
+        .await?;
+        tracked.push(first.thread_id);
+    }
+
+    sqlx::query(
+        r#"DELETE FROM regressions
+           WHERE mailing_list_id = $1
+             AND ($2::int[] IS NULL OR thread_id = ANY($2))
+             AND thread_id <> ALL($3)"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_ids)
+    .bind(&tracked)
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+    Ok(tracked.len())
+}
+
+/// Whether a command moves a regression to another state.
+fn sets_state(kind: RegzbotCommandKind) -> bool {
+    matches!(
+        kind,
+        RegzbotCommandKind::Fix | RegzbotCommandKind::Resolve | RegzbotCommandKind::Invalid
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(kind: RegzbotCommandKind, argument: &str) -> RegzbotCommand {
+        RegzbotCommand {
+            kind,
+            argument: argument.to_string(),
+        }
+    }
+
+    #[test]
+    fn requires_an_introduced_command() {
+        assert_eq!(
+            summarize_commands(&[command(RegzbotCommandKind::Title, "resume hangs")]),
+            None
+        );
+    }
+
+    #[test]
+    fn later_commands_override_earlier_ones() {
+        let summary = summarize_commands(&[
+            command(RegzbotCommandKind::Introduced, "v6.7..v6.8"),
+            command(RegzbotCommandKind::Title, "resume hangs"),
+            command(
+                RegzbotCommandKind::Monitor,
+                "https://lore.kernel.org/all/fix@x/",
+            ),
+            command(RegzbotCommandKind::Introduced, "1a2b3c4d5e6f"),
+            command(
+                RegzbotCommandKind::Link,
+                "https://lore.kernel.org/all/fix@x/",
+            ),
+            command(RegzbotCommandKind::Fix, "0123456789ab"),
+        ])
+        .unwrap();
+
+        assert_eq!(
+            summary,
+            RegressionSummary {
+                title: Some("resume hangs".to_string()),
+                state: RegressionState::Fixed,
+                introduced: "1a2b3c4d5e6f".to_string(),
+                fix: Some("0123456789ab".to_string()),
+                related_threads: vec!["https://lore.kernel.org/all/fix@x/".to_string()],
+            }
+        );
+    }
+
+    #[test]
+    fn resolve_and_invalid_close_the_regression() {
+        let resolved = summarize_commands(&[
+            command(RegzbotCommandKind::Introduced, "v6.7..v6.8"),
+            command(RegzbotCommandKind::Resolve, "reverted upstream"),
+        ])
+        .unwrap();
+        assert_eq!(resolved.state, RegressionState::Resolved);
+
+        let invalid = summarize_commands(&[
+            command(RegzbotCommandKind::Introduced, "v6.7..v6.8"),
+            command(RegzbotCommandKind::Invalid, ""),
+        ])
+        .unwrap();
+        assert_eq!(invalid.state, RegressionState::Invalid);
+    }
+}
@@ -3,6 +3,7 @@
 use crate::auth::RequireAdmin;
 use crate::error::ApiError;
 use crate::models::{ApiResponse, ResponseMeta};
+use crate::sync::jobs::{JobRegistry, JobTypeInfo};
 use crate::sync::pg_config::PgConfig;
 use crate::sync::queue::{JobQueue, JobRecord, JobStatus, JobType};
 use crate::sync::reset_database;
@@ -13,6 +14,7 @@ use rocket_okapi::okapi::schemars::JsonSchema;
 use rocket_okapi::openapi;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, rocket::form::FromForm)]
 #[serde(rename_all = "camelCase")]
@@ -102,6 +104,7 @@ pub struct DatabaseStatusResponse {
 pub async fn list_jobs(
     _admin: RequireAdmin,
     pool: &State<sqlx::PgPool>,
+    registry: &State<Arc<JobRegistry>>,
     params: Option<JobListParams>,
 ) -> Result<Json<ApiResponse<Vec<JobRecord>>>, ApiError> {
     let params = params.unwrap_or_default();
@@ -109,7 +112,7 @@ pub async fn list_jobs(
     let page_size = params.page_size;
 
     let statuses = parse_status_filters(&params.status)?;
-    let types = parse_type_filters(&params.job_type)?;
+    let types = parse_type_filters(registry, &params.job_type)?;
 
     let queue = JobQueue::new(pool.inner().clone());
     let (jobs, total) = queue.list_jobs(&statuses, &types, page, page_size).await?;
@@ -154,6 +157,7 @@ pub async fn list_jobs(
 pub async fn create_job(
     _admin: RequireAdmin,
     pool: &State<sqlx::PgPool>,
+    registry: &State<Arc<JobRegistry>>,
     request: Json<CreateJobRequest>,
 ) -> Result<Json<ApiResponse<JobRecord>>, ApiError> {
     let data = request.into_inner();
@@ -171,6 +175,10 @@ pub async fn create_job(
         data.payload
     };
 
+    registry
+        .validate(&data.job_type, mailing_list_id, &payload)
+        .map_err(ApiError::BadRequest)?;
+
     let priority = data.priority.unwrap_or(0);
     let job_id = queue
         .enqueue_job(data.job_type, mailing_list_id, payload, priority)
@@ -184,6 +192,17 @@ pub async fn create_job(
     Ok(Json(ApiResponse::new(job)))
 }
 
+/// List the job types the dispatcher can run, with the JSON schema each one
+/// validates its payload against.
+#[openapi(tag = "Admin - Jobs")]
+#[get("/job-types")]
+pub async fn list_job_types(
+    _admin: RequireAdmin,
+    registry: &State<Arc<JobRegistry>>,
+) -> Result<Json<ApiResponse<Vec<JobTypeInfo>>>, ApiError> {
+    Ok(Json(ApiResponse::new(registry.describe())))
+}
+
 #[openapi(tag = "Admin - Jobs")]
 #[get("/jobs/<job_id>")]
 pub async fn get_job(
@@ -394,13 +413,15 @@ fn parse_status_filters(values: &[String]) -> Result<Vec<JobStatus>, ApiError> {
         .collect()
 }
 
-fn parse_type_filters(values: &[String]) -> Result<Vec<JobType>, ApiError> {
+fn parse_type_filters(registry: &JobRegistry, values: &[String]) -> Result<Vec<JobType>, ApiError> {
     values
         .iter()
-        .map(|value| match value.as_str() {
-            "import" => Ok(JobType::Import),
-            "index_maintenance" => Ok(JobType::IndexMaintenance),
-            other => Err(ApiError::BadRequest(format!("Unknown job type '{other}'"))),
+        .map(|value| {
+            let job_type = JobType::new(value.clone());
+            match registry.get(&job_type) {
+                Some(_) => Ok(job_type),
+                None => Err(ApiError::BadRequest(format!("Unknown job type '{value}'"))),
+            }
         })
         .collect()
 }
@@ -0,0 +1,76 @@
+//! Stable backport endpoints.
+
+use crate::backports::{load_backport, load_commit_backports, load_patch_backports};
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{ApiResponse, ResponseMeta, StableBackport, StableBackports};
+use crate::routes::helpers::{ensure_email_exists, resolve_mailing_list_id};
+use rocket::serde::json::Json;
+use rocket::{State, get};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+
+/// Stable branches, across all lists, that received an upstream commit.
+///
+/// `commit` may be abbreviated to at least 8 hex characters.
+#[openapi(tag = "Stable Backports")]
+#[get("/stable/commits/<commit>/backports")]
+pub async fn list_commit_backports(
+    pool: &State<sqlx::PgPool>,
+    commit: String,
+) -> Result<Json<ApiResponse<StableBackports>>, ApiError> {
+    let commit = commit.trim().to_ascii_lowercase();
+    if !(8..=40).contains(&commit.len()) || !commit.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid commit '{commit}': expected 8 to 40 hex characters"
+        )));
+    }
+
+    let backports = load_commit_backports(pool.inner(), &commit).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        backports,
+        ResponseMeta::default(),
+    )))
+}
+
+/// Upstream commit, stable branch and mainline patch of a backport email.
+#[openapi(tag = "Stable Backports")]
+#[get("/lists/<slug>/emails/<email_id>/backport")]
+pub async fn get_email_backport(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<StableBackport>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let backport = load_backport(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} is not a stable backport")))?;
+
+    Ok(Json(ApiResponse::with_meta(
+        backport,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Stable branches, across all lists, that received a mainline patch.
+#[openapi(tag = "Stable Backports")]
+#[get("/lists/<slug>/emails/<email_id>/backports")]
+pub async fn list_patch_backports(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<StableBackports>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let backports = load_patch_backports(pool.inner(), mailing_list_id, email_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        backports,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
@@ -0,0 +1,129 @@
+//! syzbot and kernel test robot report endpoints.
+
+use crate::bots::{list_bot_reports, load_bot_report, load_patch_bot_reports};
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{ApiResponse, BotName, BotReport, BotReportKind, PaginationMeta, ResponseMeta};
+use crate::routes::helpers::{ensure_email_exists, resolve_mailing_list_id};
+use crate::routes::params::PaginationParams;
+use rocket::serde::json::Json;
+use rocket::{State, get};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Bot reports posted to a list, newest first.
+///
+/// `bot` filters by sender (`syzbot` or `kernel_test_robot`), `kind` by report
+/// kind (`crash`, `build_issue`, `regression` or `test_result`).
+#[openapi(tag = "Bot Reports")]
+#[get("/lists/<slug>/bot-reports?<bot>&<kind>&<params..>")]
+pub async fn list_list_bot_reports(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    bot: Option<String>,
+    kind: Option<String>,
+    params: Option<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<BotReport>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    let params = params.unwrap_or_default();
+    let page = params.page();
+    let page_size = params.page_size();
+
+    let bot = bot
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            BotName::parse(value).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid bot '{value}': expected 'syzbot' or 'kernel_test_robot'"
+                ))
+            })
+        })
+        .transpose()?;
+    let kind = kind
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            BotReportKind::parse(value).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid kind '{value}': expected 'crash', 'build_issue', 'regression' or 'test_result'"
+                ))
+            })
+        })
+        .transpose()?;
+
+    let (reports, total) = list_bot_reports(
+        pool.inner(),
+        mailing_list_id,
+        bot,
+        kind,
+        page_size,
+        (page - 1) * page_size,
+    )
+    .await?;
+
+    let mut meta = ResponseMeta::default()
+        .with_list_id(slug)
+        .with_pagination(PaginationMeta::new(page, page_size, total));
+    let mut filters = JsonMap::new();
+    if let Some(bot) = bot {
+        filters.insert(
+            "bot".to_string(),
+            JsonValue::String(bot.as_str().to_string()),
+        );
+    }
+    if let Some(kind) = kind {
+        filters.insert(
+            "kind".to_string(),
+            JsonValue::String(kind.as_str().to_string()),
+        );
+    }
+    if !filters.is_empty() {
+        meta = meta.with_filters(filters);
+    }
+
+    Ok(Json(ApiResponse::with_meta(reports, meta)))
+}
+
+/// Structured bot report parsed from an email.
+#[openapi(tag = "Bot Reports")]
+#[get("/lists/<slug>/emails/<email_id>/bot-report")]
+pub async fn get_email_bot_report(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<BotReport>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let report = load_bot_report(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} is not a bot report")))?;
+
+    Ok(Json(ApiResponse::with_meta(
+        report,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Bot reports, from any list, about a patch email, oldest first.
+#[openapi(tag = "Bot Reports")]
+#[get("/lists/<slug>/emails/<email_id>/bot-reports")]
+pub async fn list_patch_bot_reports(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<BotReport>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let reports = load_patch_bot_reports(pool.inner(), mailing_list_id, email_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        reports,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
@@ -6,6 +6,7 @@ use crate::models::{
     ApiResponse, EmailWithAuthor, PaginationMeta, ResponseMeta, SortDescriptor, SortDirection,
 };
 use crate::routes::helpers::resolve_mailing_list_id;
+use crate::sync::quotes::split_body;
 use rocket::get;
 use rocket::serde::json::Json;
 use rocket_db_pools::{Connection, sqlx};
@@ -139,7 +140,7 @@ pub async fn list_emails(
             e.id, e.mailing_list_id, e.message_id, e.git_commit_hash, e.author_id,
             e.subject, e.date, e.in_reply_to, e.body, e.created_at,
             a.canonical_name AS author_name, a.email AS author_email,
-            e.patch_type, e.is_patch_only, e.patch_metadata
+            e.patch_type, e.is_patch_only, e.patch_metadata, e.patch_id
         FROM emails e
         JOIN authors a ON e.author_id = a.id
         WHERE e.mailing_list_id = $1
@@ -172,13 +173,13 @@ pub async fn get_email(
 ) -> Result<Json<ApiResponse<EmailWithAuthor>>, ApiError> {
     let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
 
-    let email = sqlx::query_as::<_, EmailWithAuthor>(
+    let mut email = sqlx::query_as::<_, EmailWithAuthor>(
         r#"
         SELECT
             e.id, e.mailing_list_id, e.message_id, e.git_commit_hash, e.author_id,
             e.subject, e.date, e.in_reply_to, e.body, e.created_at,
             a.canonical_name AS author_name, a.email AS author_email,
-            e.patch_type, e.is_patch_only, e.patch_metadata
+            e.patch_type, e.is_patch_only, e.patch_metadata, e.patch_id
         FROM emails e
         JOIN authors a ON e.author_id = a.id
         WHERE e.mailing_list_id = $1 AND e.id = $2
@@ -188,6 +189,7 @@ pub async fn get_email(
     .bind(email_id)
     .fetch_one(&mut **db)
     .await?;
+    email.segments = split_body(email.body.as_deref().unwrap_or_default());
 
     let meta = ResponseMeta::default().with_list_id(slug);
     Ok(Json(ApiResponse::with_meta(email, meta)))
@@ -20,6 +20,24 @@ pub async fn resolve_mailing_list_id(
     Ok(record.0)
 }
 
+/// Fail with `NotFound` unless the email exists in the mailing list.
+pub async fn ensure_email_exists(
+    db: &mut Connection<NexusDb>,
+    mailing_list_id: i32,
+    email_id: i32,
+) -> Result<(), ApiError> {
+    let exists: Option<(i32,)> =
+        sqlx::query_as("SELECT id FROM emails WHERE mailing_list_id = $1 AND id = $2")
+            .bind(mailing_list_id)
+            .bind(email_id)
+            .fetch_optional(db.as_mut())
+            .await?;
+
+    exists
+        .map(|_| ())
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} not found")))
+}
+
 /// Resolve multiple mailing list slugs to their identifiers while preserving order.
 pub async fn resolve_mailing_list_ids(
     slugs: &[String],
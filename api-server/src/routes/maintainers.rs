@@ -0,0 +1,111 @@
+//! MAINTAINERS subsystem endpoints.
+
+use std::collections::HashMap;
+
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::maintainers::{index_from_subsystems, load_subsystems};
+use crate::models::{ApiResponse, PatchMaintainers, ResponseMeta, Subsystem};
+use crate::routes::helpers::resolve_mailing_list_id;
+use crate::sync::parser::extract_touched_paths;
+use rocket::serde::json::Json;
+use rocket::{State, get};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+#[openapi(tag = "Maintainers")]
+#[get("/subsystems?<q>")]
+pub async fn list_subsystems(
+    pool: &State<sqlx::PgPool>,
+    q: Option<String>,
+) -> Result<Json<ApiResponse<Vec<Subsystem>>>, ApiError> {
+    let mut subsystems = load_subsystems(pool.inner()).await?;
+
+    let query = q
+        .as_deref()
+        .map(|value| value.trim().to_lowercase())
+        .filter(|value| !value.is_empty());
+
+    let mut meta = ResponseMeta::default();
+    if let Some(needle) = query {
+        subsystems.retain(|s| s.name.to_lowercase().contains(&needle));
+        let mut filters = JsonMap::new();
+        filters.insert("q".to_string(), JsonValue::String(needle));
+        meta = meta.with_filters(filters);
+    }
+
+    Ok(Json(ApiResponse::with_meta(subsystems, meta)))
+}
+
+#[openapi(tag = "Maintainers")]
+#[get("/subsystems/<subsystem_id>")]
+pub async fn get_subsystem(
+    pool: &State<sqlx::PgPool>,
+    subsystem_id: i32,
+) -> Result<Json<ApiResponse<Subsystem>>, ApiError> {
+    let subsystem = sqlx::query_as::<_, Subsystem>(
+        r#"SELECT id, name, status, maintainers, reviewers, lists, trees,
+                  file_patterns, exclude_patterns, regex_patterns, updated_at
+           FROM subsystems
+           WHERE id = $1"#,
+    )
+    .bind(subsystem_id)
+    .fetch_optional(pool.inner())
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("Subsystem {subsystem_id} not found")))?;
+
+    Ok(Json(ApiResponse::new(subsystem)))
+}
+
+#[openapi(tag = "Maintainers")]
+#[get("/lists/<slug>/emails/<email_id>/maintainers")]
+pub async fn get_email_maintainers(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<PatchMaintainers>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let (body,): (Option<String>,) =
+        sqlx::query_as("SELECT body FROM emails WHERE mailing_list_id = $1 AND id = $2")
+            .bind(mailing_list_id)
+            .bind(email_id)
+            .fetch_one(&mut **db)
+            .await?;
+
+    let touched_paths = body
+        .as_deref()
+        .map(extract_touched_paths)
+        .unwrap_or_default();
+
+    let subsystems = if touched_paths.is_empty() {
+        Vec::new()
+    } else {
+        let stored = load_subsystems(pool.inner()).await?;
+        let index = index_from_subsystems(&stored);
+        let matched: Vec<String> = index
+            .subsystems_for_paths(&touched_paths)
+            .into_iter()
+            .map(|entry| entry.name.clone())
+            .collect();
+        let mut by_name: HashMap<String, Subsystem> =
+            stored.into_iter().map(|s| (s.name.clone(), s)).collect();
+        matched
+            .iter()
+            .filter_map(|name| by_name.remove(name))
+            .collect()
+    };
+
+    let response = PatchMaintainers {
+        email_id,
+        touched_paths,
+        subsystems,
+    };
+
+    Ok(Json(ApiResponse::with_meta(
+        response,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
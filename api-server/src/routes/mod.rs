@@ -8,11 +8,18 @@
 pub mod admin;
 pub mod auth;
 pub mod authors;
+pub mod backports;
+pub mod bots;
 pub mod emails;
 pub mod health;
 pub(crate) mod helpers;
 pub mod mailing_lists;
+pub mod maintainers;
 pub mod params;
+pub mod patches;
+pub mod pull_requests;
+pub mod regressions;
 pub mod search;
 pub mod stats;
 pub mod threads;
+pub mod upstream;
@@ -5,6 +5,7 @@
 //! The types follow Rocket's `FromForm` conventions and derive `JsonSchema` so
 //! generated documentation reflects the available parameters and their defaults.
 
+use crate::models::{PatchState, ThreadSearchFacet};
 use chrono::{DateTime, NaiveDate, TimeZone, Utc};
 use rocket::form::{self, FromFormField, ValueField};
 use rocket_okapi::okapi::schemars::{self, JsonSchema};
@@ -24,6 +25,21 @@ const fn default_search_page_size() -> i64 {
 
 const MAX_PAGE_SIZE: i64 = 100;
 
+const fn default_suggest_limit() -> i64 {
+    5
+}
+
+const MAX_SUGGEST_LIMIT: i64 = 10;
+
+/// Characters typed before suggestions are looked up.
+const MIN_SUGGEST_CHARS: usize = 2;
+
+const fn default_similar_limit() -> i64 {
+    10
+}
+
+const MAX_SIMILAR_LIMIT: i64 = 50;
+
 fn default_sort_order() -> SortOrder {
     SortOrder::Desc
 }
@@ -36,6 +52,10 @@ fn default_optional_string() -> Option<String> {
     None
 }
 
+const fn default_false() -> bool {
+    false
+}
+
 /// Wrapper for parsing ISO-8601 dates from query parameters.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(transparent)]
@@ -54,6 +74,30 @@ impl<'r> FromFormField<'r> for DateParam {
     }
 }
 
+impl<'r> FromFormField<'r> for PatchState {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        match PatchState::parse(field.value) {
+            Some(state) => Ok(state),
+            None => Err(form::Error::validation(format!(
+                "invalid patch state '{}'",
+                field.value
+            )))?,
+        }
+    }
+}
+
+impl<'r> FromFormField<'r> for ThreadSearchFacet {
+    fn from_value(field: ValueField<'r>) -> form::Result<'r, Self> {
+        match ThreadSearchFacet::parse(field.value) {
+            Some(facet) => Ok(facet),
+            None => Err(form::Error::validation(format!(
+                "invalid facet '{}'",
+                field.value
+            )))?,
+        }
+    }
+}
+
 /// Common pagination parameters applied to list endpoints.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, rocket::form::FromForm)]
 #[serde(rename_all = "camelCase")]
@@ -288,6 +332,18 @@ pub struct ThreadListParams {
     #[field(name = "sort")]
     #[serde(default)]
     sort: Vec<String>,
+    /// Optional filter matching threads tagged with any of the MAINTAINERS subsystems.
+    #[field(name = "subsystem")]
+    #[serde(default, rename = "subsystem")]
+    subsystems: Vec<String>,
+    /// Optional filter matching threads with at least one patch in any of the given states.
+    #[field(name = "state")]
+    #[serde(default, rename = "state")]
+    patch_states: Vec<PatchState>,
+    /// Hide threads started by syzbot or kernel test robot reports.
+    #[field(name = "excludeBots", default = false)]
+    #[serde(default = "default_false", rename = "excludeBots")]
+    exclude_bots: bool,
 }
 
 impl Default for ThreadListParams {
@@ -296,6 +352,9 @@ impl Default for ThreadListParams {
             page: default_page(),
             page_size: default_page_size(),
             sort: vec!["lastActivity:desc".to_string()],
+            subsystems: Vec::new(),
+            patch_states: Vec::new(),
+            exclude_bots: false,
         }
     }
 }
@@ -316,13 +375,46 @@ impl ThreadListParams {
             self.sort.clone()
         }
     }
+
+    pub fn subsystems(&self) -> Vec<String> {
+        normalize_subsystems(&self.subsystems)
+    }
+
+    pub fn patch_states(&self) -> Vec<PatchState> {
+        normalize_patch_states(&self.patch_states)
+    }
+
+    pub fn exclude_bots(&self) -> bool {
+        self.exclude_bots
+    }
+}
+
+fn normalize_patch_states(values: &[PatchState]) -> Vec<PatchState> {
+    PatchState::ALL
+        .into_iter()
+        .filter(|state| values.contains(state))
+        .collect()
+}
+
+fn normalize_subsystems(values: &[String]) -> Vec<String> {
+    let mut subsystems: Vec<String> = values
+        .iter()
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+        .collect();
+    subsystems.sort();
+    subsystems.dedup();
+    subsystems
 }
 
 /// Query parameters for the thread search endpoint.
 #[derive(Debug, Clone, Serialize, Deserialize, rocket::form::FromForm)]
 #[serde(rename_all = "camelCase")]
 pub struct ThreadSearchParams {
-    /// Free-text search term. If omitted, the endpoint returns an empty result set.
+    /// Search query in lore syntax: free text plus `s:`, `f:`, `t:`, `c:`, `b:`,
+    /// `dfn:`, `dfhh:`, `d:YYYYMMDD..YYYYMMDD` terms combined with `AND`, `OR`
+    /// and `NOT`. Required.
     #[serde(default = "default_optional_string")]
     pub q: Option<String>,
     /// Page of results to fetch (defaults to 1).
@@ -361,6 +453,18 @@ pub struct ThreadSearchParams {
     #[field(name = "seriesId")]
     #[serde(default = "default_optional_string")]
     pub series_id: Option<String>,
+    /// Optional filter matching threads tagged with any of the MAINTAINERS subsystems.
+    #[field(name = "subsystem")]
+    #[serde(default, rename = "subsystem")]
+    pub subsystems: Vec<String>,
+    /// Optional filter matching threads with at least one patch in any of the given states.
+    #[field(name = "state")]
+    #[serde(default, rename = "state")]
+    pub patch_states: Vec<PatchState>,
+    /// Optional filter limiting results to threads carrying an incomplete patch series.
+    #[field(name = "incompleteSeries")]
+    #[serde(default)]
+    pub incomplete_series: Option<bool>,
     /// Optional sort descriptors (field:direction).
     #[field(name = "sort")]
     #[serde(default)]
@@ -369,6 +473,11 @@ pub struct ThreadSearchParams {
     #[field(name = "mailingList")]
     #[serde(default)]
     pub mailing_lists: Vec<String>,
+    /// Optional facets to count over all matching threads (`mailingList`,
+    /// `year`, `month`, `hasPatches`, `participant`, `seriesVersion`).
+    #[field(name = "facet")]
+    #[serde(default, rename = "facet")]
+    pub facets: Vec<ThreadSearchFacet>,
 }
 
 impl Default for ThreadSearchParams {
@@ -384,8 +493,12 @@ impl Default for ThreadSearchParams {
             starter_id: None,
             participant_ids: Vec::new(),
             series_id: None,
+            subsystems: Vec::new(),
+            patch_states: Vec::new(),
+            incomplete_series: None,
             sort: Vec::new(),
             mailing_lists: Vec::new(),
+            facets: Vec::new(),
         }
     }
 }
@@ -441,6 +554,11 @@ impl ThreadSearchParams {
         self.has_patches
     }
 
+    /// Optional incomplete-series flag filter.
+    pub fn incomplete_series(&self) -> Option<bool> {
+        self.incomplete_series
+    }
+
     /// Optional starter id filter (positive integers only).
     pub fn starter_id(&self) -> Option<i32> {
         self.starter_id.filter(|id| *id > 0)
@@ -468,6 +586,16 @@ impl ThreadSearchParams {
             .map(|value| value.to_string())
     }
 
+    /// Normalized subsystem names (trimmed, deduplicated).
+    pub fn subsystems(&self) -> Vec<String> {
+        normalize_subsystems(&self.subsystems)
+    }
+
+    /// Deduplicated patch state filters in workflow order.
+    pub fn patch_states(&self) -> Vec<PatchState> {
+        normalize_patch_states(&self.patch_states)
+    }
+
     /// Normalized sort expressions.
     pub fn sort_fields(&self) -> Vec<String> {
         self.sort
@@ -491,6 +619,17 @@ impl ThreadSearchParams {
         lists.dedup();
         lists
     }
+
+    /// Requested facets, deduplicated in request order.
+    pub fn facets(&self) -> Vec<ThreadSearchFacet> {
+        let mut facets: Vec<ThreadSearchFacet> = Vec::new();
+        for facet in &self.facets {
+            if !facets.contains(facet) {
+                facets.push(*facet);
+            }
+        }
+        facets
+    }
 }
 
 impl JsonSchema for ThreadSearchParams {
@@ -523,20 +662,364 @@ impl JsonSchema for ThreadSearchParams {
             #[serde(default = "default_optional_string")]
             series_id: Option<String>,
             #[serde(default)]
+            subsystem: Vec<String>,
+            #[serde(default)]
+            state: Vec<PatchState>,
+            #[serde(default)]
+            incomplete_series: Option<bool>,
+            #[serde(default)]
             sort: Vec<String>,
             #[serde(default)]
             mailing_list: Vec<String>,
+            #[serde(default)]
+            facet: Vec<ThreadSearchFacet>,
         }
 
         ThreadSearchParamsDoc::json_schema(generator)
     }
 }
 
+/// Query parameters for the message-level search endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, rocket::form::FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EmailSearchParams {
+    /// Search query in lore syntax, evaluated per message. Required.
+    #[serde(default = "default_optional_string")]
+    pub q: Option<String>,
+    /// Page of results to fetch (defaults to 1).
+    #[field(default = 1)]
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Page size (defaults to 25, maximum 100).
+    #[field(default = 25)]
+    #[serde(default = "default_search_page_size")]
+    pub size: i64,
+    /// Optional inclusive lower bound (UTC date) for the message date.
+    #[field(name = "startDate")]
+    #[serde(default)]
+    pub start_date: Option<DateParam>,
+    /// Optional inclusive upper bound (UTC date) for the message date.
+    #[field(name = "endDate")]
+    #[serde(default)]
+    pub end_date: Option<DateParam>,
+    /// Optional filter on whether the message carries a patch.
+    #[field(name = "isPatch")]
+    #[serde(default)]
+    pub is_patch: Option<bool>,
+    /// Optional filter limiting results to messages sent by the given author id.
+    #[field(name = "authorId")]
+    #[serde(default)]
+    pub author_id: Option<i32>,
+    /// Optional filter limiting results to the messages of one thread.
+    #[field(name = "threadId")]
+    #[serde(default)]
+    pub thread_id: Option<i32>,
+    /// Optional mailing list filters.
+    #[field(name = "mailingList")]
+    #[serde(default)]
+    pub mailing_lists: Vec<String>,
+}
+
+impl Default for EmailSearchParams {
+    fn default() -> Self {
+        Self {
+            q: None,
+            page: default_page(),
+            size: default_search_page_size(),
+            start_date: None,
+            end_date: None,
+            is_patch: None,
+            author_id: None,
+            thread_id: None,
+            mailing_lists: Vec::new(),
+        }
+    }
+}
+
+impl EmailSearchParams {
+    /// Normalized search term (trimmed) with empty strings removed.
+    pub fn query(&self) -> Option<&str> {
+        self.q
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Normalized page index.
+    pub fn page(&self) -> i64 {
+        self.page.max(1)
+    }
+
+    /// Normalized page size.
+    pub fn size(&self) -> i64 {
+        self.size.clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Inclusive lower bound converted to UTC midnight.
+    pub fn start_date_utc(&self) -> Option<DateTime<Utc>> {
+        self.start_date.as_ref().and_then(|param| {
+            param
+                .0
+                .and_hms_opt(0, 0, 0)
+                .map(|naive| Utc.from_utc_datetime(&naive))
+        })
+    }
+
+    /// Inclusive upper bound converted to UTC end-of-day.
+    pub fn end_date_utc(&self) -> Option<DateTime<Utc>> {
+        self.end_date.as_ref().and_then(|param| {
+            param
+                .0
+                .and_hms_milli_opt(23, 59, 59, 999)
+                .map(|naive| Utc.from_utc_datetime(&naive))
+        })
+    }
+
+    /// Optional author id filter (positive integers only).
+    pub fn author_id(&self) -> Option<i32> {
+        self.author_id.filter(|id| *id > 0)
+    }
+
+    /// Optional thread id filter (positive integers only).
+    pub fn thread_id(&self) -> Option<i32> {
+        self.thread_id.filter(|id| *id > 0)
+    }
+
+    /// Normalized mailing list filters (trimmed, deduplicated).
+    pub fn mailing_lists(&self) -> Vec<String> {
+        let mut lists: Vec<String> = self
+            .mailing_lists
+            .iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .collect();
+        lists.sort();
+        lists.dedup();
+        lists
+    }
+}
+
+/// Query parameters for the patch (diff content) search endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, rocket::form::FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PatchSearchParams {
+    /// Search query in lore syntax, evaluated against the diff content; bare
+    /// words match symbols like `dfs:`. Required.
+    #[serde(default = "default_optional_string")]
+    pub q: Option<String>,
+    /// Page of results to fetch (defaults to 1).
+    #[field(default = 1)]
+    #[serde(default = "default_page")]
+    pub page: i64,
+    /// Page size (defaults to 25, maximum 100).
+    #[field(default = 25)]
+    #[serde(default = "default_search_page_size")]
+    pub size: i64,
+    /// Optional inclusive lower bound (UTC date) for the patch date.
+    #[field(name = "startDate")]
+    #[serde(default)]
+    pub start_date: Option<DateParam>,
+    /// Optional inclusive upper bound (UTC date) for the patch date.
+    #[field(name = "endDate")]
+    #[serde(default)]
+    pub end_date: Option<DateParam>,
+    /// Optional filter limiting results to patches sent by the given author id.
+    #[field(name = "authorId")]
+    #[serde(default)]
+    pub author_id: Option<i32>,
+    /// Optional filter limiting results to the patches of one thread.
+    #[field(name = "threadId")]
+    #[serde(default)]
+    pub thread_id: Option<i32>,
+    /// Optional mailing list filters.
+    #[field(name = "mailingList")]
+    #[serde(default)]
+    pub mailing_lists: Vec<String>,
+}
+
+impl Default for PatchSearchParams {
+    fn default() -> Self {
+        Self {
+            q: None,
+            page: default_page(),
+            size: default_search_page_size(),
+            start_date: None,
+            end_date: None,
+            author_id: None,
+            thread_id: None,
+            mailing_lists: Vec::new(),
+        }
+    }
+}
+
+impl PatchSearchParams {
+    /// Normalized search term (trimmed) with empty strings removed.
+    pub fn query(&self) -> Option<&str> {
+        self.q
+            .as_ref()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Normalized page index.
+    pub fn page(&self) -> i64 {
+        self.page.max(1)
+    }
+
+    /// Normalized page size.
+    pub fn size(&self) -> i64 {
+        self.size.clamp(1, MAX_PAGE_SIZE)
+    }
+
+    /// Inclusive lower bound converted to UTC midnight.
+    pub fn start_date_utc(&self) -> Option<DateTime<Utc>> {
+        self.start_date.as_ref().and_then(|param| {
+            param
+                .0
+                .and_hms_opt(0, 0, 0)
+                .map(|naive| Utc.from_utc_datetime(&naive))
+        })
+    }
+
+    /// Inclusive upper bound converted to UTC end-of-day.
+    pub fn end_date_utc(&self) -> Option<DateTime<Utc>> {
+        self.end_date.as_ref().and_then(|param| {
+            param
+                .0
+                .and_hms_milli_opt(23, 59, 59, 999)
+                .map(|naive| Utc.from_utc_datetime(&naive))
+        })
+    }
+
+    /// Optional author id filter (positive integers only).
+    pub fn author_id(&self) -> Option<i32> {
+        self.author_id.filter(|id| *id > 0)
+    }
+
+    /// Optional thread id filter (positive integers only).
+    pub fn thread_id(&self) -> Option<i32> {
+        self.thread_id.filter(|id| *id > 0)
+    }
+
+    /// Normalized mailing list filters (trimmed, deduplicated).
+    pub fn mailing_lists(&self) -> Vec<String> {
+        let mut lists: Vec<String> = self
+            .mailing_lists
+            .iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .collect();
+        lists.sort();
+        lists.dedup();
+        lists
+    }
+}
+
+/// Query parameters for the similar-threads endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, rocket::form::FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SimilarThreadsParams {
+    /// Number of threads to return (defaults to 10, maximum 50).
+    #[field(default = 10)]
+    #[serde(default = "default_similar_limit")]
+    pub limit: i64,
+    /// Search every mailing list instead of the thread's own list. Requires
+    /// global thread search to be enabled.
+    #[field(name = "allLists", default = false)]
+    #[serde(default = "default_false")]
+    pub all_lists: bool,
+    /// Leave out other versions of the thread's patch series.
+    #[field(name = "excludeSeries", default = false)]
+    #[serde(default = "default_false")]
+    pub exclude_series: bool,
+    /// Optional filter limiting results to threads with (or without) patches.
+    #[field(name = "hasPatches")]
+    #[serde(default)]
+    pub has_patches: Option<bool>,
+}
+
+impl Default for SimilarThreadsParams {
+    fn default() -> Self {
+        Self {
+            limit: default_similar_limit(),
+            all_lists: false,
+            exclude_series: false,
+            has_patches: None,
+        }
+    }
+}
+
+impl SimilarThreadsParams {
+    /// Normalized number of threads to return.
+    pub fn limit(&self) -> i64 {
+        self.limit.clamp(1, MAX_SIMILAR_LIMIT)
+    }
+}
+
+/// Query parameters for the search suggestion endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize, rocket::form::FromForm, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestParams {
+    /// Text typed so far; fewer than two characters yields no suggestions.
+    #[serde(default = "default_optional_string")]
+    pub q: Option<String>,
+    /// Suggestions per kind (defaults to 5, maximum 10).
+    #[field(default = 5)]
+    #[serde(default = "default_suggest_limit")]
+    pub limit: i64,
+    /// Optional mailing list filters.
+    #[field(name = "mailingList")]
+    #[serde(default)]
+    pub mailing_lists: Vec<String>,
+}
+
+impl Default for SuggestParams {
+    fn default() -> Self {
+        Self {
+            q: None,
+            limit: default_suggest_limit(),
+            mailing_lists: Vec::new(),
+        }
+    }
+}
+
+impl SuggestParams {
+    /// Trimmed prefix, or `None` until enough characters have been typed.
+    pub fn query(&self) -> Option<&str> {
+        self.q
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| value.chars().count() >= MIN_SUGGEST_CHARS)
+    }
+
+    /// Normalized suggestions per kind.
+    pub fn limit(&self) -> i64 {
+        self.limit.clamp(1, MAX_SUGGEST_LIMIT)
+    }
+
+    /// Normalized mailing list filters (trimmed, deduplicated).
+    pub fn mailing_lists(&self) -> Vec<String> {
+        let mut lists: Vec<String> = self
+            .mailing_lists
+            .iter()
+            .map(|value| value.trim())
+            .filter(|value| !value.is_empty())
+            .map(|value| value.to_string())
+            .collect();
+        lists.sort();
+        lists.dedup();
+        lists
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use chrono::Timelike;
     use rocket::form::Form;
+    use rocket::http::RawStr;
 
     #[test]
     fn parses_thread_search_query() {
@@ -585,6 +1068,21 @@ mod tests {
         assert_eq!(end.minute(), 59);
     }
 
+    #[test]
+    fn parses_email_search_filters() {
+        let parsed: EmailSearchParams = Form::parse(
+            "q=f:akpm&isPatch=true&authorId=7&threadId=-3&mailingList=netdev&mailingList=netdev",
+        )
+        .unwrap();
+
+        assert_eq!(parsed.query(), Some("f:akpm"));
+        assert_eq!(parsed.is_patch, Some(true));
+        assert_eq!(parsed.author_id(), Some(7));
+        assert_eq!(parsed.thread_id(), None);
+        assert_eq!(parsed.mailing_lists(), vec!["netdev".to_string()]);
+        assert_eq!(parsed.size(), 25);
+    }
+
     #[test]
     fn clamps_semantic_ratio() {
         let parsed: ThreadSearchParams = Form::parse("semanticRatio=1.5").unwrap();
@@ -597,11 +1095,12 @@ mod tests {
     #[test]
     fn parses_thread_search_filters_and_sort() {
         let parsed: ThreadSearchParams = Form::parse(
-            "hasPatches=true&starterId=42&participantId=10&participantId=42&participantId=-1&seriesId= abc123 &sort=lastActivity:desc&sort=messageCount:asc&mailingList=linux-kernel&mailingList=netdev",
+            "hasPatches=true&incompleteSeries=true&starterId=42&participantId=10&participantId=42&participantId=-1&seriesId= abc123 &sort=lastActivity:desc&sort=messageCount:asc&mailingList=linux-kernel&mailingList=netdev",
         )
         .unwrap();
 
         assert_eq!(parsed.has_patches(), Some(true));
+        assert_eq!(parsed.incomplete_series(), Some(true));
         assert_eq!(parsed.starter_id(), Some(42));
         assert_eq!(parsed.participant_ids(), vec![10, 42]);
         assert_eq!(parsed.series_id().as_deref(), Some("abc123"));
@@ -618,6 +1117,70 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parses_subsystem_filters() {
+        let parsed: ThreadSearchParams = Form::parse_encoded(RawStr::new(
+            "subsystem=BPF%20%5BCORE%5D&subsystem=%20NETWORKING%20&subsystem=BPF%20%5BCORE%5D",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed.subsystems(),
+            vec!["BPF [CORE]".to_string(), "NETWORKING".to_string()]
+        );
+
+        let list: ThreadListParams = Form::parse("subsystem=NETWORKING").unwrap();
+        assert_eq!(list.subsystems(), vec!["NETWORKING".to_string()]);
+        assert!(ThreadListParams::default().subsystems().is_empty());
+    }
+
+    #[test]
+    fn parses_patch_state_filters() {
+        let parsed: ThreadSearchParams =
+            Form::parse("state=accepted&state=NEW&state=accepted").unwrap();
+        assert_eq!(
+            parsed.patch_states(),
+            vec![PatchState::New, PatchState::Accepted]
+        );
+
+        let list: ThreadListParams = Form::parse("state=changes_requested").unwrap();
+        assert_eq!(list.patch_states(), vec![PatchState::ChangesRequested]);
+        assert!(ThreadListParams::default().patch_states().is_empty());
+
+        assert!(Form::<ThreadListParams>::parse("state=merged").is_err());
+    }
+
+    #[test]
+    fn normalizes_suggest_params() {
+        let parsed: SuggestParams = Form::parse("q=k&limit=50").unwrap();
+        assert_eq!(parsed.query(), None);
+        assert_eq!(parsed.limit(), MAX_SUGGEST_LIMIT);
+
+        let parsed: SuggestParams = Form::parse("q=km&mailingList=mm&mailingList=mm").unwrap();
+        assert_eq!(parsed.query(), Some("km"));
+        assert_eq!(parsed.limit(), 5);
+        assert_eq!(parsed.mailing_lists(), vec!["mm".to_string()]);
+    }
+
+    #[test]
+    fn parses_search_facets() {
+        let parsed: ThreadSearchParams =
+            Form::parse("q=slab&facet=month&facet=mailinglist&facet=month").unwrap();
+        assert_eq!(
+            parsed.facets(),
+            vec![ThreadSearchFacet::Month, ThreadSearchFacet::MailingList]
+        );
+        assert!(ThreadSearchParams::default().facets().is_empty());
+
+        assert!(Form::<ThreadSearchParams>::parse("q=slab&facet=subject").is_err());
+    }
+
+    #[test]
+    fn parses_exclude_bots_flag() {
+        let list: ThreadListParams = Form::parse("excludeBots=true").unwrap();
+        assert!(list.exclude_bots());
+        assert!(!ThreadListParams::default().exclude_bots());
+    }
+
     #[test]
     fn author_search_mailing_lists_dedup() {
         let parsed: AuthorSearchParams =
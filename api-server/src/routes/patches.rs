@@ -0,0 +1,470 @@
+//! Patch review state endpoints.
+
+use crate::auth::AuthUser;
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{
+    ApiResponse, FixedCommit, FixingPatch, PatchDiff, PatchIdMatch, PatchState, PatchStateChange,
+    PatchStateRecord, ResponseMeta, ReviewComment, SeriesRevision,
+};
+use crate::patches::{
+    PATCH_EMAIL_SQL, find_emails_by_patch_id, load_email_series, load_fixed_commits,
+    load_fixing_patches, load_patch_diff, load_patch_state, load_review_comments,
+    load_state_history, load_thread_series, set_patch_states, thread_patch_states,
+};
+use crate::routes::helpers::{ensure_email_exists, resolve_mailing_list_id};
+use crate::search::SearchService;
+use log::warn;
+use rocket::serde::json::Json;
+use rocket::{State, get, put};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::okapi::schemars::JsonSchema;
+use rocket_okapi::openapi;
+use serde::Deserialize;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+#[derive(Debug, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdatePatchStateRequest {
+    pub state: PatchState,
+    #[serde(default)]
+    pub comment: Option<String>,
+}
+
+impl UpdatePatchStateRequest {
+    fn comment(&self) -> Option<&str> {
+        self.comment
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    }
+}
+
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/state")]
+pub async fn get_patch_state(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<PatchStateRecord>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let record = load_patch_state(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} has no patch state")))?;
+
+    Ok(Json(ApiResponse::with_meta(
+        record,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+#[openapi(tag = "Patches")]
+#[put("/lists/<slug>/emails/<email_id>/state", data = "<request>")]
+pub async fn update_patch_state(
+    slug: String,
+    user: AuthUser,
+    pool: &State<sqlx::PgPool>,
+    search: &State<SearchService>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+    request: Json<UpdatePatchStateRequest>,
+) -> Result<Json<ApiResponse<PatchStateRecord>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    // Same emails that imports give a state, cover letters included
+    let (thread_id, is_patch): (Option<i32>, bool) = sqlx::query_as(&format!(
+        r#"SELECT tm.thread_id, {PATCH_EMAIL_SQL}
+           FROM emails e
+           LEFT JOIN thread_memberships tm ON tm.mailing_list_id = e.mailing_list_id
+               AND tm.email_id = e.id
+           WHERE e.mailing_list_id = $1 AND e.id = $2"#
+    ))
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_optional(&mut **db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} not found")))?;
+
+    if !is_patch {
+        return Err(ApiError::BadRequest(format!(
+            "Email {email_id} is not a patch"
+        )));
+    }
+    ensure_can_set_state(&mut db, &user, mailing_list_id, &[email_id]).await?;
+
+    let record = set_patch_states(
+        pool.inner(),
+        mailing_list_id,
+        &[email_id],
+        request.state,
+        Some(user.id),
+        request.comment(),
+    )
+    .await?
+    .pop()
+    .ok_or_else(|| ApiError::InternalError("Patch state was not stored".to_string()))?;
+
+    if let Some(thread_id) = thread_id {
+        refresh_search_states(pool.inner(), search.inner(), mailing_list_id, thread_id).await;
+    }
+
+    Ok(Json(ApiResponse::with_meta(
+        record,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/state/history")]
+pub async fn get_patch_state_history(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<PatchStateChange>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let history = load_state_history(pool.inner(), mailing_list_id, email_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        history,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Set the state of every patch in a thread (i.e. a whole series).
+#[openapi(tag = "Patches")]
+#[put("/lists/<slug>/threads/<thread_id>/state", data = "<request>")]
+pub async fn update_series_state(
+    slug: String,
+    user: AuthUser,
+    pool: &State<sqlx::PgPool>,
+    search: &State<SearchService>,
+    mut db: Connection<NexusDb>,
+    thread_id: i32,
+    request: Json<UpdatePatchStateRequest>,
+) -> Result<Json<ApiResponse<Vec<PatchStateRecord>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let email_ids: Vec<i32> = sqlx::query_scalar(
+        r#"SELECT ps.email_id
+           FROM thread_memberships tm
+           JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id
+               AND ps.email_id = tm.email_id
+           WHERE tm.mailing_list_id = $1 AND tm.thread_id = $2
+           ORDER BY ps.email_id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_all(&mut **db)
+    .await?;
+
+    if email_ids.is_empty() {
+        return Err(ApiError::NotFound(format!(
+            "Thread {thread_id} has no patches"
+        )));
+    }
+    ensure_can_set_state(&mut db, &user, mailing_list_id, &email_ids).await?;
+
+    let records = set_patch_states(
+        pool.inner(),
+        mailing_list_id,
+        &email_ids,
+        request.state,
+        Some(user.id),
+        request.comment(),
+    )
+    .await?;
+
+    refresh_search_states(pool.inner(), search.inner(), mailing_list_id, thread_id).await;
+
+    Ok(Json(ApiResponse::with_meta(
+        records,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Structured diff of a patch email: files, modes, renames, hunks and lines.
+///
+/// Parsed on first request and cached.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/diff")]
+pub async fn get_email_diff(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<PatchDiff>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let diff = load_patch_diff(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} has no diff")))?;
+
+    Ok(Json(ApiResponse::with_meta(
+        diff,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Every email, across all lists, whose diff has the given `git patch-id --stable`.
+#[openapi(tag = "Patches")]
+#[get("/patch-ids/<patch_id>/emails")]
+pub async fn list_patch_id_emails(
+    pool: &State<sqlx::PgPool>,
+    patch_id: String,
+) -> Result<Json<ApiResponse<Vec<PatchIdMatch>>>, ApiError> {
+    let patch_id = patch_id.trim().to_ascii_lowercase();
+    if patch_id.len() != 40 || !patch_id.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ApiError::BadRequest(format!(
+            "Invalid patch-id '{patch_id}': expected 40 hex characters"
+        )));
+    }
+
+    let emails = find_emails_by_patch_id(pool.inner(), &patch_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        emails,
+        ResponseMeta::default().with_filters(patch_id_filter(patch_id)),
+    )))
+}
+
+/// Other emails carrying the same patch as this one: resends, cross-posts,
+/// backports, and unchanged patches in later series versions.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/same-patch")]
+pub async fn list_same_patch_emails(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<PatchIdMatch>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let patch_id: Option<String> =
+        sqlx::query_scalar("SELECT patch_id FROM emails WHERE mailing_list_id = $1 AND id = $2")
+            .bind(mailing_list_id)
+            .bind(email_id)
+            .fetch_one(&mut **db)
+            .await?;
+    let patch_id =
+        patch_id.ok_or_else(|| ApiError::NotFound(format!("Email {email_id} has no patch-id")))?;
+
+    let mut emails = find_emails_by_patch_id(pool.inner(), &patch_id).await?;
+    emails.retain(|email| !(email.mailing_list_slug == slug && email.email_id == email_id));
+
+    Ok(Json(ApiResponse::with_meta(
+        emails,
+        ResponseMeta::default()
+            .with_list_id(slug)
+            .with_filters(patch_id_filter(patch_id)),
+    )))
+}
+
+/// What this patch fixes: commits named by its `Fixes:` trailers, each with
+/// the archived patch that introduced the commit when it can be found.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/fixes")]
+pub async fn list_fixed_commits(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<FixedCommit>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let fixed = load_fixed_commits(pool.inner(), mailing_list_id, email_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        fixed,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Fixes for this patch: patches whose `Fixes:` trailers point at it.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/fixed-by")]
+pub async fn list_fixing_patches(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<FixingPatch>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let fixes = load_fixing_patches(pool.inner(), mailing_list_id, email_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        fixes,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Inline review comments replying to a patch email, anchored to diff lines.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/review-comments")]
+pub async fn list_email_review_comments(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<ReviewComment>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let comments = load_review_comments(pool.inner(), mailing_list_id, &[email_id]).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        comments,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Inline review comments on every patch of a thread (series).
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/threads/<thread_id>/review-comments")]
+pub async fn list_thread_review_comments(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    thread_id: i32,
+) -> Result<Json<ApiResponse<Vec<ReviewComment>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let email_ids: Vec<i32> = sqlx::query_scalar(
+        r#"SELECT e.id
+           FROM thread_memberships tm
+           JOIN emails e ON e.mailing_list_id = tm.mailing_list_id AND e.id = tm.email_id
+           WHERE tm.mailing_list_id = $1 AND tm.thread_id = $2
+             AND e.patch_type <> 'none'
+           ORDER BY e.id"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_all(&mut **db)
+    .await?;
+
+    let comments = load_review_comments(pool.inner(), mailing_list_id, &email_ids).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        comments,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Only admins and the MAINTAINERS maintainers of a subsystem the patches'
+/// thread is tagged with may change patch states.
+async fn ensure_can_set_state(
+    db: &mut Connection<NexusDb>,
+    user: &AuthUser,
+    mailing_list_id: i32,
+    email_ids: &[i32],
+) -> Result<(), ApiError> {
+    if user.is_admin() {
+        return Ok(());
+    }
+
+    let is_maintainer: bool = sqlx::query_scalar(
+        r#"SELECT EXISTS (
+               SELECT 1
+               FROM thread_memberships tm
+               JOIN thread_subsystems ts ON ts.mailing_list_id = tm.mailing_list_id
+                   AND ts.thread_id = tm.thread_id
+               JOIN subsystems s ON s.id = ts.subsystem_id
+               CROSS JOIN LATERAL jsonb_array_elements(s.maintainers) AS m(contact)
+               WHERE tm.mailing_list_id = $1
+                 AND tm.email_id = ANY($2)
+                 AND lower(m.contact->>'email') = lower($3)
+           )"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_ids)
+    .bind(&user.email)
+    .fetch_one(db.as_mut())
+    .await?;
+
+    if is_maintainer {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden(
+            "Only admins and maintainers of the patch's subsystems may change its state"
+                .to_string(),
+        ))
+    }
+}
+
+fn patch_id_filter(patch_id: String) -> JsonMap<String, JsonValue> {
+    let mut filters = JsonMap::new();
+    filters.insert("patchId".to_string(), JsonValue::String(patch_id));
+    filters
+}
+
+/// Push the thread's new patch states to the search index.
+///
+/// Failures are logged only; the next import reindexes the thread anyway.
+async fn refresh_search_states(
+    pool: &sqlx::PgPool,
+    search: &SearchService,
+    mailing_list_id: i32,
+    thread_id: i32,
+) {
+    let updates = match thread_patch_states(pool, mailing_list_id, &[thread_id]).await {
+        Ok(updates) => updates,
+        Err(err) => {
+            warn!("failed to load patch states for thread {thread_id}: {err}");
+            return;
+        }
+    };
+
+    if let Err(err) = search.update_thread_patch_states(&updates).await {
+        warn!("failed to refresh patch states for thread {thread_id} in search: {err}");
+    }
+}
+
+/// Patch series revisions posted in a thread, with missing, duplicated and
+/// misthreaded patches reported per revision.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/threads/<thread_id>/series")]
+pub async fn list_thread_series(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    thread_id: i32,
+) -> Result<Json<ApiResponse<Vec<SeriesRevision>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let series = load_thread_series(pool.inner(), mailing_list_id, thread_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        series,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
+
+/// Series revision a numbered patch email belongs to.
+#[openapi(tag = "Patches")]
+#[get("/lists/<slug>/emails/<email_id>/series")]
+pub async fn get_email_series(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<SeriesRevision>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let series = load_email_series(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Email {email_id} is not part of a patch series"))
+        })?;
+
+    Ok(Json(ApiResponse::with_meta(
+        series,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
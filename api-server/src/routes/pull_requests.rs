@@ -0,0 +1,87 @@
+//! `[GIT PULL]` request endpoints.
+
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{ApiResponse, PaginationMeta, PullRequest, PullRequestStatus, ResponseMeta};
+use crate::pull_requests::{list_pull_requests, load_pull_request};
+use crate::routes::helpers::resolve_mailing_list_id;
+use crate::routes::params::PaginationParams;
+use rocket::serde::json::Json;
+use rocket::{State, get};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Pull requests posted to a list, newest first.
+///
+/// `status` filters by merge status (`open` or `merged`).
+#[openapi(tag = "Pull Requests")]
+#[get("/lists/<slug>/pull-requests?<status>&<params..>")]
+pub async fn list_list_pull_requests(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    status: Option<String>,
+    params: Option<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<PullRequest>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    let params = params.unwrap_or_default();
+    let page = params.page();
+    let page_size = params.page_size();
+
+    let status = status
+        .as_deref()
+        .filter(|value| !value.trim().is_empty())
+        .map(|value| {
+            PullRequestStatus::parse(value).ok_or_else(|| {
+                ApiError::BadRequest(format!(
+                    "Invalid status '{value}': expected 'open' or 'merged'"
+                ))
+            })
+        })
+        .transpose()?;
+
+    let (pulls, total) = list_pull_requests(
+        pool.inner(),
+        mailing_list_id,
+        status,
+        page_size,
+        (page - 1) * page_size,
+    )
+    .await?;
+
+    let mut meta = ResponseMeta::default()
+        .with_list_id(slug)
+        .with_pagination(PaginationMeta::new(page, page_size, total));
+    if let Some(status) = status {
+        let mut filters = JsonMap::new();
+        filters.insert(
+            "status".to_string(),
+            JsonValue::String(status.as_str().to_string()),
+        );
+        meta = meta.with_filters(filters);
+    }
+
+    Ok(Json(ApiResponse::with_meta(pulls, meta)))
+}
+
+/// Pull request fields and merge status of an email.
+#[openapi(tag = "Pull Requests")]
+#[get("/lists/<slug>/emails/<email_id>/pull-request")]
+pub async fn get_email_pull_request(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<PullRequest>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let pull = load_pull_request(pool.inner(), mailing_list_id, email_id)
+        .await?
+        .ok_or_else(|| ApiError::NotFound(format!("Email {email_id} is not a pull request")))?;
+
+    Ok(Json(ApiResponse::with_meta(
+        pull,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
@@ -0,0 +1,96 @@
+//! `#regzbot` regression tracking endpoints.
+
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{
+    ApiResponse, PaginationMeta, Regression, RegressionDetail, RegressionState, ResponseMeta,
+};
+use crate::regressions::{
+    list_regressions, load_regression, load_regression_history, load_regression_state_history,
+};
+use crate::routes::helpers::resolve_mailing_list_id;
+use crate::routes::params::PaginationParams;
+use rocket::serde::json::Json;
+use rocket::{State, get};
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Regressions tracked on a list, most recently updated first.
+///
+/// `state` filters by tracking state (`open`, `fixed`, `resolved` or
+/// `invalid`); without it, only open regressions are listed.
+#[openapi(tag = "Regressions")]
+#[get("/lists/<slug>/regressions?<state>&<params..>")]
+pub async fn list_list_regressions(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    state: Option<String>,
+    params: Option<PaginationParams>,
+) -> Result<Json<ApiResponse<Vec<Regression>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    let params = params.unwrap_or_default();
+    let page = params.page();
+    let page_size = params.page_size();
+
+    let state = match state.as_deref().filter(|value| !value.trim().is_empty()) {
+        Some(value) => RegressionState::parse(value).ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Invalid state '{value}': expected 'open', 'fixed', 'resolved' or 'invalid'"
+            ))
+        })?,
+        None => RegressionState::Open,
+    };
+
+    let (regressions, total) = list_regressions(
+        pool.inner(),
+        mailing_list_id,
+        &[state],
+        page_size,
+        (page - 1) * page_size,
+    )
+    .await?;
+
+    let mut filters = JsonMap::new();
+    filters.insert(
+        "state".to_string(),
+        JsonValue::String(state.as_str().to_string()),
+    );
+    let meta = ResponseMeta::default()
+        .with_list_id(slug)
+        .with_pagination(PaginationMeta::new(page, page_size, total))
+        .with_filters(filters);
+
+    Ok(Json(ApiResponse::with_meta(regressions, meta)))
+}
+
+/// Regression tracked in a thread, with its `#regzbot` command and state history.
+#[openapi(tag = "Regressions")]
+#[get("/lists/<slug>/regressions/<thread_id>")]
+pub async fn get_regression(
+    slug: String,
+    pool: &State<sqlx::PgPool>,
+    mut db: Connection<NexusDb>,
+    thread_id: i32,
+) -> Result<Json<ApiResponse<RegressionDetail>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+
+    let regression = load_regression(pool.inner(), mailing_list_id, thread_id)
+        .await?
+        .ok_or_else(|| {
+            ApiError::NotFound(format!("Thread {thread_id} does not track a regression"))
+        })?;
+    let history = load_regression_history(pool.inner(), mailing_list_id, thread_id).await?;
+    let state_history =
+        load_regression_state_history(pool.inner(), mailing_list_id, thread_id).await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        RegressionDetail {
+            regression,
+            history,
+            state_history,
+        },
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
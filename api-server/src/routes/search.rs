@@ -2,15 +2,26 @@ use crate::auth::RequireAdmin;
 use crate::db::NexusDb;
 use crate::error::ApiError;
 use crate::models::{
-    ApiResponse, AuthorSearchHit, AuthorSearchMailingListStats, AuthorSearchPage, PaginationMeta,
-    ResponseMeta, SortDescriptor, SortDirection, ThreadSearchHighlights, ThreadSearchHit,
-    ThreadSearchPage, ThreadSearchParticipant, ThreadSearchScore, ThreadSearchThreadSummary,
+    ApiResponse, AuthorSearchHit, AuthorSearchMailingListStats, AuthorSearchPage,
+    EmailSearchHighlights, EmailSearchHit, EmailSearchPage, EmailSearchThreadContext,
+    PaginationMeta, PatchSearchHighlights, PatchSearchHit, PatchSearchPage, ResponseMeta,
+    SearchSuggestions, SortDescriptor, SortDirection, SuggestedAuthor, SuggestedMailingList,
+    SuggestedThread, ThreadSearchFacetCounts, ThreadSearchFacetValue, ThreadSearchHighlights,
+    ThreadSearchHit, ThreadSearchPage, ThreadSearchParticipant, ThreadSearchScore,
+    ThreadSearchThreadSummary,
 };
 use crate::routes::helpers::{resolve_mailing_list_id, resolve_mailing_list_ids};
-use crate::routes::params::{AuthorSearchParams, AuthorSortField, SortOrder, ThreadSearchParams};
+use crate::routes::params::{
+    AuthorSearchParams, AuthorSortField, EmailSearchParams, PatchSearchParams,
+    SimilarThreadsParams, SortOrder, SuggestParams, ThreadSearchParams,
+};
 use crate::search::{
-    AuthorHit, AuthorSearchPayload, AuthorSearchResults, SearchService, ThreadHit,
-    ThreadMailingListFilter, ThreadSearchPayload, ThreadSearchResults,
+    AuthorHit, AuthorSearchPayload, AuthorSearchResults, EmailHit, EmailSearchPayload,
+    EmailSearchResults, FacetCounts, PatchHit, PatchSearchPayload, PatchSearchResults, QueryField,
+    QueryNode, QueryTerm, SearchBackend, SearchQuery, SearchService, SeriesKey,
+    SimilarThreadsPayload, SuggestPayload, SuggestResults, ThreadHit, ThreadMailingListFilter,
+    ThreadSearchPayload, ThreadSearchResults, escape_like, is_identifier, parse_search_query,
+    unsupported_terms_error,
 };
 use crate::sync::queue::{JobQueue, JobRecord, JobType};
 use chrono::{DateTime, SecondsFormat, Utc};
@@ -21,6 +32,7 @@ use rocket_okapi::okapi::schemars::JsonSchema;
 use rocket_okapi::openapi;
 use serde::Deserialize;
 use serde_json::{Map as JsonMap, Value as JsonValue, json};
+use std::sync::Arc;
 
 #[openapi(tag = "Search")]
 #[get("/lists/<slug>/threads/search?<params..>")]
@@ -28,6 +40,7 @@ pub async fn search_threads_for_list(
     slug: String,
     params: ThreadSearchParams,
     search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
     mut db: Connection<NexusDb>,
 ) -> Result<Json<ApiResponse<ThreadSearchPage>>, ApiError> {
     let query = params
@@ -35,10 +48,14 @@ pub async fn search_threads_for_list(
         .ok_or_else(|| ApiError::BadRequest("Query parameter 'q' is required".to_string()))?
         .to_string();
 
+    let parsed = parse_query_for(&query, backend.inner().as_ref(), |term| {
+        backend.supports_query_term(term)
+    })?;
+
     let page = params.page();
     let size = params.size();
-    let start_date = params.start_date_utc();
-    let end_date = params.end_date_utc();
+    let start_date = latest(params.start_date_utc(), parsed.start_date);
+    let end_date = earliest(params.end_date_utc(), parsed.end_date);
 
     if let (Some(start), Some(end)) = (start_date, end_date) {
         if end < start {
@@ -54,8 +71,12 @@ pub async fn search_threads_for_list(
 
     let participant_ids = params.participant_ids();
     let series_id = params.series_id();
+    let subsystems = params.subsystems();
+    let patch_states = params.patch_states();
     let has_patches = params.has_patches();
+    let incomplete_series = params.incomplete_series();
     let starter_id = params.starter_id();
+    let facets = params.facets();
 
     let sort_fields = params.sort_fields();
     let (sort_meta, sort_expressions) = parse_thread_search_sorts(&sort_fields);
@@ -63,7 +84,8 @@ pub async fn search_threads_for_list(
     let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
 
     let payload = ThreadSearchPayload {
-        query: query.clone(),
+        query: parsed.text.clone(),
+        query_fields: parsed.text_fields(),
         page,
         size,
         semantic_ratio,
@@ -73,17 +95,23 @@ pub async fn search_threads_for_list(
         starter_id,
         participant_ids: participant_ids.clone(),
         series_id: series_id.clone(),
+        subsystems: subsystems.clone(),
+        patch_states: patch_states.clone(),
+        incomplete_series,
         mailing_lists: vec![ThreadMailingListFilter {
             slug: slug.clone(),
             mailing_list_id: Some(mailing_list_id),
         }],
         sort_expressions,
+        query_filters: parsed.filters,
+        facets: facets.clone(),
     };
 
     let ThreadSearchResults {
         hits: raw_hits,
         total,
-    } = search.search_threads(payload).await?;
+        facets: facet_counts,
+    } = backend.search_threads(payload).await?;
     let hits = map_thread_hits(raw_hits, semantic_ratio);
 
     let pagination = PaginationMeta::new(page, size, total);
@@ -109,6 +137,9 @@ pub async fn search_threads_for_list(
     if let Some(value) = has_patches {
         filters.insert("hasPatches".to_string(), JsonValue::Bool(value));
     }
+    if let Some(value) = incomplete_series {
+        filters.insert("incompleteSeries".to_string(), JsonValue::Bool(value));
+    }
     if let Some(value) = starter_id {
         filters.insert("starterId".to_string(), JsonValue::from(value));
     }
@@ -126,6 +157,28 @@ pub async fn search_threads_for_list(
     if let Some(value) = series_id.clone() {
         filters.insert("seriesId".to_string(), JsonValue::String(value));
     }
+    if !subsystems.is_empty() {
+        filters.insert(
+            "subsystem".to_string(),
+            JsonValue::Array(
+                subsystems
+                    .iter()
+                    .map(|name| JsonValue::String(name.clone()))
+                    .collect(),
+            ),
+        );
+    }
+    if !patch_states.is_empty() {
+        filters.insert(
+            "state".to_string(),
+            JsonValue::Array(
+                patch_states
+                    .iter()
+                    .map(|state| JsonValue::String(state.as_str().to_string()))
+                    .collect(),
+            ),
+        );
+    }
 
     if !filters.is_empty() {
         meta = meta.with_filters(filters);
@@ -133,9 +186,14 @@ pub async fn search_threads_for_list(
 
     let mut search_meta = JsonMap::new();
     search_meta.insert("query".to_string(), JsonValue::String(query.clone()));
+    search_meta.insert("textQuery".to_string(), JsonValue::String(parsed.text));
     search_meta.insert("page".to_string(), JsonValue::from(page));
     search_meta.insert("pageSize".to_string(), JsonValue::from(size));
     search_meta.insert("semanticRatio".to_string(), JsonValue::from(semantic_ratio));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
     if !participant_ids.is_empty() {
         search_meta.insert(
             "participantIds".to_string(),
@@ -153,9 +211,23 @@ pub async fn search_threads_for_list(
     if let Some(value) = has_patches {
         search_meta.insert("hasPatches".to_string(), JsonValue::Bool(value));
     }
+    if let Some(value) = incomplete_series {
+        search_meta.insert("incompleteSeries".to_string(), JsonValue::Bool(value));
+    }
     if let Some(value) = starter_id {
         search_meta.insert("starterId".to_string(), JsonValue::from(value));
     }
+    if !facets.is_empty() {
+        search_meta.insert(
+            "facets".to_string(),
+            JsonValue::Array(
+                facets
+                    .iter()
+                    .map(|facet| JsonValue::String(facet.as_str().to_string()))
+                    .collect(),
+            ),
+        );
+    }
     if let Some(value) = start_date {
         search_meta.insert(
             "startDate".to_string(),
@@ -174,7 +246,11 @@ pub async fn search_threads_for_list(
     meta = meta.with_extra(extra);
 
     Ok(Json(ApiResponse::with_meta(
-        ThreadSearchPage { hits, total },
+        ThreadSearchPage {
+            hits,
+            total,
+            facets: map_facet_counts(facet_counts),
+        },
         meta,
     )))
 }
@@ -184,6 +260,7 @@ pub async fn search_threads_for_list(
 pub async fn search_threads_global(
     params: Option<ThreadSearchParams>,
     search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
     mut db: Connection<NexusDb>,
 ) -> Result<Json<ApiResponse<ThreadSearchPage>>, ApiError> {
     if !search.allow_global_thread_search() {
@@ -198,10 +275,14 @@ pub async fn search_threads_global(
         .ok_or_else(|| ApiError::BadRequest("Query parameter 'q' is required".to_string()))?
         .to_string();
 
+    let parsed = parse_query_for(&query, backend.inner().as_ref(), |term| {
+        backend.supports_query_term(term)
+    })?;
+
     let page = params.page();
     let size = params.size();
-    let start_date = params.start_date_utc();
-    let end_date = params.end_date_utc();
+    let start_date = latest(params.start_date_utc(), parsed.start_date);
+    let end_date = earliest(params.end_date_utc(), parsed.end_date);
 
     if let (Some(start), Some(end)) = (start_date, end_date) {
         if end < start {
@@ -217,8 +298,12 @@ pub async fn search_threads_global(
 
     let participant_ids = params.participant_ids();
     let series_id = params.series_id();
+    let subsystems = params.subsystems();
+    let patch_states = params.patch_states();
     let has_patches = params.has_patches();
+    let incomplete_series = params.incomplete_series();
     let starter_id = params.starter_id();
+    let facets = params.facets();
 
     let sort_fields = params.sort_fields();
     let (sort_meta, sort_expressions) = parse_thread_search_sorts(&sort_fields);
@@ -238,7 +323,8 @@ pub async fn search_threads_global(
     };
 
     let payload = ThreadSearchPayload {
-        query: query.clone(),
+        query: parsed.text.clone(),
+        query_fields: parsed.text_fields(),
         page,
         size,
         semantic_ratio,
@@ -248,14 +334,20 @@ pub async fn search_threads_global(
         starter_id,
         participant_ids: participant_ids.clone(),
         series_id: series_id.clone(),
+        subsystems: subsystems.clone(),
+        patch_states: patch_states.clone(),
+        incomplete_series,
         mailing_lists: mailing_filters,
         sort_expressions,
+        query_filters: parsed.filters,
+        facets: facets.clone(),
     };
 
     let ThreadSearchResults {
         hits: raw_hits,
         total,
-    } = search.search_threads(payload).await?;
+        facets: facet_counts,
+    } = backend.search_threads(payload).await?;
     let hits = map_thread_hits(raw_hits, semantic_ratio);
 
     let pagination = PaginationMeta::new(page, size, total);
@@ -280,6 +372,9 @@ pub async fn search_threads_global(
     if let Some(value) = has_patches {
         filters.insert("hasPatches".to_string(), JsonValue::Bool(value));
     }
+    if let Some(value) = incomplete_series {
+        filters.insert("incompleteSeries".to_string(), JsonValue::Bool(value));
+    }
     if let Some(value) = starter_id {
         filters.insert("starterId".to_string(), JsonValue::from(value));
     }
@@ -297,6 +392,28 @@ pub async fn search_threads_global(
     if let Some(value) = series_id.clone() {
         filters.insert("seriesId".to_string(), JsonValue::String(value));
     }
+    if !subsystems.is_empty() {
+        filters.insert(
+            "subsystem".to_string(),
+            JsonValue::Array(
+                subsystems
+                    .iter()
+                    .map(|name| JsonValue::String(name.clone()))
+                    .collect(),
+            ),
+        );
+    }
+    if !patch_states.is_empty() {
+        filters.insert(
+            "state".to_string(),
+            JsonValue::Array(
+                patch_states
+                    .iter()
+                    .map(|state| JsonValue::String(state.as_str().to_string()))
+                    .collect(),
+            ),
+        );
+    }
     if !mailing_lists_sanitized.is_empty() {
         filters.insert(
             "mailingList".to_string(),
@@ -315,9 +432,14 @@ pub async fn search_threads_global(
 
     let mut search_meta = JsonMap::new();
     search_meta.insert("query".to_string(), JsonValue::String(query.clone()));
+    search_meta.insert("textQuery".to_string(), JsonValue::String(parsed.text));
     search_meta.insert("page".to_string(), JsonValue::from(page));
     search_meta.insert("pageSize".to_string(), JsonValue::from(size));
     search_meta.insert("semanticRatio".to_string(), JsonValue::from(semantic_ratio));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
     if !participant_ids.is_empty() {
         search_meta.insert(
             "participantIds".to_string(),
@@ -335,9 +457,23 @@ pub async fn search_threads_global(
     if let Some(value) = has_patches {
         search_meta.insert("hasPatches".to_string(), JsonValue::Bool(value));
     }
+    if let Some(value) = incomplete_series {
+        search_meta.insert("incompleteSeries".to_string(), JsonValue::Bool(value));
+    }
     if let Some(value) = starter_id {
         search_meta.insert("starterId".to_string(), JsonValue::from(value));
     }
+    if !facets.is_empty() {
+        search_meta.insert(
+            "facets".to_string(),
+            JsonValue::Array(
+                facets
+                    .iter()
+                    .map(|facet| JsonValue::String(facet.as_str().to_string()))
+                    .collect(),
+            ),
+        );
+    }
     if let Some(value) = start_date {
         search_meta.insert(
             "startDate".to_string(),
@@ -367,16 +503,518 @@ pub async fn search_threads_global(
     meta = meta.with_extra(extra);
 
     Ok(Json(ApiResponse::with_meta(
-        ThreadSearchPage { hits, total },
+        ThreadSearchPage {
+            hits,
+            total,
+            facets: map_facet_counts(facet_counts),
+        },
+        meta,
+    )))
+}
+
+#[openapi(tag = "Search")]
+#[get("/search/emails?<params..>")]
+pub async fn search_emails(
+    params: Option<EmailSearchParams>,
+    search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
+    mut db: Connection<NexusDb>,
+) -> Result<Json<ApiResponse<EmailSearchPage>>, ApiError> {
+    let params = params.unwrap_or_default();
+    let query = params
+        .query()
+        .ok_or_else(|| ApiError::BadRequest("Query parameter 'q' is required".to_string()))?
+        .to_string();
+
+    let mailing_lists_sanitized = params.mailing_lists();
+    if mailing_lists_sanitized.is_empty() && !search.allow_global_thread_search() {
+        return Err(ApiError::BadRequest(
+            "Global search is disabled for this deployment; pass mailingList".to_string(),
+        ));
+    }
+
+    let parsed = parse_query_for(&query, backend.inner().as_ref(), |term| {
+        backend.supports_email_query_term(term)
+    })?;
+
+    let page = params.page();
+    let size = params.size();
+    let start_date = latest(params.start_date_utc(), parsed.start_date);
+    let end_date = earliest(params.end_date_utc(), parsed.end_date);
+
+    if let (Some(start), Some(end)) = (start_date, end_date)
+        && end < start
+    {
+        return Err(ApiError::BadRequest(
+            "endDate must be greater than or equal to startDate".to_string(),
+        ));
+    }
+
+    let is_patch = params.is_patch;
+    let author_id = params.author_id();
+    let thread_id = params.thread_id();
+
+    let mailing_filters = if mailing_lists_sanitized.is_empty() {
+        Vec::new()
+    } else {
+        resolve_mailing_list_ids(&mailing_lists_sanitized, &mut db)
+            .await?
+            .into_iter()
+            .map(|(slug, id)| ThreadMailingListFilter {
+                slug,
+                mailing_list_id: Some(id),
+            })
+            .collect()
+    };
+
+    let payload = EmailSearchPayload {
+        query: parsed.text.clone(),
+        query_fields: parsed.text_fields(),
+        page,
+        size,
+        start_date,
+        end_date,
+        is_patch,
+        author_id,
+        thread_id,
+        mailing_lists: mailing_filters,
+        query_filters: parsed.filters,
+    };
+
+    let EmailSearchResults {
+        hits: raw_hits,
+        total,
+    } = backend.search_emails(payload).await?;
+    let hits = map_email_hits(raw_hits);
+
+    let mut meta = ResponseMeta::default().with_pagination(PaginationMeta::new(page, size, total));
+
+    let mut filters = JsonMap::new();
+    if let Some(start) = start_date {
+        filters.insert(
+            "startDate".to_string(),
+            JsonValue::String(start.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        );
+    }
+    if let Some(end) = end_date {
+        filters.insert(
+            "endDate".to_string(),
+            JsonValue::String(end.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        );
+    }
+    if let Some(value) = is_patch {
+        filters.insert("isPatch".to_string(), JsonValue::Bool(value));
+    }
+    if let Some(value) = author_id {
+        filters.insert("authorId".to_string(), JsonValue::from(value));
+    }
+    if let Some(value) = thread_id {
+        filters.insert("threadId".to_string(), JsonValue::from(value));
+    }
+    if !mailing_lists_sanitized.is_empty() {
+        filters.insert(
+            "mailingList".to_string(),
+            JsonValue::Array(
+                mailing_lists_sanitized
+                    .into_iter()
+                    .map(JsonValue::String)
+                    .collect(),
+            ),
+        );
+    }
+    if !filters.is_empty() {
+        meta = meta.with_filters(filters);
+    }
+
+    let mut search_meta = JsonMap::new();
+    search_meta.insert("query".to_string(), JsonValue::String(query));
+    search_meta.insert("textQuery".to_string(), JsonValue::String(parsed.text));
+    search_meta.insert("page".to_string(), JsonValue::from(page));
+    search_meta.insert("pageSize".to_string(), JsonValue::from(size));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
+
+    let mut extra = JsonMap::new();
+    extra.insert("search".to_string(), JsonValue::Object(search_meta));
+    meta = meta.with_extra(extra);
+
+    Ok(Json(ApiResponse::with_meta(
+        EmailSearchPage { hits, total },
         meta,
     )))
 }
 
+#[openapi(tag = "Search")]
+#[get("/search/patches?<params..>")]
+pub async fn search_patches(
+    params: Option<PatchSearchParams>,
+    search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
+    mut db: Connection<NexusDb>,
+) -> Result<Json<ApiResponse<PatchSearchPage>>, ApiError> {
+    let params = params.unwrap_or_default();
+    let query = params
+        .query()
+        .ok_or_else(|| ApiError::BadRequest("Query parameter 'q' is required".to_string()))?
+        .to_string();
+
+    let mailing_lists_sanitized = params.mailing_lists();
+    if mailing_lists_sanitized.is_empty() && !search.allow_global_thread_search() {
+        return Err(ApiError::BadRequest(
+            "Global search is disabled for this deployment; pass mailingList".to_string(),
+        ));
+    }
+
+    // Bare words match symbols touched by the diff, so they become filters
+    // alongside the prefixed terms
+    let mut parsed = parse_search_query(&query)?;
+    let mut query_filters = std::mem::take(&mut parsed.text_terms);
+    query_filters.append(&mut parsed.filters);
+    if let Some(err) = unsupported_terms_error(&query_filters, backend.kind().as_str(), |term| {
+        backend.supports_patch_query_term(term)
+    }) {
+        return Err(err.into());
+    }
+    let symbols = query_symbols(&query_filters);
+
+    let page = params.page();
+    let size = params.size();
+    let start_date = latest(params.start_date_utc(), parsed.start_date);
+    let end_date = earliest(params.end_date_utc(), parsed.end_date);
+
+    if let (Some(start), Some(end)) = (start_date, end_date)
+        && end < start
+    {
+        return Err(ApiError::BadRequest(
+            "endDate must be greater than or equal to startDate".to_string(),
+        ));
+    }
+
+    let author_id = params.author_id();
+    let thread_id = params.thread_id();
+
+    let mailing_filters = if mailing_lists_sanitized.is_empty() {
+        Vec::new()
+    } else {
+        resolve_mailing_list_ids(&mailing_lists_sanitized, &mut db)
+            .await?
+            .into_iter()
+            .map(|(slug, id)| ThreadMailingListFilter {
+                slug,
+                mailing_list_id: Some(id),
+            })
+            .collect()
+    };
+
+    let payload = PatchSearchPayload {
+        query: parsed.text.clone(),
+        page,
+        size,
+        start_date,
+        end_date,
+        author_id,
+        thread_id,
+        mailing_lists: mailing_filters,
+        query_filters,
+    };
+
+    let PatchSearchResults {
+        hits: raw_hits,
+        total,
+    } = backend.search_patches(payload).await?;
+    let hits = map_patch_hits(raw_hits, &symbols);
+
+    let mut meta = ResponseMeta::default().with_pagination(PaginationMeta::new(page, size, total));
+
+    let mut filters = JsonMap::new();
+    if let Some(start) = start_date {
+        filters.insert(
+            "startDate".to_string(),
+            JsonValue::String(start.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        );
+    }
+    if let Some(end) = end_date {
+        filters.insert(
+            "endDate".to_string(),
+            JsonValue::String(end.to_rfc3339_opts(SecondsFormat::Secs, true)),
+        );
+    }
+    if let Some(value) = author_id {
+        filters.insert("authorId".to_string(), JsonValue::from(value));
+    }
+    if let Some(value) = thread_id {
+        filters.insert("threadId".to_string(), JsonValue::from(value));
+    }
+    if !mailing_lists_sanitized.is_empty() {
+        filters.insert(
+            "mailingList".to_string(),
+            JsonValue::Array(
+                mailing_lists_sanitized
+                    .into_iter()
+                    .map(JsonValue::String)
+                    .collect(),
+            ),
+        );
+    }
+    if !filters.is_empty() {
+        meta = meta.with_filters(filters);
+    }
+
+    let mut search_meta = JsonMap::new();
+    search_meta.insert("query".to_string(), JsonValue::String(query));
+    search_meta.insert("textQuery".to_string(), JsonValue::String(parsed.text));
+    search_meta.insert(
+        "symbols".to_string(),
+        JsonValue::Array(symbols.into_iter().map(JsonValue::String).collect()),
+    );
+    search_meta.insert("page".to_string(), JsonValue::from(page));
+    search_meta.insert("pageSize".to_string(), JsonValue::from(size));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
+
+    let mut extra = JsonMap::new();
+    extra.insert("search".to_string(), JsonValue::Object(search_meta));
+    meta = meta.with_extra(extra);
+
+    Ok(Json(ApiResponse::with_meta(
+        PatchSearchPage { hits, total },
+        meta,
+    )))
+}
+
+/// Threads nearest to a thread by embedding similarity ("more like this").
+#[openapi(tag = "Search")]
+#[get("/lists/<slug>/threads/<thread_id>/similar?<params..>")]
+pub async fn similar_threads(
+    slug: String,
+    thread_id: i32,
+    params: Option<SimilarThreadsParams>,
+    search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
+    mut db: Connection<NexusDb>,
+) -> Result<Json<ApiResponse<ThreadSearchPage>>, ApiError> {
+    let params = params.unwrap_or_default();
+    if params.all_lists && !search.allow_global_thread_search() {
+        return Err(ApiError::BadRequest(
+            "Global search is disabled for this deployment; omit allLists".to_string(),
+        ));
+    }
+
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    let (starter_id, normalized_subject): (i32, Option<String>) = sqlx::query_as(
+        r#"SELECT starter.author_id, starter.normalized_subject
+           FROM threads t
+           JOIN emails starter ON starter.mailing_list_id = t.mailing_list_id
+               AND starter.message_id = t.root_message_id
+           WHERE t.mailing_list_id = $1 AND t.id = $2"#,
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .fetch_optional(&mut **db)
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("Thread {thread_id} not found in {slug}")))?;
+
+    let limit = params.limit();
+    let exclude_series = if params.exclude_series {
+        normalized_subject
+            .filter(|subject| !subject.is_empty())
+            .map(|normalized_subject| SeriesKey {
+                starter_id,
+                normalized_subject,
+            })
+    } else {
+        None
+    };
+
+    let payload = SimilarThreadsPayload {
+        thread_id,
+        mailing_list_id,
+        mailing_lists: if params.all_lists {
+            Vec::new()
+        } else {
+            vec![ThreadMailingListFilter {
+                slug: slug.clone(),
+                mailing_list_id: Some(mailing_list_id),
+            }]
+        },
+        limit,
+        exclude_series,
+        has_patches: params.has_patches,
+    };
+
+    let hits = map_thread_hits(backend.similar_threads(payload).await?, 1.0);
+    let total = hits.len() as i64;
+
+    let mut search_meta = JsonMap::new();
+    search_meta.insert("threadId".to_string(), JsonValue::from(thread_id));
+    search_meta.insert("limit".to_string(), JsonValue::from(limit));
+    search_meta.insert("allLists".to_string(), JsonValue::Bool(params.all_lists));
+    search_meta.insert(
+        "excludeSeries".to_string(),
+        JsonValue::Bool(params.exclude_series),
+    );
+    if let Some(value) = params.has_patches {
+        search_meta.insert("hasPatches".to_string(), JsonValue::Bool(value));
+    }
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
+    let mut extra = JsonMap::new();
+    extra.insert("search".to_string(), JsonValue::Object(search_meta));
+
+    Ok(Json(ApiResponse::with_meta(
+        ThreadSearchPage {
+            hits,
+            total,
+            facets: Vec::new(),
+        },
+        ResponseMeta::default().with_list_id(slug).with_extra(extra),
+    )))
+}
+
+/// Completions for a search box, cheap enough to call on every keystroke.
+#[openapi(tag = "Search")]
+#[get("/search/suggest?<params..>")]
+pub async fn search_suggest(
+    params: Option<SuggestParams>,
+    search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
+    mut db: Connection<NexusDb>,
+) -> Result<Json<ApiResponse<SearchSuggestions>>, ApiError> {
+    let params = params.unwrap_or_default();
+    let Some(query) = params.query() else {
+        return Ok(Json(ApiResponse::new(SearchSuggestions::default())));
+    };
+    let limit = params.limit();
+
+    let mailing_lists = if params.mailing_lists().is_empty() {
+        Vec::new()
+    } else {
+        resolve_mailing_list_ids(&params.mailing_lists(), &mut db)
+            .await?
+            .into_iter()
+            .map(|(slug, id)| ThreadMailingListFilter {
+                slug,
+                mailing_list_id: Some(id),
+            })
+            .collect()
+    };
+
+    let payload = SuggestPayload {
+        query: query.to_string(),
+        limit,
+        include_threads: !mailing_lists.is_empty() || search.allow_global_thread_search(),
+        mailing_lists,
+    };
+
+    // Backend lookups and the catalog queries below run concurrently
+    let (results, catalog) = tokio::join!(
+        backend.suggest(payload),
+        load_catalog_suggestions(query, limit, &mut db)
+    );
+    let SuggestResults { authors, threads } = results?;
+    let mut suggestions = catalog?;
+
+    suggestions.authors = authors
+        .into_iter()
+        .map(|author| SuggestedAuthor {
+            id: author.author_id,
+            name: author.canonical_name,
+            email: author.email,
+        })
+        .collect();
+    suggestions.threads = threads
+        .into_iter()
+        .map(|thread| SuggestedThread {
+            thread_id: thread.thread_id,
+            mailing_list: thread.mailing_list,
+            subject: thread.subject,
+            last_activity: timestamp_to_datetime(thread.last_ts),
+        })
+        .collect();
+
+    let mut search_meta = JsonMap::new();
+    search_meta.insert("query".to_string(), JsonValue::String(query.to_string()));
+    search_meta.insert("limit".to_string(), JsonValue::from(limit));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
+    let mut extra = JsonMap::new();
+    extra.insert("search".to_string(), JsonValue::Object(search_meta));
+
+    Ok(Json(ApiResponse::with_meta(
+        suggestions,
+        ResponseMeta::default().with_extra(extra),
+    )))
+}
+
+/// Mailing lists, subsystems and MAINTAINERS paths matching `query`. These
+/// tables are small, so they are queried directly for every backend.
+async fn load_catalog_suggestions(
+    query: &str,
+    limit: i64,
+    db: &mut Connection<NexusDb>,
+) -> Result<SearchSuggestions, ApiError> {
+    let prefix = format!("{}%", escape_like(query));
+    let substring = format!("%{}%", escape_like(query));
+
+    let mailing_lists = sqlx::query_as::<_, SuggestedMailingList>(
+        r#"SELECT slug, name
+           FROM mailing_lists
+           WHERE enabled AND (slug ILIKE $1 OR name ILIKE $2)
+           ORDER BY slug ILIKE $1 DESC, slug
+           LIMIT $3"#,
+    )
+    .bind(&prefix)
+    .bind(&substring)
+    .bind(limit)
+    .fetch_all(&mut ***db)
+    .await?;
+
+    let subsystems = sqlx::query_scalar::<_, String>(
+        r#"SELECT name
+           FROM subsystems
+           WHERE name ILIKE $2
+           ORDER BY name ILIKE $1 DESC, name
+           LIMIT $3"#,
+    )
+    .bind(&prefix)
+    .bind(&substring)
+    .bind(limit)
+    .fetch_all(&mut ***db)
+    .await?;
+
+    let paths = sqlx::query_scalar::<_, String>(
+        r#"SELECT DISTINCT pattern
+           FROM subsystems, unnest(file_patterns) AS pattern
+           WHERE pattern LIKE $1
+           ORDER BY pattern
+           LIMIT $2"#,
+    )
+    .bind(&prefix)
+    .bind(limit)
+    .fetch_all(&mut ***db)
+    .await?;
+
+    Ok(SearchSuggestions {
+        mailing_lists,
+        subsystems,
+        paths,
+        ..SearchSuggestions::default()
+    })
+}
+
 #[openapi(tag = "Search")]
 #[get("/authors/search?<params..>")]
 pub async fn search_authors(
     params: Option<AuthorSearchParams>,
-    search: &State<SearchService>,
+    backend: &State<Arc<dyn SearchBackend>>,
     mut db: Connection<NexusDb>,
 ) -> Result<Json<ApiResponse<AuthorSearchPage>>, ApiError> {
     let params = params.unwrap_or_default();
@@ -404,7 +1042,7 @@ pub async fn search_authors(
     let AuthorSearchResults {
         hits: raw_hits,
         total,
-    } = search.search_authors(payload).await?;
+    } = backend.search_authors(payload).await?;
     let hits = map_author_hits(raw_hits);
 
     let pagination = PaginationMeta::new(page, size, total);
@@ -436,6 +1074,10 @@ pub async fn search_authors(
     }
     search_meta.insert("page".to_string(), JsonValue::from(page));
     search_meta.insert("pageSize".to_string(), JsonValue::from(size));
+    search_meta.insert(
+        "backend".to_string(),
+        JsonValue::String(backend.kind().as_str().to_string()),
+    );
     search_meta.insert(
         "sortBy".to_string(),
         JsonValue::String(author_sort_field_name(sort_field).to_string()),
@@ -502,7 +1144,7 @@ pub async fn refresh_search_indexes(
 
     let job_id = queue
         .enqueue_job(
-            JobType::IndexMaintenance,
+            JobType::INDEX_MAINTENANCE,
             None,
             json!({
                 "action": "refresh",
@@ -534,7 +1176,7 @@ pub async fn reset_search_indexes(
 
     let job_id = queue
         .enqueue_job(
-            JobType::IndexMaintenance,
+            JobType::INDEX_MAINTENANCE,
             None,
             json!({ "action": "reset" }),
             priority,
@@ -549,6 +1191,33 @@ pub async fn reset_search_indexes(
     Ok(Json(ApiResponse::with_meta(job, ResponseMeta::default())))
 }
 
+/// Parse a lore-style query, move the conditions the backend searches as text
+/// into the text query and make sure it can evaluate every other term.
+fn parse_query_for(
+    query: &str,
+    backend: &dyn SearchBackend,
+    supported: impl Fn(&QueryTerm) -> bool,
+) -> Result<SearchQuery, ApiError> {
+    let mut parsed = parse_search_query(query)?;
+    parsed.fold_text_filters(|term| backend.searches_query_term_as_text(term));
+    if let Some(err) = unsupported_terms_error(&parsed.filters, backend.kind().as_str(), supported)
+    {
+        return Err(err.into());
+    }
+    Ok(parsed)
+}
+
+fn latest(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    a.max(b)
+}
+
+fn earliest(a: Option<DateTime<Utc>>, b: Option<DateTime<Utc>>) -> Option<DateTime<Utc>> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
 fn parse_thread_search_sorts(values: &[String]) -> (Vec<SortDescriptor>, Vec<String>) {
     if values.is_empty() {
         return (
@@ -612,6 +1281,20 @@ fn parse_thread_search_sorts(values: &[String]) -> (Vec<SortDescriptor>, Vec<Str
     (descriptors, expressions)
 }
 
+fn map_facet_counts(facets: Vec<FacetCounts>) -> Vec<ThreadSearchFacetCounts> {
+    facets
+        .into_iter()
+        .map(|counts| ThreadSearchFacetCounts {
+            facet: counts.facet,
+            values: counts
+                .values
+                .into_iter()
+                .map(|(value, count)| ThreadSearchFacetValue { value, count })
+                .collect(),
+        })
+        .collect()
+}
+
 fn map_thread_hits(hits: Vec<ThreadHit>, semantic_ratio: f32) -> Vec<ThreadSearchHit> {
     hits.into_iter()
         .map(|hit| {
@@ -650,6 +1333,9 @@ fn map_thread_hits(hits: Vec<ThreadHit>, semantic_ratio: f32) -> Vec<ThreadSearc
                 series_id: document.series_id.clone(),
                 series_number: document.series_number,
                 series_total: document.series_total,
+                subsystems: document.subsystems.clone(),
+                patch_states: document.patch_states.clone(),
+                incomplete_series: document.incomplete_series,
                 first_post_excerpt: document.first_post_excerpt.clone(),
                 score: ThreadSearchScore {
                     ranking_score,
@@ -663,6 +1349,120 @@ fn map_thread_hits(hits: Vec<ThreadHit>, semantic_ratio: f32) -> Vec<ThreadSearc
         .collect()
 }
 
+fn map_email_hits(hits: Vec<EmailHit>) -> Vec<EmailSearchHit> {
+    hits.into_iter()
+        .map(|hit| {
+            let EmailHit {
+                document,
+                ranking_score,
+                formatted,
+            } = hit;
+
+            let date = document
+                .date()
+                .or_else(|| timestamp_to_datetime(document.date_ts))
+                .unwrap_or_else(Utc::now);
+            let author_name = document
+                .author_name
+                .filter(|name| !name.is_empty() && *name != document.author_email);
+
+            EmailSearchHit {
+                email_id: document.email_id,
+                mailing_list_id: document.mailing_list_id,
+                mailing_list_slug: document.mailing_list,
+                message_id: document.message_id,
+                subject: document.subject,
+                date,
+                author: ThreadSearchParticipant {
+                    id: document.author_id,
+                    name: author_name,
+                    email: document.author_email,
+                },
+                to: document.to,
+                cc: document.cc,
+                is_patch: document.is_patch,
+                files: document.files,
+                thread: EmailSearchThreadContext {
+                    thread_id: document.thread_id,
+                    subject: document.thread_subject,
+                    root_message_id: document.thread_root_message_id,
+                    message_count: document.thread_message_count,
+                },
+                ranking_score,
+                highlights: formatted.as_ref().and_then(build_email_highlights),
+            }
+        })
+        .collect()
+}
+
+/// Identifiers the query looks for in diffs, in query order.
+fn query_symbols(filters: &[QueryNode]) -> Vec<String> {
+    let mut symbols: Vec<String> = Vec::new();
+    for term in filters.iter().flat_map(QueryNode::terms) {
+        let code_field = matches!(
+            term.field,
+            QueryField::Text
+                | QueryField::DiffSymbol
+                | QueryField::DiffAdded
+                | QueryField::DiffRemoved
+                | QueryField::DiffHunkHeader
+        );
+        if code_field
+            && !term.phrase
+            && is_identifier(&term.value)
+            && !symbols.contains(&term.value)
+        {
+            symbols.push(term.value.clone());
+        }
+    }
+    symbols
+}
+
+fn map_patch_hits(hits: Vec<PatchHit>, symbols: &[String]) -> Vec<PatchSearchHit> {
+    hits.into_iter()
+        .map(|hit| {
+            let PatchHit {
+                document,
+                ranking_score,
+                formatted,
+            } = hit;
+
+            let date = document
+                .date()
+                .or_else(|| timestamp_to_datetime(document.date_ts))
+                .unwrap_or_else(Utc::now);
+            let matched_symbols = symbols
+                .iter()
+                .filter(|symbol| document.touches(symbol))
+                .cloned()
+                .collect();
+            let author_name = document
+                .author_name
+                .filter(|name| !name.is_empty() && *name != document.author_email);
+
+            PatchSearchHit {
+                email_id: document.email_id,
+                mailing_list_id: document.mailing_list_id,
+                mailing_list_slug: document.mailing_list,
+                message_id: document.message_id,
+                subject: document.subject,
+                date,
+                author: ThreadSearchParticipant {
+                    id: document.author_id,
+                    name: author_name,
+                    email: document.author_email,
+                },
+                thread_id: document.thread_id,
+                files: document.files,
+                functions: document.functions,
+                matched_symbols,
+                ranking_score,
+                highlights: formatted.as_ref().and_then(build_patch_highlights),
+            }
+        })
+        .collect()
+}
+
 fn map_author_hits(hits: Vec<AuthorHit>) -> Vec<AuthorSearchHit> {
     hits.into_iter()
         .map(|hit| {
@@ -729,6 +1529,50 @@ fn build_thread_highlights(value: &JsonValue) -> Option<ThreadSearchHighlights>
     })
 }
 
+fn build_email_highlights(value: &JsonValue) -> Option<EmailSearchHighlights> {
+    let subject_html = value
+        .get("subject")
+        .and_then(|item| item.as_str())
+        .map(|s| s.to_string());
+    let body_html = value
+        .get("body")
+        .and_then(|item| item.as_str())
+        .map(|s| s.to_string());
+
+    if subject_html.is_none() && body_html.is_none() {
+        return None;
+    }
+
+    Some(EmailSearchHighlights {
+        subject_text: subject_html.as_deref().map(strip_markup),
+        body_text: body_html.as_deref().map(strip_markup),
+        subject_html,
+        body_html,
+    })
+}
+
+fn build_patch_highlights(value: &JsonValue) -> Option<PatchSearchHighlights> {
+    let added_html = value
+        .get("added_lines")
+        .and_then(|item| item.as_str())
+        .map(|s| s.to_string());
+    let removed_html = value
+        .get("removed_lines")
+        .and_then(|item| item.as_str())
+        .map(|s| s.to_string());
+
+    if added_html.is_none() && removed_html.is_none() {
+        return None;
+    }
+
+    Some(PatchSearchHighlights {
+        added_text: added_html.as_deref().map(strip_markup),
+        removed_text: removed_html.as_deref().map(strip_markup),
+        added_html,
+        removed_html,
+    })
+}
+
 fn collect_participants(document: &crate::search::ThreadDocument) -> Vec<ThreadSearchParticipant> {
     let mut participants = Vec::new();
     let limit = document
@@ -8,11 +8,54 @@ use crate::models::{
     ApiResponse, EmailHierarchy, PaginationMeta, ResponseMeta, SortDescriptor, SortDirection,
     Thread, ThreadDetail, ThreadWithStarter,
 };
+use crate::patches::load_thread_series;
 use crate::routes::{helpers::resolve_mailing_list_id, params::ThreadListParams};
-use rocket::get;
+use crate::sync::quotes::split_body;
 use rocket::serde::json::Json;
+use rocket::{State, get};
 use rocket_db_pools::{Connection, sqlx};
 use rocket_okapi::openapi;
+use serde_json::{Map as JsonMap, Value as JsonValue};
+
+/// Matches threads tagged with any subsystem in `$2` (no-op when `$2` is empty).
+const SUBSYSTEM_FILTER_SQL: &str = r#"(
+    cardinality($2::text[]) = 0
+    OR EXISTS (
+        SELECT 1
+        FROM thread_subsystems ts
+        JOIN subsystems s ON s.id = ts.subsystem_id
+        WHERE ts.mailing_list_id = t.mailing_list_id
+          AND ts.thread_id = t.id
+          AND s.name = ANY($2::text[])
+    )
+)"#;
+
+/// Matches threads with at least one patch in any state in `$3` (no-op when `$3` is empty).
+const PATCH_STATE_FILTER_SQL: &str = r#"(
+    cardinality($3::patch_state[]) = 0
+    OR EXISTS (
+        SELECT 1
+        FROM thread_memberships tm
+        JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id
+            AND ps.email_id = tm.email_id
+        WHERE tm.mailing_list_id = t.mailing_list_id
+          AND tm.thread_id = t.id
+          AND ps.state = ANY($3::patch_state[])
+    )
+)"#;
+
+/// Drops threads started by a bot report when `$4` is true.
+const BOT_FILTER_SQL: &str = r#"(
+    NOT $4::boolean
+    OR NOT EXISTS (
+        SELECT 1
+        FROM emails root
+        JOIN bot_reports br ON br.mailing_list_id = root.mailing_list_id
+            AND br.email_id = root.id
+        WHERE root.mailing_list_id = t.mailing_list_id
+          AND root.message_id = t.root_message_id
+    )
+)"#;
 
 fn parse_thread_sorts(values: &[String]) -> (Vec<String>, Vec<SortDescriptor>) {
     let mut clauses = Vec::new();
@@ -76,11 +119,21 @@ pub async fn list_threads(
     let sort_values = params.sort();
     let (order_clauses, sort_meta) = parse_thread_sorts(&sort_values);
     let order_sql = order_clauses.join(", ");
+    let subsystems = params.subsystems();
+    let patch_states = params.patch_states();
+    let exclude_bots = params.exclude_bots();
 
-    let total: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM threads WHERE mailing_list_id = $1")
-        .bind(mailing_list_id)
-        .fetch_one(&mut **db)
-        .await?;
+    let total: (i64,) = sqlx::query_as(&format!(
+        "SELECT COUNT(*) FROM threads t
+         WHERE t.mailing_list_id = $1 AND {SUBSYSTEM_FILTER_SQL} AND {PATCH_STATE_FILTER_SQL}
+           AND {BOT_FILTER_SQL}"
+    ))
+    .bind(mailing_list_id)
+    .bind(&subsystems)
+    .bind(&patch_states)
+    .bind(exclude_bots)
+    .fetch_one(&mut **db)
+    .await?;
 
     let query = format!(
         r#"
@@ -88,28 +141,72 @@ pub async fn list_threads(
                CAST(t.message_count AS INTEGER) AS message_count,
                e.author_id AS starter_id,
                a.canonical_name AS starter_name,
-               a.email AS starter_email
+               a.email AS starter_email,
+               COALESCE(
+                   (SELECT array_agg(s.name ORDER BY s.name)
+                    FROM thread_subsystems ts
+                    JOIN subsystems s ON s.id = ts.subsystem_id
+                    WHERE ts.mailing_list_id = t.mailing_list_id AND ts.thread_id = t.id),
+                   '{{}}'
+               ) AS subsystems,
+               COALESCE(
+                   (SELECT array_agg(DISTINCT ps.state)
+                    FROM thread_memberships tm
+                    JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id
+                        AND ps.email_id = tm.email_id
+                    WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id),
+                   '{{}}'
+               ) AS patch_states
         FROM threads t
         JOIN emails e ON t.root_message_id = e.message_id AND t.mailing_list_id = e.mailing_list_id
         JOIN authors a ON e.author_id = a.id
-        WHERE t.mailing_list_id = $1
+        WHERE t.mailing_list_id = $1 AND {SUBSYSTEM_FILTER_SQL} AND {PATCH_STATE_FILTER_SQL}
+           AND {BOT_FILTER_SQL}
         ORDER BY {order_sql}
-        LIMIT $2 OFFSET $3
+        LIMIT $5 OFFSET $6
         "#
     );
 
     let threads = sqlx::query_as::<_, ThreadWithStarter>(&query)
         .bind(mailing_list_id)
+        .bind(&subsystems)
+        .bind(&patch_states)
+        .bind(exclude_bots)
         .bind(page_size)
         .bind(offset)
         .fetch_all(&mut **db)
         .await?;
 
-    let meta = ResponseMeta::default()
+    let mut meta = ResponseMeta::default()
         .with_list_id(slug)
         .with_sort(sort_meta)
         .with_pagination(PaginationMeta::new(page, page_size, total.0));
 
+    let mut filters = JsonMap::new();
+    if !subsystems.is_empty() {
+        filters.insert(
+            "subsystem".to_string(),
+            JsonValue::Array(subsystems.into_iter().map(JsonValue::String).collect()),
+        );
+    }
+    if !patch_states.is_empty() {
+        filters.insert(
+            "state".to_string(),
+            JsonValue::Array(
+                patch_states
+                    .iter()
+                    .map(|state| JsonValue::String(state.as_str().to_string()))
+                    .collect(),
+            ),
+        );
+    }
+    if exclude_bots {
+        filters.insert("excludeBots".to_string(), JsonValue::Bool(true));
+    }
+    if !filters.is_empty() {
+        meta = meta.with_filters(filters);
+    }
+
     Ok(Json(ApiResponse::with_meta(threads, meta)))
 }
 
@@ -118,6 +215,7 @@ pub async fn list_threads(
 pub async fn get_thread(
     slug: String,
     mut db: Connection<NexusDb>,
+    pool: &State<sqlx::PgPool>,
     thread_id: i32,
 ) -> Result<Json<ApiResponse<ThreadDetail>>, ApiError> {
     let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
@@ -142,7 +240,7 @@ pub async fn get_thread(
             e.subject, e.date, e.in_reply_to, e.body, e.created_at,
             a.canonical_name AS author_name, a.email AS author_email,
             CAST(COALESCE(tm.depth, 0) AS INTEGER) AS depth,
-            e.patch_type, e.is_patch_only, e.patch_metadata
+            e.patch_type, e.is_patch_only, e.patch_metadata, e.patch_id
         FROM emails e
         JOIN authors a ON e.author_id = a.id
         JOIN thread_memberships tm ON e.id = tm.email_id AND tm.mailing_list_id = $1
@@ -155,8 +253,17 @@ pub async fn get_thread(
     .await?;
 
     emails = sort_emails_by_thread_order(emails);
+    for email in &mut emails {
+        email.segments = split_body(email.body.as_deref().unwrap_or_default());
+    }
+
+    let series = load_thread_series(pool.inner(), mailing_list_id, thread_id).await?;
 
-    let detail = ThreadDetail { thread, emails };
+    let detail = ThreadDetail {
+        thread,
+        emails,
+        series,
+    };
     let meta = ResponseMeta::default().with_list_id(slug);
     Ok(Json(ApiResponse::with_meta(detail, meta)))
 }
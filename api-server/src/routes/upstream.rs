@@ -0,0 +1,42 @@
+//! Upstream merge status endpoints.
+
+use crate::db::NexusDb;
+use crate::error::ApiError;
+use crate::models::{ApiResponse, ResponseMeta, UpstreamApplication};
+use crate::routes::helpers::{ensure_email_exists, resolve_mailing_list_id};
+use rocket::get;
+use rocket::serde::json::Json;
+use rocket_db_pools::{Connection, sqlx};
+use rocket_okapi::openapi;
+
+/// Upstream commits that applied a patch email, newest first.
+///
+/// An empty list means the patch hasn't been seen in any scanned tree.
+#[openapi(tag = "Upstream")]
+#[get("/lists/<slug>/emails/<email_id>/upstream")]
+pub async fn get_email_upstream(
+    slug: String,
+    mut db: Connection<NexusDb>,
+    email_id: i32,
+) -> Result<Json<ApiResponse<Vec<UpstreamApplication>>>, ApiError> {
+    let mailing_list_id = resolve_mailing_list_id(&slug, &mut db).await?;
+    ensure_email_exists(&mut db, mailing_list_id, email_id).await?;
+
+    let applications = sqlx::query_as::<_, UpstreamApplication>(
+        r#"SELECT t.name AS tree, ua.commit_hash, ua.commit_subject, ua.committed_at,
+                  ua.release_tag, ua.match_method, ua.matched_at
+           FROM upstream_applications ua
+           JOIN upstream_trees t ON t.id = ua.tree_id
+           WHERE ua.mailing_list_id = $1 AND ua.email_id = $2
+           ORDER BY ua.committed_at DESC, t.name"#,
+    )
+    .bind(mailing_list_id)
+    .bind(email_id)
+    .fetch_all(&mut **db)
+    .await?;
+
+    Ok(Json(ApiResponse::with_meta(
+        applications,
+        ResponseMeta::default().with_list_id(slug),
+    )))
+}
@@ -0,0 +1,153 @@
+//! Pluggable search backends.
+//!
+//! Thread, email, patch and author searches and search suggestions go through
+//! a [`SearchBackend`] selected by the `SEARCH_BACKEND` setting. Both backends
+//! accept the same [`ThreadSearchPayload`], [`EmailSearchPayload`],
+//! [`PatchSearchPayload`] and [`AuthorSearchPayload`] filters and return the
+//! same documents, so routes do not depend on which one is configured:
+//!
+//! - `meilisearch` (default): hybrid keyword/semantic search over the documents
+//!   maintained by the indexer ([`SearchService`]).
+//! - `postgres`: full-text search over the `emails.lex_ts` vectors that import
+//!   already writes (and the `patch_code` rows for diff content), for
+//!   deployments without Meilisearch. Semantic ratios are ignored and no
+//!   search documents are indexed; the indexer only stores thread embeddings
+//!   for similar-thread lookups.
+//!
+//! Lore-style query filters ([`QueryNode`](crate::search::QueryNode)) are
+//! evaluated by each backend as far as its data allows.
+
+use crate::search::error::SearchError;
+use crate::search::postgres::PostgresSearchBackend;
+use crate::search::query::QueryTerm;
+use crate::search::service::{
+    AuthorSearchPayload, AuthorSearchResults, EmailSearchPayload, EmailSearchResults,
+    PatchSearchPayload, PatchSearchResults, SearchService, SimilarThreadsPayload, SuggestPayload,
+    SuggestResults, ThreadHit, ThreadSearchPayload, ThreadSearchResults,
+};
+use rocket_db_pools::sqlx::PgPool;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Future returned by search backend queries.
+pub type SearchFuture<'a, T> = Pin<Box<dyn Future<Output = Result<T, SearchError>> + Send + 'a>>;
+
+/// Executes thread, email, patch and author searches.
+pub trait SearchBackend: Send + Sync {
+    /// Which backend this is.
+    fn kind(&self) -> SearchBackendKind;
+
+    /// Whether a term in [`ThreadSearchPayload::query_filters`] can be
+    /// evaluated. Date ranges and boolean operators are always supported, and
+    /// routes reject queries containing unsupported terms before searching.
+    fn supports_query_term(&self, term: &QueryTerm) -> bool;
+
+    /// Same as [`SearchBackend::supports_query_term`] for
+    /// [`EmailSearchPayload::query_filters`], which are evaluated per message.
+    fn supports_email_query_term(&self, term: &QueryTerm) -> bool;
+
+    /// Same as [`SearchBackend::supports_query_term`] for
+    /// [`PatchSearchPayload::query_filters`], which are evaluated against the
+    /// diff content of each patch.
+    fn supports_patch_query_term(&self, term: &QueryTerm) -> bool;
+
+    /// Whether thread and email searches match a term as free text in the
+    /// term's field rather than filtering on it. Routes move groups of such
+    /// terms into the text query before checking for unsupported terms.
+    fn searches_query_term_as_text(&self, term: &QueryTerm) -> bool;
+
+    fn search_threads<'a>(
+        &'a self,
+        options: ThreadSearchPayload,
+    ) -> SearchFuture<'a, ThreadSearchResults>;
+
+    fn search_authors<'a>(
+        &'a self,
+        options: AuthorSearchPayload,
+    ) -> SearchFuture<'a, AuthorSearchResults>;
+
+    fn search_emails<'a>(
+        &'a self,
+        options: EmailSearchPayload,
+    ) -> SearchFuture<'a, EmailSearchResults>;
+
+    fn search_patches<'a>(
+        &'a self,
+        options: PatchSearchPayload,
+    ) -> SearchFuture<'a, PatchSearchResults>;
+
+    /// Threads nearest to one thread by embedding similarity, closest first.
+    /// The source thread is never among the hits.
+    fn similar_threads<'a>(
+        &'a self,
+        options: SimilarThreadsPayload,
+    ) -> SearchFuture<'a, Vec<ThreadHit>>;
+
+    /// Authors and recent thread subjects for a partially typed query. Called
+    /// on every keystroke, so implementations skip embeddings, highlighting
+    /// and totals.
+    fn suggest<'a>(&'a self, options: SuggestPayload) -> SearchFuture<'a, SuggestResults>;
+}
+
+/// Configured search backend.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SearchBackendKind {
+    #[default]
+    Meilisearch,
+    Postgres,
+}
+
+impl SearchBackendKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchBackendKind::Meilisearch => "meilisearch",
+            SearchBackendKind::Postgres => "postgres",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "meilisearch" | "meili" => Some(SearchBackendKind::Meilisearch),
+            "postgres" | "postgresql" | "pg" => Some(SearchBackendKind::Postgres),
+            _ => None,
+        }
+    }
+
+    /// Whether the backend searches documents that the indexer must maintain.
+    pub fn uses_document_index(&self) -> bool {
+        matches!(self, SearchBackendKind::Meilisearch)
+    }
+
+    /// Instantiate the backend.
+    pub fn build(self, search: &SearchService, pool: PgPool) -> Arc<dyn SearchBackend> {
+        match self {
+            SearchBackendKind::Meilisearch => Arc::new(search.clone()),
+            SearchBackendKind::Postgres => Arc::new(PostgresSearchBackend::new(
+                pool,
+                search.embedding_model(),
+                search.thread_embedding_dimensions(),
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_backend_names() {
+        assert_eq!(
+            SearchBackendKind::parse("Postgres"),
+            Some(SearchBackendKind::Postgres)
+        );
+        assert_eq!(
+            SearchBackendKind::parse(" meilisearch "),
+            Some(SearchBackendKind::Meilisearch)
+        );
+        assert_eq!(SearchBackendKind::parse("elastic"), None);
+        assert!(SearchBackendKind::Meilisearch.uses_document_index());
+        assert!(!SearchBackendKind::Postgres.uses_document_index());
+    }
+}
@@ -0,0 +1,325 @@
+//! Code-aware tokenization of patch diffs.
+//!
+//! [`strip_patch_payload`](crate::search::sanitize::strip_patch_payload) keeps
+//! diffs out of the prose search text. This module indexes them separately:
+//! [`PatchCode`] collects the touched paths, the functions named in hunk
+//! headers, and the identifiers and text of added and removed lines. The
+//! `patch_code` email processor stores it per patch email; the PostgreSQL
+//! backend queries that table and the indexer copies it into the `patches`
+//! Meilisearch index.
+//!
+//! Identifiers are kept whole (`kmem_cache_alloc`, `CONFIG_FOO`) instead of
+//! being split on `_` like prose tokenizers do, so a symbol only matches
+//! itself.
+
+use crate::models::{DiffFile, DiffLineKind, PatchDiff, PatchType};
+use crate::patches::parse_patch_diff;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::HashSet;
+
+/// Identifiers kept per side of a patch.
+const MAX_IDENTIFIERS: usize = 5_000;
+
+/// Bytes of added or removed line text kept per patch.
+const MAX_LINE_TEXT: usize = 64 * 1024;
+
+/// C keywords and types too common to be worth indexing.
+const STOP_IDENTIFIERS: &[&str] = &[
+    "auto", "bool", "break", "case", "char", "const", "continue", "default", "do", "double",
+    "else", "enum", "extern", "false", "float", "for", "goto", "if", "inline", "int", "long",
+    "return", "short", "signed", "sizeof", "static", "struct", "switch", "true", "typedef",
+    "union", "unsigned", "void", "volatile", "while",
+];
+
+/// Searchable view of a patch's diff.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PatchCode {
+    /// Paths touched by the patch (new path, or old path for deletions).
+    pub files: Vec<String>,
+    /// Functions or sections named in hunk headers.
+    pub functions: Vec<String>,
+    pub added_identifiers: Vec<String>,
+    pub removed_identifiers: Vec<String>,
+    /// Added lines, newline separated and truncated to [`MAX_LINE_TEXT`].
+    pub added_lines: String,
+    pub removed_lines: String,
+}
+
+impl PatchCode {
+    pub fn from_diff(diff: &PatchDiff) -> Self {
+        let mut code = PatchCode::default();
+        let mut functions = HashSet::new();
+        let mut added = HashSet::new();
+        let mut removed = HashSet::new();
+
+        for file in &diff.files {
+            if let Some(path) = file.new_path.as_ref().or(file.old_path.as_ref()) {
+                code.files.push(path.clone());
+            }
+            let kconfig = is_kconfig(file);
+
+            for hunk in &file.hunks {
+                if let Some(name) = hunk.section.as_deref().and_then(function_name)
+                    && functions.insert(name.clone())
+                {
+                    code.functions.push(name);
+                }
+
+                for line in &hunk.lines {
+                    let (identifiers, seen, text) = match line.kind {
+                        DiffLineKind::Addition => (
+                            &mut code.added_identifiers,
+                            &mut added,
+                            &mut code.added_lines,
+                        ),
+                        DiffLineKind::Deletion => (
+                            &mut code.removed_identifiers,
+                            &mut removed,
+                            &mut code.removed_lines,
+                        ),
+                        DiffLineKind::Context | DiffLineKind::NoNewline => continue,
+                    };
+
+                    push_line_text(text, &line.content);
+                    let mut tokens = code_identifiers(&line.content);
+                    if kconfig && let Some(symbol) = kconfig_symbol(&line.content) {
+                        tokens.push(symbol);
+                    }
+                    for token in tokens {
+                        if identifiers.len() < MAX_IDENTIFIERS && seen.insert(token.clone()) {
+                            identifiers.push(token);
+                        }
+                    }
+                }
+            }
+        }
+
+        code
+    }
+}
+
+/// Whether `value` is a single identifier, matched exactly against the
+/// identifier lists rather than as a substring of line text.
+pub fn is_identifier(value: &str) -> bool {
+    let mut chars = value.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Identifiers in a line of code, in order of appearance.
+///
+/// Tokens run over ASCII letters, digits and `_`; numbers, single characters
+/// and [`STOP_IDENTIFIERS`] are skipped.
+pub fn code_identifiers(line: &str) -> Vec<String> {
+    line.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|token| token.len() > 1 && is_identifier(token))
+        .filter(|token| !STOP_IDENTIFIERS.contains(token))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Function (or struct, macro, Kconfig entry) named by a hunk header section:
+/// the last identifier before any parameter list, body or initializer.
+fn function_name(section: &str) -> Option<String> {
+    let head = section
+        .split(['(', '{', '=', '"'])
+        .next()
+        .unwrap_or(section);
+    code_identifiers(head).pop()
+}
+
+fn is_kconfig(file: &DiffFile) -> bool {
+    file.new_path
+        .as_deref()
+        .or(file.old_path.as_deref())
+        .and_then(|path| path.rsplit('/').next())
+        .is_some_and(|name| name.starts_with("Kconfig"))
+}
+
+/// `CONFIG_FOO` for a `config FOO` or `menuconfig FOO` Kconfig line.
+fn kconfig_symbol(line: &str) -> Option<String> {
+    let mut words = line.split_whitespace();
+    let keyword = words.next()?;
+    if keyword != "config" && keyword != "menuconfig" {
+        return None;
+    }
+    let name = words.next().filter(|name| is_identifier(name))?;
+    Some(format!("CONFIG_{name}"))
+}
+
+fn push_line_text(text: &mut String, line: &str) {
+    if text.len() + line.len() + 1 > MAX_LINE_TEXT {
+        return;
+    }
+    if !text.is_empty() {
+        text.push('\n');
+    }
+    text.push_str(line);
+}
+
+/// Stores the [`PatchCode`] of patch emails in `patch_code`.
+pub struct PatchCodeProcessor;
+
+impl EmailProcessor for PatchCodeProcessor {
+    fn name(&self) -> &'static str {
+        "patch_code"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let mut email_ids = Vec::new();
+            let mut files = Vec::new();
+            let mut functions = Vec::new();
+            let mut added_identifiers = Vec::new();
+            let mut removed_identifiers = Vec::new();
+            let mut added_lines = Vec::new();
+            let mut removed_lines = Vec::new();
+
+            for email in emails {
+                if email.patch_type == PatchType::None {
+                    continue;
+                }
+                let Some(diff) = parse_patch_diff(
+                    &email.body,
+                    email.patch_metadata.as_ref().map(|json| &json.0),
+                ) else {
+                    continue;
+                };
+                let code = PatchCode::from_diff(&diff);
+                email_ids.push(email.email_id);
+                // Lists travel newline-joined since arrays can't be unnested per row
+                files.push(code.files.join("\n"));
+                functions.push(code.functions.join("\n"));
+                added_identifiers.push(code.added_identifiers.join("\n"));
+                removed_identifiers.push(code.removed_identifiers.join("\n"));
+                added_lines.push(code.added_lines);
+                removed_lines.push(code.removed_lines);
+            }
+
+            if email_ids.is_empty() {
+                return Ok(0);
+            }
+
+            sqlx::query(
+                r#"INSERT INTO patch_code (
+                       mailing_list_id, email_id, files, functions,
+                       added_identifiers, removed_identifiers, added_lines, removed_lines
+                   )
+                   SELECT $1, p.email_id,
+                          string_to_array(p.files, E'\n'),
+                          string_to_array(p.functions, E'\n'),
+                          string_to_array(p.added_identifiers, E'\n'),
+                          string_to_array(p.removed_identifiers, E'\n'),
+                          p.added_lines, p.removed_lines
+                   FROM UNNEST($2::int[], $3::text[], $4::text[], $5::text[], $6::text[],
+                               $7::text[], $8::text[])
+                       AS p(email_id, files, functions, added_identifiers,
+                            removed_identifiers, added_lines, removed_lines)
+                   ON CONFLICT (mailing_list_id, email_id) DO UPDATE
+                   SET files = EXCLUDED.files,
+                       functions = EXCLUDED.functions,
+                       added_identifiers = EXCLUDED.added_identifiers,
+                       removed_identifiers = EXCLUDED.removed_identifiers,
+                       added_lines = EXCLUDED.added_lines,
+                       removed_lines = EXCLUDED.removed_lines"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&email_ids)
+            .bind(&files)
+            .bind(&functions)
+            .bind(&added_identifiers)
+            .bind(&removed_identifiers)
+            .bind(&added_lines)
+            .bind(&removed_lines)
+            .execute(pool)
+            .await?;
+
+            log::trace!("stored diff content of {} patches", email_ids.len());
+            Ok(email_ids.len())
+        })
+    }
+
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            let result = sqlx::query("DELETE FROM patch_code WHERE mailing_list_id = $1")
+                .bind(mailing_list_id)
+                .execute(pool)
+                .await?;
+            Ok(result.rows_affected() as usize)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "Subject: [PATCH] mm: switch to kmem_cache_zalloc\n\
+\n\
+---\n\
+diff --git a/mm/slab.c b/mm/slab.c\n\
+index 1111111..2222222 100644\n\
+--- a/mm/slab.c\n\
++++ b/mm/slab.c\n\
+@@ -10,3 +10,3 @@ static struct page *alloc_slab_page(gfp_t flags)\n \
+int nr = 0;\n\
+-\tpage = kmem_cache_alloc(cachep, flags);\n\
++\tpage = kmem_cache_zalloc(cachep, flags | __GFP_ZERO);\n \
+return page;\n\
+diff --git a/mm/Kconfig b/mm/Kconfig\n\
+index 3333333..4444444 100644\n\
+--- a/mm/Kconfig\n\
++++ b/mm/Kconfig\n\
+@@ -1,2 +1,5 @@ config SLUB\n \
+\n\
++config SLAB_ZERO\n\
++\tbool \"Zero slab pages\"\n\
++\n \
+source \"mm/Kconfig.debug\"\n";
+
+    #[test]
+    fn tokenizes_identifiers_whole() {
+        assert_eq!(
+            code_identifiers("if (ret < 0x1f && kmem_cache_alloc(s, GFP_KERNEL))"),
+            vec!["ret", "kmem_cache_alloc", "GFP_KERNEL"]
+        );
+        assert!(is_identifier("CONFIG_FOO"));
+        assert!(!is_identifier("net/core.c"));
+        assert!(!is_identifier("9p"));
+    }
+
+    #[test]
+    fn extracts_patch_code() {
+        let diff = parse_patch_diff(PATCH, None).expect("diff");
+        let code = PatchCode::from_diff(&diff);
+
+        assert_eq!(code.files, vec!["mm/slab.c", "mm/Kconfig"]);
+        assert_eq!(code.functions, vec!["alloc_slab_page", "SLUB"]);
+        assert_eq!(
+            code.removed_identifiers,
+            vec!["page", "kmem_cache_alloc", "cachep", "flags"]
+        );
+        assert!(
+            code.added_identifiers
+                .contains(&"kmem_cache_zalloc".to_string())
+        );
+        assert!(
+            code.added_identifiers
+                .contains(&"CONFIG_SLAB_ZERO".to_string())
+        );
+        assert!(code.added_lines.contains("Zero slab pages"));
+        assert!(!code.removed_identifiers.contains(&"nr".to_string()));
+    }
+}
@@ -0,0 +1,271 @@
+//! Thread embeddings cached across reindexes.
+//!
+//! Embedding every thread on every reindex makes full rebuilds of large lists
+//! slow and loads the embeddings server with text it has already seen. The
+//! indexer keeps the last vector of each thread in `thread_embeddings`, along
+//! with a SHA-256 of the embedded text and the embedding model and dimensions
+//! that produced it. A thread is only re-embedded when one of those changes;
+//! rows from another model or dimension count are purged before a reindex.
+//! Fallback zero vectors (embedding failures) are never stored; their threads
+//! are listed in `thread_embedding_failures` instead, and the next incremental
+//! reindex of the list embeds them again.
+//!
+//! The same rows back the similar-threads lookups of the PostgreSQL search
+//! backend, so vectors are stored whichever backend is configured.
+
+use crate::search::{SearchError, SearchService};
+use log::{debug, warn};
+use rocket_db_pools::sqlx::{self, PgPool};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+
+/// SHA-256 of the text sent to the embedding model.
+pub fn content_hash(text: &str) -> Vec<u8> {
+    Sha256::digest(text.as_bytes()).to_vec()
+}
+
+#[derive(sqlx::FromRow)]
+struct CachedEmbeddingRow {
+    thread_id: i32,
+    content_hash: Vec<u8>,
+    embedding: Vec<f32>,
+}
+
+/// Text to embed for one thread.
+pub struct EmbeddingInput<'t> {
+    pub mailing_list_id: i32,
+    pub thread_id: i32,
+    pub text: &'t str,
+}
+
+struct PendingEmbedding {
+    mailing_list_id: i32,
+    thread_id: i32,
+    content_hash: Vec<u8>,
+    embedding: Vec<f32>,
+}
+
+/// Cached embeddings of one batch of threads, plus the vectors computed for
+/// the misses until they are written back by [`ThreadEmbeddingCache::flush`].
+pub struct ThreadEmbeddingCache<'a> {
+    pool: &'a PgPool,
+    model: &'a str,
+    dimensions: usize,
+    cached: HashMap<i32, CachedEmbeddingRow>,
+    pending: Vec<PendingEmbedding>,
+    /// `(mailing_list_id, thread_id)` of threads given a zero vector
+    failed: Vec<(i32, i32)>,
+    hits: usize,
+}
+
+impl<'a> ThreadEmbeddingCache<'a> {
+    /// Load the cached embeddings of `thread_ids` produced by the configured
+    /// model and dimensions.
+    pub async fn load(
+        pool: &'a PgPool,
+        search: &'a SearchService,
+        thread_ids: &[i32],
+    ) -> Result<Self, SearchError> {
+        let model = search.embedding_model();
+        let dimensions = search.thread_embedding_dimensions();
+
+        let rows = sqlx::query_as::<_, CachedEmbeddingRow>(
+            r#"SELECT thread_id, content_hash, embedding::real[] AS embedding
+               FROM thread_embeddings
+               WHERE thread_id = ANY($1) AND model = $2 AND dimensions = $3"#,
+        )
+        .bind(thread_ids)
+        .bind(model)
+        .bind(dimensions as i32)
+        .fetch_all(pool)
+        .await
+        .map_err(SearchError::Database)?;
+
+        Ok(Self {
+            pool,
+            model,
+            dimensions,
+            cached: rows.into_iter().map(|row| (row.thread_id, row)).collect(),
+            pending: Vec::new(),
+            failed: Vec::new(),
+            hits: 0,
+        })
+    }
+
+    /// Embeddings of `inputs`, in order: the cached vector of each thread
+    /// whose text is unchanged, and fresh ones for the rest, requested from
+    /// the embeddings provider in batches.
+    pub async fn embed(
+        &mut self,
+        search: &SearchService,
+        inputs: &[EmbeddingInput<'_>],
+    ) -> Vec<Vec<f32>> {
+        let mut vectors: Vec<Option<Vec<f32>>> = Vec::with_capacity(inputs.len());
+        let mut misses = Vec::new();
+        for (position, input) in inputs.iter().enumerate() {
+            let hash = content_hash(input.text);
+            match self.cached.remove(&input.thread_id) {
+                Some(row) if row.content_hash == hash && row.embedding.len() == self.dimensions => {
+                    self.hits += 1;
+                    vectors.push(Some(row.embedding));
+                }
+                _ => {
+                    misses.push((position, hash));
+                    vectors.push(None);
+                }
+            }
+        }
+
+        // Each batch falls back on its own, so one failing request only
+        // leaves its own threads with zero vectors
+        let texts: Vec<&str> = misses
+            .iter()
+            .map(|(position, _)| inputs[*position].text)
+            .collect();
+        let embedded = search.embeddings().embed_each_batch(&texts).await;
+        let mut failed = 0;
+        for ((position, hash), embedding) in misses.into_iter().zip(embedded) {
+            let input = &inputs[position];
+            let Some(embedding) = embedding else {
+                self.failed.push((input.mailing_list_id, input.thread_id));
+                failed += 1;
+                continue;
+            };
+            self.pending.push(PendingEmbedding {
+                mailing_list_id: input.mailing_list_id,
+                thread_id: input.thread_id,
+                content_hash: hash,
+                embedding: embedding.clone(),
+            });
+            vectors[position] = Some(embedding);
+        }
+        if failed > 0 {
+            warn!(
+                "embedding requests failed for {} threads. Falling back to zero vectors.",
+                failed
+            );
+        }
+
+        vectors
+            .into_iter()
+            .map(|vector| vector.unwrap_or_else(|| vec![0.0; self.dimensions]))
+            .collect()
+    }
+
+    /// Store the embeddings computed since [`ThreadEmbeddingCache::load`] and
+    /// record the threads that fell back to zero vectors for a retry.
+    pub async fn flush(self) -> Result<(), SearchError> {
+        debug!(
+            "thread embedding cache: {} reused, {} embedded, {} failed",
+            self.hits,
+            self.pending.len(),
+            self.failed.len()
+        );
+        if self.pending.is_empty() && self.failed.is_empty() {
+            return Ok(());
+        }
+
+        let mut tx = self.pool.begin().await.map_err(SearchError::Database)?;
+        for entry in &self.pending {
+            sqlx::query(
+                r#"INSERT INTO thread_embeddings (
+                       mailing_list_id, thread_id, content_hash, model, dimensions, embedding
+                   )
+                   VALUES ($1, $2, $3, $4, $5, $6::real[]::vector)
+                   ON CONFLICT (mailing_list_id, thread_id) DO UPDATE
+                   SET content_hash = EXCLUDED.content_hash,
+                       model = EXCLUDED.model,
+                       dimensions = EXCLUDED.dimensions,
+                       embedding = EXCLUDED.embedding,
+                       aggregated_at = NOW()"#,
+            )
+            .bind(entry.mailing_list_id)
+            .bind(entry.thread_id)
+            .bind(&entry.content_hash)
+            .bind(self.model)
+            .bind(self.dimensions as i32)
+            .bind(&entry.embedding)
+            .execute(&mut *tx)
+            .await
+            .map_err(SearchError::Database)?;
+        }
+
+        let (list_ids, thread_ids): (Vec<i32>, Vec<i32>) = self
+            .pending
+            .iter()
+            .map(|entry| (entry.mailing_list_id, entry.thread_id))
+            .unzip();
+        sqlx::query(
+            r#"DELETE FROM thread_embedding_failures f
+               USING UNNEST($1::int[], $2::int[]) AS t(mailing_list_id, thread_id)
+               WHERE f.mailing_list_id = t.mailing_list_id AND f.thread_id = t.thread_id"#,
+        )
+        .bind(&list_ids)
+        .bind(&thread_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(SearchError::Database)?;
+
+        let (list_ids, thread_ids): (Vec<i32>, Vec<i32>) = self.failed.iter().copied().unzip();
+        sqlx::query(
+            r#"INSERT INTO thread_embedding_failures (mailing_list_id, thread_id)
+               SELECT * FROM UNNEST($1::int[], $2::int[])
+               ON CONFLICT (mailing_list_id, thread_id) DO UPDATE SET failed_at = NOW()"#,
+        )
+        .bind(&list_ids)
+        .bind(&thread_ids)
+        .execute(&mut *tx)
+        .await
+        .map_err(SearchError::Database)?;
+        tx.commit().await.map_err(SearchError::Database)?;
+
+        Ok(())
+    }
+}
+
+/// Threads of a mailing list that were indexed with a fallback zero vector
+/// and still need an embedding.
+pub async fn load_failed_embeddings(
+    pool: &PgPool,
+    mailing_list_id: i32,
+) -> Result<Vec<i32>, SearchError> {
+    sqlx::query_scalar(
+        "SELECT thread_id FROM thread_embedding_failures WHERE mailing_list_id = $1 ORDER BY thread_id",
+    )
+    .bind(mailing_list_id)
+    .fetch_all(pool)
+    .await
+    .map_err(SearchError::Database)
+}
+
+/// Drop stored embeddings that the configured model and dimensions can no
+/// longer reuse or compare against.
+///
+/// # Returns
+/// Number of rows removed
+pub async fn purge_stale_embeddings(
+    pool: &PgPool,
+    search: &SearchService,
+) -> Result<u64, SearchError> {
+    let result = sqlx::query("DELETE FROM thread_embeddings WHERE model <> $1 OR dimensions <> $2")
+        .bind(search.embedding_model())
+        .bind(search.thread_embedding_dimensions() as i32)
+        .execute(pool)
+        .await
+        .map_err(SearchError::Database)?;
+
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hashes_embedded_text() {
+        let hash = content_hash("mm: fix slab leak\n\nbody");
+        assert_eq!(hash.len(), 32);
+        assert_eq!(hash, content_hash("mm: fix slab leak\n\nbody"));
+        assert_ne!(hash, content_hash("mm: fix slab leak\n\nbody "));
+    }
+}
@@ -1,163 +1,628 @@
+//! Pluggable embedding providers.
+//!
+//! Thread documents and semantic queries are embedded through an
+//! [`EmbeddingsClient`] wrapping one [`EmbeddingProvider`] selected by the
+//! `EMBEDDINGS_PROVIDER` setting:
+//!
+//! - `tei`: Text Embeddings Inference `POST /embed`.
+//! - `openai`: OpenAI-compatible `POST /v1/embeddings` (OpenAI, vLLM, Ollama,
+//!   TEI's compatibility route, ...), with an optional bearer key.
+//! - `hash`: deterministic feature hashing of the text's words, computed
+//!   locally. Needs no server, so tests and offline deployments can index and
+//!   query without one; similarity is lexical only.
+//!
+//! Providers only issue a single request. The client splits input into
+//! batches, bounds every request with a timeout, retries transport errors,
+//! timeouts, `429` and `5xx` responses with exponential backoff, and checks
+//! that every batch returns one vector per text with the configured dimension
+//! count. Longer vectors are truncated (Matryoshka-trained models such as
+//! Qwen3 keep their leading components meaningful); shorter ones are an error.
+
+use crate::search::backend::SearchFuture;
 use crate::search::error::SearchError;
 use log::{debug, warn};
 use reqwest::{Client, StatusCode};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::sync::Arc;
 use std::time::Duration;
 use tokio::time::{sleep, timeout};
 
-const EMBEDDING_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
-const EMBEDDING_MAX_RETRIES: usize = 3;
+/// Model served by the embeddings server unless `EMBEDDINGS_MODEL_ID` says
+/// otherwise.
+const DEFAULT_EMBEDDING_MODEL: &str = "Qwen/Qwen3-Embedding-0.6B";
+/// Model identifier recorded for vectors of the `hash` provider.
+const HASH_EMBEDDING_MODEL: &str = "local-feature-hash-v1";
+const DEFAULT_EMBEDDING_DIMENSIONS: usize = 1024;
+const DEFAULT_EMBEDDING_BATCH_SIZE: usize = 32;
+const DEFAULT_EMBEDDING_REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const DEFAULT_EMBEDDING_MAX_RETRIES: usize = 3;
 const EMBEDDING_RETRY_BACKOFF_MS: u64 = 750;
 const EMBEDDING_RETRY_BACKOFF_FACTOR: u64 = 2;
 
+/// Configured embedding provider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingProviderKind {
+    Tei,
+    OpenAi,
+    Hash,
+}
+
+impl EmbeddingProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EmbeddingProviderKind::Tei => "tei",
+            EmbeddingProviderKind::OpenAi => "openai",
+            EmbeddingProviderKind::Hash => "hash",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "tei" => Some(EmbeddingProviderKind::Tei),
+            "openai" | "openai-compatible" => Some(EmbeddingProviderKind::OpenAi),
+            "hash" | "local" => Some(EmbeddingProviderKind::Hash),
+            _ => None,
+        }
+    }
+}
+
+/// Settings of the embeddings client.
+#[derive(Debug, Clone)]
+pub struct EmbeddingsConfig {
+    pub provider: EmbeddingProviderKind,
+    /// Server root; required by the `tei` and `openai` providers.
+    pub url: Option<String>,
+    pub model: String,
+    /// Bearer token sent by the `openai` provider.
+    pub api_key: Option<String>,
+    pub dimensions: usize,
+    pub batch_size: usize,
+    pub request_timeout: Duration,
+    pub max_retries: usize,
+}
+
+impl EmbeddingsConfig {
+    /// Settings for `provider` with default batching, timeout and retries.
+    pub fn new(provider: EmbeddingProviderKind, dimensions: usize) -> Self {
+        Self {
+            provider,
+            url: None,
+            model: match provider {
+                EmbeddingProviderKind::Hash => HASH_EMBEDDING_MODEL.to_string(),
+                _ => DEFAULT_EMBEDDING_MODEL.to_string(),
+            },
+            api_key: None,
+            dimensions,
+            batch_size: DEFAULT_EMBEDDING_BATCH_SIZE,
+            request_timeout: DEFAULT_EMBEDDING_REQUEST_TIMEOUT,
+            max_retries: DEFAULT_EMBEDDING_MAX_RETRIES,
+        }
+    }
+
+    /// Read the settings from the environment:
+    ///
+    /// - `EMBEDDINGS_PROVIDER`: `tei`, `openai` or `hash`. Defaults to `tei`
+    ///   when `EMBEDDINGS_URL` is set and to `hash` otherwise.
+    /// - `EMBEDDINGS_URL`, `EMBEDDINGS_MODEL_ID`, `EMBEDDINGS_API_KEY`
+    /// - `SEARCH_EMBEDDING_DIM` (default 1024)
+    /// - `EMBEDDINGS_BATCH_SIZE` (default 32), `EMBEDDINGS_TIMEOUT_SECS`
+    ///   (default 20), `EMBEDDINGS_MAX_RETRIES` (default 3)
+    pub fn from_env() -> Result<Self, String> {
+        let url = env_var("EMBEDDINGS_URL");
+        let provider = match env_var("EMBEDDINGS_PROVIDER") {
+            Some(value) => EmbeddingProviderKind::parse(&value).ok_or_else(|| {
+                format!("Unknown EMBEDDINGS_PROVIDER '{value}' (expected tei, openai or hash)")
+            })?,
+            None if url.is_some() => EmbeddingProviderKind::Tei,
+            None => EmbeddingProviderKind::Hash,
+        };
+        if provider != EmbeddingProviderKind::Hash && url.is_none() {
+            return Err(format!(
+                "EMBEDDINGS_URL is required by the {} embeddings provider",
+                provider.as_str()
+            ));
+        }
+
+        let mut config = Self::new(
+            provider,
+            env_number("SEARCH_EMBEDDING_DIM")?.unwrap_or(DEFAULT_EMBEDDING_DIMENSIONS),
+        );
+        config.url = url;
+        if provider != EmbeddingProviderKind::Hash
+            && let Some(model) = env_var("EMBEDDINGS_MODEL_ID")
+        {
+            config.model = model;
+        }
+        config.api_key = env_var("EMBEDDINGS_API_KEY");
+        if let Some(batch_size) = env_number("EMBEDDINGS_BATCH_SIZE")? {
+            config.batch_size = batch_size;
+        }
+        if let Some(secs) = env_number("EMBEDDINGS_TIMEOUT_SECS")? {
+            config.request_timeout = Duration::from_secs(secs);
+        }
+        if let Some(retries) = env_number("EMBEDDINGS_MAX_RETRIES")? {
+            config.max_retries = retries;
+        }
+
+        Ok(config)
+    }
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+fn env_number<T: std::str::FromStr>(name: &str) -> Result<Option<T>, String> {
+    env_var(name)
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| format!("{name} must be a number, got '{value}'"))
+        })
+        .transpose()
+}
+
+/// Turns a batch of texts into vectors with one request.
+pub trait EmbeddingProvider: Send + Sync + fmt::Debug {
+    fn kind(&self) -> EmbeddingProviderKind;
+
+    /// Embed `texts`, returning one vector per text in input order.
+    fn embed_batch<'a>(&'a self, texts: &'a [&'a str]) -> SearchFuture<'a, Vec<Vec<f32>>>;
+}
+
+/// Batching, timeouts, retries and dimension checks around a provider.
 #[derive(Debug, Clone)]
 pub struct EmbeddingsClient {
-    base_url: String,
-    http: Client,
+    provider: Arc<dyn EmbeddingProvider>,
+    model: String,
+    dimensions: usize,
+    batch_size: usize,
+    request_timeout: Duration,
+    max_retries: usize,
 }
 
 impl EmbeddingsClient {
-    pub fn new(base_url: impl Into<String>, http: Client) -> Self {
-        let base = base_url.into().trim_end_matches('/').to_string();
+    pub fn new(config: EmbeddingsConfig) -> Self {
+        let provider: Arc<dyn EmbeddingProvider> = match config.provider {
+            EmbeddingProviderKind::Tei => Arc::new(TeiProvider::new(
+                config.url.clone().unwrap_or_default(),
+                Client::new(),
+            )),
+            EmbeddingProviderKind::OpenAi => Arc::new(OpenAiProvider::new(
+                config.url.clone().unwrap_or_default(),
+                config.model.clone(),
+                config.api_key.clone(),
+                Client::new(),
+            )),
+            EmbeddingProviderKind::Hash => Arc::new(HashProvider::new(config.dimensions)),
+        };
+
+        Self::with_provider(provider, config)
+    }
+
+    /// Client around a custom provider; `config.provider` is ignored.
+    pub fn with_provider(provider: Arc<dyn EmbeddingProvider>, config: EmbeddingsConfig) -> Self {
         Self {
-            base_url: base,
-            http,
+            provider,
+            model: config.model,
+            dimensions: config.dimensions,
+            batch_size: config.batch_size.max(1),
+            request_timeout: config.request_timeout,
+            max_retries: config.max_retries.max(1),
         }
     }
 
+    pub fn provider(&self) -> EmbeddingProviderKind {
+        self.provider.kind()
+    }
+
+    /// Identifier of the model producing the vectors.
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    pub fn dimensions(&self) -> usize {
+        self.dimensions
+    }
+
     pub async fn embed(&self, text: &str) -> Result<Vec<f32>, SearchError> {
-        let mut backoff_ms = EMBEDDING_RETRY_BACKOFF_MS;
-        for attempt in 1..=EMBEDDING_MAX_RETRIES {
-            debug!(
-                "embeddings: dispatching request (attempt {} of {})",
-                attempt, EMBEDDING_MAX_RETRIES
-            );
+        self.embed_many(&[text])
+            .await?
+            .pop()
+            .ok_or(SearchError::EmptyEmbedding)
+    }
 
-            let result = timeout(
-                EMBEDDING_REQUEST_TIMEOUT,
-                self.dispatch_embedding_request(text),
-            )
-            .await;
+    /// Embed `texts` in batches, returning one vector per text in input order.
+    pub async fn embed_many(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SearchError> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let embedded = self.embed_batch_with_retries(batch).await?;
+            vectors.extend(fit_dimensions(embedded, batch.len(), self.dimensions)?);
+        }
+        Ok(vectors)
+    }
 
-            match result {
-                Ok(Ok(vector)) => return Ok(vector),
-                Ok(Err(err)) => {
-                    warn!("embeddings: request attempt {} failed: {}", attempt, err);
-                    if attempt == EMBEDDING_MAX_RETRIES {
-                        return Err(err);
-                    }
-                }
-                Err(_) => {
-                    warn!(
-                        "embeddings: request attempt {} timed out after {:?}",
-                        attempt, EMBEDDING_REQUEST_TIMEOUT
-                    );
-                    if attempt == EMBEDDING_MAX_RETRIES {
-                        return Err(SearchError::EmbeddingTimeout(EMBEDDING_REQUEST_TIMEOUT));
-                    }
+    /// Embed `texts` batch by batch like [`EmbeddingsClient::embed_many`],
+    /// but keep going when a batch fails: its texts get `None` and the error is
+    /// logged, while the other batches still return vectors.
+    pub async fn embed_each_batch(&self, texts: &[&str]) -> Vec<Option<Vec<f32>>> {
+        let mut vectors = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(self.batch_size) {
+            let embedded = match self.embed_batch_with_retries(batch).await {
+                Ok(embedded) => fit_dimensions(embedded, batch.len(), self.dimensions),
+                Err(err) => Err(err),
+            };
+            match embedded {
+                Ok(embedded) => vectors.extend(embedded.into_iter().map(Some)),
+                Err(err) => {
+                    warn!("embeddings: batch of {} texts failed: {}", batch.len(), err);
+                    vectors.extend(std::iter::repeat_n(None, batch.len()));
                 }
             }
+        }
+        vectors
+    }
+
+    async fn embed_batch_with_retries(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>, SearchError> {
+        let mut backoff_ms = EMBEDDING_RETRY_BACKOFF_MS;
+        let mut attempt = 1;
+        loop {
+            debug!(
+                "embeddings: dispatching {} request for {} texts (attempt {} of {})",
+                self.provider.kind().as_str(),
+                texts.len(),
+                attempt,
+                self.max_retries
+            );
 
-            if attempt < EMBEDDING_MAX_RETRIES {
-                let delay = Duration::from_millis(backoff_ms);
-                debug!(
-                    "embeddings: retrying after {:?} backoff (attempt {} of {})",
-                    delay,
-                    attempt + 1,
-                    EMBEDDING_MAX_RETRIES
-                );
-                sleep(delay).await;
-                backoff_ms = backoff_ms.saturating_mul(EMBEDDING_RETRY_BACKOFF_FACTOR);
+            let err = match timeout(self.request_timeout, self.provider.embed_batch(texts)).await {
+                Ok(Ok(vectors)) => return Ok(vectors),
+                Ok(Err(err)) => err,
+                Err(_) => SearchError::EmbeddingTimeout(self.request_timeout),
+            };
+            if attempt >= self.max_retries || !is_retryable(&err) {
+                return Err(err);
             }
-        }
 
-        Err(SearchError::EmbeddingTimeout(EMBEDDING_REQUEST_TIMEOUT))
+            let delay = Duration::from_millis(backoff_ms);
+            warn!(
+                "embeddings: request attempt {} failed: {}. Retrying after {:?}",
+                attempt, err, delay
+            );
+            sleep(delay).await;
+            backoff_ms = backoff_ms.saturating_mul(EMBEDDING_RETRY_BACKOFF_FACTOR);
+            attempt += 1;
+        }
     }
+}
 
-    async fn dispatch_embedding_request(&self, text: &str) -> Result<Vec<f32>, SearchError> {
-        #[derive(Serialize)]
-        struct EmbeddingRequest<'a> {
-            inputs: &'a [&'a str],
+fn is_retryable(err: &SearchError) -> bool {
+    match err {
+        SearchError::EmbeddingHttp(_) | SearchError::EmbeddingTimeout(_) => true,
+        SearchError::EmbeddingStatus { status, .. } => {
+            *status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
         }
+        _ => false,
+    }
+}
 
-        #[derive(Serialize)]
-        struct OpenAiCompatibleRequest<'a> {
-            input: &'a [&'a str],
+/// Check that a batch returned one vector per text with at least `dimensions`
+/// components, truncating longer vectors.
+fn fit_dimensions(
+    mut vectors: Vec<Vec<f32>>,
+    expected_count: usize,
+    dimensions: usize,
+) -> Result<Vec<Vec<f32>>, SearchError> {
+    if vectors.len() != expected_count {
+        return Err(SearchError::EmbeddingCount {
+            expected: expected_count,
+            actual: vectors.len(),
+        });
+    }
+    for vector in &mut vectors {
+        if vector.len() < dimensions {
+            return Err(SearchError::EmbeddingDimensions {
+                expected: dimensions,
+                actual: vector.len(),
+            });
         }
+        vector.truncate(dimensions);
+    }
+    Ok(vectors)
+}
 
-        let url = format!("{}/embeddings", self.base_url);
-        let payload = EmbeddingRequest { inputs: &[text] };
+async fn error_status(response: reqwest::Response) -> SearchError {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .unwrap_or_else(|_| "failed to read error body".to_string());
+    SearchError::embedding_status(status, body)
+}
 
-        let primary_response = self
-            .http
-            .post(url.clone())
-            .json(&payload)
-            .send()
-            .await
-            .map_err(SearchError::EmbeddingHttp)?;
+/// Text Embeddings Inference `POST /embed`.
+#[derive(Debug)]
+pub struct TeiProvider {
+    url: String,
+    http: Client,
+}
 
-        if primary_response.status().is_success() {
-            return Self::parse_embedding_response(primary_response).await;
+impl TeiProvider {
+    pub fn new(base_url: impl Into<String>, http: Client) -> Self {
+        let base = base_url.into().trim_end_matches('/').to_string();
+        Self {
+            url: format!("{base}/embed"),
+            http,
         }
+    }
+}
+
+impl EmbeddingProvider for TeiProvider {
+    fn kind(&self) -> EmbeddingProviderKind {
+        EmbeddingProviderKind::Tei
+    }
 
-        let status = primary_response.status();
-        let body = primary_response
-            .text()
-            .await
-            .unwrap_or_else(|_| "failed to read error body".to_string());
+    fn embed_batch<'a>(&'a self, texts: &'a [&'a str]) -> SearchFuture<'a, Vec<Vec<f32>>> {
+        #[derive(Serialize)]
+        struct TeiRequest<'a> {
+            inputs: &'a [&'a str],
+            truncate: bool,
+        }
 
-        // Some deployments expose an OpenAI-compatible API that expects an `input` field instead
-        // of `inputs`. Retry once using that schema when the server explicitly reports the missing
-        // `input` field.
-        if status == StatusCode::UNPROCESSABLE_ENTITY && body.contains("missing field `input`") {
-            let fallback_payload = OpenAiCompatibleRequest { input: &[text] };
-            let fallback_response = self
+        Box::pin(async move {
+            let response = self
                 .http
-                .post(url)
-                .json(&fallback_payload)
+                .post(&self.url)
+                .json(&TeiRequest {
+                    inputs: texts,
+                    truncate: true,
+                })
                 .send()
                 .await
                 .map_err(SearchError::EmbeddingHttp)?;
+            if !response.status().is_success() {
+                return Err(error_status(response).await);
+            }
+
+            response.json().await.map_err(SearchError::EmbeddingHttp)
+        })
+    }
+}
+
+/// OpenAI-compatible `POST /v1/embeddings`.
+#[derive(Debug)]
+pub struct OpenAiProvider {
+    url: String,
+    model: String,
+    api_key: Option<String>,
+    http: Client,
+}
 
-            if fallback_response.status().is_success() {
-                return Self::parse_embedding_response(fallback_response).await;
+impl OpenAiProvider {
+    /// `base_url` is the server root; a trailing `/v1` is accepted too.
+    pub fn new(
+        base_url: impl Into<String>,
+        model: impl Into<String>,
+        api_key: Option<String>,
+        http: Client,
+    ) -> Self {
+        let base = base_url.into();
+        let base = base.trim_end_matches('/');
+        let base = base.strip_suffix("/v1").unwrap_or(base);
+        Self {
+            url: format!("{base}/v1/embeddings"),
+            model: model.into(),
+            api_key,
+            http,
+        }
+    }
+}
+
+impl EmbeddingProvider for OpenAiProvider {
+    fn kind(&self) -> EmbeddingProviderKind {
+        EmbeddingProviderKind::OpenAi
+    }
+
+    fn embed_batch<'a>(&'a self, texts: &'a [&'a str]) -> SearchFuture<'a, Vec<Vec<f32>>> {
+        #[derive(Serialize)]
+        struct OpenAiRequest<'a> {
+            model: &'a str,
+            input: &'a [&'a str],
+        }
+
+        #[derive(Deserialize)]
+        struct OpenAiResponse {
+            data: Vec<OpenAiEmbedding>,
+        }
+
+        #[derive(Deserialize)]
+        struct OpenAiEmbedding {
+            #[serde(default)]
+            index: usize,
+            embedding: Vec<f32>,
+        }
+
+        Box::pin(async move {
+            let mut request = self.http.post(&self.url).json(&OpenAiRequest {
+                model: &self.model,
+                input: texts,
+            });
+            if let Some(key) = &self.api_key {
+                request = request.bearer_auth(key);
             }
 
-            let fallback_status = fallback_response.status();
-            let fallback_body = fallback_response
-                .text()
-                .await
-                .unwrap_or_else(|_| "failed to read error body".to_string());
-            return Err(SearchError::embedding_status(
-                fallback_status,
-                fallback_body,
-            ));
+            let response = request.send().await.map_err(SearchError::EmbeddingHttp)?;
+            if !response.status().is_success() {
+                return Err(error_status(response).await);
+            }
+
+            let mut parsed: OpenAiResponse =
+                response.json().await.map_err(SearchError::EmbeddingHttp)?;
+            parsed.data.sort_by_key(|entry| entry.index);
+            Ok(parsed
+                .data
+                .into_iter()
+                .map(|entry| entry.embedding)
+                .collect())
+        })
+    }
+}
+
+/// Deterministic local embeddings: each lowercased word adds ±1 to a
+/// component picked by its FNV-1a hash, and the vector is L2-normalised.
+/// Texts sharing words score as similar; text without words embeds to zeros.
+#[derive(Debug)]
+pub struct HashProvider {
+    dimensions: usize,
+}
+
+impl HashProvider {
+    pub fn new(dimensions: usize) -> Self {
+        Self { dimensions }
+    }
+
+    pub fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vector = vec![0.0f32; self.dimensions];
+        if self.dimensions == 0 {
+            return vector;
+        }
+
+        for word in text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|word| !word.is_empty())
+        {
+            let hash = fnv1a(word.to_lowercase().as_bytes());
+            let index = (hash % self.dimensions as u64) as usize;
+            vector[index] += if hash >> 63 == 0 { 1.0 } else { -1.0 };
         }
 
-        Err(SearchError::embedding_status(status, body))
+        let norm = vector.iter().map(|value| value * value).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut vector {
+                *value /= norm;
+            }
+        }
+        vector
     }
+}
 
-    async fn parse_embedding_response(
-        response: reqwest::Response,
-    ) -> Result<Vec<f32>, SearchError> {
-        let parsed: EmbeddingResponse =
-            response.json().await.map_err(SearchError::EmbeddingHttp)?;
+impl EmbeddingProvider for HashProvider {
+    fn kind(&self) -> EmbeddingProviderKind {
+        EmbeddingProviderKind::Hash
+    }
 
-        parsed
-            .data
-            .into_iter()
-            .next()
-            .map(|entry| entry.embedding)
-            .ok_or(SearchError::EmptyEmbedding)
+    fn embed_batch<'a>(&'a self, texts: &'a [&'a str]) -> SearchFuture<'a, Vec<Vec<f32>>> {
+        Box::pin(async move { Ok(texts.iter().map(|text| self.embed_text(text)).collect()) })
     }
 }
 
-#[derive(serde::Deserialize)]
-struct EmbeddingResponse {
-    data: Vec<EmbeddingData>,
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
 }
 
-#[derive(serde::Deserialize)]
-struct EmbeddingData {
-    embedding: Vec<f32>,
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cosine(a: &[f32], b: &[f32]) -> f32 {
+        a.iter().zip(b).map(|(x, y)| x * y).sum()
+    }
+
+    /// Fails every batch containing a text starting with `fail`.
+    #[derive(Debug)]
+    struct FlakyProvider;
+
+    impl EmbeddingProvider for FlakyProvider {
+        fn kind(&self) -> EmbeddingProviderKind {
+            EmbeddingProviderKind::Hash
+        }
+
+        fn embed_batch<'a>(&'a self, texts: &'a [&'a str]) -> SearchFuture<'a, Vec<Vec<f32>>> {
+            Box::pin(async move {
+                if texts.iter().any(|text| text.starts_with("fail")) {
+                    return Err(SearchError::EmptyEmbedding);
+                }
+                Ok(texts.iter().map(|_| vec![1.0; 4]).collect())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_batches_only_drop_their_own_texts() {
+        let mut config = EmbeddingsConfig::new(EmbeddingProviderKind::Hash, 4);
+        config.batch_size = 2;
+        config.max_retries = 1;
+        let client = EmbeddingsClient::with_provider(Arc::new(FlakyProvider), config);
+
+        let vectors = client
+            .embed_each_batch(&["one", "two", "fail", "three", "four"])
+            .await;
+
+        let embedded: Vec<bool> = vectors.iter().map(Option::is_some).collect();
+        assert_eq!(embedded, vec![true, true, false, false, true]);
+    }
+
+    #[test]
+    fn parses_provider_names() {
+        assert_eq!(
+            EmbeddingProviderKind::parse(" TEI "),
+            Some(EmbeddingProviderKind::Tei)
+        );
+        assert_eq!(
+            EmbeddingProviderKind::parse("openai"),
+            Some(EmbeddingProviderKind::OpenAi)
+        );
+        assert_eq!(
+            EmbeddingProviderKind::parse("hash"),
+            Some(EmbeddingProviderKind::Hash)
+        );
+        assert_eq!(EmbeddingProviderKind::parse("cohere"), None);
+    }
+
+    #[test]
+    fn hashes_text_deterministically() {
+        let provider = HashProvider::new(64);
+        let slab = provider.embed_text("mm/slub: fix kmem_cache leak");
+        assert_eq!(slab.len(), 64);
+        assert_eq!(slab, provider.embed_text("MM/SLUB: fix KMEM_CACHE leak"));
+        assert!((cosine(&slab, &slab) - 1.0).abs() < 1e-5);
+
+        let related = provider.embed_text("kmem_cache leak in slub");
+        let unrelated = provider.embed_text("net: phy: add driver");
+        assert!(cosine(&slab, &related) > cosine(&slab, &unrelated));
+
+        assert!(
+            provider
+                .embed_text("  -- ")
+                .iter()
+                .all(|value| *value == 0.0)
+        );
+    }
+
+    #[test]
+    fn fits_vectors_to_dimensions() {
+        let fitted = fit_dimensions(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0]], 2, 2).unwrap();
+        assert_eq!(fitted, vec![vec![1.0, 2.0], vec![4.0, 5.0]]);
+
+        assert!(matches!(
+            fit_dimensions(vec![vec![1.0]], 1, 2),
+            Err(SearchError::EmbeddingDimensions {
+                expected: 2,
+                actual: 1
+            })
+        ));
+        assert!(matches!(
+            fit_dimensions(vec![vec![1.0, 2.0]], 2, 2),
+            Err(SearchError::EmbeddingCount {
+                expected: 2,
+                actual: 1
+            })
+        ));
+    }
 }
@@ -12,6 +12,10 @@ pub enum SearchError {
     EmbeddingStatus { status: StatusCode, body: String },
     #[error("embedding response did not include any vectors")]
     EmptyEmbedding,
+    #[error("embedding response returned {actual} vectors for {expected} texts")]
+    EmbeddingCount { expected: usize, actual: usize },
+    #[error("embedding has {actual} dimensions, expected at least {expected}")]
+    EmbeddingDimensions { expected: usize, actual: usize },
     #[error("embedding request timed out after {0:?}")]
     EmbeddingTimeout(Duration),
     #[error("meilisearch HTTP error: {0}")]
@@ -20,6 +24,8 @@ pub enum SearchError {
     MeilisearchStatus { status: StatusCode, body: String },
     #[error("database error: {0}")]
     Database(sqlx::Error),
+    #[error("job was cancelled")]
+    Cancelled,
 }
 
 impl SearchError {
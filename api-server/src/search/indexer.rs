@@ -1,13 +1,20 @@
-use crate::models::PatchType;
-use crate::search::models::{AuthorDocument, AuthorMailingListStats, ThreadDocument};
+use crate::models::{PatchState, PatchType};
+use crate::patches::load_patch_diffs;
+use crate::search::embedding_cache::{
+    EmbeddingInput, ThreadEmbeddingCache, load_failed_embeddings, purge_stale_embeddings,
+};
+use crate::search::models::{
+    AuthorDocument, AuthorMailingListStats, EmailDocument, PatchDocument, ThreadDocument,
+};
 use crate::search::{SearchError, SearchService};
 use crate::sync::queue::JobQueue;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Datelike, Utc};
 use log::{debug, warn};
 use rocket_db_pools::sqlx::{self, PgPool};
 use std::collections::{BTreeSet, HashMap, HashSet};
 
 const THREAD_BATCH_SIZE: i64 = 200;
+const AUTHOR_BATCH_SIZE: usize = 500;
 const DISCUSSION_REPLY_LIMIT: usize = 5;
 const DISCUSSION_CHAR_LIMIT: usize = 16_000;
 const DISCUSSION_EXCERPT_LIMIT: usize = 400;
@@ -18,10 +25,28 @@ pub async fn reindex_threads(
     mailing_list_id: Option<i32>,
     job_context: Option<(&JobQueue, i32)>,
 ) -> Result<usize, SearchError> {
-    search.ensure_thread_index().await?;
+    // Backends searching the database directly still get thread embeddings
+    // for similar-thread lookups
+    if search.backend().uses_document_index() {
+        search.ensure_thread_index().await?;
+        search.ensure_email_index().await?;
+        search.ensure_patch_index().await?;
 
-    if let Some(list_id) = mailing_list_id {
-        search.delete_threads_by_mailing_list(list_id).await?;
+        if let Some(list_id) = mailing_list_id {
+            search.delete_threads_by_mailing_list(list_id).await?;
+            search.delete_emails_by_mailing_list(list_id).await?;
+            search.delete_patches_by_mailing_list(list_id).await?;
+        }
+    }
+
+    let purged = purge_stale_embeddings(pool, search).await?;
+    if purged > 0 {
+        debug!(
+            "reindex_threads: dropped {} cached embeddings not produced by {} ({} dims)",
+            purged,
+            search.embedding_model(),
+            search.thread_embedding_dimensions()
+        );
     }
 
     let mut last_id: i32 = 0;
@@ -34,10 +59,13 @@ pub async fn reindex_threads(
     }
 
     loop {
+        ensure_not_cancelled(job_context).await?;
+
         let thread_rows: Vec<ThreadRow> = sqlx::query_as::<_, ThreadRow>(THREAD_QUERY)
             .bind(last_id)
             .bind(mailing_list_id)
             .bind(THREAD_BATCH_SIZE)
+            .bind(None::<Vec<i32>>)
             .fetch_all(pool)
             .await
             .map_err(SearchError::Database)?;
@@ -46,7 +74,6 @@ pub async fn reindex_threads(
             break;
         }
 
-        let thread_ids: Vec<i32> = thread_rows.iter().map(|row| row.id).collect();
         debug!(
             "reindex_threads: processing batch ({} threads, id range {}-{})",
             thread_rows.len(),
@@ -54,17 +81,7 @@ pub async fn reindex_threads(
             thread_rows.last().map(|row| row.id).unwrap_or_default()
         );
 
-        let email_rows = fetch_emails(pool, &thread_ids).await?;
-        let email_map = group_emails_by_thread(email_rows);
-
-        let mut documents = Vec::with_capacity(thread_rows.len());
-        for row in thread_rows.iter() {
-            let emails = email_map.get(&row.id).cloned().unwrap_or_else(Vec::new);
-            let document = build_thread_document(search, row, emails).await?;
-            documents.push(document);
-        }
-
-        search.upsert_threads(&documents).await?;
+        let documents = index_thread_batch(pool, search, &thread_rows).await?;
         total_threads_processed += documents.len();
         last_id = thread_rows.last().map(|row| row.id).unwrap_or(last_id);
 
@@ -78,20 +95,102 @@ pub async fn reindex_threads(
     Ok(total_threads_processed)
 }
 
-pub async fn reindex_authors(
+/// Refresh the documents of the threads an import created or changed and
+/// remove those of the threads it merged away, leaving the rest of the list's
+/// documents in place. Threads a previous run indexed with a fallback zero
+/// vector are refreshed along with them.
+///
+/// # Returns
+/// Number of threads upserted and the authors taking part in them, whose
+/// documents the caller refreshes with [`reindex_authors_by_id`]
+pub async fn reindex_changed_threads(
     pool: &PgPool,
     search: &SearchService,
+    mailing_list_id: i32,
+    changed: &[i32],
+    removed: &[i32],
     job_context: Option<(&JobQueue, i32)>,
-) -> Result<usize, SearchError> {
-    search.ensure_author_index().await?;
+) -> Result<(usize, Vec<i32>), SearchError> {
+    // Embeddings of merged threads go away with the threads themselves
+    if search.backend().uses_document_index() {
+        search.ensure_thread_index().await?;
+        search.ensure_email_index().await?;
+        search.ensure_patch_index().await?;
+    }
 
-    let activity_rows: Vec<AuthorActivityRow> =
-        sqlx::query_as::<_, AuthorActivityRow>(AUTHOR_ACTIVITY_QUERY)
+    if !removed.is_empty() && search.backend().uses_document_index() {
+        debug!(
+            "reindex_changed_threads: removing {} merged threads",
+            removed.len()
+        );
+        search.delete_threads(mailing_list_id, removed).await?;
+    }
+
+    let failed_embeddings = load_failed_embeddings(pool, mailing_list_id).await?;
+    if !failed_embeddings.is_empty() {
+        debug!(
+            "reindex_changed_threads: retrying {} threads without an embedding",
+            failed_embeddings.len()
+        );
+    }
+    let changed: Vec<i32> = changed
+        .iter()
+        .chain(&failed_embeddings)
+        .copied()
+        .filter(|thread_id| !removed.contains(thread_id))
+        .collect::<BTreeSet<i32>>()
+        .into_iter()
+        .collect();
+    let mut participants: BTreeSet<i32> = BTreeSet::new();
+    let mut total_threads_processed: usize = 0;
+
+    for batch in changed.chunks(THREAD_BATCH_SIZE as usize) {
+        ensure_not_cancelled(job_context).await?;
+
+        let thread_rows: Vec<ThreadRow> = sqlx::query_as::<_, ThreadRow>(THREAD_QUERY)
+            .bind(0)
+            .bind(Some(mailing_list_id))
+            .bind(batch.len() as i64)
+            .bind(batch)
             .fetch_all(pool)
             .await
             .map_err(SearchError::Database)?;
 
-    if activity_rows.is_empty() {
+        let documents = index_thread_batch(pool, search, &thread_rows).await?;
+        total_threads_processed += documents.len();
+        participants.extend(
+            documents
+                .iter()
+                .flat_map(|document| document.participant_ids.iter().copied()),
+        );
+
+        if let Some((queue, job_id)) = job_context
+            && let Err(err) = queue.heartbeat(job_id).await
+        {
+            warn!("job {}: failed to record heartbeat: {}", job_id, err);
+        }
+    }
+
+    Ok((total_threads_processed, participants.into_iter().collect()))
+}
+
+pub async fn reindex_authors(
+    pool: &PgPool,
+    search: &SearchService,
+    job_context: Option<(&JobQueue, i32)>,
+) -> Result<usize, SearchError> {
+    if !search.backend().uses_document_index() {
+        debug!(
+            "reindex_authors: skipped, {} backend searches the database directly",
+            search.backend().as_str()
+        );
+        return Ok(0);
+    }
+
+    search.ensure_author_index().await?;
+
+    let documents = build_author_documents(pool, None).await?;
+    if documents.is_empty() {
         if let Some((queue, job_id)) = job_context {
             if let Err(err) = queue.heartbeat(job_id).await {
                 warn!("job {}: failed to record heartbeat: {}", job_id, err);
@@ -100,7 +199,327 @@ pub async fn reindex_authors(
         return Ok(0);
     }
 
+    let processed = documents.len();
+
+    search.upsert_authors(&documents).await?;
+
+    if let Some((queue, job_id)) = job_context {
+        if let Err(err) = queue.heartbeat(job_id).await {
+            warn!("job {}: failed to record heartbeat: {}", job_id, err);
+        }
+    }
+
+    Ok(processed)
+}
+
+/// Refresh the documents of the given authors only.
+pub async fn reindex_authors_by_id(
+    pool: &PgPool,
+    search: &SearchService,
+    author_ids: &[i32],
+    job_context: Option<(&JobQueue, i32)>,
+) -> Result<usize, SearchError> {
+    if !search.backend().uses_document_index() || author_ids.is_empty() {
+        return Ok(0);
+    }
+
+    search.ensure_author_index().await?;
+
+    let mut processed = 0;
+    for batch in author_ids.chunks(AUTHOR_BATCH_SIZE) {
+        ensure_not_cancelled(job_context).await?;
+
+        let documents = build_author_documents(pool, Some(batch)).await?;
+        search.upsert_authors(&documents).await?;
+        processed += documents.len();
+
+        if let Some((queue, job_id)) = job_context
+            && let Err(err) = queue.heartbeat(job_id).await
+        {
+            warn!("job {}: failed to record heartbeat: {}", job_id, err);
+        }
+    }
+
+    Ok(processed)
+}
+
+/// Embed one batch of threads and, for backends searching indexed
+/// documents, upsert them along with their email and patch documents.
+async fn index_thread_batch(
+    pool: &PgPool,
+    search: &SearchService,
+    thread_rows: &[ThreadRow],
+) -> Result<Vec<ThreadDocument>, SearchError> {
+    let thread_ids: Vec<i32> = thread_rows.iter().map(|row| row.id).collect();
+    let email_rows = fetch_emails(pool, &thread_ids).await?;
+    let email_map = group_emails_by_thread(email_rows);
+
+    let mut documents: Vec<ThreadDocument> = thread_rows
+        .iter()
+        .map(|row| {
+            let emails = email_map.get(&row.id).cloned().unwrap_or_else(Vec::new);
+            assemble_thread_document(row, emails)
+        })
+        .collect();
+
+    // Threads without any text keep a zero vector
+    let texts: Vec<String> = documents.iter().map(thread_embedding_text).collect();
+    let inputs: Vec<EmbeddingInput<'_>> = documents
+        .iter()
+        .zip(&texts)
+        .filter(|(_, text)| !text.is_empty())
+        .map(|(document, text)| EmbeddingInput {
+            mailing_list_id: document.mailing_list_id,
+            thread_id: document.thread_id,
+            text,
+        })
+        .collect();
+    let mut embeddings = ThreadEmbeddingCache::load(pool, search, &thread_ids).await?;
+    let mut vectors = embeddings.embed(search, &inputs).await.into_iter();
+    embeddings.flush().await?;
+
+    for (document, text) in documents.iter_mut().zip(&texts) {
+        document.vector = Some(if text.is_empty() {
+            vec![0.0; search.thread_embedding_dimensions()]
+        } else {
+            vectors.next().unwrap_or_default()
+        });
+    }
+
+    if !search.backend().uses_document_index() {
+        return Ok(documents);
+    }
+
+    search.upsert_threads(&documents).await?;
+
+    let email_documents = build_email_documents(pool, Some(&thread_ids), None).await?;
+    search.upsert_emails(&email_documents).await?;
+
+    let patch_documents = build_patch_documents(pool, Some(&thread_ids), None).await?;
+    search.upsert_patches(&patch_documents).await?;
+
+    Ok(documents)
+}
+
+/// Build search documents for the given threads, in the order given, without
+/// embedding vectors. Used by backends that query the database directly.
+pub(crate) async fn load_thread_documents(
+    pool: &PgPool,
+    thread_ids: &[i32],
+) -> Result<Vec<ThreadDocument>, SearchError> {
+    if thread_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let thread_rows: Vec<ThreadRow> = sqlx::query_as::<_, ThreadRow>(THREAD_QUERY)
+        .bind(0)
+        .bind(None::<i32>)
+        .bind(thread_ids.len() as i64)
+        .bind(thread_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(SearchError::Database)?;
+
+    let mut email_map = group_emails_by_thread(fetch_emails(pool, thread_ids).await?);
+    let mut documents: HashMap<i32, ThreadDocument> = thread_rows
+        .iter()
+        .map(|row| {
+            let emails = email_map.remove(&row.id).unwrap_or_default();
+            (row.id, assemble_thread_document(row, emails))
+        })
+        .collect();
+
+    Ok(thread_ids
+        .iter()
+        .filter_map(|id| documents.remove(id))
+        .collect())
+}
+
+/// Build email documents for the given emails, in the order given. Used by
+/// backends that query the database directly.
+pub(crate) async fn load_email_documents(
+    pool: &PgPool,
+    email_ids: &[i32],
+) -> Result<Vec<EmailDocument>, SearchError> {
+    if email_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut documents: HashMap<i32, EmailDocument> = HashMap::new();
+    for document in build_email_documents(pool, None, Some(email_ids)).await? {
+        documents.entry(document.email_id).or_insert(document);
+    }
+
+    Ok(email_ids
+        .iter()
+        .filter_map(|id| documents.remove(id))
+        .collect())
+}
+
+/// Build email documents for the members of `thread_ids` and/or the emails in
+/// `email_ids`, with the files touched by each patch.
+async fn build_email_documents(
+    pool: &PgPool,
+    thread_ids: Option<&[i32]>,
+    email_ids: Option<&[i32]>,
+) -> Result<Vec<EmailDocument>, SearchError> {
+    let rows: Vec<EmailDocumentRow> = sqlx::query_as::<_, EmailDocumentRow>(EMAIL_DOCUMENT_QUERY)
+        .bind(thread_ids)
+        .bind(email_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(SearchError::Database)?;
+
+    let mut patches_by_list: HashMap<i32, Vec<i32>> = HashMap::new();
+    for row in rows.iter().filter(|row| row.is_patch) {
+        patches_by_list
+            .entry(row.mailing_list_id)
+            .or_default()
+            .push(row.email_id);
+    }
+
+    let mut files: HashMap<(i32, i32), Vec<String>> = HashMap::new();
+    for (list_id, ids) in patches_by_list {
+        let diffs = load_patch_diffs(pool, list_id, &ids)
+            .await
+            .map_err(SearchError::Database)?;
+        for (email_id, diff) in diffs {
+            let paths = diff
+                .files
+                .into_iter()
+                .filter_map(|file| file.new_path.or(file.old_path))
+                .collect();
+            files.insert((list_id, email_id), paths);
+        }
+    }
+
+    Ok(rows
+        .into_iter()
+        .map(|row| {
+            let files = files
+                .remove(&(row.mailing_list_id, row.email_id))
+                .unwrap_or_default();
+            EmailDocument {
+                email_id: row.email_id,
+                mailing_list_id: row.mailing_list_id,
+                mailing_list: row.mailing_list_slug,
+                message_id: row.message_id,
+                subject: row.subject,
+                date_ts: row.date.timestamp(),
+                author_id: row.author_id,
+                author_name: row.author_name,
+                author_email: row.author_email,
+                to: row.to_emails,
+                cc: row.cc_emails,
+                body: row.body,
+                is_patch: row.is_patch,
+                files,
+                thread_id: row.thread_id,
+                thread_subject: row.thread_subject,
+                thread_root_message_id: row.thread_root_message_id,
+                thread_message_count: row.thread_message_count,
+            }
+        })
+        .collect())
+}
+
+/// Build patch documents for the given emails, in the order given. Used by
+/// backends that query the database directly.
+pub(crate) async fn load_patch_documents(
+    pool: &PgPool,
+    email_ids: &[i32],
+) -> Result<Vec<PatchDocument>, SearchError> {
+    if email_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut documents: HashMap<i32, PatchDocument> = HashMap::new();
+    for document in build_patch_documents(pool, None, Some(email_ids)).await? {
+        documents.entry(document.email_id).or_insert(document);
+    }
+
+    Ok(email_ids
+        .iter()
+        .filter_map(|id| documents.remove(id))
+        .collect())
+}
+
+/// Build patch documents from the `patch_code` rows of the members of
+/// `thread_ids` and/or the emails in `email_ids`.
+async fn build_patch_documents(
+    pool: &PgPool,
+    thread_ids: Option<&[i32]>,
+    email_ids: Option<&[i32]>,
+) -> Result<Vec<PatchDocument>, SearchError> {
+    let rows: Vec<PatchDocumentRow> = sqlx::query_as::<_, PatchDocumentRow>(PATCH_DOCUMENT_QUERY)
+        .bind(thread_ids)
+        .bind(email_ids)
+        .fetch_all(pool)
+        .await
+        .map_err(SearchError::Database)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PatchDocument {
+            email_id: row.email_id,
+            mailing_list_id: row.mailing_list_id,
+            mailing_list: row.mailing_list_slug,
+            message_id: row.message_id,
+            subject: row.subject,
+            date_ts: row.date.timestamp(),
+            author_id: row.author_id,
+            author_name: row.author_name,
+            author_email: row.author_email,
+            thread_id: row.thread_id,
+            files: row.files,
+            functions: row.functions,
+            added_identifiers: row.added_identifiers,
+            removed_identifiers: row.removed_identifiers,
+            added_lines: row.added_lines,
+            removed_lines: row.removed_lines,
+        })
+        .collect())
+}
+
+/// Build search documents for the given authors, in the order given.
+pub(crate) async fn load_author_documents(
+    pool: &PgPool,
+    author_ids: &[i32],
+) -> Result<Vec<AuthorDocument>, SearchError> {
+    if author_ids.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut documents: HashMap<i32, AuthorDocument> =
+        build_author_documents(pool, Some(author_ids))
+            .await?
+            .into_iter()
+            .map(|document| (document.author_id, document))
+            .collect();
+
+    Ok(author_ids
+        .iter()
+        .filter_map(|id| documents.remove(id))
+        .collect())
+}
+
+async fn build_author_documents(
+    pool: &PgPool,
+    author_ids: Option<&[i32]>,
+) -> Result<Vec<AuthorDocument>, SearchError> {
+    let activity_rows: Vec<AuthorActivityRow> =
+        sqlx::query_as::<_, AuthorActivityRow>(AUTHOR_ACTIVITY_QUERY)
+            .bind(author_ids)
+            .fetch_all(pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+    if activity_rows.is_empty() {
+        return Ok(Vec::new());
+    }
+
     let alias_rows: Vec<AuthorAliasRow> = sqlx::query_as::<_, AuthorAliasRow>(AUTHOR_ALIAS_QUERY)
+        .bind(author_ids)
         .fetch_all(pool)
         .await
         .map_err(SearchError::Database)?;
@@ -157,17 +576,18 @@ pub async fn reindex_authors(
         documents.push(builder.into_document(aliases));
     }
 
-    let processed = documents.len();
-
-    search.upsert_authors(&documents).await?;
+    Ok(documents)
+}
 
-    if let Some((queue, job_id)) = job_context {
-        if let Err(err) = queue.heartbeat(job_id).await {
-            warn!("job {}: failed to record heartbeat: {}", job_id, err);
+/// Stop a job's indexing between batches once the job was cancelled.
+async fn ensure_not_cancelled(job_context: Option<(&JobQueue, i32)>) -> Result<(), SearchError> {
+    match job_context {
+        Some((queue, job_id)) if queue.is_job_cancelled(job_id).await.unwrap_or(false) => {
+            warn!("job {}: cancelled by user during indexing", job_id);
+            Err(SearchError::Cancelled)
         }
+        _ => Ok(()),
     }
-
-    Ok(processed)
 }
 
 fn combine_min(current: Option<i64>, candidate: Option<i64>) -> Option<i64> {
@@ -203,11 +623,20 @@ async fn fetch_emails(
     Ok(rows)
 }
 
-async fn build_thread_document(
-    search: &SearchService,
-    thread: &ThreadRow,
-    emails: Vec<ThreadEmailRow>,
-) -> Result<ThreadDocument, SearchError> {
+/// Text embedded for a thread: its subject followed by the discussion text.
+fn thread_embedding_text(document: &ThreadDocument) -> String {
+    let mut embed_text = document
+        .normalized_subject
+        .clone()
+        .unwrap_or_else(|| document.subject.clone());
+    if !document.discussion_text.is_empty() {
+        embed_text.push_str("\n\n");
+        embed_text.push_str(&document.discussion_text);
+    }
+    embed_text.trim().to_string()
+}
+
+fn assemble_thread_document(thread: &ThreadRow, emails: Vec<ThreadEmailRow>) -> ThreadDocument {
     let mut participant_ids = Vec::new();
     let mut participant_names = Vec::new();
     let mut participant_emails = Vec::new();
@@ -228,37 +657,19 @@ async fn build_thread_document(
             );
             participant_emails.push(email.author_email.clone());
         }
-        if email.is_patch_only || email.patch_type.is_some() {
+        if email.is_patch_only
+            || matches!(
+                email.patch_type,
+                Some(PatchType::Inline | PatchType::Attachment)
+            )
+        {
             has_patches = true;
         }
     }
 
     let (discussion_text, first_post_excerpt) = build_discussion_text(thread, &ordered_emails);
 
-    let mut embed_text = thread
-        .normalized_subject
-        .clone()
-        .unwrap_or_else(|| thread.subject.clone());
-    if !discussion_text.is_empty() {
-        embed_text.push_str("\n\n");
-        embed_text.push_str(&discussion_text);
-    }
-
-    let embed_text_trimmed = embed_text.trim();
-    let vector = if embed_text_trimmed.is_empty() {
-        Some(vec![0.0; search.thread_embedding_dimensions()])
-    } else {
-        let embedding = search
-            .embed_with_fallback(
-                embed_text_trimmed,
-                search.thread_embedding_dimensions(),
-                &format!("thread {}", thread.id),
-            )
-            .await?;
-        Some(embedding)
-    };
-
-    Ok(ThreadDocument {
+    ThreadDocument {
         thread_id: thread.id,
         mailing_list_id: thread.mailing_list_id,
         mailing_list: thread.mailing_list_slug.clone(),
@@ -266,6 +677,8 @@ async fn build_thread_document(
         subject: thread.subject.clone(),
         normalized_subject: thread.normalized_subject.clone(),
         start_ts: thread.start_date.timestamp(),
+        start_year: thread.start_date.year(),
+        start_month: thread.start_date.format("%Y-%m").to_string(),
         last_ts: thread.last_date.timestamp(),
         message_count: thread.message_count,
         discussion_text,
@@ -275,13 +688,20 @@ async fn build_thread_document(
         series_id: thread.series_id.clone(),
         series_number: thread.series_number,
         series_total: thread.series_total,
+        subsystems: thread.subsystems.clone(),
+        patch_states: thread.patch_states.clone(),
+        incomplete_series: thread.incomplete_series,
         starter_id: thread.starter_id,
         starter_name: thread.starter_name.clone(),
         starter_email: thread.starter_email.clone(),
         first_post_excerpt,
         participant_emails,
-        vector,
-    })
+        to_emails: thread.to_emails.clone(),
+        cc_emails: thread.cc_emails.clone(),
+        files: thread.files.clone(),
+        functions: thread.functions.clone(),
+        vector: None,
+    }
 }
 
 fn build_discussion_text(
@@ -409,6 +829,13 @@ struct ThreadRow {
     series_id: Option<String>,
     series_number: Option<i32>,
     series_total: Option<i32>,
+    subsystems: Vec<String>,
+    patch_states: Vec<PatchState>,
+    incomplete_series: bool,
+    to_emails: Vec<String>,
+    cc_emails: Vec<String>,
+    files: Vec<String>,
+    functions: Vec<String>,
 }
 
 #[derive(sqlx::FromRow, Clone)]
@@ -424,6 +851,47 @@ struct ThreadEmailRow {
     author_email: String,
 }
 
+#[derive(sqlx::FromRow)]
+struct EmailDocumentRow {
+    email_id: i32,
+    mailing_list_id: i32,
+    mailing_list_slug: String,
+    message_id: String,
+    subject: String,
+    date: DateTime<Utc>,
+    author_id: i32,
+    author_name: Option<String>,
+    author_email: String,
+    to_emails: Vec<String>,
+    cc_emails: Vec<String>,
+    body: String,
+    is_patch: bool,
+    thread_id: i32,
+    thread_subject: String,
+    thread_root_message_id: String,
+    thread_message_count: i32,
+}
+
+#[derive(sqlx::FromRow)]
+struct PatchDocumentRow {
+    email_id: i32,
+    mailing_list_id: i32,
+    mailing_list_slug: String,
+    message_id: String,
+    subject: String,
+    date: DateTime<Utc>,
+    author_id: i32,
+    author_name: Option<String>,
+    author_email: String,
+    thread_id: i32,
+    files: Vec<String>,
+    functions: Vec<String>,
+    added_identifiers: Vec<String>,
+    removed_identifiers: Vec<String>,
+    added_lines: String,
+    removed_lines: String,
+}
+
 #[derive(sqlx::FromRow, Clone)]
 struct AuthorActivityRow {
     author_id: i32,
@@ -460,7 +928,89 @@ const THREAD_QUERY: &str = r#"
         starter_author.email AS starter_email,
         starter.series_id,
         starter.series_number,
-        starter.series_total
+        starter.series_total,
+        COALESCE(
+            (
+                SELECT array_agg(s.name ORDER BY s.name)
+                FROM thread_subsystems ts
+                JOIN subsystems s ON s.id = ts.subsystem_id
+                WHERE ts.mailing_list_id = t.mailing_list_id
+                  AND ts.thread_id = t.id
+            ),
+            '{}'
+        ) AS subsystems,
+        COALESCE(
+            (
+                SELECT array_agg(DISTINCT ps.state)
+                FROM thread_memberships tm
+                JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id
+                    AND ps.email_id = tm.email_id
+                WHERE tm.mailing_list_id = t.mailing_list_id
+                  AND tm.thread_id = t.id
+            ),
+            '{}'
+        ) AS patch_states,
+        EXISTS (
+            SELECT 1
+            FROM thread_memberships tm
+            JOIN series_revision_patches srp ON srp.mailing_list_id = tm.mailing_list_id
+                AND srp.email_id = tm.email_id
+            JOIN series_revisions sr ON sr.mailing_list_id = srp.mailing_list_id
+                AND sr.id = srp.revision_id
+            WHERE tm.mailing_list_id = t.mailing_list_id
+              AND tm.thread_id = t.id
+              AND NOT sr.complete
+        ) AS incomplete_series,
+        COALESCE(
+            (
+                SELECT array_agg(DISTINCT ra.email)
+                FROM thread_memberships tm
+                JOIN email_recipients r ON r.mailing_list_id = tm.mailing_list_id
+                    AND r.email_id = tm.email_id
+                JOIN authors ra ON ra.id = r.author_id
+                WHERE tm.mailing_list_id = t.mailing_list_id
+                  AND tm.thread_id = t.id
+                  AND r.recipient_type = 'to'
+            ),
+            '{}'
+        ) AS to_emails,
+        COALESCE(
+            (
+                SELECT array_agg(DISTINCT ra.email)
+                FROM thread_memberships tm
+                JOIN email_recipients r ON r.mailing_list_id = tm.mailing_list_id
+                    AND r.email_id = tm.email_id
+                JOIN authors ra ON ra.id = r.author_id
+                WHERE tm.mailing_list_id = t.mailing_list_id
+                  AND tm.thread_id = t.id
+                  AND r.recipient_type = 'cc'
+            ),
+            '{}'
+        ) AS cc_emails,
+        COALESCE(
+            (
+                SELECT array_agg(DISTINCT item.value)
+                FROM thread_memberships tm
+                JOIN patch_code pc ON pc.mailing_list_id = tm.mailing_list_id
+                    AND pc.email_id = tm.email_id
+                CROSS JOIN LATERAL unnest(pc.files) AS item(value)
+                WHERE tm.mailing_list_id = t.mailing_list_id
+                  AND tm.thread_id = t.id
+            ),
+            '{}'
+        ) AS files,
+        COALESCE(
+            (
+                SELECT array_agg(DISTINCT item.value)
+                FROM thread_memberships tm
+                JOIN patch_code pc ON pc.mailing_list_id = tm.mailing_list_id
+                    AND pc.email_id = tm.email_id
+                CROSS JOIN LATERAL unnest(pc.functions) AS item(value)
+                WHERE tm.mailing_list_id = t.mailing_list_id
+                  AND tm.thread_id = t.id
+            ),
+            '{}'
+        ) AS functions
     FROM threads t
     JOIN mailing_lists ml ON ml.id = t.mailing_list_id
     JOIN emails starter ON starter.message_id = t.root_message_id
@@ -468,6 +1018,7 @@ const THREAD_QUERY: &str = r#"
     JOIN authors starter_author ON starter.author_id = starter_author.id
     WHERE t.id > $1
       AND ($2::int IS NULL OR t.mailing_list_id = $2)
+      AND ($4::int[] IS NULL OR t.id = ANY($4))
     ORDER BY t.id
     LIMIT $3
 "#;
@@ -490,6 +1041,83 @@ const THREAD_EMAIL_QUERY: &str = r#"
     ORDER BY tm.thread_id, e.date
 "#;
 
+const EMAIL_DOCUMENT_QUERY: &str = r#"
+    SELECT
+        e.id AS email_id,
+        e.mailing_list_id,
+        ml.slug AS mailing_list_slug,
+        e.message_id,
+        e.subject,
+        e.date,
+        e.author_id,
+        a.canonical_name AS author_name,
+        a.email AS author_email,
+        COALESCE(
+            (
+                SELECT array_agg(ra.email ORDER BY r.id)
+                FROM email_recipients r
+                JOIN authors ra ON ra.id = r.author_id
+                WHERE r.mailing_list_id = e.mailing_list_id
+                  AND r.email_id = e.id
+                  AND r.recipient_type = 'to'
+            ),
+            '{}'
+        ) AS to_emails,
+        COALESCE(
+            (
+                SELECT array_agg(ra.email ORDER BY r.id)
+                FROM email_recipients r
+                JOIN authors ra ON ra.id = r.author_id
+                WHERE r.mailing_list_id = e.mailing_list_id
+                  AND r.email_id = e.id
+                  AND r.recipient_type = 'cc'
+            ),
+            '{}'
+        ) AS cc_emails,
+        COALESCE(e.search_body, '') AS body,
+        (e.is_patch_only OR e.patch_type <> 'none') AS is_patch,
+        t.id AS thread_id,
+        t.subject AS thread_subject,
+        t.root_message_id AS thread_root_message_id,
+        COALESCE(t.message_count, 0) AS thread_message_count
+    FROM thread_memberships tm
+    JOIN threads t ON t.mailing_list_id = tm.mailing_list_id AND t.id = tm.thread_id
+    JOIN emails e ON e.mailing_list_id = tm.mailing_list_id AND e.id = tm.email_id
+    JOIN mailing_lists ml ON ml.id = e.mailing_list_id
+    JOIN authors a ON a.id = e.author_id
+    WHERE ($1::int[] IS NULL OR tm.thread_id = ANY($1))
+      AND ($2::int[] IS NULL OR e.id = ANY($2))
+    ORDER BY e.date
+"#;
+
+const PATCH_DOCUMENT_QUERY: &str = r#"
+    SELECT
+        e.id AS email_id,
+        e.mailing_list_id,
+        ml.slug AS mailing_list_slug,
+        e.message_id,
+        e.subject,
+        e.date,
+        e.author_id,
+        a.canonical_name AS author_name,
+        a.email AS author_email,
+        tm.thread_id,
+        pc.files,
+        pc.functions,
+        pc.added_identifiers,
+        pc.removed_identifiers,
+        pc.added_lines,
+        pc.removed_lines
+    FROM patch_code pc
+    JOIN emails e ON e.mailing_list_id = pc.mailing_list_id AND e.id = pc.email_id
+    JOIN thread_memberships tm ON tm.mailing_list_id = e.mailing_list_id AND tm.email_id = e.id
+    JOIN mailing_lists ml ON ml.id = e.mailing_list_id
+    JOIN authors a ON a.id = e.author_id
+    WHERE ($1::int[] IS NULL OR tm.thread_id = ANY($1))
+      AND ($2::int[] IS NULL OR e.id = ANY($2))
+    ORDER BY e.date
+"#;
+
 const AUTHOR_ACTIVITY_QUERY: &str = r#"
     SELECT
         act.author_id,
@@ -505,6 +1133,7 @@ const AUTHOR_ACTIVITY_QUERY: &str = r#"
     FROM author_mailing_list_activity act
     JOIN authors a ON a.id = act.author_id
     JOIN mailing_lists ml ON ml.id = act.mailing_list_id
+    WHERE $1::int[] IS NULL OR act.author_id = ANY($1)
 "#;
 
 const AUTHOR_ALIAS_QUERY: &str = r#"
@@ -512,5 +1141,74 @@ const AUTHOR_ALIAS_QUERY: &str = r#"
         author_id,
         name
     FROM author_name_aliases
+    WHERE $1::int[] IS NULL OR author_id = ANY($1)
     ORDER BY author_id, usage_count DESC
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn thread() -> ThreadRow {
+        let date = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        ThreadRow {
+            id: 7,
+            mailing_list_id: 1,
+            mailing_list_slug: "lkml".to_string(),
+            root_message_id: "root@example.org".to_string(),
+            subject: "mm: fix slab leak".to_string(),
+            normalized_subject: None,
+            start_date: date,
+            last_date: date,
+            message_count: 2,
+            starter_id: 1,
+            starter_name: None,
+            starter_email: "dev@example.org".to_string(),
+            series_id: None,
+            series_number: None,
+            series_total: None,
+            subsystems: Vec::new(),
+            patch_states: Vec::new(),
+            incomplete_series: false,
+            to_emails: Vec::new(),
+            cc_emails: Vec::new(),
+            files: Vec::new(),
+            functions: Vec::new(),
+        }
+    }
+
+    fn email(message_id: &str, patch_type: Option<PatchType>) -> ThreadEmailRow {
+        ThreadEmailRow {
+            thread_id: 7,
+            message_id: message_id.to_string(),
+            date: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+            search_body: Some("body".to_string()),
+            is_patch_only: false,
+            patch_type,
+            author_id: 1,
+            author_name: None,
+            author_email: "dev@example.org".to_string(),
+        }
+    }
+
+    #[test]
+    fn discussion_without_patches_is_not_flagged() {
+        let emails = vec![
+            email("root@example.org", Some(PatchType::None)),
+            email("reply@example.org", None),
+        ];
+        assert!(!assemble_thread_document(&thread(), emails).has_patches);
+    }
+
+    #[test]
+    fn inline_or_attached_patches_are_flagged() {
+        for patch_type in [PatchType::Inline, PatchType::Attachment] {
+            let emails = vec![
+                email("root@example.org", Some(PatchType::None)),
+                email("reply@example.org", Some(patch_type)),
+            ];
+            assert!(assemble_thread_document(&thread(), emails).has_patches);
+        }
+    }
+}
@@ -1,15 +1,37 @@
+mod backend;
+mod code;
+mod embedding_cache;
 mod embeddings;
 mod error;
 mod indexer;
 mod models;
+mod postgres;
+mod query;
 pub mod sanitize;
 mod service;
 
-pub use embeddings::EmbeddingsClient;
+pub use backend::{SearchBackend, SearchBackendKind, SearchFuture};
+pub use code::{PatchCode, PatchCodeProcessor, is_identifier};
+pub use embeddings::{
+    EmbeddingProvider, EmbeddingProviderKind, EmbeddingsClient, EmbeddingsConfig, HashProvider,
+    OpenAiProvider, TeiProvider,
+};
 pub use error::SearchError;
-pub use indexer::{reindex_authors, reindex_threads};
-pub use models::{AuthorDocument, AuthorMailingListStats, ThreadDocument};
+pub use indexer::{
+    reindex_authors, reindex_authors_by_id, reindex_changed_threads, reindex_threads,
+};
+pub use models::{
+    AuthorDocument, AuthorMailingListStats, EmailDocument, PatchDocument, ThreadDocument,
+};
+pub use postgres::{PostgresSearchBackend, escape_like};
+pub use query::{
+    DateRange, QueryError, QueryField, QueryNode, QueryTerm, SearchQuery, parse_search_query,
+    unsupported_terms_error,
+};
 pub use service::{
-    AuthorHit, AuthorSearchPayload, AuthorSearchResults, SearchService, ThreadHit,
-    ThreadMailingListFilter, ThreadSearchPayload, ThreadSearchResults,
+    AuthorHit, AuthorSearchPayload, AuthorSearchResults, AuthorSuggestion, EmailHit,
+    EmailSearchPayload, EmailSearchResults, FacetCounts, PatchHit, PatchSearchPayload,
+    PatchSearchResults, SearchService, SeriesKey, SimilarThreadsPayload, SuggestPayload,
+    SuggestResults, ThreadHit, ThreadMailingListFilter, ThreadSearchPayload, ThreadSearchResults,
+    ThreadSuggestion,
 };
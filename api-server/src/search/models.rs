@@ -1,3 +1,4 @@
+use crate::models::PatchState;
 use chrono::{DateTime, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
 
@@ -12,6 +13,11 @@ pub struct ThreadDocument {
     pub subject: String,
     pub normalized_subject: Option<String>,
     pub start_ts: i64,
+    /// UTC year and `YYYY-MM` month of `start_ts`, kept for faceting.
+    #[serde(default)]
+    pub start_year: i32,
+    #[serde(default)]
+    pub start_month: String,
     pub last_ts: i64,
     pub message_count: i32,
     pub discussion_text: String,
@@ -19,10 +25,26 @@ pub struct ThreadDocument {
     pub participant_ids: Vec<i32>,
     #[serde(default)]
     pub participant_emails: Vec<String>,
+    /// Addresses messages of the thread were sent or copied to.
+    #[serde(default)]
+    pub to_emails: Vec<String>,
+    #[serde(default)]
+    pub cc_emails: Vec<String>,
+    /// Files and hunk-header functions touched by patches in the thread.
+    #[serde(default)]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub functions: Vec<String>,
     pub has_patches: bool,
     pub series_id: Option<String>,
     pub series_number: Option<i32>,
     pub series_total: Option<i32>,
+    #[serde(default)]
+    pub subsystems: Vec<String>,
+    #[serde(default)]
+    pub patch_states: Vec<PatchState>,
+    #[serde(default)]
+    pub incomplete_series: bool,
     pub starter_id: i32,
     pub starter_name: Option<String>,
     pub starter_email: String,
@@ -60,6 +82,89 @@ impl ThreadDocument {
     }
 }
 
+/// Representation of an email document stored in Meilisearch, one per
+/// message, carrying the context of the thread it belongs to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct EmailDocument {
+    pub email_id: i32,
+    pub mailing_list_id: i32,
+    pub mailing_list: String,
+    pub message_id: String,
+    pub subject: String,
+    pub date_ts: i64,
+    pub author_id: i32,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    #[serde(default)]
+    pub to: Vec<String>,
+    #[serde(default)]
+    pub cc: Vec<String>,
+    /// Text written by the sender, without quotes, signature or patch.
+    pub body: String,
+    pub is_patch: bool,
+    /// Paths touched by the patch carried by the email.
+    #[serde(default)]
+    pub files: Vec<String>,
+    pub thread_id: i32,
+    pub thread_subject: String,
+    pub thread_root_message_id: String,
+    pub thread_message_count: i32,
+}
+
+impl EmailDocument {
+    pub fn date(&self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_opt(self.date_ts, 0).single()
+    }
+}
+
+/// Representation of a patch document stored in Meilisearch: the diff content
+/// of one patch email, tokenized by [`PatchCode`](crate::search::PatchCode).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub struct PatchDocument {
+    pub email_id: i32,
+    pub mailing_list_id: i32,
+    pub mailing_list: String,
+    pub message_id: String,
+    pub subject: String,
+    pub date_ts: i64,
+    pub author_id: i32,
+    pub author_name: Option<String>,
+    pub author_email: String,
+    pub thread_id: i32,
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// Functions or sections named in hunk headers.
+    #[serde(default)]
+    pub functions: Vec<String>,
+    #[serde(default)]
+    pub added_identifiers: Vec<String>,
+    #[serde(default)]
+    pub removed_identifiers: Vec<String>,
+    #[serde(default)]
+    pub added_lines: String,
+    #[serde(default)]
+    pub removed_lines: String,
+}
+
+impl PatchDocument {
+    pub fn date(&self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_opt(self.date_ts, 0).single()
+    }
+
+    /// Whether the patch adds, removes or names `symbol` in a hunk header.
+    pub fn touches(&self, symbol: &str) -> bool {
+        [
+            &self.added_identifiers,
+            &self.removed_identifiers,
+            &self.functions,
+        ]
+        .iter()
+        .any(|list| list.iter().any(|value| value == symbol))
+    }
+}
+
 /// Representation of an author document stored in Meilisearch.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
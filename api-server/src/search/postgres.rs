@@ -0,0 +1,1307 @@
+//! PostgreSQL full-text search backend.
+//!
+//! Threads match when any of their emails matches the query in `emails.lex_ts`
+//! (subject plus authored body, maintained at import). A thread ranks by its
+//! best matching email. Filters are evaluated against the same tables the
+//! indexer reads, so results carry the same [`ThreadDocument`] fields as
+//! Meilisearch hits, minus embeddings.
+//!
+//! Facet counts group the same filtered thread set, one query per facet.
+//!
+//! Similar threads are the nearest `thread_embeddings` rows by cosine
+//! distance. The indexer stores those vectors for this backend too; only rows
+//! of the configured embedding model and dimensions are compared, and threads
+//! without a stored embedding have no neighbours.
+//!
+//! Suggestions match author names and addresses by prefix and thread
+//! subjects by substring, most recent threads first.
+//!
+//! Email searches match single messages the same way and return
+//! [`EmailDocument`] fields with the thread each message belongs to.
+//!
+//! Patch searches match the diff content stored in `patch_code` by the
+//! `patch_code` email processor and return [`PatchDocument`] fields, newest
+//! first. Identifiers are compared whole against the identifier arrays; other
+//! values match as substrings of the added or removed lines.
+//!
+//! [`ThreadDocument`]: crate::search::ThreadDocument
+//! [`EmailDocument`]: crate::search::EmailDocument
+//! [`PatchDocument`]: crate::search::PatchDocument
+
+use crate::models::ThreadSearchFacet;
+use crate::search::backend::{SearchBackend, SearchBackendKind, SearchFuture};
+use crate::search::code::is_identifier;
+use crate::search::error::SearchError;
+use crate::search::indexer::{
+    load_author_documents, load_email_documents, load_patch_documents, load_thread_documents,
+};
+use crate::search::query::{QueryField, QueryNode, QueryTerm};
+use crate::search::service::{
+    AuthorHit, AuthorSearchPayload, AuthorSearchResults, AuthorSuggestion, EmailHit,
+    EmailSearchPayload, EmailSearchResults, FacetCounts, PatchHit, PatchSearchPayload,
+    PatchSearchResults, SimilarThreadsPayload, SuggestPayload, SuggestResults, ThreadHit,
+    ThreadSearchPayload, ThreadSearchResults, ThreadSuggestion,
+};
+use rocket_db_pools::sqlx::{self, PgPool, Postgres, QueryBuilder};
+use serde_json::{Map as JsonMap, Value as JsonValue};
+use std::collections::HashMap;
+
+/// `ts_headline` options matching Meilisearch's `<em>` highlight markers.
+const HEADLINE_OPTIONS: &str =
+    "StartSel=<em>, StopSel=</em>, MaxFragments=1, MaxWords=30, MinWords=10";
+
+/// Whether thread `t` carries a patch, as `has_patches` is computed at indexing.
+const THREAD_HAS_PATCHES: &str = "EXISTS (SELECT 1 FROM thread_memberships tm \
+     JOIN emails e ON e.mailing_list_id = tm.mailing_list_id AND e.id = tm.email_id \
+     WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id \
+     AND (e.is_patch_only OR e.patch_type <> 'none'))";
+
+#[derive(Clone)]
+pub struct PostgresSearchBackend {
+    pool: PgPool,
+    embedding_model: String,
+    embedding_dimensions: usize,
+}
+
+impl PostgresSearchBackend {
+    /// Backend comparing thread embeddings produced by `embedding_model` with
+    /// `embedding_dimensions` components.
+    pub fn new(
+        pool: PgPool,
+        embedding_model: impl Into<String>,
+        embedding_dimensions: usize,
+    ) -> Self {
+        Self {
+            pool,
+            embedding_model: embedding_model.into(),
+            embedding_dimensions,
+        }
+    }
+
+    pub async fn search_threads(
+        &self,
+        options: ThreadSearchPayload,
+    ) -> Result<ThreadSearchResults, SearchError> {
+        let query = options.query.trim();
+        let text = (!query.is_empty()).then_some(query);
+
+        let mut count_builder = QueryBuilder::new("SELECT COUNT(*) FROM threads t");
+        push_thread_source(&mut count_builder, text, &options);
+        push_thread_filters(&mut count_builder, &options);
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let mut facets = Vec::with_capacity(options.facets.len());
+        for facet in &options.facets {
+            let counts = if total == 0 {
+                Vec::new()
+            } else {
+                self.count_thread_facet(*facet, text, &options).await?
+            };
+            facets.push(FacetCounts::new(*facet, counts));
+        }
+
+        if total == 0 {
+            return Ok(ThreadSearchResults {
+                hits: Vec::new(),
+                total,
+                facets,
+            });
+        }
+
+        let mut data_builder = QueryBuilder::new("SELECT t.id, ");
+        match text {
+            Some(text) => {
+                data_builder.push("m.rank, ts_headline('english', t.subject, ");
+                data_builder.push("websearch_to_tsquery('english', ");
+                data_builder.push_bind(text);
+                data_builder.push("), ");
+                data_builder.push_bind(HEADLINE_OPTIONS);
+                data_builder.push(") AS subject_html, ");
+                data_builder.push("ts_headline('english', COALESCE(starter.search_body, ''), ");
+                data_builder.push("websearch_to_tsquery('english', ");
+                data_builder.push_bind(text);
+                data_builder.push("), ");
+                data_builder.push_bind(HEADLINE_OPTIONS);
+                data_builder.push(") AS discussion_html");
+            }
+            None => {
+                data_builder.push(
+                    "NULL::real AS rank, NULL::text AS subject_html, NULL::text AS discussion_html",
+                );
+            }
+        }
+        data_builder.push(" FROM threads t");
+        push_thread_source(&mut data_builder, text, &options);
+        push_thread_filters(&mut data_builder, &options);
+
+        let mut order_clauses = thread_order_clauses(&options.sort_expressions);
+        order_clauses.push("rank DESC NULLS LAST".to_string());
+        order_clauses.push("t.id DESC".to_string());
+        data_builder.push(" ORDER BY ");
+        data_builder.push(order_clauses.join(", "));
+        data_builder.push(" LIMIT ");
+        data_builder.push_bind(options.size);
+        data_builder.push(" OFFSET ");
+        data_builder.push_bind((options.page - 1) * options.size);
+
+        let rows: Vec<ThreadMatchRow> = data_builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let thread_ids: Vec<i32> = rows.iter().map(|row| row.id).collect();
+        let documents = load_thread_documents(&self.pool, &thread_ids).await?;
+        let mut matches: HashMap<i32, ThreadMatchRow> =
+            rows.into_iter().map(|row| (row.id, row)).collect();
+
+        let hits = documents
+            .into_iter()
+            .map(|document| {
+                let matched = matches.remove(&document.thread_id);
+                ThreadHit {
+                    ranking_score: matched.as_ref().and_then(|row| row.rank),
+                    formatted: matched.and_then(ThreadMatchRow::formatted),
+                    document,
+                }
+            })
+            .collect();
+
+        Ok(ThreadSearchResults {
+            hits,
+            total,
+            facets,
+        })
+    }
+
+    /// `(value, count)` pairs of one facet over the threads matching `options`.
+    async fn count_thread_facet(
+        &self,
+        facet: ThreadSearchFacet,
+        text: Option<&str>,
+        options: &ThreadSearchPayload,
+    ) -> Result<Vec<(String, i64)>, SearchError> {
+        let value = match facet {
+            ThreadSearchFacet::MailingList => "ml.slug".to_string(),
+            ThreadSearchFacet::Year => {
+                "to_char(t.start_date AT TIME ZONE 'UTC', 'YYYY')".to_string()
+            }
+            ThreadSearchFacet::Month => {
+                "to_char(t.start_date AT TIME ZONE 'UTC', 'YYYY-MM')".to_string()
+            }
+            ThreadSearchFacet::HasPatches => format!("({THREAD_HAS_PATCHES})::text"),
+            ThreadSearchFacet::Participant => "p.email".to_string(),
+            ThreadSearchFacet::SeriesVersion => "starter.series_id".to_string(),
+        };
+
+        let mut builder = QueryBuilder::new("SELECT f.value, COUNT(*) FROM (SELECT ");
+        builder.push(value);
+        builder.push(" AS value FROM threads t");
+        push_thread_source(&mut builder, text, options);
+        if facet == ThreadSearchFacet::Participant {
+            builder.push(
+                " CROSS JOIN LATERAL (SELECT DISTINCT a.email FROM thread_memberships tm \
+                 JOIN emails e ON e.mailing_list_id = tm.mailing_list_id AND e.id = tm.email_id \
+                 JOIN authors a ON a.id = e.author_id \
+                 WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id) p",
+            );
+        }
+        push_thread_filters(&mut builder, options);
+        builder.push(") f WHERE f.value IS NOT NULL GROUP BY f.value");
+
+        builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)
+    }
+
+    pub async fn search_emails(
+        &self,
+        options: EmailSearchPayload,
+    ) -> Result<EmailSearchResults, SearchError> {
+        let query = options.query.trim();
+        let text = (!query.is_empty()).then_some(query);
+
+        let mut count_builder = QueryBuilder::new("SELECT COUNT(*) FROM emails e");
+        push_email_filters(&mut count_builder, text, &options);
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        if total == 0 {
+            return Ok(EmailSearchResults {
+                hits: Vec::new(),
+                total,
+            });
+        }
+
+        let mut data_builder = QueryBuilder::new("SELECT e.id, ");
+        match text {
+            Some(text) => {
+                data_builder.push("ts_rank_cd(e.lex_ts, websearch_to_tsquery('english', ");
+                data_builder.push_bind(text);
+                data_builder.push("), 32) AS rank, ts_headline('english', e.subject, ");
+                data_builder.push("websearch_to_tsquery('english', ");
+                data_builder.push_bind(text);
+                data_builder.push("), ");
+                data_builder.push_bind(HEADLINE_OPTIONS);
+                data_builder.push(") AS subject_html, ");
+                data_builder.push("ts_headline('english', COALESCE(e.search_body, ''), ");
+                data_builder.push("websearch_to_tsquery('english', ");
+                data_builder.push_bind(text);
+                data_builder.push("), ");
+                data_builder.push_bind(HEADLINE_OPTIONS);
+                data_builder.push(") AS body_html");
+            }
+            None => {
+                data_builder.push(
+                    "NULL::real AS rank, NULL::text AS subject_html, NULL::text AS body_html",
+                );
+            }
+        }
+        data_builder.push(" FROM emails e");
+        push_email_filters(&mut data_builder, text, &options);
+        data_builder.push(" ORDER BY rank DESC NULLS LAST, e.date DESC, e.id DESC LIMIT ");
+        data_builder.push_bind(options.size);
+        data_builder.push(" OFFSET ");
+        data_builder.push_bind((options.page - 1) * options.size);
+
+        let rows: Vec<EmailMatchRow> = data_builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let email_ids: Vec<i32> = rows.iter().map(|row| row.id).collect();
+        let documents = load_email_documents(&self.pool, &email_ids).await?;
+        let mut matches: HashMap<i32, EmailMatchRow> =
+            rows.into_iter().map(|row| (row.id, row)).collect();
+
+        let hits = documents
+            .into_iter()
+            .map(|document| {
+                let matched = matches.remove(&document.email_id);
+                EmailHit {
+                    ranking_score: matched.as_ref().and_then(|row| row.rank),
+                    formatted: matched.and_then(EmailMatchRow::formatted),
+                    document,
+                }
+            })
+            .collect();
+
+        Ok(EmailSearchResults { hits, total })
+    }
+
+    pub async fn search_patches(
+        &self,
+        options: PatchSearchPayload,
+    ) -> Result<PatchSearchResults, SearchError> {
+        let mut count_builder = QueryBuilder::new(
+            "SELECT COUNT(*) FROM patch_code pc \
+             JOIN emails e ON e.mailing_list_id = pc.mailing_list_id AND e.id = pc.email_id",
+        );
+        push_patch_filters(&mut count_builder, &options);
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        if total == 0 {
+            return Ok(PatchSearchResults {
+                hits: Vec::new(),
+                total,
+            });
+        }
+
+        let mut data_builder = QueryBuilder::new(
+            "SELECT e.id FROM patch_code pc \
+             JOIN emails e ON e.mailing_list_id = pc.mailing_list_id AND e.id = pc.email_id",
+        );
+        push_patch_filters(&mut data_builder, &options);
+        data_builder.push(" ORDER BY e.date DESC, e.id DESC LIMIT ");
+        data_builder.push_bind(options.size);
+        data_builder.push(" OFFSET ");
+        data_builder.push_bind((options.page - 1) * options.size);
+
+        let email_ids: Vec<i32> = data_builder
+            .build_query_scalar()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let hits = load_patch_documents(&self.pool, &email_ids)
+            .await?
+            .into_iter()
+            .map(|document| PatchHit {
+                document,
+                ranking_score: None,
+                formatted: None,
+            })
+            .collect();
+
+        Ok(PatchSearchResults { hits, total })
+    }
+
+    pub async fn search_authors(
+        &self,
+        options: AuthorSearchPayload,
+    ) -> Result<AuthorSearchResults, SearchError> {
+        let query = options
+            .query
+            .as_deref()
+            .map(str::trim)
+            .filter(|value| !value.is_empty());
+
+        let mut count_builder = QueryBuilder::new("SELECT COUNT(*) FROM authors a");
+        push_author_filters(&mut count_builder, query, &options.mailing_lists);
+
+        let total: i64 = count_builder
+            .build_query_scalar()
+            .fetch_one(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        if total == 0 {
+            return Ok(AuthorSearchResults {
+                hits: Vec::new(),
+                total,
+            });
+        }
+
+        let mut data_builder = QueryBuilder::new(
+            "SELECT a.id FROM authors a \
+             CROSS JOIN LATERAL ( \
+                SELECT SUM(act.email_count) AS email_count, \
+                       SUM(act.thread_count) AS thread_count, \
+                       MIN(act.first_email_date) AS first_email_date, \
+                       MAX(act.last_email_date) AS last_email_date \
+                FROM author_mailing_list_activity act \
+                WHERE act.author_id = a.id \
+             ) stats",
+        );
+        push_author_filters(&mut data_builder, query, &options.mailing_lists);
+
+        data_builder.push(" ORDER BY ");
+        if let Some(clause) = options
+            .sort_expression
+            .as_deref()
+            .and_then(author_order_clause)
+        {
+            data_builder.push(clause);
+            data_builder.push(", ");
+        } else if let Some(query) = query {
+            data_builder.push("GREATEST(similarity(a.email, ");
+            data_builder.push_bind(query);
+            data_builder.push("), similarity(COALESCE(a.canonical_name, ''), ");
+            data_builder.push_bind(query);
+            data_builder.push(")) DESC, ");
+        }
+        data_builder.push("stats.email_count DESC NULLS LAST, a.id");
+        data_builder.push(" LIMIT ");
+        data_builder.push_bind(options.size);
+        data_builder.push(" OFFSET ");
+        data_builder.push_bind((options.page - 1) * options.size);
+
+        let author_ids: Vec<i32> = data_builder
+            .build_query_scalar()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let hits = load_author_documents(&self.pool, &author_ids)
+            .await?
+            .into_iter()
+            .map(|document| AuthorHit { document })
+            .collect();
+
+        Ok(AuthorSearchResults { hits, total })
+    }
+
+    pub async fn similar_threads(
+        &self,
+        options: SimilarThreadsPayload,
+    ) -> Result<Vec<ThreadHit>, SearchError> {
+        // The source vector is a scalar subquery and the dimension count a
+        // literal, so the ORDER BY can use the partial vector index on
+        // `embedding::vector(1024) WHERE dimensions = 1024`
+        let vector = format!("embedding::vector({})", self.embedding_dimensions);
+        let mut builder = QueryBuilder::new("WITH source AS (SELECT ");
+        builder.push(&vector);
+        builder.push(" AS embedding FROM thread_embeddings WHERE mailing_list_id = ");
+        builder.push_bind(options.mailing_list_id);
+        builder.push(" AND thread_id = ");
+        builder.push_bind(options.thread_id);
+        builder.push(" AND model = ");
+        builder.push_bind(self.embedding_model.clone());
+        builder.push(" AND dimensions = ");
+        builder.push(self.embedding_dimensions);
+        builder.push(") SELECT t.id, (1 - (te.");
+        builder.push(&vector);
+        builder.push(
+            " <=> (SELECT embedding FROM source)))::real AS score \
+             FROM thread_embeddings te \
+             JOIN threads t ON t.mailing_list_id = te.mailing_list_id AND t.id = te.thread_id \
+             JOIN emails starter ON starter.message_id = t.root_message_id \
+                 AND starter.mailing_list_id = t.mailing_list_id \
+             WHERE te.model = ",
+        );
+        builder.push_bind(self.embedding_model.clone());
+        builder.push(" AND te.dimensions = ");
+        builder.push(self.embedding_dimensions);
+        builder.push(" AND t.id <> ");
+        builder.push_bind(options.thread_id);
+
+        let list_ids: Vec<i32> = options
+            .mailing_lists
+            .iter()
+            .filter_map(|list| list.mailing_list_id)
+            .collect();
+        if !list_ids.is_empty() {
+            builder.push(" AND te.mailing_list_id = ANY(");
+            builder.push_bind(list_ids);
+            builder.push(")");
+        }
+
+        if let Some(has_patches) = options.has_patches {
+            builder.push(" AND ");
+            builder.push(THREAD_HAS_PATCHES);
+            builder.push(" = ");
+            builder.push_bind(has_patches);
+        }
+
+        if let Some(series) = options.exclude_series {
+            builder.push(" AND NOT (starter.author_id = ");
+            builder.push_bind(series.starter_id);
+            builder.push(" AND starter.normalized_subject IS NOT DISTINCT FROM ");
+            builder.push_bind(series.normalized_subject);
+            builder.push(")");
+        }
+
+        builder.push(" ORDER BY te.");
+        builder.push(&vector);
+        builder.push(" <=> (SELECT embedding FROM source) LIMIT ");
+        builder.push_bind(options.limit);
+
+        let rows: Vec<(i32, Option<f32>)> = builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        let thread_ids: Vec<i32> = rows.iter().map(|(id, _)| *id).collect();
+        let mut scores: HashMap<i32, Option<f32>> = rows.into_iter().collect();
+
+        Ok(load_thread_documents(&self.pool, &thread_ids)
+            .await?
+            .into_iter()
+            .map(|document| ThreadHit {
+                ranking_score: scores.remove(&document.thread_id).flatten(),
+                formatted: None,
+                document,
+            })
+            .collect())
+    }
+
+    pub async fn suggest(&self, options: SuggestPayload) -> Result<SuggestResults, SearchError> {
+        let query = escape_like(options.query.trim());
+        let slugs: Vec<String> = options
+            .mailing_lists
+            .iter()
+            .map(|list| list.slug.clone())
+            .collect();
+
+        // Match the start of the address, the name or any word of the name
+        // before summing activity, so only matching authors are aggregated
+        let mut author_builder = QueryBuilder::new(
+            "WITH matched AS MATERIALIZED ( \
+                SELECT a.id, a.canonical_name, a.email FROM authors a WHERE ",
+        );
+        push_author_match(&mut author_builder, format!("{query}%"));
+        author_builder.push(" OR a.canonical_name ILIKE ");
+        author_builder.push_bind(format!("% {query}%"));
+        author_builder.push(
+            ") SELECT a.id AS author_id, a.canonical_name, a.email FROM matched a \
+             CROSS JOIN LATERAL ( \
+                SELECT SUM(act.email_count) AS email_count \
+                FROM author_mailing_list_activity act \
+                JOIN mailing_lists ml ON ml.id = act.mailing_list_id \
+                WHERE act.author_id = a.id",
+        );
+        if !slugs.is_empty() {
+            author_builder.push(" AND ml.slug = ANY(");
+            author_builder.push_bind(slugs);
+            author_builder.push(")");
+        }
+        author_builder.push(
+            ") stats WHERE stats.email_count IS NOT NULL \
+             ORDER BY stats.email_count DESC, a.id LIMIT ",
+        );
+        author_builder.push_bind(options.limit);
+
+        let authors: Vec<AuthorSuggestion> = author_builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        if !options.include_threads {
+            return Ok(SuggestResults {
+                authors,
+                threads: Vec::new(),
+            });
+        }
+
+        let mut thread_builder = QueryBuilder::new(
+            "SELECT t.id AS thread_id, ml.slug AS mailing_list, t.subject, \
+             EXTRACT(EPOCH FROM t.last_date)::bigint AS last_ts \
+             FROM threads t JOIN mailing_lists ml ON ml.id = t.mailing_list_id \
+             WHERE t.subject ILIKE ",
+        );
+        thread_builder.push_bind(format!("%{query}%"));
+        let list_ids: Vec<i32> = options
+            .mailing_lists
+            .iter()
+            .filter_map(|list| list.mailing_list_id)
+            .collect();
+        if !list_ids.is_empty() {
+            thread_builder.push(" AND t.mailing_list_id = ANY(");
+            thread_builder.push_bind(list_ids);
+            thread_builder.push(")");
+        }
+        thread_builder.push(" ORDER BY t.last_date DESC LIMIT ");
+        thread_builder.push_bind(options.limit);
+
+        let threads: Vec<ThreadSuggestion> = thread_builder
+            .build_query_as()
+            .fetch_all(&self.pool)
+            .await
+            .map_err(SearchError::Database)?;
+
+        Ok(SuggestResults { authors, threads })
+    }
+}
+
+impl SearchBackend for PostgresSearchBackend {
+    fn kind(&self) -> SearchBackendKind {
+        SearchBackendKind::Postgres
+    }
+
+    fn supports_query_term(&self, _term: &QueryTerm) -> bool {
+        true
+    }
+
+    fn supports_email_query_term(&self, _term: &QueryTerm) -> bool {
+        true
+    }
+
+    fn searches_query_term_as_text(&self, _term: &QueryTerm) -> bool {
+        false
+    }
+
+    fn supports_patch_query_term(&self, _term: &QueryTerm) -> bool {
+        true
+    }
+
+    fn search_threads<'a>(
+        &'a self,
+        options: ThreadSearchPayload,
+    ) -> SearchFuture<'a, ThreadSearchResults> {
+        Box::pin(PostgresSearchBackend::search_threads(self, options))
+    }
+
+    fn search_authors<'a>(
+        &'a self,
+        options: AuthorSearchPayload,
+    ) -> SearchFuture<'a, AuthorSearchResults> {
+        Box::pin(PostgresSearchBackend::search_authors(self, options))
+    }
+
+    fn search_emails<'a>(
+        &'a self,
+        options: EmailSearchPayload,
+    ) -> SearchFuture<'a, EmailSearchResults> {
+        Box::pin(PostgresSearchBackend::search_emails(self, options))
+    }
+
+    fn search_patches<'a>(
+        &'a self,
+        options: PatchSearchPayload,
+    ) -> SearchFuture<'a, PatchSearchResults> {
+        Box::pin(PostgresSearchBackend::search_patches(self, options))
+    }
+
+    fn similar_threads<'a>(
+        &'a self,
+        options: SimilarThreadsPayload,
+    ) -> SearchFuture<'a, Vec<ThreadHit>> {
+        Box::pin(PostgresSearchBackend::similar_threads(self, options))
+    }
+
+    fn suggest<'a>(&'a self, options: SuggestPayload) -> SearchFuture<'a, SuggestResults> {
+        Box::pin(PostgresSearchBackend::suggest(self, options))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct ThreadMatchRow {
+    id: i32,
+    rank: Option<f32>,
+    subject_html: Option<String>,
+    discussion_html: Option<String>,
+}
+
+impl ThreadMatchRow {
+    /// Highlights in the shape of Meilisearch's `_formatted` object.
+    fn formatted(self) -> Option<JsonValue> {
+        let mut formatted = JsonMap::new();
+        if let Some(subject) = self.subject_html {
+            formatted.insert("subject".to_string(), JsonValue::String(subject));
+        }
+        if let Some(discussion) = self.discussion_html.filter(|text| !text.is_empty()) {
+            formatted.insert("discussion_text".to_string(), JsonValue::String(discussion));
+        }
+        (!formatted.is_empty()).then_some(JsonValue::Object(formatted))
+    }
+}
+
+#[derive(sqlx::FromRow)]
+struct EmailMatchRow {
+    id: i32,
+    rank: Option<f32>,
+    subject_html: Option<String>,
+    body_html: Option<String>,
+}
+
+impl EmailMatchRow {
+    /// Highlights in the shape of Meilisearch's `_formatted` object.
+    fn formatted(self) -> Option<JsonValue> {
+        let mut formatted = JsonMap::new();
+        if let Some(subject) = self.subject_html {
+            formatted.insert("subject".to_string(), JsonValue::String(subject));
+        }
+        if let Some(body) = self.body_html.filter(|text| !text.is_empty()) {
+            formatted.insert("body".to_string(), JsonValue::String(body));
+        }
+        (!formatted.is_empty()).then_some(JsonValue::Object(formatted))
+    }
+}
+
+/// Join the thread's list, root email and (for text queries) its best
+/// matching email rank.
+fn push_thread_source<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    text: Option<&'a str>,
+    options: &ThreadSearchPayload,
+) {
+    builder.push(" JOIN mailing_lists ml ON ml.id = t.mailing_list_id");
+    builder.push(
+        " JOIN emails starter ON starter.message_id = t.root_message_id \
+         AND starter.mailing_list_id = t.mailing_list_id",
+    );
+
+    let Some(text) = text else {
+        return;
+    };
+
+    builder.push(
+        " JOIN (SELECT tm.mailing_list_id, tm.thread_id, \
+         MAX(ts_rank_cd(e.lex_ts, q.query, 32)) AS rank \
+         FROM emails e \
+         JOIN thread_memberships tm ON tm.mailing_list_id = e.mailing_list_id \
+             AND tm.email_id = e.id \
+         CROSS JOIN (SELECT websearch_to_tsquery('english', ",
+    );
+    builder.push_bind(text);
+    builder.push(") AS query) q WHERE e.lex_ts @@ q.query");
+
+    // Narrow the match scan to the requested lists
+    let list_ids: Vec<i32> = options
+        .mailing_lists
+        .iter()
+        .filter_map(|list| list.mailing_list_id)
+        .collect();
+    if !list_ids.is_empty() {
+        builder.push(" AND e.mailing_list_id = ANY(");
+        builder.push_bind(list_ids);
+        builder.push(")");
+    }
+
+    builder.push(
+        " GROUP BY tm.mailing_list_id, tm.thread_id) m \
+         ON m.mailing_list_id = t.mailing_list_id AND m.thread_id = t.id",
+    );
+}
+
+/// Mirror of the Meilisearch filters built by `build_thread_filters`.
+fn push_thread_filters(builder: &mut QueryBuilder<'_, Postgres>, options: &ThreadSearchPayload) {
+    let mut has_where = false;
+
+    if !options.mailing_lists.is_empty() {
+        let slugs: Vec<String> = options
+            .mailing_lists
+            .iter()
+            .map(|list| list.slug.clone())
+            .collect();
+        push_clause(builder, &mut has_where);
+        builder.push("ml.slug = ANY(");
+        builder.push_bind(slugs);
+        builder.push(")");
+
+        let ids: Vec<i32> = options
+            .mailing_lists
+            .iter()
+            .filter_map(|list| list.mailing_list_id)
+            .collect();
+        if !ids.is_empty() {
+            push_clause(builder, &mut has_where);
+            builder.push("t.mailing_list_id = ANY(");
+            builder.push_bind(ids);
+            builder.push(")");
+        }
+    }
+
+    if let Some(start) = options.start_date {
+        push_clause(builder, &mut has_where);
+        builder.push("t.last_date >= ");
+        builder.push_bind(start);
+    }
+
+    if let Some(end) = options.end_date {
+        push_clause(builder, &mut has_where);
+        builder.push("t.start_date <= ");
+        builder.push_bind(end);
+    }
+
+    if let Some(has_patches) = options.has_patches {
+        push_clause(builder, &mut has_where);
+        builder.push(THREAD_HAS_PATCHES);
+        builder.push(" = ");
+        builder.push_bind(has_patches);
+    }
+
+    if let Some(incomplete_series) = options.incomplete_series {
+        push_clause(builder, &mut has_where);
+        builder.push(
+            "EXISTS (SELECT 1 FROM thread_memberships tm \
+             JOIN series_revision_patches srp ON srp.mailing_list_id = tm.mailing_list_id \
+                 AND srp.email_id = tm.email_id \
+             JOIN series_revisions sr ON sr.mailing_list_id = srp.mailing_list_id \
+                 AND sr.id = srp.revision_id \
+             WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id \
+             AND NOT sr.complete) = ",
+        );
+        builder.push_bind(incomplete_series);
+    }
+
+    if let Some(starter_id) = options.starter_id {
+        push_clause(builder, &mut has_where);
+        builder.push("starter.author_id = ");
+        builder.push_bind(starter_id);
+    }
+
+    if !options.participant_ids.is_empty() {
+        push_clause(builder, &mut has_where);
+        builder.push(
+            "EXISTS (SELECT 1 FROM thread_memberships tm \
+             JOIN emails e ON e.mailing_list_id = tm.mailing_list_id AND e.id = tm.email_id \
+             WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id \
+             AND e.author_id = ANY(",
+        );
+        builder.push_bind(options.participant_ids.clone());
+        builder.push("))");
+    }
+
+    if let Some(series_id) = options.series_id.as_ref() {
+        push_clause(builder, &mut has_where);
+        builder.push("starter.series_id = ");
+        builder.push_bind(series_id.clone());
+    }
+
+    if !options.subsystems.is_empty() {
+        push_clause(builder, &mut has_where);
+        builder.push(
+            "EXISTS (SELECT 1 FROM thread_subsystems ts \
+             JOIN subsystems s ON s.id = ts.subsystem_id \
+             WHERE ts.mailing_list_id = t.mailing_list_id AND ts.thread_id = t.id \
+             AND s.name = ANY(",
+        );
+        builder.push_bind(options.subsystems.clone());
+        builder.push("))");
+    }
+
+    if !options.patch_states.is_empty() {
+        push_clause(builder, &mut has_where);
+        builder.push(
+            "EXISTS (SELECT 1 FROM thread_memberships tm \
+             JOIN patch_states ps ON ps.mailing_list_id = tm.mailing_list_id \
+                 AND ps.email_id = tm.email_id \
+             WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id \
+             AND ps.state = ANY(",
+        );
+        builder.push_bind(options.patch_states.clone());
+        builder.push("))");
+    }
+
+    for node in &options.query_filters {
+        push_clause(builder, &mut has_where);
+        push_query_filter(builder, node, FilterScope::Thread);
+    }
+}
+
+/// Only threaded emails are searched, so every hit has thread context.
+fn push_email_filters<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    text: Option<&'a str>,
+    options: &EmailSearchPayload,
+) {
+    builder.push(
+        " WHERE EXISTS (SELECT 1 FROM thread_memberships tm \
+         WHERE tm.mailing_list_id = e.mailing_list_id AND tm.email_id = e.id",
+    );
+    if let Some(thread_id) = options.thread_id {
+        builder.push(" AND tm.thread_id = ");
+        builder.push_bind(thread_id);
+    }
+    builder.push(")");
+
+    if let Some(text) = text {
+        builder.push(" AND e.lex_ts @@ websearch_to_tsquery('english', ");
+        builder.push_bind(text);
+        builder.push(")");
+    }
+
+    let ids: Vec<i32> = options
+        .mailing_lists
+        .iter()
+        .filter_map(|list| list.mailing_list_id)
+        .collect();
+    if !ids.is_empty() {
+        builder.push(" AND e.mailing_list_id = ANY(");
+        builder.push_bind(ids);
+        builder.push(")");
+    }
+
+    if let Some(start) = options.start_date {
+        builder.push(" AND e.date >= ");
+        builder.push_bind(start);
+    }
+
+    if let Some(end) = options.end_date {
+        builder.push(" AND e.date <= ");
+        builder.push_bind(end);
+    }
+
+    if let Some(is_patch) = options.is_patch {
+        builder.push(" AND (e.is_patch_only OR e.patch_type <> 'none') = ");
+        builder.push_bind(is_patch);
+    }
+
+    if let Some(author_id) = options.author_id {
+        builder.push(" AND e.author_id = ");
+        builder.push_bind(author_id);
+    }
+
+    for node in &options.query_filters {
+        builder.push(" AND ");
+        push_query_filter(builder, node, FilterScope::Email);
+    }
+}
+
+/// Only threaded patches are searched, like emails.
+fn push_patch_filters(builder: &mut QueryBuilder<'_, Postgres>, options: &PatchSearchPayload) {
+    builder.push(
+        " WHERE EXISTS (SELECT 1 FROM thread_memberships tm \
+         WHERE tm.mailing_list_id = e.mailing_list_id AND tm.email_id = e.id",
+    );
+    if let Some(thread_id) = options.thread_id {
+        builder.push(" AND tm.thread_id = ");
+        builder.push_bind(thread_id);
+    }
+    builder.push(")");
+
+    let ids: Vec<i32> = options
+        .mailing_lists
+        .iter()
+        .filter_map(|list| list.mailing_list_id)
+        .collect();
+    if !ids.is_empty() {
+        builder.push(" AND pc.mailing_list_id = ANY(");
+        builder.push_bind(ids);
+        builder.push(")");
+    }
+
+    if let Some(start) = options.start_date {
+        builder.push(" AND e.date >= ");
+        builder.push_bind(start);
+    }
+
+    if let Some(end) = options.end_date {
+        builder.push(" AND e.date <= ");
+        builder.push_bind(end);
+    }
+
+    if let Some(author_id) = options.author_id {
+        builder.push(" AND e.author_id = ");
+        builder.push_bind(author_id);
+    }
+
+    for node in &options.query_filters {
+        builder.push(" AND ");
+        push_query_filter(builder, node, FilterScope::Patch);
+    }
+}
+
+/// What a lore-style query condition is evaluated against.
+#[derive(Clone, Copy)]
+enum FilterScope {
+    /// Threads (`t`), matching when any of their emails does.
+    Thread,
+    /// A single email (`e`).
+    Email,
+    /// The diff of a single patch email (`e`); bare words match symbols.
+    Patch,
+}
+
+/// Translate a lore-style query condition. In thread scope each term matches
+/// threads with at least one email satisfying it, and date ranges apply to
+/// the thread span like the `start_date`/`end_date` filters.
+fn push_query_filter(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    node: &QueryNode,
+    scope: FilterScope,
+) {
+    match node {
+        QueryNode::Term(term) => match scope {
+            FilterScope::Thread => {
+                builder.push(
+                    "EXISTS (SELECT 1 FROM thread_memberships tm \
+                     JOIN emails e ON e.mailing_list_id = tm.mailing_list_id \
+                         AND e.id = tm.email_id \
+                     WHERE tm.mailing_list_id = t.mailing_list_id AND tm.thread_id = t.id AND ",
+                );
+                push_term_condition(builder, term);
+                builder.push(")");
+            }
+            FilterScope::Email => {
+                builder.push("(");
+                push_term_condition(builder, term);
+                builder.push(")");
+            }
+            FilterScope::Patch => {
+                builder.push("(");
+                if term.field == QueryField::Text {
+                    push_code_condition(builder, QueryField::DiffSymbol, term);
+                } else {
+                    push_term_condition(builder, term);
+                }
+                builder.push(")");
+            }
+        },
+        QueryNode::Date(range) => {
+            let (after, before) = match scope {
+                FilterScope::Thread => (" AND t.last_date >= ", " AND t.start_date <= "),
+                FilterScope::Email | FilterScope::Patch => (" AND e.date >= ", " AND e.date <= "),
+            };
+            builder.push("(TRUE");
+            if let Some(start) = range.start {
+                builder.push(after);
+                builder.push_bind(start);
+            }
+            if let Some(end) = range.end {
+                builder.push(before);
+                builder.push_bind(end);
+            }
+            builder.push(")");
+        }
+        QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+            let operator = if matches!(node, QueryNode::And(_)) {
+                " AND "
+            } else {
+                " OR "
+            };
+            builder.push("(");
+            for (index, child) in nodes.iter().enumerate() {
+                if index > 0 {
+                    builder.push(operator);
+                }
+                push_query_filter(builder, child, scope);
+            }
+            builder.push(")");
+        }
+        QueryNode::Not(inner) => {
+            builder.push("NOT ");
+            push_query_filter(builder, inner, scope);
+        }
+    }
+}
+
+/// Condition on the email `e` for a single term.
+fn push_term_condition(builder: &mut QueryBuilder<'_, Postgres>, term: &QueryTerm) {
+    let pattern = format!("%{}%", escape_like(&term.value));
+    match term.field {
+        QueryField::Text | QueryField::SubjectBody => {
+            builder.push("e.lex_ts @@ ");
+            push_tsquery(builder, term);
+        }
+        QueryField::Body => {
+            builder.push("e.body_ts @@ ");
+            push_tsquery(builder, term);
+        }
+        QueryField::Subject => {
+            builder.push("e.subject ILIKE ");
+            builder.push_bind(pattern);
+        }
+        QueryField::From => {
+            builder.push("EXISTS (SELECT 1 FROM authors a WHERE a.id = e.author_id AND ");
+            push_author_match(builder, pattern);
+            builder.push(")");
+        }
+        QueryField::To | QueryField::Cc | QueryField::Address => {
+            builder.push("(");
+            if term.field == QueryField::Address {
+                builder.push("EXISTS (SELECT 1 FROM authors a WHERE a.id = e.author_id AND ");
+                push_author_match(builder, pattern.clone());
+                builder.push(") OR ");
+            }
+            builder.push(
+                "EXISTS (SELECT 1 FROM email_recipients r JOIN authors a ON a.id = r.author_id \
+                 WHERE r.mailing_list_id = e.mailing_list_id AND r.email_id = e.id AND ",
+            );
+            match term.field {
+                QueryField::To => {
+                    builder.push("r.recipient_type = 'to' AND ");
+                }
+                QueryField::Cc => {
+                    builder.push("r.recipient_type = 'cc' AND ");
+                }
+                _ => {}
+            }
+            push_author_match(builder, pattern);
+            builder.push("))");
+        }
+        QueryField::MessageId => {
+            builder.push("e.message_id = ");
+            builder.push_bind(
+                term.value
+                    .trim_start_matches('<')
+                    .trim_end_matches('>')
+                    .to_string(),
+            );
+        }
+        QueryField::DiffFileName => push_code_list_condition(builder, "pc.files", term, false),
+        QueryField::DiffHunkHeader => {
+            push_code_list_condition(builder, "pc.functions", term, true);
+        }
+        QueryField::DiffAdded | QueryField::DiffRemoved | QueryField::DiffSymbol => {
+            push_code_condition(builder, term.field, term);
+        }
+    }
+}
+
+/// Condition on the `patch_code` row of the email `e`: identifiers match
+/// whole entries of the identifier arrays, anything else a substring of the
+/// changed lines.
+fn push_code_condition(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    field: QueryField,
+    term: &QueryTerm,
+) {
+    let identifier = !term.phrase && is_identifier(&term.value);
+    let columns: &[&str] = match (field, identifier) {
+        (QueryField::DiffAdded, true) => &["pc.added_identifiers"],
+        (QueryField::DiffRemoved, true) => &["pc.removed_identifiers"],
+        (_, true) => &[
+            "pc.added_identifiers",
+            "pc.removed_identifiers",
+            "pc.functions",
+        ],
+        (QueryField::DiffAdded, false) => &["pc.added_lines"],
+        (QueryField::DiffRemoved, false) => &["pc.removed_lines"],
+        (_, false) => &["pc.added_lines", "pc.removed_lines"],
+    };
+
+    builder.push(
+        "EXISTS (SELECT 1 FROM patch_code pc \
+         WHERE pc.mailing_list_id = e.mailing_list_id AND pc.email_id = e.id AND (",
+    );
+    for (index, column) in columns.iter().enumerate() {
+        if index > 0 {
+            builder.push(" OR ");
+        }
+        builder.push(*column);
+        if identifier {
+            builder.push(" @> ARRAY[");
+            builder.push_bind(term.value.clone());
+            builder.push("]::text[]");
+        } else {
+            builder.push(" ILIKE ");
+            builder.push_bind(format!("%{}%", escape_like(&term.value)));
+        }
+    }
+    builder.push("))");
+}
+
+/// Condition on an array column of the `patch_code` row of the email `e`:
+/// a substring of any entry, or with `whole_identifiers` a whole entry when
+/// the term is an identifier.
+fn push_code_list_condition(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    column: &'static str,
+    term: &QueryTerm,
+    whole_identifiers: bool,
+) {
+    builder.push(
+        "EXISTS (SELECT 1 FROM patch_code pc \
+         WHERE pc.mailing_list_id = e.mailing_list_id AND pc.email_id = e.id AND ",
+    );
+    if whole_identifiers && !term.phrase && is_identifier(&term.value) {
+        builder.push(column);
+        builder.push(" @> ARRAY[");
+        builder.push_bind(term.value.clone());
+        builder.push("]::text[]");
+    } else {
+        builder.push("EXISTS (SELECT 1 FROM unnest(");
+        builder.push(column);
+        builder.push(") entry WHERE entry ILIKE ");
+        builder.push_bind(format!("%{}%", escape_like(&term.value)));
+        builder.push(")");
+    }
+    builder.push(")");
+}
+
+fn push_tsquery(builder: &mut QueryBuilder<'_, Postgres>, term: &QueryTerm) {
+    builder.push(if term.phrase {
+        "phraseto_tsquery('english', "
+    } else {
+        "plainto_tsquery('english', "
+    });
+    builder.push_bind(term.value.clone());
+    builder.push(")");
+}
+
+fn push_author_match(builder: &mut QueryBuilder<'_, Postgres>, pattern: String) {
+    builder.push("(a.email ILIKE ");
+    builder.push_bind(pattern.clone());
+    builder.push(" OR a.canonical_name ILIKE ");
+    builder.push_bind(pattern);
+    builder.push(")");
+}
+
+fn push_author_filters<'a>(
+    builder: &mut QueryBuilder<'a, Postgres>,
+    query: Option<&'a str>,
+    mailing_lists: &[String],
+) {
+    // Same population as the author index: authors with list activity
+    builder.push(
+        " WHERE EXISTS (SELECT 1 FROM author_mailing_list_activity act \
+         JOIN mailing_lists ml ON ml.id = act.mailing_list_id \
+         WHERE act.author_id = a.id",
+    );
+    if !mailing_lists.is_empty() {
+        builder.push(" AND ml.slug = ANY(");
+        builder.push_bind(mailing_lists.to_vec());
+        builder.push(")");
+    }
+    builder.push(")");
+
+    if let Some(query) = query {
+        let pattern = format!("%{}%", escape_like(query));
+        builder.push(" AND (a.email ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(" OR a.canonical_name ILIKE ");
+        builder.push_bind(pattern.clone());
+        builder.push(
+            " OR EXISTS (SELECT 1 FROM author_name_aliases alias \
+             WHERE alias.author_id = a.id AND alias.name ILIKE ",
+        );
+        builder.push_bind(pattern);
+        builder.push("))");
+    }
+}
+
+fn push_clause(builder: &mut QueryBuilder<'_, Postgres>, has_where: &mut bool) {
+    builder.push(if *has_where { " AND " } else { " WHERE " });
+    *has_where = true;
+}
+
+/// Translate Meilisearch sort expressions (`last_ts:desc`) to SQL.
+fn thread_order_clauses(sort_expressions: &[String]) -> Vec<String> {
+    sort_expressions
+        .iter()
+        .filter_map(|expression| {
+            let (field, direction) = split_sort_expression(expression)?;
+            let column = match field {
+                "last_ts" => "t.last_date",
+                "start_ts" => "t.start_date",
+                "message_count" => "t.message_count",
+                _ => return None,
+            };
+            Some(format!("{column} {direction}"))
+        })
+        .collect()
+}
+
+fn author_order_clause(expression: &str) -> Option<String> {
+    let (field, direction) = split_sort_expression(expression)?;
+    let column = match field {
+        "email_count" => "stats.email_count",
+        "thread_count" => "stats.thread_count",
+        "first_email_ts" => "stats.first_email_date",
+        "last_email_ts" => "stats.last_email_date",
+        _ => return None,
+    };
+    Some(format!("{column} {direction} NULLS LAST"))
+}
+
+fn split_sort_expression(expression: &str) -> Option<(&str, &'static str)> {
+    let (field, direction) = expression.split_once(':')?;
+    let direction = match direction.trim() {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        _ => return None,
+    };
+    Some((field.trim(), direction))
+}
+
+/// Escape `%`, `_` and `\\` so `input` matches literally in a `LIKE` pattern.
+pub fn escape_like(input: &str) -> String {
+    input
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translates_sort_expressions() {
+        let clauses = thread_order_clauses(&[
+            "last_ts:desc".to_string(),
+            "message_count:asc".to_string(),
+            "semantic:desc".to_string(),
+            "start_ts".to_string(),
+        ]);
+        assert_eq!(clauses, vec!["t.last_date DESC", "t.message_count ASC"]);
+
+        assert_eq!(
+            author_order_clause("thread_count:asc").as_deref(),
+            Some("stats.thread_count ASC NULLS LAST")
+        );
+        assert_eq!(author_order_clause("canonical_name:asc"), None);
+        assert_eq!(escape_like("50%_a\\b"), "50\\%\\_a\\\\b");
+    }
+}
@@ -0,0 +1,980 @@
+//! Lore-style search query language.
+//!
+//! Accepts the public-inbox syntax kernel developers know from lore:
+//! prefixed terms (`s:`, `f:`, `t:`, `c:`, `a:`, `b:`, `bs:`, `m:`, `dfn:`,
+//! `dfhh:`, `dfa:`, `dfb:`, `dfs:`), date ranges (`d:20240101..20240301`), quoted phrases, grouping
+//! parentheses and the `AND`, `OR`, `NOT` (or leading `-`) operators.
+//!
+//! [`parse_search_query`] lowers a query into a [`SearchQuery`]: bare words at
+//! the top level become the backend's free-text query, top-level `d:` ranges
+//! become the payload date filters, and everything else is kept as structured
+//! [`QueryNode`] filters that each backend translates (or rejects with
+//! [`unsupported_terms_error`]).
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::fmt;
+
+/// Field a prefixed term is matched against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryField {
+    /// Bare words: subject and authored body.
+    Text,
+    /// `s:` subject.
+    Subject,
+    /// `f:` sender name or address.
+    From,
+    /// `t:` To recipients.
+    To,
+    /// `c:` Cc recipients.
+    Cc,
+    /// `a:` sender or any recipient.
+    Address,
+    /// `b:` message body.
+    Body,
+    /// `bs:` subject or body.
+    SubjectBody,
+    /// `m:` Message-ID.
+    MessageId,
+    /// `dfn:` file name touched by a diff.
+    DiffFileName,
+    /// `dfhh:` diff hunk header (function context).
+    DiffHunkHeader,
+    /// `dfa:` identifier or text in added diff lines.
+    DiffAdded,
+    /// `dfb:` identifier or text in removed diff lines.
+    DiffRemoved,
+    /// `dfs:` symbol added, removed or named in a hunk header.
+    DiffSymbol,
+}
+
+impl QueryField {
+    fn from_prefix(prefix: &str) -> Option<Self> {
+        match prefix {
+            "s" => Some(QueryField::Subject),
+            "f" => Some(QueryField::From),
+            "t" => Some(QueryField::To),
+            "c" => Some(QueryField::Cc),
+            "a" => Some(QueryField::Address),
+            "b" => Some(QueryField::Body),
+            "bs" => Some(QueryField::SubjectBody),
+            "m" => Some(QueryField::MessageId),
+            "dfn" => Some(QueryField::DiffFileName),
+            "dfhh" => Some(QueryField::DiffHunkHeader),
+            "dfa" => Some(QueryField::DiffAdded),
+            "dfb" => Some(QueryField::DiffRemoved),
+            "dfs" => Some(QueryField::DiffSymbol),
+            _ => None,
+        }
+    }
+
+    /// Query prefix including the colon; empty for bare words.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            QueryField::Text => "",
+            QueryField::Subject => "s:",
+            QueryField::From => "f:",
+            QueryField::To => "t:",
+            QueryField::Cc => "c:",
+            QueryField::Address => "a:",
+            QueryField::Body => "b:",
+            QueryField::SubjectBody => "bs:",
+            QueryField::MessageId => "m:",
+            QueryField::DiffFileName => "dfn:",
+            QueryField::DiffHunkHeader => "dfhh:",
+            QueryField::DiffAdded => "dfa:",
+            QueryField::DiffRemoved => "dfb:",
+            QueryField::DiffSymbol => "dfs:",
+        }
+    }
+}
+
+/// public-inbox prefixes that have no equivalent in our data.
+const UNSUPPORTED_PREFIXES: &[&str] = &[
+    "q", "nq", "tc", "l", "rt", "dt", "z", "dfctx", "dfpre", "dfpost", "dfblob", "patchid",
+];
+
+/// A word or phrase matched against one field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryTerm {
+    pub field: QueryField,
+    pub value: String,
+    /// Whether the value was quoted and must match as a phrase.
+    pub phrase: bool,
+    /// Character offset of the term in the query.
+    pub position: usize,
+}
+
+impl QueryTerm {
+    /// The term as written in the query, for error messages.
+    pub fn token(&self) -> String {
+        if self.phrase {
+            format!("{}\"{}\"", self.field.prefix(), self.value)
+        } else {
+            format!("{}{}", self.field.prefix(), self.value)
+        }
+    }
+}
+
+/// A `d:` range; either bound may be open. Date-only upper bounds cover the
+/// whole day.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DateRange {
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    /// Character offset of the term in the query.
+    pub position: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryNode {
+    Term(QueryTerm),
+    Date(DateRange),
+    And(Vec<QueryNode>),
+    Or(Vec<QueryNode>),
+    Not(Box<QueryNode>),
+}
+
+impl QueryNode {
+    /// Visit every term (not date ranges) in the expression.
+    pub fn terms(&self) -> Vec<&QueryTerm> {
+        let mut terms = Vec::new();
+        self.collect_terms(&mut terms);
+        terms
+    }
+
+    fn collect_terms<'a>(&'a self, terms: &mut Vec<&'a QueryTerm>) {
+        match self {
+            QueryNode::Term(term) => terms.push(term),
+            QueryNode::Date(_) => {}
+            QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+                for node in nodes {
+                    node.collect_terms(terms);
+                }
+            }
+            QueryNode::Not(node) => node.collect_terms(terms),
+        }
+    }
+}
+
+/// A parsed query, split into what the payload can carry directly and the
+/// structured filters backends translate themselves.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchQuery {
+    /// Free-text query (quoted phrases, `-` exclusions) for the backend.
+    pub text: String,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    /// Remaining conditions, all of which must hold.
+    pub filters: Vec<QueryNode>,
+    /// The bare words and phrases (or their negations) `text` was rendered
+    /// from, for searches that match them against a field of their own.
+    pub text_terms: Vec<QueryNode>,
+}
+
+impl SearchQuery {
+    /// Move filters made only of terms `searchable` accepts into the free
+    /// text, for backends that search those fields as text instead of
+    /// filtering on them. `OR` groups become their words, which keyword
+    /// search ranks by how many of them match.
+    pub fn fold_text_filters(&mut self, searchable: impl Fn(&QueryTerm) -> bool) {
+        let (text, filters): (Vec<QueryNode>, Vec<QueryNode>) = std::mem::take(&mut self.filters)
+            .into_iter()
+            .partition(|node| is_text_node(node, false, &searchable));
+        if text.is_empty() {
+            self.filters = filters;
+            return;
+        }
+        self.filters = filters;
+        self.text_terms.extend(text);
+        self.text = render_text_nodes(&self.text_terms);
+    }
+
+    /// Fields of the terms `text` was rendered from, in order of appearance.
+    pub fn text_fields(&self) -> Vec<QueryField> {
+        let mut fields = Vec::new();
+        for term in self.text_terms.iter().flat_map(QueryNode::terms) {
+            if !fields.contains(&term.field) {
+                fields.push(term.field);
+            }
+        }
+        fields
+    }
+}
+
+/// Syntax error, pointing at the offending token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryError {
+    pub message: String,
+    /// Character offset of the token in the query.
+    pub position: usize,
+    pub token: String,
+}
+
+impl QueryError {
+    fn new(message: impl Into<String>, position: usize, token: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            position,
+            token: token.into(),
+        }
+    }
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at position {} (`{}`)",
+            self.message, self.position, self.token
+        )
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+/// Parse a lore-style query.
+///
+/// # Returns
+/// The lowered query, or the first syntax error
+pub fn parse_search_query(input: &str) -> Result<SearchQuery, QueryError> {
+    let tokens = tokenize(input, false)?;
+    let boolean = uses_boolean_operators(&tokens);
+    let root = match parse_tokens(tokens, input) {
+        Ok(root) => root,
+        // Stray parentheses in a plain query are part of what is searched for
+        Err(err) if !boolean => parse_tokens(tokenize(input, true)?, input).map_err(|_| err)?,
+        Err(err) => return Err(err),
+    };
+    Ok(root.map(lower).unwrap_or_default())
+}
+
+fn parse_tokens(tokens: Vec<Token>, input: &str) -> Result<Option<QueryNode>, QueryError> {
+    let mut parser = Parser {
+        tokens,
+        index: 0,
+        end: input.chars().count(),
+    };
+    parser.parse_query()
+}
+
+fn uses_boolean_operators(tokens: &[Token]) -> bool {
+    tokens.iter().any(|token| match token.kind {
+        TokenKind::And | TokenKind::Or => true,
+        TokenKind::Not => token.text == "NOT",
+        _ => false,
+    })
+}
+
+/// Error naming every term in `filters` that `supported` rejects.
+///
+/// # Returns
+/// `None` if every term is supported
+pub fn unsupported_terms_error(
+    filters: &[QueryNode],
+    backend: &str,
+    supported: impl Fn(&QueryTerm) -> bool,
+) -> Option<QueryError> {
+    let unsupported: Vec<&QueryTerm> = filters
+        .iter()
+        .flat_map(QueryNode::terms)
+        .filter(|term| !supported(term))
+        .collect();
+    let first = unsupported.first()?;
+
+    let listed: Vec<String> = unsupported
+        .iter()
+        .map(|term| format!("`{}` (position {})", term.token(), term.position))
+        .collect();
+    Some(QueryError::new(
+        format!(
+            "the {backend} search backend cannot evaluate {}",
+            listed.join(", ")
+        ),
+        first.position,
+        first.token(),
+    ))
+}
+
+fn lower(root: QueryNode) -> SearchQuery {
+    let items = match root {
+        QueryNode::And(items) => items,
+        other => vec![other],
+    };
+
+    let mut query = SearchQuery::default();
+
+    for item in items {
+        match item {
+            QueryNode::Term(term) if term.field == QueryField::Text => {
+                query.text_terms.push(QueryNode::Term(term));
+            }
+            QueryNode::Not(inner) => match *inner {
+                QueryNode::Term(term) if term.field == QueryField::Text => {
+                    query
+                        .text_terms
+                        .push(QueryNode::Not(Box::new(QueryNode::Term(term))));
+                }
+                inner => query.filters.push(QueryNode::Not(Box::new(inner))),
+            },
+            QueryNode::Date(range) => {
+                if let Some(start) = range.start {
+                    query.start_date = Some(query.start_date.map_or(start, |s| s.max(start)));
+                }
+                if let Some(end) = range.end {
+                    query.end_date = Some(query.end_date.map_or(end, |e| e.min(end)));
+                }
+            }
+            other => query.filters.push(other),
+        }
+    }
+
+    query.text = render_text_nodes(&query.text_terms);
+    query
+}
+
+/// Free text for `nodes`: words and phrases, `-` before negated ones. Boolean
+/// groups are flattened into their words.
+fn render_text_nodes(nodes: &[QueryNode]) -> String {
+    let mut parts = Vec::new();
+    for node in nodes {
+        push_text_parts(node, &mut parts);
+    }
+    parts.join(" ")
+}
+
+fn push_text_parts(node: &QueryNode, parts: &mut Vec<String>) {
+    match node {
+        QueryNode::Term(term) => parts.push(render_text(term, false)),
+        QueryNode::Not(inner) => {
+            if let QueryNode::Term(term) = inner.as_ref() {
+                parts.push(render_text(term, true));
+            }
+        }
+        QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+            for node in nodes {
+                push_text_parts(node, parts);
+            }
+        }
+        QueryNode::Date(_) => {}
+    }
+}
+
+fn render_text(term: &QueryTerm, negated: bool) -> String {
+    let sign = if negated { "-" } else { "" };
+    if term.phrase {
+        format!("{sign}\"{}\"", term.value)
+    } else {
+        format!("{sign}{}", term.value)
+    }
+}
+
+/// Whether `node` keeps its meaning as free text: only `searchable` terms,
+/// negated on their own and outside of `OR` groups.
+fn is_text_node(node: &QueryNode, in_or: bool, searchable: &impl Fn(&QueryTerm) -> bool) -> bool {
+    match node {
+        QueryNode::Term(term) => searchable(term),
+        QueryNode::Not(inner) => {
+            !in_or && matches!(inner.as_ref(), QueryNode::Term(term) if searchable(term))
+        }
+        QueryNode::And(nodes) => nodes
+            .iter()
+            .all(|node| is_text_node(node, in_or, searchable)),
+        QueryNode::Or(nodes) => nodes
+            .iter()
+            .all(|node| is_text_node(node, true, searchable)),
+        QueryNode::Date(_) => false,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+    Word {
+        prefix: Option<String>,
+        value: String,
+        phrase: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    position: usize,
+    text: String,
+}
+
+/// Split `input` into tokens. Parentheses inside a word (`kfree()`,
+/// `kmalloc(`) belong to it; with `literal_parens` every parenthesis does.
+fn tokenize(input: &str, literal_parens: bool) -> Result<Vec<Token>, QueryError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        match c {
+            '(' | ')' if !literal_parens => {
+                tokens.push(Token {
+                    kind: if c == '(' {
+                        TokenKind::Open
+                    } else {
+                        TokenKind::Close
+                    },
+                    position: start,
+                    text: c.to_string(),
+                });
+                i += 1;
+                continue;
+            }
+            '-' if chars
+                .get(i + 1)
+                .is_some_and(|next| !next.is_whitespace() && *next != '-') =>
+            {
+                tokens.push(Token {
+                    kind: TokenKind::Not,
+                    position: start,
+                    text: "-".to_string(),
+                });
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+
+        // Bare run up to whitespace, an opening quote or a parenthesis closing
+        // a group
+        let mut open_parens = 0;
+        while i < chars.len() && !chars[i].is_whitespace() && chars[i] != '"' {
+            match chars[i] {
+                '(' => open_parens += 1,
+                ')' if open_parens > 0 => open_parens -= 1,
+                ')' if !literal_parens => break,
+                _ => {}
+            }
+            i += 1;
+        }
+        let bare: String = chars[start..i].iter().collect();
+
+        let (prefix, mut value) = split_prefix(&bare);
+        let mut phrase = false;
+        if i < chars.len() && chars[i] == '"' && (bare.is_empty() || prefix.is_some()) {
+            let quote = i;
+            i += 1;
+            let content_start = i;
+            while i < chars.len() && chars[i] != '"' {
+                i += 1;
+            }
+            if i == chars.len() {
+                let text: String = chars[start..].iter().collect();
+                return Err(QueryError::new("unterminated quote", quote, text));
+            }
+            value = chars[content_start..i].iter().collect();
+            phrase = true;
+            i += 1;
+        }
+
+        let text: String = chars[start..i].iter().collect();
+        let kind = match (prefix.as_deref(), bare.as_str(), phrase) {
+            (None, "AND", false) => TokenKind::And,
+            (None, "OR", false) => TokenKind::Or,
+            (None, "NOT", false) => TokenKind::Not,
+            _ => TokenKind::Word {
+                prefix,
+                value,
+                phrase,
+            },
+        };
+        tokens.push(Token {
+            kind,
+            position: start,
+            text,
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// Split `s:value` into prefix and value when the prefix is a lore prefix;
+/// other colons (`drm/i915:`) are part of the word.
+fn split_prefix(word: &str) -> (Option<String>, String) {
+    if let Some((prefix, value)) = word.split_once(':') {
+        let known = prefix == "d"
+            || QueryField::from_prefix(prefix).is_some()
+            || UNSUPPORTED_PREFIXES.contains(&prefix);
+        if known {
+            return (Some(prefix.to_string()), value.to_string());
+        }
+    }
+    (None, word.to_string())
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    index: usize,
+    /// Query length, reported for errors at the end of input.
+    end: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.index)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.index).cloned();
+        self.index += 1;
+        token
+    }
+
+    fn parse_query(&mut self) -> Result<Option<QueryNode>, QueryError> {
+        if self.tokens.is_empty() {
+            return Ok(None);
+        }
+        let node = self.parse_or()?;
+        if let Some(token) = self.peek() {
+            let message = if token.kind == TokenKind::Close {
+                "unmatched closing parenthesis"
+            } else {
+                "unexpected token"
+            };
+            return Err(QueryError::new(message, token.position, token.text.clone()));
+        }
+        Ok(Some(node))
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode, QueryError> {
+        let mut nodes = vec![self.parse_and()?];
+        while self.peek().is_some_and(|token| token.kind == TokenKind::Or) {
+            let operator = self.next().expect("peeked");
+            self.expect_operand(&operator)?;
+            nodes.push(self.parse_and()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().expect("one node")
+        } else {
+            QueryNode::Or(nodes)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<QueryNode, QueryError> {
+        let mut nodes = vec![self.parse_unary()?];
+        loop {
+            match self.peek().map(|token| &token.kind) {
+                Some(TokenKind::And) => {
+                    let operator = self.next().expect("peeked");
+                    self.expect_operand(&operator)?;
+                }
+                Some(TokenKind::Open | TokenKind::Not | TokenKind::Word { .. }) => {}
+                _ => break,
+            }
+            nodes.push(self.parse_unary()?);
+        }
+        Ok(if nodes.len() == 1 {
+            nodes.pop().expect("one node")
+        } else {
+            QueryNode::And(nodes)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode, QueryError> {
+        if self
+            .peek()
+            .is_some_and(|token| token.kind == TokenKind::Not)
+        {
+            let operator = self.next().expect("peeked");
+            self.expect_operand(&operator)?;
+            let inner = self.parse_unary()?;
+            return Ok(QueryNode::Not(Box::new(inner)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<QueryNode, QueryError> {
+        let Some(token) = self.next() else {
+            return Err(QueryError::new("expected a search term", self.end, ""));
+        };
+
+        match token.kind {
+            TokenKind::Open => {
+                if self
+                    .peek()
+                    .is_some_and(|next| next.kind == TokenKind::Close)
+                {
+                    return Err(QueryError::new("empty group", token.position, "()"));
+                }
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(close) if close.kind == TokenKind::Close => Ok(node),
+                    _ => Err(QueryError::new(
+                        "unclosed parenthesis",
+                        token.position,
+                        token.text,
+                    )),
+                }
+            }
+            TokenKind::Close => Err(QueryError::new(
+                "unmatched closing parenthesis",
+                token.position,
+                token.text,
+            )),
+            TokenKind::And | TokenKind::Or => Err(QueryError::new(
+                "operator without a left-hand term",
+                token.position,
+                token.text,
+            )),
+            TokenKind::Not => unreachable!("handled by parse_unary"),
+            TokenKind::Word {
+                prefix,
+                value,
+                phrase,
+            } => word_node(prefix, value, phrase, token.position, &token.text),
+        }
+    }
+
+    /// Operators must be followed by a term, not the end of the query or
+    /// another binary operator.
+    fn expect_operand(&self, operator: &Token) -> Result<(), QueryError> {
+        match self.peek().map(|token| &token.kind) {
+            None | Some(TokenKind::Close | TokenKind::And | TokenKind::Or) => Err(QueryError::new(
+                format!("expected a term after `{}`", operator.text),
+                operator.position,
+                operator.text.clone(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn word_node(
+    prefix: Option<String>,
+    value: String,
+    phrase: bool,
+    position: usize,
+    text: &str,
+) -> Result<QueryNode, QueryError> {
+    let Some(prefix) = prefix else {
+        return Ok(QueryNode::Term(QueryTerm {
+            field: QueryField::Text,
+            value,
+            phrase,
+            position,
+        }));
+    };
+
+    if value.is_empty() {
+        return Err(QueryError::new(
+            format!("missing value for `{prefix}:`"),
+            position,
+            text,
+        ));
+    }
+
+    if prefix == "d" {
+        if phrase {
+            return Err(QueryError::new(
+                "date ranges cannot be quoted",
+                position,
+                text,
+            ));
+        }
+        return parse_date_range(&value, position, text).map(QueryNode::Date);
+    }
+
+    let Some(field) = QueryField::from_prefix(&prefix) else {
+        return Err(QueryError::new(
+            format!("the `{prefix}:` prefix is not supported"),
+            position,
+            text,
+        ));
+    };
+
+    Ok(QueryNode::Term(QueryTerm {
+        field,
+        value,
+        phrase,
+        position,
+    }))
+}
+
+/// Parse `start..end`, `start..`, `..end` or a single day.
+fn parse_date_range(value: &str, position: usize, text: &str) -> Result<DateRange, QueryError> {
+    let invalid = |bound: &str| {
+        QueryError::new(
+            format!("invalid date `{bound}`, expected YYYYMMDD, YYYY-MM-DD or YYYYMMDDhhmmss"),
+            position,
+            text,
+        )
+    };
+
+    let (start, end) = match value.split_once("..") {
+        Some((start, end)) => {
+            let start = match start {
+                "" => None,
+                bound => Some(parse_date_bound(bound, false).ok_or_else(|| invalid(bound))?),
+            };
+            let end = match end {
+                "" => None,
+                bound => Some(parse_date_bound(bound, true).ok_or_else(|| invalid(bound))?),
+            };
+            (start, end)
+        }
+        None => (
+            Some(parse_date_bound(value, false).ok_or_else(|| invalid(value))?),
+            Some(parse_date_bound(value, true).ok_or_else(|| invalid(value))?),
+        ),
+    };
+
+    if start.is_none() && end.is_none() {
+        return Err(QueryError::new("empty date range", position, text));
+    }
+    if let (Some(start), Some(end)) = (start, end)
+        && end < start
+    {
+        return Err(QueryError::new(
+            "date range ends before it starts",
+            position,
+            text,
+        ));
+    }
+
+    Ok(DateRange {
+        start,
+        end,
+        position,
+    })
+}
+
+fn parse_date_bound(value: &str, end_of_day: bool) -> Option<DateTime<Utc>> {
+    if let Ok(datetime) = NaiveDateTime::parse_from_str(value, "%Y%m%d%H%M%S") {
+        return Some(Utc.from_utc_datetime(&datetime));
+    }
+
+    let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+        .ok()?;
+    let time = if end_of_day {
+        NaiveTime::from_hms_opt(23, 59, 59)?
+    } else {
+        NaiveTime::MIN
+    };
+    Some(Utc.from_utc_datetime(&date.and_time(time)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn term(field: QueryField, value: &str, position: usize) -> QueryNode {
+        QueryNode::Term(QueryTerm {
+            field,
+            value: value.to_string(),
+            phrase: false,
+            position,
+        })
+    }
+
+    #[test]
+    fn lowers_text_dates_and_filters() {
+        let query =
+            parse_search_query("s:mm \"page fault\" -rfc d:20240101..20240301 f:akpm").unwrap();
+
+        assert_eq!(query.text, "\"page fault\" -rfc");
+        assert_eq!(
+            query.text_terms,
+            vec![
+                QueryNode::Term(QueryTerm {
+                    field: QueryField::Text,
+                    value: "page fault".to_string(),
+                    phrase: true,
+                    position: 5,
+                }),
+                QueryNode::Not(Box::new(term(QueryField::Text, "rfc", 19))),
+            ]
+        );
+        assert_eq!(
+            query.start_date,
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            query.end_date,
+            Some(Utc.with_ymd_and_hms(2024, 3, 1, 23, 59, 59).unwrap())
+        );
+        assert_eq!(
+            query.filters,
+            vec![
+                term(QueryField::Subject, "mm", 0),
+                term(QueryField::From, "akpm", 44)
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_boolean_operators_with_precedence() {
+        let query =
+            parse_search_query("dfn:mm/slub.c OR (dfhh:kmalloc AND NOT t:linux-mm)").unwrap();
+
+        assert_eq!(query.text, "");
+        assert_eq!(
+            query.filters,
+            vec![QueryNode::Or(vec![
+                term(QueryField::DiffFileName, "mm/slub.c", 0),
+                QueryNode::And(vec![
+                    term(QueryField::DiffHunkHeader, "kmalloc", 18),
+                    QueryNode::Not(Box::new(term(QueryField::To, "linux-mm", 39))),
+                ]),
+            ])]
+        );
+    }
+
+    #[test]
+    fn parses_diff_content_prefixes() {
+        let query =
+            parse_search_query("dfa:CONFIG_FOO -dfb:kmem_cache_alloc dfs:slab_free").unwrap();
+
+        assert_eq!(
+            query.filters,
+            vec![
+                term(QueryField::DiffAdded, "CONFIG_FOO", 0),
+                QueryNode::Not(Box::new(term(
+                    QueryField::DiffRemoved,
+                    "kmem_cache_alloc",
+                    16
+                ))),
+                term(QueryField::DiffSymbol, "slab_free", 37),
+            ]
+        );
+    }
+
+    #[test]
+    fn keeps_non_prefix_colons_and_quoted_prefixes() {
+        let query = parse_search_query("drm/i915: c:\"Jani Nikula\"").unwrap();
+
+        assert_eq!(query.text, "drm/i915:");
+        assert_eq!(
+            query.filters,
+            vec![QueryNode::Term(QueryTerm {
+                field: QueryField::Cc,
+                value: "Jani Nikula".to_string(),
+                phrase: true,
+                position: 10,
+            })]
+        );
+    }
+
+    #[test]
+    fn open_date_ranges_intersect() {
+        let query = parse_search_query("d:2024-01-01.. d:..20240201 d:20240115..").unwrap();
+
+        assert_eq!(
+            query.start_date,
+            Some(Utc.with_ymd_and_hms(2024, 1, 15, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            query.end_date,
+            Some(Utc.with_ymd_and_hms(2024, 2, 1, 23, 59, 59).unwrap())
+        );
+        assert!(query.filters.is_empty());
+    }
+
+    #[test]
+    fn errors_point_at_offending_token() {
+        let error = parse_search_query("s:fix d:2024xx01").unwrap_err();
+        assert_eq!((error.position, error.token.as_str()), (6, "d:2024xx01"));
+
+        let error = parse_search_query("foo OR").unwrap_err();
+        assert_eq!((error.position, error.token.as_str()), (4, "OR"));
+
+        let error = parse_search_query("(foo OR bar").unwrap_err();
+        assert_eq!(error.message, "unclosed parenthesis");
+        assert_eq!(error.position, 0);
+
+        let error = parse_search_query("foo) OR bar").unwrap_err();
+        assert_eq!(error.position, 3);
+
+        let error = parse_search_query("s:\"unterminated").unwrap_err();
+        assert_eq!(error.message, "unterminated quote");
+
+        let error = parse_search_query("nq:reviewed").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "the `nq:` prefix is not supported at position 0 (`nq:reviewed`)"
+        );
+
+        let error = parse_search_query("b:").unwrap_err();
+        assert_eq!(error.message, "missing value for `b:`");
+    }
+
+    #[test]
+    fn keeps_parentheses_inside_words() {
+        assert_eq!(
+            parse_search_query("kfree() leak").unwrap().text,
+            "kfree() leak"
+        );
+        assert_eq!(parse_search_query("kmalloc(").unwrap().text, "kmalloc(");
+        assert_eq!(parse_search_query("() foo)").unwrap().text, "() foo)");
+
+        let query = parse_search_query("(kfree() OR kmalloc() b:leak)").unwrap();
+        assert_eq!(
+            query.filters,
+            vec![QueryNode::Or(vec![
+                term(QueryField::Text, "kfree()", 1),
+                QueryNode::And(vec![
+                    term(QueryField::Text, "kmalloc()", 12),
+                    term(QueryField::Body, "leak", 22),
+                ]),
+            ])]
+        );
+
+        let error = parse_search_query("kfree() OR (leak").unwrap_err();
+        assert_eq!(error.message, "unclosed parenthesis");
+    }
+
+    #[test]
+    fn folds_text_like_filters_into_text() {
+        let mut query = parse_search_query(
+            "(s:oops OR b:panic) -s:rfc (foo OR -bar) f:akpm@linux-foundation.org",
+        )
+        .unwrap();
+        query.fold_text_filters(|term| {
+            matches!(
+                term.field,
+                QueryField::Text | QueryField::Subject | QueryField::Body
+            )
+        });
+
+        assert_eq!(query.text, "oops panic -rfc");
+        assert_eq!(
+            query.text_fields(),
+            vec![QueryField::Subject, QueryField::Body]
+        );
+        assert_eq!(query.filters.len(), 2);
+        assert!(matches!(query.filters[0], QueryNode::Or(_)));
+        assert!(
+            matches!(&query.filters[1], QueryNode::Term(term) if term.field == QueryField::From)
+        );
+    }
+
+    #[test]
+    fn reports_unsupported_terms() {
+        let query = parse_search_query("s:fix OR dfn:mm/slub.c").unwrap();
+        let error = unsupported_terms_error(&query.filters, "meilisearch", |term| {
+            term.field == QueryField::Subject
+        })
+        .unwrap();
+
+        assert_eq!(error.position, 9);
+        assert_eq!(error.token, "dfn:mm/slub.c");
+        assert!(error.message.contains("`dfn:mm/slub.c` (position 9)"));
+        assert!(unsupported_terms_error(&query.filters, "postgres", |_| true).is_none());
+    }
+}
@@ -1,15 +1,25 @@
+use crate::models::{PatchState, ThreadSearchFacet};
+use crate::search::backend::{SearchBackend, SearchBackendKind, SearchFuture};
+use crate::search::code::is_identifier;
 use crate::search::embeddings::EmbeddingsClient;
 use crate::search::error::SearchError;
-use crate::search::models::{AuthorDocument, ThreadDocument};
+use crate::search::models::{AuthorDocument, EmailDocument, PatchDocument, ThreadDocument};
+use crate::search::query::{QueryField, QueryNode, QueryTerm};
 use chrono::{DateTime, Utc};
 use log::{debug, warn};
 use reqwest::{Client, Method, RequestBuilder, StatusCode};
+use rocket_db_pools::sqlx;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use tokio::time::{Duration, sleep};
 
 const TASK_POLL_INTERVAL_MS: u64 = 200;
 const TASK_TIMEOUT_MS: u64 = 60_000;
 const UPSERT_BATCH_SIZE: usize = 400;
+/// Distinct values Meilisearch counts per facet (`maxValuesPerFacet`).
+const MAX_FACET_VALUES: usize = 1_000;
+/// Values returned for facets ordered by count.
+const FACET_VALUE_LIMIT: usize = 25;
 
 #[derive(Clone)]
 pub struct SearchService {
@@ -19,10 +29,12 @@ pub struct SearchService {
     embeddings: EmbeddingsClient,
     threads_index_uid: String,
     authors_index_uid: String,
+    emails_index_uid: String,
+    patches_index_uid: String,
     thread_embedder: String,
     default_semantic_ratio: f32,
-    thread_embedding_dimensions: usize,
     allow_global_thread_search: bool,
+    backend: SearchBackendKind,
 }
 
 #[derive(Debug, Clone)]
@@ -34,6 +46,9 @@ pub struct ThreadMailingListFilter {
 #[derive(Debug, Clone)]
 pub struct ThreadSearchPayload {
     pub query: String,
+    /// Fields `query` was written for (`s:`, `b:`, ...); searches every
+    /// attribute when empty or containing [`QueryField::Text`].
+    pub query_fields: Vec<QueryField>,
     pub page: i64,
     pub size: i64,
     pub semantic_ratio: f32,
@@ -43,8 +58,83 @@ pub struct ThreadSearchPayload {
     pub starter_id: Option<i32>,
     pub participant_ids: Vec<i32>,
     pub series_id: Option<String>,
+    pub subsystems: Vec<String>,
+    pub patch_states: Vec<PatchState>,
+    pub incomplete_series: Option<bool>,
     pub mailing_lists: Vec<ThreadMailingListFilter>,
     pub sort_expressions: Vec<String>,
+    /// Lore-style query conditions beyond free text and dates, all of which
+    /// must hold.
+    pub query_filters: Vec<QueryNode>,
+    /// Facets to count over all matching threads.
+    pub facets: Vec<ThreadSearchFacet>,
+}
+
+/// Nearest neighbours of one thread's embedding.
+#[derive(Debug, Clone)]
+pub struct SimilarThreadsPayload {
+    pub thread_id: i32,
+    pub mailing_list_id: i32,
+    /// Lists to search; empty searches every list.
+    pub mailing_lists: Vec<ThreadMailingListFilter>,
+    pub limit: i64,
+    /// Leave out other versions of this series.
+    pub exclude_series: Option<SeriesKey>,
+    pub has_patches: Option<bool>,
+}
+
+/// Identity of a patch series across versions: the same starter posting
+/// under the same normalized subject, as used to supersede older versions.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesKey {
+    pub starter_id: i32,
+    pub normalized_subject: String,
+}
+
+/// Partially typed text to complete while the user types.
+#[derive(Debug, Clone)]
+pub struct SuggestPayload {
+    pub query: String,
+    /// Suggestions returned per kind.
+    pub limit: i64,
+    pub mailing_lists: Vec<ThreadMailingListFilter>,
+    /// Whether to suggest thread subjects; routes turn this off for
+    /// cross-list suggestions when global search is disabled.
+    pub include_threads: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct EmailSearchPayload {
+    pub query: String,
+    /// Fields `query` was written for, as in [`ThreadSearchPayload`].
+    pub query_fields: Vec<QueryField>,
+    pub page: i64,
+    pub size: i64,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub is_patch: Option<bool>,
+    pub author_id: Option<i32>,
+    pub thread_id: Option<i32>,
+    pub mailing_lists: Vec<ThreadMailingListFilter>,
+    /// Lore-style query conditions, evaluated per message.
+    pub query_filters: Vec<QueryNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PatchSearchPayload {
+    /// Free text, used for ranking and highlighting only; routes turn bare
+    /// words into [`QueryField::Text`] terms of `query_filters`, which match
+    /// symbols touched by the diff.
+    pub query: String,
+    pub page: i64,
+    pub size: i64,
+    pub start_date: Option<DateTime<Utc>>,
+    pub end_date: Option<DateTime<Utc>>,
+    pub author_id: Option<i32>,
+    pub thread_id: Option<i32>,
+    pub mailing_lists: Vec<ThreadMailingListFilter>,
+    /// Lore-style query conditions, evaluated against the diff content.
+    pub query_filters: Vec<QueryNode>,
 }
 
 #[derive(Debug, Clone)]
@@ -60,9 +150,8 @@ impl SearchService {
     pub fn new(
         base_url: impl Into<String>,
         api_key: Option<String>,
-        embeddings_url: impl Into<String>,
+        embeddings: EmbeddingsClient,
         default_semantic_ratio: f32,
-        thread_embedding_dimensions: usize,
         allow_global_thread_search: bool,
     ) -> Self {
         let http = Client::builder()
@@ -70,7 +159,6 @@ impl SearchService {
             .expect("failed to construct reqwest client");
 
         let base = base_url.into().trim_end_matches('/').to_string();
-        let embeddings = EmbeddingsClient::new(embeddings_url, http.clone());
 
         Self {
             http,
@@ -79,13 +167,26 @@ impl SearchService {
             embeddings,
             threads_index_uid: "threads".to_string(),
             authors_index_uid: "authors".to_string(),
+            emails_index_uid: "emails".to_string(),
+            patches_index_uid: "patches".to_string(),
             thread_embedder: "threads-qwen3".to_string(),
             default_semantic_ratio: default_semantic_ratio.clamp(0.0, 1.0),
-            thread_embedding_dimensions,
             allow_global_thread_search,
+            backend: SearchBackendKind::default(),
         }
     }
 
+    /// Record which backend serves searches. Document indexing is skipped for
+    /// backends that do not read the Meilisearch indexes.
+    pub fn with_backend(mut self, backend: SearchBackendKind) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    pub fn backend(&self) -> SearchBackendKind {
+        self.backend
+    }
+
     pub fn default_semantic_ratio(&self) -> f32 {
         self.default_semantic_ratio
     }
@@ -102,6 +203,14 @@ impl SearchService {
         &self.authors_index_uid
     }
 
+    pub fn emails_index_uid(&self) -> &str {
+        &self.emails_index_uid
+    }
+
+    pub fn patches_index_uid(&self) -> &str {
+        &self.patches_index_uid
+    }
+
     pub fn thread_embedder(&self) -> &str {
         &self.thread_embedder
     }
@@ -111,41 +220,28 @@ impl SearchService {
     }
 
     pub fn thread_embedding_dimensions(&self) -> usize {
-        self.thread_embedding_dimensions
+        self.embeddings.dimensions()
     }
 
-    pub async fn embed_with_fallback(
-        &self,
-        text: &str,
-        expected_dimension: usize,
-        context: &str,
-    ) -> Result<Vec<f32>, SearchError> {
+    pub fn embedding_model(&self) -> &str {
+        self.embeddings.model()
+    }
+
+    /// Embed a semantic query, or `None` when the embeddings provider fails so
+    /// that the search runs on keywords alone.
+    pub async fn embed_query(&self, text: &str) -> Option<Vec<f32>> {
         if text.trim().is_empty() {
-            return Ok(vec![0.0; expected_dimension]);
+            return None;
         }
 
         match self.embeddings.embed(text).await {
-            Ok(mut vector) => {
-                let original_len = vector.len();
-                if original_len != expected_dimension {
-                    warn!(
-                        "embedding dimension mismatch for {}: expected {}, got {}. Adjusting output.",
-                        context, expected_dimension, original_len
-                    );
-                    if original_len > expected_dimension {
-                        vector.truncate(expected_dimension);
-                    } else {
-                        vector.resize(expected_dimension, 0.0);
-                    }
-                }
-                Ok(vector)
-            }
+            Ok(vector) => Some(vector),
             Err(err) => {
                 warn!(
-                    "embedding request failed for {}: {}. Falling back to zero vector.",
-                    context, err
+                    "embedding request failed for search query: {}. Falling back to keyword search.",
+                    err
                 );
-                Ok(vec![0.0; expected_dimension])
+                None
             }
         }
     }
@@ -471,14 +567,7 @@ impl SearchService {
         let semantic_ratio = options.semantic_ratio.clamp(0.0, 1.0);
 
         let vector = if semantic_ratio > 0.0 {
-            Some(
-                self.embed_with_fallback(
-                    &options.query,
-                    self.thread_embedding_dimensions(),
-                    "thread search query",
-                )
-                .await?,
-            )
+            self.embed_query(&options.query).await
         } else {
             None
         };
@@ -494,6 +583,10 @@ impl SearchService {
             } else {
                 Some(filters)
             },
+            attributes_to_search_on: search_attributes(
+                &options.query_fields,
+                thread_text_attributes,
+            ),
             attributes_to_highlight: Some(vec!["subject", "discussion_text"]),
             attributes_to_crop: Some(vec!["discussion_text"]),
             crop_length: Some(160),
@@ -507,6 +600,13 @@ impl SearchService {
             } else {
                 Some(options.sort_expressions.clone())
             },
+            facets: (!options.facets.is_empty()).then(|| {
+                options
+                    .facets
+                    .iter()
+                    .map(|facet| facet_attribute(*facet))
+                    .collect()
+            }),
         };
 
         let response = self
@@ -517,7 +617,7 @@ impl SearchService {
             )
             .await?;
 
-        parse_search_response(response).await
+        parse_search_response(response, &options.facets).await
     }
 
     pub async fn search_authors(
@@ -557,6 +657,176 @@ impl SearchService {
         parse_author_search_response(response).await
     }
 
+    pub async fn search_emails(
+        &self,
+        options: EmailSearchPayload,
+    ) -> Result<EmailSearchResults, SearchError> {
+        let filters = build_email_filters(&options);
+
+        let payload = KeywordSearchRequest {
+            q: &options.query,
+            limit: options.size as usize,
+            offset: ((options.page - 1) * options.size) as usize,
+            filter: if filters.is_empty() {
+                None
+            } else {
+                Some(filters)
+            },
+            attributes_to_search_on: search_attributes(
+                &options.query_fields,
+                email_text_attributes,
+            ),
+            attributes_to_highlight: Some(vec!["subject", "body"]),
+            attributes_to_crop: Some(vec!["body"]),
+            crop_length: Some(60),
+            sort: options
+                .query
+                .trim()
+                .is_empty()
+                .then(|| vec!["date_ts:desc".to_string()]),
+        };
+
+        let response = self
+            .send_json(
+                Method::POST,
+                &format!("/indexes/{}/search", self.emails_index_uid),
+                &payload,
+            )
+            .await?;
+
+        parse_email_search_response(response).await
+    }
+
+    pub async fn search_patches(
+        &self,
+        options: PatchSearchPayload,
+    ) -> Result<PatchSearchResults, SearchError> {
+        let filters = build_patch_filters(&options);
+
+        let payload = KeywordSearchRequest {
+            q: &options.query,
+            limit: options.size as usize,
+            offset: ((options.page - 1) * options.size) as usize,
+            filter: if filters.is_empty() {
+                None
+            } else {
+                Some(filters)
+            },
+            attributes_to_search_on: None,
+            attributes_to_highlight: Some(vec!["added_lines", "removed_lines"]),
+            attributes_to_crop: Some(vec!["added_lines", "removed_lines"]),
+            crop_length: Some(40),
+            sort: options
+                .query
+                .trim()
+                .is_empty()
+                .then(|| vec!["date_ts:desc".to_string()]),
+        };
+
+        let response = self
+            .send_json(
+                Method::POST,
+                &format!("/indexes/{}/search", self.patches_index_uid),
+                &payload,
+            )
+            .await?;
+
+        parse_patch_search_response(response).await
+    }
+
+    /// Threads closest to `options.thread_id` in the `threads-qwen3`
+    /// embedder. A thread that is not indexed yet has no neighbours.
+    pub async fn similar_threads(
+        &self,
+        options: SimilarThreadsPayload,
+    ) -> Result<Vec<ThreadHit>, SearchError> {
+        let filters = build_similar_thread_filters(&options);
+
+        let payload = SimilarThreadsRequest {
+            id: options.thread_id,
+            embedder: &self.thread_embedder,
+            limit: options.limit as usize,
+            filter: (!filters.is_empty()).then_some(filters),
+            show_ranking_score: true,
+        };
+
+        let response = match self
+            .send_json(
+                Method::POST,
+                &format!("/indexes/{}/similar", self.threads_index_uid),
+                &payload,
+            )
+            .await
+        {
+            Ok(response) => response,
+            Err(SearchError::MeilisearchStatus {
+                status: StatusCode::NOT_FOUND,
+                ..
+            }) => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+
+        Ok(parse_search_response(response, &[]).await?.hits)
+    }
+
+    /// Authors and recent thread subjects matching a typed prefix, fetched
+    /// with one multi-search request and without embeddings or highlighting.
+    pub async fn suggest(&self, options: SuggestPayload) -> Result<SuggestResults, SearchError> {
+        let author_filters: Vec<String> = options
+            .mailing_lists
+            .iter()
+            .map(|ml| format!("mailing_lists = \"{}\"", ml.slug))
+            .collect();
+        let thread_filters: Vec<String> = options
+            .mailing_lists
+            .iter()
+            .map(|ml| format!("mailing_list = \"{}\"", ml.slug))
+            .collect();
+
+        let payload = MultiSearchRequest {
+            queries: vec![
+                MultiSearchQuery {
+                    index_uid: &self.authors_index_uid,
+                    q: &options.query,
+                    limit: options.limit as usize,
+                    filter: (!author_filters.is_empty())
+                        .then(|| vec![join_filter_clauses(author_filters)]),
+                    attributes_to_retrieve: &["author_id", "canonical_name", "email"],
+                    attributes_to_search_on: None,
+                    sort: None,
+                },
+                MultiSearchQuery {
+                    index_uid: &self.threads_index_uid,
+                    q: &options.query,
+                    // Skipped threads still take a slot so the response shape stays fixed
+                    limit: if options.include_threads {
+                        options.limit as usize
+                    } else {
+                        0
+                    },
+                    filter: (!thread_filters.is_empty())
+                        .then(|| vec![join_filter_clauses(thread_filters)]),
+                    attributes_to_retrieve: &["thread_id", "mailing_list", "subject", "last_ts"],
+                    attributes_to_search_on: Some(&["subject"]),
+                    sort: Some(vec!["last_ts:desc".to_string()]),
+                },
+            ],
+        };
+
+        let response: SuggestMultiSearchResponse = self
+            .send_json(Method::POST, "/multi-search", &payload)
+            .await?
+            .json()
+            .await
+            .map_err(SearchError::MeilisearchHttp)?;
+        let (authors, threads) = response.results;
+
+        Ok(SuggestResults {
+            authors: authors.hits.into_iter().map(|hit| hit.document).collect(),
+            threads: threads.hits.into_iter().map(|hit| hit.document).collect(),
+        })
+    }
+
     pub async fn ensure_thread_index(&self) -> Result<(), SearchError> {
         self.ensure_vector_features().await?;
         self.create_index_if_missing(&self.threads_index_uid, "thread_id")
@@ -585,10 +855,21 @@ impl SearchService {
                     "mailing_list",
                     "mailing_list_id",
                     "participant_ids",
+                    "participant_emails",
+                    "to_emails",
+                    "cc_emails",
+                    "files",
+                    "functions",
                     "starter_id",
                     "has_patches",
                     "series_id",
+                    "normalized_subject",
+                    "subsystems",
+                    "patch_states",
+                    "incomplete_series",
                     "start_ts",
+                    "start_year",
+                    "start_month",
                     "last_ts",
                     "message_count",
                 ],
@@ -596,6 +877,23 @@ impl SearchService {
             .await?;
         self.wait_for_task(filterable_task).await?;
 
+        // Dates are listed chronologically; everything else by count
+        let faceting_task = self
+            .submit_task(
+                Method::PATCH,
+                &format!("/indexes/{}/settings/faceting", self.threads_index_uid),
+                &serde_json::json!({
+                    "maxValuesPerFacet": MAX_FACET_VALUES,
+                    "sortFacetValuesBy": {
+                        "*": "count",
+                        "start_year": "alpha",
+                        "start_month": "alpha",
+                    },
+                }),
+            )
+            .await?;
+        self.wait_for_task(faceting_task).await?;
+
         let sortable_task = self
             .submit_task(
                 Method::PUT,
@@ -623,7 +921,7 @@ impl SearchService {
         let embedder_payload = EmbeddersPayload {
             threads: EmbedderSpec {
                 source: "userProvided".to_string(),
-                dimensions: self.thread_embedding_dimensions,
+                dimensions: self.thread_embedding_dimensions(),
             },
         };
         let embedder_task = self
@@ -687,6 +985,124 @@ impl SearchService {
         Ok(())
     }
 
+    pub async fn ensure_email_index(&self) -> Result<(), SearchError> {
+        self.create_index_if_missing(&self.emails_index_uid, "email_id")
+            .await?;
+
+        let searchable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/searchable-attributes",
+                    self.emails_index_uid
+                ),
+                &["subject", "body", "author_name", "author_email", "files"],
+            )
+            .await?;
+        self.wait_for_task(searchable_task).await?;
+
+        let filterable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/filterable-attributes",
+                    self.emails_index_uid
+                ),
+                &[
+                    "mailing_list",
+                    "mailing_list_id",
+                    "message_id",
+                    "thread_id",
+                    "author_id",
+                    "author_email",
+                    "to",
+                    "cc",
+                    "is_patch",
+                    "files",
+                    "date_ts",
+                ],
+            )
+            .await?;
+        self.wait_for_task(filterable_task).await?;
+
+        let sortable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/sortable-attributes",
+                    self.emails_index_uid
+                ),
+                &["date_ts"],
+            )
+            .await?;
+        self.wait_for_task(sortable_task).await?;
+
+        Ok(())
+    }
+
+    pub async fn ensure_patch_index(&self) -> Result<(), SearchError> {
+        self.create_index_if_missing(&self.patches_index_uid, "email_id")
+            .await?;
+
+        let searchable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/searchable-attributes",
+                    self.patches_index_uid
+                ),
+                &[
+                    "functions",
+                    "added_identifiers",
+                    "removed_identifiers",
+                    "files",
+                    "subject",
+                    "added_lines",
+                    "removed_lines",
+                ],
+            )
+            .await?;
+        self.wait_for_task(searchable_task).await?;
+
+        let filterable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/filterable-attributes",
+                    self.patches_index_uid
+                ),
+                &[
+                    "mailing_list",
+                    "mailing_list_id",
+                    "message_id",
+                    "thread_id",
+                    "author_id",
+                    "author_email",
+                    "files",
+                    "functions",
+                    "added_identifiers",
+                    "removed_identifiers",
+                    "date_ts",
+                ],
+            )
+            .await?;
+        self.wait_for_task(filterable_task).await?;
+
+        let sortable_task = self
+            .submit_task(
+                Method::PUT,
+                &format!(
+                    "/indexes/{}/settings/sortable-attributes",
+                    self.patches_index_uid
+                ),
+                &["date_ts"],
+            )
+            .await?;
+        self.wait_for_task(sortable_task).await?;
+
+        Ok(())
+    }
+
     pub async fn upsert_threads(&self, documents: &[ThreadDocument]) -> Result<(), SearchError> {
         if documents.is_empty() {
             return Ok(());
@@ -722,37 +1138,175 @@ impl SearchService {
         Ok(())
     }
 
-    pub async fn upsert_authors(&self, documents: &[AuthorDocument]) -> Result<(), SearchError> {
+    /// Refresh the `patch_states` attribute of already indexed thread documents.
+    ///
+    /// Uses Meilisearch's partial update (`PUT`) so the rest of each document,
+    /// including its embedding, is left untouched. Threads without a document
+    /// are skipped, since a partial update would create a stub document for
+    /// them.
+    pub async fn update_thread_patch_states(
+        &self,
+        updates: &[(i32, Vec<PatchState>)],
+    ) -> Result<(), SearchError> {
+        #[derive(Serialize)]
+        struct PatchStatesUpdate<'a> {
+            thread_id: i32,
+            patch_states: &'a [PatchState],
+        }
+
+        if updates.is_empty() || !self.backend.uses_document_index() {
+            return Ok(());
+        }
+
+        for chunk in updates.chunks(UPSERT_BATCH_SIZE) {
+            let thread_ids: Vec<i32> = chunk.iter().map(|(thread_id, _)| *thread_id).collect();
+            let indexed = self.indexed_thread_ids(&thread_ids).await?;
+            let documents: Vec<PatchStatesUpdate<'_>> = chunk
+                .iter()
+                .filter(|(thread_id, _)| indexed.contains(thread_id))
+                .map(|(thread_id, states)| PatchStatesUpdate {
+                    thread_id: *thread_id,
+                    patch_states: states,
+                })
+                .collect();
+            if documents.is_empty() {
+                continue;
+            }
+            let task = self
+                .submit_task(
+                    Method::PUT,
+                    &format!("/indexes/{}/documents", self.threads_index_uid),
+                    &documents,
+                )
+                .await?;
+            self.wait_for_task(task).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Which of `thread_ids` have a document in the threads index.
+    async fn indexed_thread_ids(&self, thread_ids: &[i32]) -> Result<HashSet<i32>, SearchError> {
+        #[derive(Serialize)]
+        struct FetchDocumentsRequest<'a> {
+            ids: Vec<String>,
+            fields: &'a [&'a str],
+            limit: usize,
+        }
+
+        #[derive(Deserialize)]
+        struct ThreadIdDocument {
+            thread_id: i32,
+        }
+
+        #[derive(Deserialize)]
+        struct FetchDocumentsResponse {
+            results: Vec<ThreadIdDocument>,
+        }
+
+        let response: FetchDocumentsResponse = self
+            .send_json(
+                Method::POST,
+                &format!("/indexes/{}/documents/fetch", self.threads_index_uid),
+                &FetchDocumentsRequest {
+                    ids: thread_ids.iter().map(i32::to_string).collect(),
+                    fields: &["thread_id"],
+                    limit: thread_ids.len(),
+                },
+            )
+            .await?
+            .json()
+            .await
+            .map_err(SearchError::MeilisearchHttp)?;
+
+        Ok(response
+            .results
+            .into_iter()
+            .map(|document| document.thread_id)
+            .collect())
+    }
+
+    pub async fn upsert_emails(&self, documents: &[EmailDocument]) -> Result<(), SearchError> {
         if documents.is_empty() {
             return Ok(());
         }
 
         debug!(
-            "meilisearch upsert_authors: preparing to send {} documents (chunk size {})",
+            "meilisearch upsert_emails: preparing to send {} documents (chunk size {})",
             documents.len(),
             UPSERT_BATCH_SIZE
         );
 
-        for (chunk_index, chunk) in documents.chunks(UPSERT_BATCH_SIZE).enumerate() {
-            debug!(
-                "meilisearch upsert_authors: submitting chunk #{} ({} documents)",
-                chunk_index + 1,
-                chunk.len()
-            );
+        for chunk in documents.chunks(UPSERT_BATCH_SIZE) {
             let task = self
                 .submit_task(
                     Method::POST,
-                    &format!("/indexes/{}/documents", self.authors_index_uid),
+                    &format!("/indexes/{}/documents", self.emails_index_uid),
                     chunk,
                 )
                 .await?;
             self.wait_for_task(task).await?;
         }
 
-        debug!(
-            "meilisearch upsert_authors: completed {} documents",
-            documents.len()
-        );
+        Ok(())
+    }
+
+    pub async fn upsert_patches(&self, documents: &[PatchDocument]) -> Result<(), SearchError> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            "meilisearch upsert_patches: preparing to send {} documents (chunk size {})",
+            documents.len(),
+            UPSERT_BATCH_SIZE
+        );
+
+        for chunk in documents.chunks(UPSERT_BATCH_SIZE) {
+            let task = self
+                .submit_task(
+                    Method::POST,
+                    &format!("/indexes/{}/documents", self.patches_index_uid),
+                    chunk,
+                )
+                .await?;
+            self.wait_for_task(task).await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn upsert_authors(&self, documents: &[AuthorDocument]) -> Result<(), SearchError> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        debug!(
+            "meilisearch upsert_authors: preparing to send {} documents (chunk size {})",
+            documents.len(),
+            UPSERT_BATCH_SIZE
+        );
+
+        for (chunk_index, chunk) in documents.chunks(UPSERT_BATCH_SIZE).enumerate() {
+            debug!(
+                "meilisearch upsert_authors: submitting chunk #{} ({} documents)",
+                chunk_index + 1,
+                chunk.len()
+            );
+            let task = self
+                .submit_task(
+                    Method::POST,
+                    &format!("/indexes/{}/documents", self.authors_index_uid),
+                    chunk,
+                )
+                .await?;
+            self.wait_for_task(task).await?;
+        }
+
+        debug!(
+            "meilisearch upsert_authors: completed {} documents",
+            documents.len()
+        );
 
         Ok(())
     }
@@ -775,6 +1329,77 @@ impl SearchService {
         self.wait_for_task(task).await
     }
 
+    pub async fn delete_emails_by_mailing_list(
+        &self,
+        mailing_list_id: i32,
+    ) -> Result<(), SearchError> {
+        let payload = DeleteByFilter {
+            filter: format!("mailing_list_id = {}", mailing_list_id),
+        };
+
+        let task = self
+            .submit_task(
+                Method::POST,
+                &format!("/indexes/{}/documents/delete", self.emails_index_uid),
+                &payload,
+            )
+            .await?;
+        self.wait_for_task(task).await
+    }
+
+    pub async fn delete_patches_by_mailing_list(
+        &self,
+        mailing_list_id: i32,
+    ) -> Result<(), SearchError> {
+        let payload = DeleteByFilter {
+            filter: format!("mailing_list_id = {}", mailing_list_id),
+        };
+
+        let task = self
+            .submit_task(
+                Method::POST,
+                &format!("/indexes/{}/documents/delete", self.patches_index_uid),
+                &payload,
+            )
+            .await?;
+        self.wait_for_task(task).await
+    }
+
+    /// Remove the documents of `thread_ids` together with the email and patch
+    /// documents filed under them.
+    pub async fn delete_threads(
+        &self,
+        mailing_list_id: i32,
+        thread_ids: &[i32],
+    ) -> Result<(), SearchError> {
+        for chunk in thread_ids.chunks(UPSERT_BATCH_SIZE) {
+            let task = self
+                .submit_task(
+                    Method::POST,
+                    &format!("/indexes/{}/documents/delete-batch", self.threads_index_uid),
+                    chunk,
+                )
+                .await?;
+            self.wait_for_task(task).await?;
+
+            let payload = DeleteByFilter {
+                filter: thread_documents_filter(mailing_list_id, chunk),
+            };
+            for index_uid in [&self.emails_index_uid, &self.patches_index_uid] {
+                let task = self
+                    .submit_task(
+                        Method::POST,
+                        &format!("/indexes/{}/documents/delete", index_uid),
+                        &payload,
+                    )
+                    .await?;
+                self.wait_for_task(task).await?;
+            }
+        }
+
+        Ok(())
+    }
+
     pub async fn delete_authors_by_slug(&self, slug: &str) -> Result<(), SearchError> {
         let payload = DeleteByFilter {
             filter: format!("mailing_lists = \"{}\"", slug),
@@ -793,8 +1418,12 @@ impl SearchService {
     pub async fn reset_indexes(&self) -> Result<(), SearchError> {
         self.drop_index(&self.threads_index_uid).await?;
         self.drop_index(&self.authors_index_uid).await?;
+        self.drop_index(&self.emails_index_uid).await?;
+        self.drop_index(&self.patches_index_uid).await?;
         self.ensure_thread_index().await?;
         self.ensure_author_index().await?;
+        self.ensure_email_index().await?;
+        self.ensure_patch_index().await?;
         Ok(())
     }
 
@@ -809,6 +1438,171 @@ impl SearchService {
     }
 }
 
+impl SearchBackend for SearchService {
+    fn kind(&self) -> SearchBackendKind {
+        SearchBackendKind::Meilisearch
+    }
+
+    /// Thread documents carry exact addresses, file paths and hunk-header
+    /// functions; message IDs and changed lines are only on emails and
+    /// patches.
+    fn supports_query_term(&self, term: &QueryTerm) -> bool {
+        match term.field {
+            QueryField::From | QueryField::To | QueryField::Cc | QueryField::Address => {
+                term.value.contains('@')
+            }
+            QueryField::DiffFileName => true,
+            QueryField::DiffHunkHeader => !term.phrase && is_identifier(&term.value),
+            _ => false,
+        }
+    }
+
+    /// Email documents carry exact addresses, Message-IDs and file paths.
+    fn supports_email_query_term(&self, term: &QueryTerm) -> bool {
+        match term.field {
+            QueryField::From | QueryField::To | QueryField::Cc | QueryField::Address => {
+                term.value.contains('@')
+            }
+            QueryField::MessageId | QueryField::DiffFileName => true,
+            _ => false,
+        }
+    }
+
+    /// Patch documents hold whole identifiers, so code terms must be a single
+    /// unquoted identifier; text inside lines is out of reach of filters.
+    fn supports_patch_query_term(&self, term: &QueryTerm) -> bool {
+        match term.field {
+            QueryField::Text
+            | QueryField::DiffSymbol
+            | QueryField::DiffAdded
+            | QueryField::DiffRemoved
+            | QueryField::DiffHunkHeader => !term.phrase && is_identifier(&term.value),
+            QueryField::From => term.value.contains('@'),
+            QueryField::MessageId | QueryField::DiffFileName => true,
+            _ => false,
+        }
+    }
+
+    /// Subjects, bodies and sender names are searchable attributes of thread
+    /// and email documents, so `q` covers them through `attributesToSearchOn`.
+    fn searches_query_term_as_text(&self, term: &QueryTerm) -> bool {
+        match term.field {
+            QueryField::Text | QueryField::Subject | QueryField::Body | QueryField::SubjectBody => {
+                true
+            }
+            QueryField::From => !term.value.contains('@'),
+            _ => false,
+        }
+    }
+
+    fn search_threads<'a>(
+        &'a self,
+        options: ThreadSearchPayload,
+    ) -> SearchFuture<'a, ThreadSearchResults> {
+        Box::pin(SearchService::search_threads(self, options))
+    }
+
+    fn search_authors<'a>(
+        &'a self,
+        options: AuthorSearchPayload,
+    ) -> SearchFuture<'a, AuthorSearchResults> {
+        Box::pin(SearchService::search_authors(self, options))
+    }
+
+    fn search_emails<'a>(
+        &'a self,
+        options: EmailSearchPayload,
+    ) -> SearchFuture<'a, EmailSearchResults> {
+        Box::pin(SearchService::search_emails(self, options))
+    }
+
+    fn search_patches<'a>(
+        &'a self,
+        options: PatchSearchPayload,
+    ) -> SearchFuture<'a, PatchSearchResults> {
+        Box::pin(SearchService::search_patches(self, options))
+    }
+
+    fn similar_threads<'a>(
+        &'a self,
+        options: SimilarThreadsPayload,
+    ) -> SearchFuture<'a, Vec<ThreadHit>> {
+        Box::pin(SearchService::similar_threads(self, options))
+    }
+
+    fn suggest<'a>(&'a self, options: SuggestPayload) -> SearchFuture<'a, SuggestResults> {
+        Box::pin(SearchService::suggest(self, options))
+    }
+}
+
+fn build_similar_thread_filters(options: &SimilarThreadsPayload) -> Vec<String> {
+    let mut filters: Vec<String> = Vec::new();
+
+    if !options.mailing_lists.is_empty() {
+        let slug_filters: Vec<String> = options
+            .mailing_lists
+            .iter()
+            .map(|ml| format!("mailing_list = \"{}\"", ml.slug))
+            .collect();
+        filters.push(join_filter_clauses(slug_filters));
+    }
+
+    if let Some(has_patches) = options.has_patches {
+        filters.push(format!("has_patches = {}", has_patches));
+    }
+
+    if let Some(series) = options.exclude_series.as_ref() {
+        filters.push(format!(
+            "NOT (starter_id = {} AND normalized_subject = \"{}\")",
+            series.starter_id,
+            escape_quotes(&series.normalized_subject)
+        ));
+    }
+
+    filters
+}
+
+/// Attributes to run `q` on for the fields it was written for; `None`
+/// searches every searchable attribute.
+fn search_attributes(
+    fields: &[QueryField],
+    attributes: fn(QueryField) -> &'static [&'static str],
+) -> Option<Vec<&'static str>> {
+    let mut names = Vec::new();
+    for field in fields {
+        let field_names = attributes(*field);
+        if field_names.is_empty() {
+            return None;
+        }
+        for name in field_names {
+            if !names.contains(name) {
+                names.push(*name);
+            }
+        }
+    }
+    (!names.is_empty()).then_some(names)
+}
+
+fn thread_text_attributes(field: QueryField) -> &'static [&'static str] {
+    match field {
+        QueryField::Subject => &["subject"],
+        QueryField::Body => &["discussion_text"],
+        QueryField::SubjectBody => &["subject", "discussion_text"],
+        QueryField::From => &["participants"],
+        _ => &[],
+    }
+}
+
+fn email_text_attributes(field: QueryField) -> &'static [&'static str] {
+    match field {
+        QueryField::Subject => &["subject"],
+        QueryField::Body => &["body"],
+        QueryField::SubjectBody => &["subject", "body"],
+        QueryField::From => &["author_name", "author_email"],
+        _ => &[],
+    }
+}
+
 fn build_thread_filters(options: &ThreadSearchPayload) -> Vec<String> {
     let mut filters: Vec<String> = Vec::new();
 
@@ -847,6 +1641,10 @@ fn build_thread_filters(options: &ThreadSearchPayload) -> Vec<String> {
         filters.push(format!("has_patches = {}", has_patches));
     }
 
+    if let Some(incomplete_series) = options.incomplete_series {
+        filters.push(format!("incomplete_series = {}", incomplete_series));
+    }
+
     if let Some(starter_id) = options.starter_id {
         filters.push(format!("starter_id = {}", starter_id));
     }
@@ -864,9 +1662,272 @@ fn build_thread_filters(options: &ThreadSearchPayload) -> Vec<String> {
         filters.push(format!("series_id = \"{}\"", escape_quotes(series_id)));
     }
 
+    if !options.subsystems.is_empty() {
+        let subsystem_filters: Vec<String> = options
+            .subsystems
+            .iter()
+            .map(|name| format!("subsystems = \"{}\"", escape_quotes(name)))
+            .collect();
+        filters.push(join_filter_clauses(subsystem_filters));
+    }
+
+    if !options.patch_states.is_empty() {
+        let state_filters: Vec<String> = options
+            .patch_states
+            .iter()
+            .map(|state| format!("patch_states = \"{}\"", state.as_str()))
+            .collect();
+        filters.push(join_filter_clauses(state_filters));
+    }
+
+    filters.extend(
+        options
+            .query_filters
+            .iter()
+            .filter_map(query_filter_expression),
+    );
+
+    filters
+}
+
+/// Translate a lore-style query condition into a filter expression.
+/// Unsupported terms (see `supports_query_term`) yield `None`.
+fn query_filter_expression(node: &QueryNode) -> Option<String> {
+    match node {
+        QueryNode::Term(term) => {
+            let value = escape_quotes(&term.value);
+            match term.field {
+                QueryField::From if term.value.contains('@') => {
+                    Some(format!("participant_emails = \"{value}\""))
+                }
+                QueryField::To if term.value.contains('@') => {
+                    Some(format!("to_emails = \"{value}\""))
+                }
+                QueryField::Cc if term.value.contains('@') => {
+                    Some(format!("cc_emails = \"{value}\""))
+                }
+                QueryField::Address if term.value.contains('@') => Some(format!(
+                    "(participant_emails = \"{value}\" OR to_emails = \"{value}\" \
+                     OR cc_emails = \"{value}\")"
+                )),
+                QueryField::DiffFileName => Some(format!("files = \"{value}\"")),
+                QueryField::DiffHunkHeader if !term.phrase && is_identifier(&term.value) => {
+                    Some(format!("functions = \"{value}\""))
+                }
+                _ => None,
+            }
+        }
+        QueryNode::Date(range) => {
+            let mut bounds = Vec::new();
+            if let Some(start) = range.start {
+                bounds.push(format!("last_ts >= {}", start.timestamp()));
+            }
+            if let Some(end) = range.end {
+                bounds.push(format!("start_ts <= {}", end.timestamp()));
+            }
+            Some(format!("({})", bounds.join(" AND ")))
+        }
+        QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+            let operator = if matches!(node, QueryNode::And(_)) {
+                " AND "
+            } else {
+                " OR "
+            };
+            let parts: Option<Vec<String>> = nodes.iter().map(query_filter_expression).collect();
+            Some(format!("({})", parts?.join(operator)))
+        }
+        QueryNode::Not(inner) => Some(format!("NOT {}", query_filter_expression(inner)?)),
+    }
+}
+
+fn build_email_filters(options: &EmailSearchPayload) -> Vec<String> {
+    let mut filters: Vec<String> = Vec::new();
+
+    let id_filters: Vec<String> = options
+        .mailing_lists
+        .iter()
+        .map(|ml| match ml.mailing_list_id {
+            Some(id) => format!("mailing_list_id = {}", id),
+            None => format!("mailing_list = \"{}\"", escape_quotes(&ml.slug)),
+        })
+        .collect();
+    if !id_filters.is_empty() {
+        filters.push(join_filter_clauses(id_filters));
+    }
+
+    if let Some(start) = options.start_date {
+        filters.push(format!("date_ts >= {}", start.timestamp()));
+    }
+
+    if let Some(end) = options.end_date {
+        filters.push(format!("date_ts <= {}", end.timestamp()));
+    }
+
+    if let Some(is_patch) = options.is_patch {
+        filters.push(format!("is_patch = {}", is_patch));
+    }
+
+    if let Some(author_id) = options.author_id {
+        filters.push(format!("author_id = {}", author_id));
+    }
+
+    if let Some(thread_id) = options.thread_id {
+        filters.push(format!("thread_id = {}", thread_id));
+    }
+
+    filters.extend(
+        options
+            .query_filters
+            .iter()
+            .filter_map(email_query_filter_expression),
+    );
+
+    filters
+}
+
+/// Email-index counterpart of `query_filter_expression`.
+fn email_query_filter_expression(node: &QueryNode) -> Option<String> {
+    match node {
+        QueryNode::Term(term) => {
+            let value = escape_quotes(&term.value);
+            match term.field {
+                QueryField::From if term.value.contains('@') => {
+                    Some(format!("author_email = \"{value}\""))
+                }
+                QueryField::To if term.value.contains('@') => Some(format!("to = \"{value}\"")),
+                QueryField::Cc if term.value.contains('@') => Some(format!("cc = \"{value}\"")),
+                QueryField::Address if term.value.contains('@') => Some(format!(
+                    "(author_email = \"{value}\" OR to = \"{value}\" OR cc = \"{value}\")"
+                )),
+                QueryField::MessageId => Some(format!(
+                    "message_id = \"{}\"",
+                    escape_quotes(term.value.trim_start_matches('<').trim_end_matches('>'))
+                )),
+                QueryField::DiffFileName => Some(format!("files = \"{value}\"")),
+                _ => None,
+            }
+        }
+        QueryNode::Date(range) => {
+            let mut bounds = Vec::new();
+            if let Some(start) = range.start {
+                bounds.push(format!("date_ts >= {}", start.timestamp()));
+            }
+            if let Some(end) = range.end {
+                bounds.push(format!("date_ts <= {}", end.timestamp()));
+            }
+            Some(format!("({})", bounds.join(" AND ")))
+        }
+        QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+            let operator = if matches!(node, QueryNode::And(_)) {
+                " AND "
+            } else {
+                " OR "
+            };
+            let parts: Option<Vec<String>> =
+                nodes.iter().map(email_query_filter_expression).collect();
+            Some(format!("({})", parts?.join(operator)))
+        }
+        QueryNode::Not(inner) => Some(format!("NOT {}", email_query_filter_expression(inner)?)),
+    }
+}
+
+fn build_patch_filters(options: &PatchSearchPayload) -> Vec<String> {
+    let mut filters: Vec<String> = Vec::new();
+
+    let id_filters: Vec<String> = options
+        .mailing_lists
+        .iter()
+        .map(|ml| match ml.mailing_list_id {
+            Some(id) => format!("mailing_list_id = {}", id),
+            None => format!("mailing_list = \"{}\"", escape_quotes(&ml.slug)),
+        })
+        .collect();
+    if !id_filters.is_empty() {
+        filters.push(join_filter_clauses(id_filters));
+    }
+
+    if let Some(start) = options.start_date {
+        filters.push(format!("date_ts >= {}", start.timestamp()));
+    }
+
+    if let Some(end) = options.end_date {
+        filters.push(format!("date_ts <= {}", end.timestamp()));
+    }
+
+    if let Some(author_id) = options.author_id {
+        filters.push(format!("author_id = {}", author_id));
+    }
+
+    if let Some(thread_id) = options.thread_id {
+        filters.push(format!("thread_id = {}", thread_id));
+    }
+
+    filters.extend(
+        options
+            .query_filters
+            .iter()
+            .filter_map(patch_query_filter_expression),
+    );
+
     filters
 }
 
+/// Patch-index counterpart of `query_filter_expression`. Bare words match
+/// symbols, like `dfs:`.
+fn patch_query_filter_expression(node: &QueryNode) -> Option<String> {
+    match node {
+        QueryNode::Term(term) => {
+            let value = escape_quotes(&term.value);
+            let identifier = !term.phrase && is_identifier(&term.value);
+            match term.field {
+                QueryField::Text | QueryField::DiffSymbol if identifier => Some(format!(
+                    "(added_identifiers = \"{value}\" OR removed_identifiers = \"{value}\" \
+                     OR functions = \"{value}\")"
+                )),
+                QueryField::DiffAdded if identifier => {
+                    Some(format!("added_identifiers = \"{value}\""))
+                }
+                QueryField::DiffRemoved if identifier => {
+                    Some(format!("removed_identifiers = \"{value}\""))
+                }
+                QueryField::DiffHunkHeader if identifier => {
+                    Some(format!("functions = \"{value}\""))
+                }
+                QueryField::DiffFileName => Some(format!("files = \"{value}\"")),
+                QueryField::From if term.value.contains('@') => {
+                    Some(format!("author_email = \"{value}\""))
+                }
+                QueryField::MessageId => Some(format!(
+                    "message_id = \"{}\"",
+                    escape_quotes(term.value.trim_start_matches('<').trim_end_matches('>'))
+                )),
+                _ => None,
+            }
+        }
+        QueryNode::Date(range) => {
+            let mut bounds = Vec::new();
+            if let Some(start) = range.start {
+                bounds.push(format!("date_ts >= {}", start.timestamp()));
+            }
+            if let Some(end) = range.end {
+                bounds.push(format!("date_ts <= {}", end.timestamp()));
+            }
+            Some(format!("({})", bounds.join(" AND ")))
+        }
+        QueryNode::And(nodes) | QueryNode::Or(nodes) => {
+            let operator = if matches!(node, QueryNode::And(_)) {
+                " AND "
+            } else {
+                " OR "
+            };
+            let parts: Option<Vec<String>> =
+                nodes.iter().map(patch_query_filter_expression).collect();
+            Some(format!("({})", parts?.join(operator)))
+        }
+        QueryNode::Not(inner) => Some(format!("NOT {}", patch_query_filter_expression(inner)?)),
+    }
+}
+
 fn join_filter_clauses(clauses: Vec<String>) -> String {
     if clauses.len() == 1 {
         clauses.into_iter().next().unwrap()
@@ -888,6 +1949,8 @@ struct ThreadSearchRequest<'a> {
     #[serde(skip_serializing_if = "Option::is_none")]
     filter: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    attributes_to_search_on: Option<Vec<&'static str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     attributes_to_highlight: Option<Vec<&'a str>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     attributes_to_crop: Option<Vec<&'a str>>,
@@ -899,6 +1962,8 @@ struct ThreadSearchRequest<'a> {
     hybrid: Option<HybridSpec>,
     #[serde(skip_serializing_if = "Option::is_none")]
     sort: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    facets: Option<Vec<&'static str>>,
 }
 
 #[derive(Serialize)]
@@ -909,6 +1974,27 @@ struct HybridSpec {
     semantic_ratio: f32,
 }
 
+/// Keyword-only search request, used by the email and patch indexes.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct KeywordSearchRequest<'a> {
+    q: &'a str,
+    limit: usize,
+    offset: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes_to_search_on: Option<Vec<&'static str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes_to_highlight: Option<Vec<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes_to_crop: Option<Vec<&'a str>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crop_length: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<Vec<String>>,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct AuthorSearchRequest<'a> {
@@ -922,10 +2008,131 @@ struct AuthorSearchRequest<'a> {
     sort: Option<Vec<String>>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SimilarThreadsRequest<'a> {
+    id: i32,
+    embedder: &'a str,
+    limit: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<Vec<String>>,
+    show_ranking_score: bool,
+}
+
+#[derive(Serialize)]
+struct MultiSearchRequest<'a> {
+    queries: Vec<MultiSearchQuery<'a>>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct MultiSearchQuery<'a> {
+    index_uid: &'a str,
+    q: &'a str,
+    limit: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    filter: Option<Vec<String>>,
+    attributes_to_retrieve: &'static [&'static str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    attributes_to_search_on: Option<&'static [&'static str]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sort: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct SuggestMultiSearchResponse {
+    results: (
+        MeiliSearchResponse<AuthorSuggestion>,
+        MeiliSearchResponse<ThreadSuggestion>,
+    ),
+}
+
+#[derive(Debug, Default)]
+pub struct SuggestResults {
+    pub authors: Vec<AuthorSuggestion>,
+    pub threads: Vec<ThreadSuggestion>,
+}
+
+#[derive(Debug, Clone, Deserialize, sqlx::FromRow)]
+pub struct AuthorSuggestion {
+    pub author_id: i32,
+    pub canonical_name: Option<String>,
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Deserialize, sqlx::FromRow)]
+pub struct ThreadSuggestion {
+    pub thread_id: i32,
+    pub mailing_list: String,
+    pub subject: String,
+    pub last_ts: i64,
+}
+
 #[derive(Debug)]
 pub struct ThreadSearchResults {
     pub hits: Vec<ThreadHit>,
     pub total: i64,
+    /// Counts for the requested facets, in request order.
+    pub facets: Vec<FacetCounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FacetCounts {
+    pub facet: ThreadSearchFacet,
+    /// `(value, count)` pairs, chronological for dates and by descending
+    /// count otherwise.
+    pub values: Vec<(String, i64)>,
+}
+
+impl FacetCounts {
+    /// Order raw counts the way both backends report them. The first posting
+    /// of a series carries no version and is reported as `v1`.
+    pub(crate) fn new(
+        facet: ThreadSearchFacet,
+        counts: impl IntoIterator<Item = (String, i64)>,
+    ) -> Self {
+        let mut merged: HashMap<String, i64> = HashMap::new();
+        for (value, count) in counts {
+            let value = if facet == ThreadSearchFacet::SeriesVersion && value.is_empty() {
+                "v1".to_string()
+            } else {
+                value
+            };
+            *merged.entry(value).or_default() += count;
+        }
+
+        let mut values: Vec<(String, i64)> = merged.into_iter().collect();
+        if facet.is_chronological() {
+            values.sort();
+        } else {
+            values.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            values.truncate(FACET_VALUE_LIMIT);
+        }
+
+        Self { facet, values }
+    }
+}
+
+/// Thread document attribute counted for a facet.
+/// Filter matching email and patch documents filed under `thread_ids`.
+fn thread_documents_filter(mailing_list_id: i32, thread_ids: &[i32]) -> String {
+    let ids: Vec<String> = thread_ids.iter().map(i32::to_string).collect();
+    format!(
+        "mailing_list_id = {} AND thread_id IN [{}]",
+        mailing_list_id,
+        ids.join(", ")
+    )
+}
+
+fn facet_attribute(facet: ThreadSearchFacet) -> &'static str {
+    match facet {
+        ThreadSearchFacet::MailingList => "mailing_list",
+        ThreadSearchFacet::Year => "start_year",
+        ThreadSearchFacet::Month => "start_month",
+        ThreadSearchFacet::HasPatches => "has_patches",
+        ThreadSearchFacet::Participant => "participant_emails",
+        ThreadSearchFacet::SeriesVersion => "series_id",
+    }
 }
 
 #[derive(Debug)]
@@ -946,6 +2153,32 @@ pub struct AuthorHit {
     pub document: AuthorDocument,
 }
 
+#[derive(Debug)]
+pub struct EmailSearchResults {
+    pub hits: Vec<EmailHit>,
+    pub total: i64,
+}
+
+#[derive(Debug)]
+pub struct EmailHit {
+    pub document: EmailDocument,
+    pub ranking_score: Option<f32>,
+    pub formatted: Option<serde_json::Value>,
+}
+
+#[derive(Debug)]
+pub struct PatchSearchResults {
+    pub hits: Vec<PatchHit>,
+    pub total: i64,
+}
+
+#[derive(Debug)]
+pub struct PatchHit {
+    pub document: PatchDocument,
+    pub ranking_score: Option<f32>,
+    pub formatted: Option<serde_json::Value>,
+}
+
 #[derive(serde::Deserialize)]
 struct MeiliSearchResponse<T> {
     hits: Vec<MeiliHit<T>>,
@@ -953,6 +2186,8 @@ struct MeiliSearchResponse<T> {
     estimated_total_hits: Option<u64>,
     #[serde(rename = "totalHits")]
     total_hits: Option<u64>,
+    #[serde(rename = "facetDistribution", default)]
+    facet_distribution: HashMap<String, HashMap<String, u64>>,
 }
 
 #[derive(serde::Deserialize)]
@@ -967,12 +2202,29 @@ struct MeiliHit<T> {
 
 async fn parse_search_response(
     response: reqwest::Response,
+    facets: &[ThreadSearchFacet],
 ) -> Result<ThreadSearchResults, SearchError> {
-    let payload: MeiliSearchResponse<ThreadDocument> = response
+    let mut payload: MeiliSearchResponse<ThreadDocument> = response
         .json()
         .await
         .map_err(SearchError::MeilisearchHttp)?;
 
+    let facets = facets
+        .iter()
+        .map(|facet| {
+            let counts = payload
+                .facet_distribution
+                .remove(facet_attribute(*facet))
+                .unwrap_or_default();
+            FacetCounts::new(
+                *facet,
+                counts
+                    .into_iter()
+                    .map(|(value, count)| (value, count as i64)),
+            )
+        })
+        .collect();
+
     let total = payload
         .total_hits
         .or(payload.estimated_total_hits)
@@ -988,7 +2240,63 @@ async fn parse_search_response(
         })
         .collect();
 
-    Ok(ThreadSearchResults { hits, total })
+    Ok(ThreadSearchResults {
+        hits,
+        total,
+        facets,
+    })
+}
+
+async fn parse_email_search_response(
+    response: reqwest::Response,
+) -> Result<EmailSearchResults, SearchError> {
+    let payload: MeiliSearchResponse<EmailDocument> = response
+        .json()
+        .await
+        .map_err(SearchError::MeilisearchHttp)?;
+
+    let total = payload
+        .total_hits
+        .or(payload.estimated_total_hits)
+        .unwrap_or(payload.hits.len() as u64) as i64;
+
+    let hits = payload
+        .hits
+        .into_iter()
+        .map(|hit| EmailHit {
+            document: hit.document,
+            ranking_score: hit.ranking_score,
+            formatted: hit.formatted,
+        })
+        .collect();
+
+    Ok(EmailSearchResults { hits, total })
+}
+
+async fn parse_patch_search_response(
+    response: reqwest::Response,
+) -> Result<PatchSearchResults, SearchError> {
+    let payload: MeiliSearchResponse<PatchDocument> = response
+        .json()
+        .await
+        .map_err(SearchError::MeilisearchHttp)?;
+
+    let total = payload
+        .total_hits
+        .or(payload.estimated_total_hits)
+        .unwrap_or(payload.hits.len() as u64) as i64;
+
+    let hits = payload
+        .hits
+        .into_iter()
+        .map(|hit| PatchHit {
+            document: hit.document,
+            ranking_score: hit.ranking_score,
+            formatted: hit.formatted,
+        })
+        .collect();
+
+    Ok(PatchSearchResults { hits, total })
 }
 
 async fn parse_author_search_response(
@@ -1055,3 +2363,186 @@ struct EmbedderSpec {
     source: String,
     dimensions: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::search::query::parse_search_query;
+
+    #[test]
+    fn translates_query_filters() {
+        let query =
+            parse_search_query("f:akpm@linux-foundation.org OR NOT d:20240101..20240102").unwrap();
+
+        assert_eq!(
+            query_filter_expression(&query.filters[0]).as_deref(),
+            Some(
+                "(participant_emails = \"akpm@linux-foundation.org\" OR \
+                 NOT (last_ts >= 1704067200 AND start_ts <= 1704239999))"
+            )
+        );
+
+        let query =
+            parse_search_query("(t:linux-mm@kvack.org OR a:willy@infradead.org) dfn:mm/slub.c")
+                .unwrap();
+        assert_eq!(
+            query_filter_expression(&query.filters[0]).as_deref(),
+            Some(
+                "(to_emails = \"linux-mm@kvack.org\" OR (participant_emails = \
+                 \"willy@infradead.org\" OR to_emails = \"willy@infradead.org\" \
+                 OR cc_emails = \"willy@infradead.org\"))"
+            )
+        );
+        assert_eq!(
+            query_filter_expression(&query.filters[1]).as_deref(),
+            Some("files = \"mm/slub.c\"")
+        );
+
+        let query = parse_search_query("f:akpm dfhh:\"kfree(\" dfa:kfree").unwrap();
+        assert!(
+            query
+                .filters
+                .iter()
+                .all(|node| query_filter_expression(node).is_none())
+        );
+    }
+
+    #[test]
+    fn restricts_text_to_prefixed_fields() {
+        let mut query = parse_search_query("s:oops OR f:akpm").unwrap();
+        query
+            .fold_text_filters(|term| matches!(term.field, QueryField::Subject | QueryField::From));
+        assert_eq!(
+            search_attributes(&query.text_fields(), thread_text_attributes),
+            Some(vec!["subject", "participants"])
+        );
+        assert_eq!(
+            search_attributes(&[QueryField::Body], email_text_attributes),
+            Some(vec!["body"])
+        );
+        assert_eq!(
+            search_attributes(
+                &[QueryField::Subject, QueryField::Text],
+                thread_text_attributes
+            ),
+            None
+        );
+        assert_eq!(search_attributes(&[], thread_text_attributes), None);
+    }
+
+    #[test]
+    fn translates_email_query_filters() {
+        let query =
+            parse_search_query("dfn:mm/slub.c AND (a:willy@infradead.org OR m:<a@b>)").unwrap();
+
+        let expressions: Vec<String> = query
+            .filters
+            .iter()
+            .filter_map(email_query_filter_expression)
+            .collect();
+        assert_eq!(
+            expressions,
+            vec![
+                "files = \"mm/slub.c\"",
+                "((author_email = \"willy@infradead.org\" OR to = \"willy@infradead.org\" \
+                 OR cc = \"willy@infradead.org\") OR message_id = \"a@b\")",
+            ]
+        );
+    }
+
+    #[test]
+    fn builds_similar_thread_filters() {
+        let mut options = SimilarThreadsPayload {
+            thread_id: 7,
+            mailing_list_id: 1,
+            mailing_lists: Vec::new(),
+            limit: 10,
+            exclude_series: None,
+            has_patches: None,
+        };
+        assert!(build_similar_thread_filters(&options).is_empty());
+
+        options.mailing_lists = vec![ThreadMailingListFilter {
+            slug: "linux-mm".to_string(),
+            mailing_list_id: Some(1),
+        }];
+        options.has_patches = Some(true);
+        options.exclude_series = Some(SeriesKey {
+            starter_id: 42,
+            normalized_subject: "mm: add \"zero\" slab flag".to_string(),
+        });
+        assert_eq!(
+            build_similar_thread_filters(&options),
+            vec![
+                "mailing_list = \"linux-mm\"".to_string(),
+                "has_patches = true".to_string(),
+                "NOT (starter_id = 42 AND normalized_subject = \"mm: add \\\"zero\\\" slab flag\")"
+                    .to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_documents_of_threads() {
+        assert_eq!(
+            thread_documents_filter(3, &[11, 12]),
+            "mailing_list_id = 3 AND thread_id IN [11, 12]"
+        );
+    }
+
+    #[test]
+    fn orders_facet_counts() {
+        let months = FacetCounts::new(
+            ThreadSearchFacet::Month,
+            [("2024-03".to_string(), 1), ("2023-11".to_string(), 9)],
+        );
+        assert_eq!(
+            months.values,
+            vec![("2023-11".to_string(), 9), ("2024-03".to_string(), 1)]
+        );
+
+        let versions = FacetCounts::new(
+            ThreadSearchFacet::SeriesVersion,
+            [
+                ("v2".to_string(), 3),
+                ("".to_string(), 2),
+                ("v1".to_string(), 2),
+            ],
+        );
+        assert_eq!(
+            versions.values,
+            vec![("v1".to_string(), 4), ("v2".to_string(), 3)]
+        );
+
+        let lists = FacetCounts::new(
+            ThreadSearchFacet::MailingList,
+            (0..40).map(|i| (format!("list-{i:02}"), i)),
+        );
+        assert_eq!(lists.values.len(), FACET_VALUE_LIMIT);
+        assert_eq!(lists.values[0], ("list-39".to_string(), 39));
+    }
+
+    #[test]
+    fn translates_patch_query_filters() {
+        let query = parse_search_query("kmem_cache_alloc dfa:CONFIG_FOO -dfn:mm/slab.c").unwrap();
+        let mut filters = query.text_terms.clone();
+        filters.extend(query.filters);
+
+        let expressions: Vec<String> = filters
+            .iter()
+            .filter_map(patch_query_filter_expression)
+            .collect();
+        assert_eq!(
+            expressions,
+            vec![
+                "(added_identifiers = \"kmem_cache_alloc\" OR removed_identifiers = \
+                 \"kmem_cache_alloc\" OR functions = \"kmem_cache_alloc\")",
+                "added_identifiers = \"CONFIG_FOO\"",
+                "NOT files = \"mm/slab.c\"",
+            ]
+        );
+
+        let query = parse_search_query("dfa:\"out of memory\"").unwrap();
+        assert_eq!(patch_query_filter_expression(&query.filters[0]), None);
+    }
+}
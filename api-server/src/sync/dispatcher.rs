@@ -17,7 +17,10 @@
 //!    - Populate threading cache with email metadata
 //! 4. **Threading**: Run JWZ algorithm on complete cache to build thread hierarchy
 //! 5. **Persistence**: Save cache to disk for future incremental syncs
-//! 6. **Finalization**: Update author statistics and save checkpoints
+//! 6. **Finalization**: Update author statistics, re-run outdated email
+//!    processors over history, seed patch review states and supersede older
+//!    series versions, rebuild `#regzbot` regressions, tag threads with
+//!    MAINTAINERS subsystems, refresh search indexes, and save checkpoints
 //!
 //! # Synchronization Modes
 //!
@@ -48,22 +51,42 @@
 //! - **Change Detection**: SHA256 membership hashing skips unchanged threads
 //! - **Checkpoint Recovery**: Resume from last successful epoch
 
-use crate::search::{SearchService, reindex_authors, reindex_threads};
+use crate::maintainers::{
+    MaintainersIndex, MaintainersSource, load_maintainers_hash, maintainers_hash,
+    record_maintainers_hash, sync_subsystems, tag_threads, tag_threads_for_list,
+};
+use crate::patches::{
+    initialize_patch_states, patch_states_initialized, record_patch_states_initialized,
+    refresh_series_revisions, supersede_older_versions, thread_patch_states,
+};
+use crate::pull_requests::{mark_pulls_merged, open_pull_heads};
+use crate::regressions::refresh_regressions;
+use crate::search::{
+    SearchService, reindex_authors, reindex_authors_by_id, reindex_changed_threads, reindex_threads,
+};
 use crate::sync::bulk_import::BulkImporter;
 use crate::sync::database::checkpoint;
 use crate::sync::import::coordinator::EMAIL_IMPORT_BATCH_SIZE;
+use crate::sync::jobs::{
+    IndexJobAction, IndexJobPayload, JobContext, JobRegistry, UpstreamScanPayload,
+};
 use crate::sync::parser::ParsedEmail;
+use crate::sync::processors::ProcessorRegistry;
 use crate::sync::{
     SyncOrchestrator,
     git::{MailingListSyncConfig, RepoConfig},
-    queue::{Job, JobQueue, JobType},
+    queue::JobQueue,
 };
 use crate::threading::container::ThreadInfo;
 use crate::threading::{MailingListCache, build_email_threads};
+use crate::upstream::{
+    self, ScanOptions, UpstreamTreeConfig, contained_commits, first_release_tags, scan_commits,
+};
 use rocket_db_pools::sqlx::{self, Acquire, PgPool};
-use serde::Deserialize;
+use serde_json::json;
 use std::collections::HashMap;
 use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Dispatcher that orchestrates the complete sync job lifecycle.
@@ -76,38 +99,26 @@ use std::time::Duration;
 ///
 /// - `pool`: Database connection pool for all operations
 /// - `queue`: Job queue manager for claiming/updating jobs
+/// - `processors`: Email processors run over every imported chunk
+/// - `handlers`: Job handlers by job type name
 pub struct SyncDispatcher {
     pool: PgPool,
     queue: JobQueue,
     search: SearchService,
+    processors: Arc<ProcessorRegistry>,
+    handlers: Arc<JobRegistry>,
 }
 
-#[derive(Debug, Deserialize)]
-#[serde(default)]
-struct IndexJobPayload {
-    action: IndexJobAction,
-    #[serde(rename = "mailingListSlug")]
-    mailing_list_slug: Option<String>,
-    #[serde(default)]
-    #[serde(rename = "reindex")]
-    _reindex: bool,
-}
-
-impl Default for IndexJobPayload {
-    fn default() -> Self {
-        Self {
-            action: IndexJobAction::Refresh,
-            mailing_list_slug: None,
-            _reindex: false,
-        }
-    }
-}
+/// Upper bound on commits read by a single upstream scan.
+const DEFAULT_UPSTREAM_MAX_COMMITS: usize = 50_000;
 
-#[derive(Debug, Deserialize, Clone, Copy)]
-#[serde(rename_all = "snake_case")]
-enum IndexJobAction {
-    Refresh,
-    Reset,
+/// Threads whose search documents a sync run made stale.
+#[derive(Debug, Default)]
+struct ThreadChanges {
+    /// Threads created, or whose membership or patch states changed
+    changed: Vec<i32>,
+    /// Threads merged into another thread and deleted
+    removed: Vec<i32>,
 }
 
 impl SyncDispatcher {
@@ -117,9 +128,29 @@ impl SyncDispatcher {
             pool,
             queue,
             search,
+            processors: Arc::new(ProcessorRegistry::builtin()),
+            handlers: Arc::new(JobRegistry::builtin()),
         }
     }
 
+    /// Run jobs with the given handlers instead of the built-in ones.
+    pub fn with_handlers(mut self, handlers: Arc<JobRegistry>) -> Self {
+        self.handlers = handlers;
+        self
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub fn queue(&self) -> &JobQueue {
+        &self.queue
+    }
+
+    pub fn search(&self) -> &SearchService {
+        &self.search
+    }
+
     /// Run dispatcher loop forever
     pub async fn run(self) -> ! {
         log::info!("SyncDispatcher started");
@@ -127,7 +158,7 @@ impl SyncDispatcher {
         loop {
             let job = match self.queue.get_next_job().await {
                 Ok(Some(job)) => {
-                    log::info!("dispatcher: claimed job {} ({:?})", job.id, job.job_type);
+                    log::info!("dispatcher: claimed job {} ({})", job.id, job.job_type);
                     job
                 }
                 Ok(None) => {
@@ -141,13 +172,29 @@ impl SyncDispatcher {
                 }
             };
 
-            let result = match job.job_type {
-                JobType::Import => self.process_import_job(job).await,
-                JobType::IndexMaintenance => self.process_index_job(job).await,
+            // Payloads are validated when queued, but handlers may have changed
+            // since, and jobs can be queued without going through the admin API
+            let result =
+                match self
+                    .handlers
+                    .validate(&job.job_type, job.mailing_list_id, &job.payload)
+                {
+                    Ok(handler) => handler.run(JobContext::new(&self, &job)).await,
+                    Err(err) => Err(err),
+                };
+
+            let recorded = match &result {
+                Ok(()) => {
+                    log::info!("job {}: {} job complete", job.id, job.job_type);
+                    self.queue.complete_job(job.id).await
+                }
+                Err(err) => {
+                    log::error!("job {}: {} job failed: {}", job.id, job.job_type, err);
+                    self.queue.fail_job(job.id, err.clone()).await
+                }
             };
-
-            if let Err(err) = result {
-                log::error!("dispatcher: job processing failed: {}", err);
+            if let Err(err) = recorded {
+                log::error!("job {}: failed to record job outcome: {}", job.id, err);
             }
         }
     }
@@ -159,12 +206,12 @@ impl SyncDispatcher {
     ///
     /// # Arguments
     ///
-    /// - `job`: The sync job to process (contains job_id and mailing_list_id)
+    /// - `ctx`: Context of the import job (job_id and mailing_list_id)
     ///
     /// # Returns
     ///
-    /// - `Ok(())`: Job completed successfully (marked as complete in queue)
-    /// - `Err(String)`: Job failed (marked as failed in queue with error message)
+    /// - `Ok(())`: Job completed successfully (the run loop marks it complete)
+    /// - `Err(String)`: Job failed (the run loop marks it failed with the message)
     ///
     /// # Phases
     ///
@@ -178,7 +225,10 @@ impl SyncDispatcher {
     ///    - Save checkpoint after each epoch
     /// 4. **Threading**: Run JWZ algorithm on complete cache and insert to database
     /// 5. **Persistence**: Save cache to disk for next incremental sync
-    /// 6. **Finalization**: Update author statistics and save final checkpoints
+    /// 6. **Finalization**: Update author statistics, re-run outdated email
+    ///    processors over history, update `#regzbot` regressions, tag
+    ///    MAINTAINERS subsystems, refresh search indexes, and save final
+    ///    checkpoints
     ///
     /// # Sync Mode Determination
     ///
@@ -194,31 +244,28 @@ impl SyncDispatcher {
     /// - Before each epoch processing
     /// - Every 5 chunks during import (to avoid excessive DB queries)
     /// - Before threading phase
+    /// - Before enrichment, before re-running email processors and before indexing
+    /// - Between search indexing batches
+    ///
+    /// A job cancelled after threading leaves the search documents of the
+    /// threads it changed stale until the next index refresh.
     ///
     /// # Error Handling
     ///
     /// All errors are propagated to fail the job. Non-fatal errors (e.g., cache save
     /// failures) are logged as warnings but don't fail the job.
-    async fn process_import_job(&self, job: Job) -> Result<(), String> {
-        let job_id = job.id;
-        let list_id = match job.mailing_list_id {
-            Some(id) => id,
-            None => {
-                let error_msg = "Import job missing mailing_list_id".to_string();
-                let _ = self.queue.fail_job(job_id, error_msg.clone()).await;
-                return Err(error_msg);
-            }
-        };
+    pub(crate) async fn run_import_job(&self, ctx: &JobContext<'_>) -> Result<(), String> {
+        let job_id = ctx.job().id;
+        let list_id = ctx
+            .job()
+            .mailing_list_id
+            .ok_or_else(|| "Import job missing mailing_list_id".to_string())?;
 
         // Phase 0: Load mailing list configuration
-        let (slug, repos) = match self.load_mailing_list_configuration(list_id).await {
-            Ok(config) => config,
-            Err(e) => {
-                let error_msg = format!("Failed to load config: {}", e);
-                let _ = self.queue.fail_job(job_id, error_msg.clone()).await;
-                return Err(error_msg);
-            }
-        };
+        let (slug, repos) = self
+            .load_mailing_list_configuration(list_id)
+            .await
+            .map_err(|e| format!("Failed to load config: {}", e))?;
 
         log::info!(
             "job {}: processing mailing list '{}' with {} repos (epochs)",
@@ -235,13 +282,22 @@ impl SyncDispatcher {
             .initialize_cache_for_sync(job_id, list_id, &repos)
             .await?;
 
+        // A list's first import runs its processors chunk by chunk, so their
+        // current versions already cover its history
+        self.processors
+            .initialize_versions(&self.pool, list_id)
+            .await
+            .map_err(|e| format!("Failed to record email processor versions: {}", e))?;
+
         // Phase 2: Parse and import all epochs
+        ctx.report_progress(json!({ "phase": "import" })).await;
         let (total_emails_imported, epoch_checkpoints) = self
             .parse_and_import_epochs(job_id, list_id, git_config, &epochs_to_process, &cache)
             .await?;
 
         // Phase 3: Build threads and insert to database
-        let (total_threads, total_memberships) = self
+        ctx.report_progress(json!({ "phase": "threading" })).await;
+        let (total_threads, total_memberships, mut thread_changes) = self
             .build_and_insert_threads(job_id, list_id, &cache)
             .await?;
 
@@ -249,20 +305,43 @@ impl SyncDispatcher {
         self.persist_cache_to_storage(job_id, list_id, &cache).await;
 
         // Phase 5: Update author statistics
+        ctx.check_cancelled().await?;
+        ctx.report_progress(json!({ "phase": "enrichment" })).await;
         self.update_author_statistics(job_id, list_id).await?;
 
-        // Phase 6: Update Meilisearch indexes
-        self.update_search_indexes(job_id, list_id).await?;
+        // Phase 6: Re-run email processors whose version changed over history
+        ctx.check_cancelled().await?;
+        let processors_rerun = self.rerun_outdated_processors(job_id, list_id).await?;
 
-        // Phase 7: Save checkpoints
-        self.save_sync_checkpoints(job_id, list_id, &epoch_checkpoints)
+        // Phase 7: Seed patch states, supersede older series versions and group
+        // series revisions
+        let patch_threads = self
+            .update_patch_states(job_id, list_id, &thread_changes.changed)
+            .await?;
+        thread_changes.changed.extend(patch_threads);
+
+        // Phase 8: Fold threaded `#regzbot` commands into regressions
+        self.update_regressions(job_id, list_id).await?;
+
+        // Phase 9: Tag threads with MAINTAINERS subsystems (before indexing)
+        let retagged = self
+            .tag_thread_subsystems(job_id, list_id, &thread_changes.changed)
+            .await;
+        thread_changes.changed.extend(retagged);
+        thread_changes.changed.sort_unstable();
+        thread_changes.changed.dedup();
+
+        // Phase 10: Update Meilisearch indexes. Re-run processors may have
+        // changed any email of the list, so those runs rebuild all of it
+        ctx.check_cancelled().await?;
+        ctx.report_progress(json!({ "phase": "indexing" })).await;
+        let thread_changes = (!processors_rerun).then_some(thread_changes);
+        self.update_search_indexes(job_id, list_id, thread_changes)
             .await?;
 
-        // Complete job
-        self.queue
-            .complete_job(job_id)
-            .await
-            .map_err(|e| format!("Failed to mark job complete: {}", e))?;
+        // Phase 11: Save checkpoints
+        self.save_sync_checkpoints(job_id, list_id, &epoch_checkpoints)
+            .await?;
 
         log::info!(
             "job {}: complete - {} emails, {} threads, {} memberships",
@@ -329,7 +408,8 @@ impl SyncDispatcher {
         epoch: i32,
         cache: &MailingListCache,
     ) -> Result<usize, String> {
-        let importer = BulkImporter::new(self.pool.clone(), mailing_list_id);
+        let importer = BulkImporter::new(self.pool.clone(), mailing_list_id)
+            .with_processors(Arc::clone(&self.processors));
         let total = parsed_emails.len();
 
         log::info!(
@@ -408,7 +488,8 @@ impl SyncDispatcher {
     ///
     /// # Returns
     ///
-    /// - `Ok((thread_count, membership_count))`: Number of threads and memberships created
+    /// - `Ok((thread_count, membership_count, changes))`: Number of threads and memberships
+    ///   created, and the threads changed or merged away
     /// - `Err(String)`: Threading or database insertion failure
     ///
     /// # Process Flow
@@ -452,7 +533,7 @@ impl SyncDispatcher {
         &self,
         mailing_list_id: i32,
         cache: &MailingListCache,
-    ) -> Result<(usize, usize), String> {
+    ) -> Result<(usize, usize, ThreadChanges), String> {
         log::info!("Running threading on unified cache");
 
         // Step 1: Get all data from unified cache (no merging needed!)
@@ -480,17 +561,19 @@ impl SyncDispatcher {
             threads_to_create.len()
         );
 
-        let (thread_count, membership_count) = self
+        let (thread_count, membership_count, changes) = self
             .insert_thread_batch_with_memberships(mailing_list_id, threads_to_create)
             .await?;
 
         log::info!(
-            "Threading complete: {} threads, {} memberships inserted",
+            "Threading complete: {} threads, {} memberships inserted, {} threads changed, {} merged away",
             thread_count,
-            membership_count
+            membership_count,
+            changes.changed.len(),
+            changes.removed.len()
         );
 
-        Ok((thread_count, membership_count))
+        Ok((thread_count, membership_count, changes))
     }
 
     /// Prepare thread data by computing membership hashes and statistics.
@@ -579,7 +662,8 @@ impl SyncDispatcher {
     ///
     /// # Returns
     ///
-    /// - `Ok((thread_count, membership_count))`: Total threads processed and memberships inserted
+    /// - `Ok((thread_count, membership_count, changes))`: Total threads processed, memberships
+    ///   inserted, and the threads changed or merged away
     /// - `Err(String)`: Database operation failure
     ///
     /// # Change Detection Strategy
@@ -604,7 +688,9 @@ impl SyncDispatcher {
     /// 2. **Bulk Check Existing**: Query database for existing threads
     /// 3. **Filter Unchanged**: Compare hashes to skip unchanged threads
     /// 4. **Bulk Upsert Threads**: Insert/update changed threads
-    /// 5. **Bulk Insert Memberships**: Insert thread memberships
+    /// 5. **Remove Merged Threads**: Delete threads whose root message is now a
+    ///    reply inside a changed thread, with their memberships
+    /// 6. **Bulk Insert Memberships**: Replace the memberships of changed threads
     ///
     /// # Performance Impact
     ///
@@ -616,9 +702,9 @@ impl SyncDispatcher {
         &self,
         mailing_list_id: i32,
         threads_to_create: Vec<ThreadInfo>,
-    ) -> Result<(usize, usize), String> {
+    ) -> Result<(usize, usize, ThreadChanges), String> {
         if threads_to_create.is_empty() {
-            return Ok((0, 0));
+            return Ok((0, 0, ThreadChanges::default()));
         }
 
         let mut conn = self
@@ -668,6 +754,7 @@ impl SyncDispatcher {
         // This is the key optimization that skips unchanged threads in incremental syncs
         let mut threads_to_upsert = Vec::new();
         let mut thread_id_map: HashMap<String, i32> = HashMap::new(); // Preserves existing IDs
+        let mut replaced_thread_ids = Vec::new();
         let mut skipped_count = 0;
 
         for thread in prepared_threads {
@@ -683,6 +770,7 @@ impl SyncDispatcher {
                 // Hash mismatch = membership changed = need to update
                 // Preserve existing thread_id for membership updates
                 thread_id_map.insert(root_msg_id.clone(), *existing_id);
+                replaced_thread_ids.push(*existing_id);
             }
             // Either new thread or changed thread - needs upsert
             threads_to_upsert.push(thread);
@@ -699,7 +787,7 @@ impl SyncDispatcher {
             tx.commit()
                 .await
                 .map_err(|e| format!("Failed to commit transaction: {}", e))?;
-            return Ok((thread_count, 0));
+            return Ok((thread_count, 0, ThreadChanges::default()));
         }
 
         // Step 4: Bulk insert/update changed threads
@@ -755,11 +843,129 @@ impl SyncDispatcher {
         );
 
         // Merge returned thread IDs with existing thread IDs
+        let changed_thread_ids: Vec<i32> = thread_ids_from_insert
+            .iter()
+            .map(|(_, thread_id)| *thread_id)
+            .collect();
         for (root_msg_id, thread_id) in thread_ids_from_insert {
             thread_id_map.insert(root_msg_id, thread_id);
         }
 
-        // Step 5: Bulk insert thread memberships
+        // Step 5: Delete threads merged into a changed thread. Their root
+        // message now sits below another root, and a merge always changes the
+        // membership hash of the thread that absorbed it
+        let member_email_ids: Vec<i32> = threads_to_upsert
+            .iter()
+            .flat_map(|(.., membership_map)| membership_map.keys().copied())
+            .collect();
+        let merged_threads: Vec<(i32, i32)> = sqlx::query_as(
+            r#"SELECT t.id, e.id
+               FROM threads t
+               JOIN emails e ON e.mailing_list_id = t.mailing_list_id
+                   AND e.message_id = t.root_message_id
+               WHERE t.mailing_list_id = $1
+                 AND e.id = ANY($2)
+                 AND t.root_message_id <> ALL($3)"#,
+        )
+        .bind(mailing_list_id)
+        .bind(&member_email_ids)
+        .bind(&root_message_ids)
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| format!("Failed to find merged threads: {}", e))?;
+        let removed_thread_ids: Vec<i32> = merged_threads.iter().map(|(id, _)| *id).collect();
+
+        if !removed_thread_ids.is_empty() {
+            log::debug!("Deleting {} merged threads", removed_thread_ids.len());
+
+            // Follows and notifications have no foreign key to threads; carry
+            // them over to the thread that now holds the merged root
+            let absorbing_thread: HashMap<i32, i32> = threads_to_upsert
+                .iter()
+                .filter_map(|(root_msg_id, .., membership_map)| {
+                    let thread_id = *thread_id_map.get(root_msg_id)?;
+                    Some(
+                        membership_map
+                            .keys()
+                            .map(move |email_id| (*email_id, thread_id)),
+                    )
+                })
+                .flatten()
+                .collect();
+            let (merged_ids, absorbing_ids): (Vec<i32>, Vec<i32>) = merged_threads
+                .iter()
+                .filter_map(|(thread_id, root_email_id)| {
+                    Some((*thread_id, *absorbing_thread.get(root_email_id)?))
+                })
+                .unzip();
+            sqlx::query(
+                r#"INSERT INTO user_thread_follows (user_id, mailing_list_id, thread_id, level, created_at)
+                   SELECT f.user_id, f.mailing_list_id, m.absorbing_id, f.level, f.created_at
+                   FROM user_thread_follows f
+                   JOIN UNNEST($2::int[], $3::int[]) AS m(merged_id, absorbing_id)
+                       ON f.thread_id = m.merged_id
+                   WHERE f.mailing_list_id = $1
+                   ON CONFLICT (user_id, mailing_list_id, thread_id) DO NOTHING"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&merged_ids)
+            .bind(&absorbing_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to move merged thread follows: {}", e))?;
+            sqlx::query(
+                r#"DELETE FROM user_thread_follows
+                   WHERE mailing_list_id = $1 AND thread_id = ANY($2)"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&removed_thread_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete merged thread follows: {}", e))?;
+            sqlx::query(
+                r#"UPDATE notifications n
+                   SET thread_id = m.absorbing_id
+                   FROM UNNEST($2::int[], $3::int[]) AS m(merged_id, absorbing_id)
+                   WHERE n.mailing_list_id = $1 AND n.thread_id = m.merged_id"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&merged_ids)
+            .bind(&absorbing_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to move merged thread notifications: {}", e))?;
+
+            sqlx::query(
+                r#"DELETE FROM thread_memberships
+                   WHERE mailing_list_id = $1 AND thread_id = ANY($2)"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&removed_thread_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to delete merged thread memberships: {}", e))?;
+            sqlx::query(r#"DELETE FROM threads WHERE mailing_list_id = $1 AND id = ANY($2)"#)
+                .bind(mailing_list_id)
+                .bind(&removed_thread_ids)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| format!("Failed to delete merged threads: {}", e))?;
+        }
+
+        // Emails that left a changed thread must not keep their old membership
+        if !replaced_thread_ids.is_empty() {
+            sqlx::query(
+                r#"DELETE FROM thread_memberships
+                   WHERE mailing_list_id = $1 AND thread_id = ANY($2)"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&replaced_thread_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| format!("Failed to clear changed thread memberships: {}", e))?;
+        }
+
+        // Step 6: Bulk insert thread memberships
         log::debug!("Preparing memberships for bulk insert");
 
         let mut membership_list_ids = Vec::new();
@@ -802,7 +1008,14 @@ impl SyncDispatcher {
             .await
             .map_err(|e| format!("Failed to commit transaction: {}", e))?;
 
-        Ok((thread_count, membership_count))
+        Ok((
+            thread_count,
+            membership_count,
+            ThreadChanges {
+                changed: changed_thread_ids,
+                removed: removed_thread_ids,
+            },
+        ))
     }
 
     /// Build email threads and insert to database.
@@ -818,14 +1031,15 @@ impl SyncDispatcher {
     ///
     /// # Returns
     ///
-    /// - `Ok((thread_count, membership_count))`: Number of threads and memberships created
+    /// - `Ok((thread_count, membership_count, changes))`: Number of threads and memberships
+    ///   created, and the threads changed or merged away
     /// - `Err(String)`: Threading failure
     async fn build_and_insert_threads(
         &self,
         job_id: i32,
         list_id: i32,
         cache: &MailingListCache,
-    ) -> Result<(usize, usize), String> {
+    ) -> Result<(usize, usize, ThreadChanges), String> {
         // Check if job was cancelled before threading
         if self.queue.is_job_cancelled(job_id).await.unwrap_or(false) {
             log::warn!(
@@ -844,7 +1058,7 @@ impl SyncDispatcher {
             );
         }
 
-        let (total_threads, total_memberships) =
+        let (total_threads, total_memberships, changes) =
             self.build_threads_from_cache(list_id, cache).await?;
 
         log::info!(
@@ -854,7 +1068,7 @@ impl SyncDispatcher {
             total_memberships
         );
 
-        Ok((total_threads, total_memberships))
+        Ok((total_threads, total_memberships, changes))
     }
 
     /// Persist the threading cache to disk for future incremental syncs.
@@ -914,7 +1128,302 @@ impl SyncDispatcher {
         Ok(())
     }
 
-    async fn update_search_indexes(&self, job_id: i32, list_id: i32) -> Result<(), String> {
+    /// Re-run email processors whose recorded version for the list is missing
+    /// or differs from their current one over the list's stored emails.
+    ///
+    /// Must run before regressions are rebuilt since they read the
+    /// `#regzbot` commands written by a processor.
+    ///
+    /// # Arguments
+    ///
+    /// - `job_id`: Current job ID for logging
+    /// - `list_id`: Mailing list ID
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(rerun)`: Processors are up to date; `rerun` is whether any had to
+    ///   be re-run
+    /// - `Err(String)`: Database failure
+    async fn rerun_outdated_processors(&self, job_id: i32, list_id: i32) -> Result<bool, String> {
+        let rerun = self
+            .processors
+            .rerun_outdated(&self.pool, list_id)
+            .await
+            .map_err(|e| format!("Failed to re-run email processors: {}", e))?;
+
+        if !rerun.is_empty() {
+            log::info!(
+                "job {}: phase=email_processors complete (re-ran {})",
+                job_id,
+                rerun.join(", ")
+            );
+        }
+
+        Ok(!rerun.is_empty())
+    }
+
+    /// Give newly imported patches an initial review state and supersede patches
+    /// whose series has a newer version on the list. New numbered patches are
+    /// grouped into series revisions.
+    ///
+    /// Only the threads the run touched are checked, except on the list's first
+    /// run after patch states were introduced, which covers every thread.
+    /// Must run after threading since superseding works on thread roots.
+    ///
+    /// # Arguments
+    ///
+    /// - `job_id`: Current job ID for logging
+    /// - `list_id`: Mailing list ID
+    /// - `thread_ids`: Threads created or changed by this run
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(thread_ids)`: Threads whose older patches were superseded or whose
+    ///   series revisions were regrouped
+    /// - `Err(String)`: Database failure
+    async fn update_patch_states(
+        &self,
+        job_id: i32,
+        list_id: i32,
+        thread_ids: &[i32],
+    ) -> Result<Vec<i32>, String> {
+        log::info!("job {}: phase=patch_states start", job_id);
+
+        let whole_list = !patch_states_initialized(&self.pool, list_id)
+            .await
+            .map_err(|e| format!("Failed to load patch state progress: {}", e))?;
+        let scope = (!whole_list).then_some(thread_ids);
+
+        let initialized = initialize_patch_states(&self.pool, list_id, scope)
+            .await
+            .map_err(|e| format!("Failed to initialize patch states: {}", e))?;
+
+        let superseded = supersede_older_versions(&self.pool, list_id, scope)
+            .await
+            .map_err(|e| format!("Failed to supersede older patch versions: {}", e))?;
+
+        if whole_list {
+            record_patch_states_initialized(&self.pool, list_id)
+                .await
+                .map_err(|e| format!("Failed to record patch state progress: {}", e))?;
+        }
+
+        let regrouped = refresh_series_revisions(&self.pool, list_id)
+            .await
+            .map_err(|e| format!("Failed to group series revisions: {}", e))?;
+
+        log::info!(
+            "job {}: phase=patch_states complete ({} new patches, {} threads superseded, {} threads with regrouped series)",
+            job_id,
+            initialized,
+            superseded.len(),
+            regrouped.len()
+        );
+
+        let mut thread_ids = superseded;
+        thread_ids.extend(regrouped);
+        Ok(thread_ids)
+    }
+
+    /// Update the list's regressions from its `#regzbot` commands.
+    ///
+    /// Must run after threading since regressions are keyed by report thread.
+    ///
+    /// # Arguments
+    ///
+    /// - `job_id`: Current job ID for logging
+    /// - `list_id`: Mailing list ID
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())`: Regressions updated successfully
+    /// - `Err(String)`: Database failure
+    async fn update_regressions(&self, job_id: i32, list_id: i32) -> Result<(), String> {
+        let tracked = refresh_regressions(&self.pool, list_id, None)
+            .await
+            .map_err(|e| format!("Failed to update regressions: {}", e))?;
+
+        log::info!(
+            "job {}: phase=regressions complete ({} regressions tracked)",
+            job_id,
+            tracked
+        );
+
+        Ok(())
+    }
+
+    /// Refresh MAINTAINERS subsystems and re-tag the threads the run touched.
+    ///
+    /// Every thread of the list is re-tagged instead when the MAINTAINERS file
+    /// differs from the one the list was last tagged against. Skipped when no
+    /// MAINTAINERS source is configured. Failures are logged as warnings and
+    /// don't fail the job since tags only enrich the thread data.
+    ///
+    /// # Arguments
+    ///
+    /// - `job_id`: Current job ID for logging
+    /// - `list_id`: Mailing list ID
+    /// - `thread_ids`: Threads created or changed by this run
+    ///
+    /// # Returns
+    ///
+    /// Threads whose subsystem tags changed, empty when tagging was skipped
+    /// or failed
+    async fn tag_thread_subsystems(
+        &self,
+        job_id: i32,
+        list_id: i32,
+        thread_ids: &[i32],
+    ) -> Vec<i32> {
+        let Some(source) = MaintainersSource::from_env() else {
+            log::debug!("job {}: no MAINTAINERS source configured", job_id);
+            return Vec::new();
+        };
+
+        log::info!("job {}: phase=subsystem_tagging start", job_id);
+
+        let contents = match tokio::task::spawn_blocking(move || source.read()).await {
+            Ok(Ok(contents)) => contents,
+            Ok(Err(e)) => {
+                log::warn!(
+                    "job {}: failed to load MAINTAINERS (non-fatal): {}",
+                    job_id,
+                    e
+                );
+                return Vec::new();
+            }
+            Err(e) => {
+                log::warn!(
+                    "job {}: MAINTAINERS loader panicked (non-fatal): {}",
+                    job_id,
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        let index = MaintainersIndex::parse(&contents);
+        if index.is_empty() {
+            log::warn!("job {}: MAINTAINERS contained no entries", job_id);
+            return Vec::new();
+        }
+
+        if let Err(e) = sync_subsystems(&self.pool, &index).await {
+            log::warn!(
+                "job {}: failed to store subsystems (non-fatal): {}",
+                job_id,
+                e
+            );
+            return Vec::new();
+        }
+
+        let hash = maintainers_hash(&contents);
+        let retag_all = match load_maintainers_hash(&self.pool, list_id).await {
+            Ok(tagged_hash) => tagged_hash.as_deref() != Some(hash.as_slice()),
+            Err(e) => {
+                log::warn!(
+                    "job {}: failed to load MAINTAINERS hash (non-fatal): {}",
+                    job_id,
+                    e
+                );
+                return Vec::new();
+            }
+        };
+
+        let result = if retag_all {
+            log::info!(
+                "job {}: MAINTAINERS changed since the last tagging, re-tagging every thread",
+                job_id
+            );
+            match tag_threads_for_list(&self.pool, list_id, &index).await {
+                Ok(tagged) => record_maintainers_hash(&self.pool, list_id, &hash)
+                    .await
+                    .map(|_| tagged),
+                Err(e) => Err(e),
+            }
+        } else {
+            tag_threads(&self.pool, list_id, &index, thread_ids).await
+        };
+
+        match result {
+            Ok(changed) => {
+                log::info!(
+                    "job {}: phase=subsystem_tagging complete ({} threads re-tagged)",
+                    job_id,
+                    changed.len()
+                );
+                changed
+            }
+            Err(e) => {
+                log::warn!("job {}: failed to tag threads (non-fatal): {}", job_id, e);
+                Vec::new()
+            }
+        }
+    }
+
+    /// Refresh the search documents a sync run made stale.
+    ///
+    /// With `changes`, only the changed threads and their participants' author
+    /// documents are re-embedded and upserted, and merged-away threads are
+    /// removed. Without, the list's thread documents and all author documents
+    /// are rebuilt.
+    async fn update_search_indexes(
+        &self,
+        job_id: i32,
+        list_id: i32,
+        changes: Option<ThreadChanges>,
+    ) -> Result<(), String> {
+        let Some(changes) = changes else {
+            return self.rebuild_search_indexes(job_id, list_id).await;
+        };
+
+        log::info!(
+            "job {}: phase=thread_reindex start (mailing_list_id={}, {} changed, {} removed)",
+            job_id,
+            list_id,
+            changes.changed.len(),
+            changes.removed.len()
+        );
+        let (threads_processed, author_ids) = reindex_changed_threads(
+            &self.pool,
+            &self.search,
+            list_id,
+            &changes.changed,
+            &changes.removed,
+            Some((&self.queue, job_id)),
+        )
+        .await
+        .map_err(|e| format!("Failed to reindex thread documents: {}", e))?;
+        log::info!(
+            "job {}: phase=thread_reindex complete (processed {} threads)",
+            job_id,
+            threads_processed
+        );
+
+        log::info!(
+            "job {}: phase=author_reindex start ({} authors)",
+            job_id,
+            author_ids.len()
+        );
+        let authors_processed = reindex_authors_by_id(
+            &self.pool,
+            &self.search,
+            &author_ids,
+            Some((&self.queue, job_id)),
+        )
+        .await
+        .map_err(|e| format!("Failed to reindex author documents: {}", e))?;
+        log::info!(
+            "job {}: phase=author_reindex complete (processed {} authors)",
+            job_id,
+            authors_processed
+        );
+
+        Ok(())
+    }
+
+    /// Rebuild the list's thread documents and all author documents.
+    async fn rebuild_search_indexes(&self, job_id: i32, list_id: i32) -> Result<(), String> {
         log::info!(
             "job {}: phase=thread_reindex start (mailing_list_id={})",
             job_id,
@@ -948,46 +1457,21 @@ impl SyncDispatcher {
         Ok(())
     }
 
-    async fn process_index_job(&self, job: Job) -> Result<(), String> {
-        let job_id = job.id;
-
-        let payload = if job.payload.is_null() {
-            IndexJobPayload::default()
-        } else {
-            match serde_json::from_value(job.payload.clone()) {
-                Ok(value) => value,
-                Err(err) => {
-                    let message = format!("Invalid index maintenance payload: {}", err);
-                    if let Err(queue_err) = self.queue.fail_job(job_id, message.clone()).await {
-                        log::error!(
-                            "job {}: failed to mark job as failed after payload parsing error: {}",
-                            job_id,
-                            queue_err
-                        );
-                    }
-                    return Err(message);
-                }
-            }
-        };
-
-        let mailing_list_id = if let Some(id) = job.mailing_list_id {
-            Some(id)
-        } else if let Some(slug) = payload.mailing_list_slug.as_ref() {
-            match self.lookup_mailing_list_id(slug).await {
-                Ok(id) => Some(id),
-                Err(err) => {
-                    if let Err(queue_err) = self.queue.fail_job(job_id, err.clone()).await {
-                        log::error!(
-                            "job {}: failed to mark job as failed after slug lookup error: {}",
-                            job_id,
-                            queue_err
-                        );
-                    }
-                    return Err(err);
-                }
-            }
-        } else {
-            None
+    /// Refresh or rebuild search documents for index maintenance jobs.
+    pub(crate) async fn run_index_job(
+        &self,
+        ctx: &JobContext<'_>,
+        payload: IndexJobPayload,
+    ) -> Result<(), String> {
+        let job_id = ctx.job().id;
+
+        let mailing_list_id = match (
+            ctx.job().mailing_list_id,
+            payload.mailing_list_slug.as_ref(),
+        ) {
+            (Some(id), _) => Some(id),
+            (None, Some(slug)) => Some(self.lookup_mailing_list_id(slug).await?),
+            (None, None) => None,
         };
 
         match payload.action {
@@ -1014,6 +1498,7 @@ impl SyncDispatcher {
                     threads_processed
                 );
 
+                ctx.check_cancelled().await?;
                 log::info!("job {}: phase=author_reindex start", job_id);
                 let authors_processed =
                     reindex_authors(&self.pool, &self.search, Some((&self.queue, job_id)))
@@ -1050,6 +1535,7 @@ impl SyncDispatcher {
                     threads_processed
                 );
 
+                ctx.check_cancelled().await?;
                 log::info!("job {}: phase=author_reindex start", job_id);
                 let authors_processed =
                     reindex_authors(&self.pool, &self.search, Some((&self.queue, job_id)))
@@ -1063,16 +1549,197 @@ impl SyncDispatcher {
             }
         }
 
-        log::info!("job {}: index maintenance job complete", job_id);
+        Ok(())
+    }
+
+    /// Scan an upstream git tree and record which patches were merged.
+    ///
+    /// Only commits added since the previous scan of the tree are read. Release
+    /// tags are resolved for new matches and for earlier matches that were not
+    /// part of a release yet, and open pull requests are checked against the
+    /// scanned head.
+    pub(crate) async fn run_upstream_scan(
+        &self,
+        ctx: &JobContext<'_>,
+        payload: UpstreamScanPayload,
+    ) -> Result<(), String> {
+        let job_id = ctx.job().id;
+
+        let config = UpstreamTreeConfig::from_env();
+        let repo_path = payload
+            .repo_path
+            .or_else(|| config.as_ref().map(|c| c.repo_path.clone()))
+            .ok_or_else(|| "No upstream tree configured (set UPSTREAM_GIT_PATH)".to_string())?;
+        let tree_name = payload
+            .tree_name
+            .or_else(|| config.as_ref().map(|c| c.name.clone()))
+            .unwrap_or_else(|| "mainline".to_string());
+        let reference = payload
+            .reference
+            .or_else(|| config.as_ref().map(|c| c.reference.clone()))
+            .unwrap_or_else(|| "HEAD".to_string());
+
+        let (tree_id, last_scanned) =
+            upstream::upsert_tree(&self.pool, &tree_name, &repo_path, &reference)
+                .await
+                .map_err(|e| format!("Failed to register upstream tree: {}", e))?;
+
+        // Phase 1: read new commits
+        let options = ScanOptions {
+            reference,
+            exclude: last_scanned.into_iter().chain(payload.since).collect(),
+            max_commits: payload.max_commits.unwrap_or(DEFAULT_UPSTREAM_MAX_COMMITS),
+        };
+        log::info!(
+            "job {}: phase=upstream_scan start (tree={}, exclude={:?})",
+            job_id,
+            tree_name,
+            options.exclude
+        );
+        let scan_path = PathBuf::from(&repo_path);
+        let scan = tokio::task::spawn_blocking(move || scan_commits(&scan_path, &options))
+            .await
+            .map_err(|e| format!("Upstream scan task failed: {}", e))??;
+        log::info!(
+            "job {}: phase=upstream_scan complete ({} commits{})",
+            job_id,
+            scan.commits.len(),
+            if scan.truncated {
+                format!(", newer history left for the next scan after {}", scan.head)
+            } else {
+                String::new()
+            }
+        );
+        if let Err(err) = self.queue.heartbeat(job_id).await {
+            log::warn!(
+                "job {}: failed to record heartbeat after scan: {}",
+                job_id,
+                err
+            );
+        }
+
+        // Phase 2: match commits to patch emails
+        let matches = upstream::match_commits(&self.pool, &scan.commits)
+            .await
+            .map_err(|e| format!("Failed to match upstream commits: {}", e))?;
+        let accepted =
+            upstream::record_applications(&self.pool, tree_id, &tree_name, &scan.commits, &matches)
+                .await
+                .map_err(|e| format!("Failed to store upstream applications: {}", e))?;
+        log::info!(
+            "job {}: phase=upstream_match complete ({} matches, {} patches accepted)",
+            job_id,
+            matches.len(),
+            accepted.values().map(Vec::len).sum::<usize>()
+        );
+        if let Err(err) = self.queue.heartbeat(job_id).await {
+            log::warn!(
+                "job {}: failed to record heartbeat after matching: {}",
+                job_id,
+                err
+            );
+        }
+
+        for (mailing_list_id, email_ids) in &accepted {
+            self.refresh_patch_states_in_search(job_id, *mailing_list_id, email_ids)
+                .await;
+        }
+
+        // Phase 3: release tags for commits not yet in a release
+        let pending = upstream::unreleased_commits(&self.pool, tree_id)
+            .await
+            .map_err(|e| format!("Failed to load unreleased upstream commits: {}", e))?;
+        if !pending.is_empty() {
+            let tag_path = PathBuf::from(&repo_path);
+            let tags = tokio::task::spawn_blocking(move || first_release_tags(&tag_path, &pending))
+                .await
+                .map_err(|e| format!("Release tag task failed: {}", e))??;
+            let tagged = upstream::update_release_tags(&self.pool, tree_id, &tags)
+                .await
+                .map_err(|e| format!("Failed to store release tags: {}", e))?;
+            log::info!(
+                "job {}: phase=release_tags complete ({} applications tagged)",
+                job_id,
+                tagged
+            );
+        }
 
-        if let Err(err) = self.queue.complete_job(job_id).await {
-            let message = format!("Failed to mark index maintenance job complete: {}", err);
-            return Err(message);
+        // Phase 4: pull requests whose head commit is now in the tree
+        let heads = open_pull_heads(&self.pool)
+            .await
+            .map_err(|e| format!("Failed to load open pull requests: {}", e))?;
+        if !heads.is_empty() {
+            let pull_path = PathBuf::from(&repo_path);
+            let tip = scan.head.clone();
+            let merged_heads =
+                tokio::task::spawn_blocking(move || contained_commits(&pull_path, &tip, &heads))
+                    .await
+                    .map_err(|e| format!("Pull request check task failed: {}", e))??;
+            let merged_heads: Vec<String> = merged_heads.into_iter().collect();
+            let merged = mark_pulls_merged(&self.pool, tree_id, &merged_heads)
+                .await
+                .map_err(|e| format!("Failed to mark pull requests merged: {}", e))?;
+            log::info!(
+                "job {}: phase=pull_requests complete ({} pull requests merged)",
+                job_id,
+                merged
+            );
         }
 
+        upstream::finish_scan(&self.pool, tree_id, &scan.head)
+            .await
+            .map_err(|e| format!("Failed to save upstream scan position: {}", e))?;
+
         Ok(())
     }
 
+    /// Push new patch states of the threads containing `email_ids` to search.
+    ///
+    /// Failures are logged only; the next import reindexes the threads anyway.
+    async fn refresh_patch_states_in_search(
+        &self,
+        job_id: i32,
+        mailing_list_id: i32,
+        email_ids: &[i32],
+    ) {
+        let thread_ids: Vec<i32> = match sqlx::query_scalar(
+            r#"SELECT DISTINCT thread_id
+               FROM thread_memberships
+               WHERE mailing_list_id = $1 AND email_id = ANY($2)"#,
+        )
+        .bind(mailing_list_id)
+        .bind(email_ids)
+        .fetch_all(&self.pool)
+        .await
+        {
+            Ok(ids) => ids,
+            Err(e) => {
+                log::warn!(
+                    "job {}: failed to load threads for accepted patches: {}",
+                    job_id,
+                    e
+                );
+                return;
+            }
+        };
+
+        let updates = match thread_patch_states(&self.pool, mailing_list_id, &thread_ids).await {
+            Ok(updates) => updates,
+            Err(e) => {
+                log::warn!("job {}: failed to load thread patch states: {}", job_id, e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.search.update_thread_patch_states(&updates).await {
+            log::warn!(
+                "job {}: failed to refresh patch states in search (non-fatal): {}",
+                job_id,
+                e
+            );
+        }
+    }
+
     async fn lookup_mailing_list_id(&self, slug: &str) -> Result<i32, String> {
         let result: Option<(i32,)> = sqlx::query_as("SELECT id FROM mailing_lists WHERE slug = $1")
             .bind(slug)
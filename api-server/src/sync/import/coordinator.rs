@@ -4,16 +4,19 @@
 //! 1. Extract unique authors
 //! 2. Insert authors
 //! 3. Prepare and insert emails
-//! 4. Insert recipients and references in parallel
+//! 4. Insert recipients, references and `Fixes:` trailers, and run the
+//!    registered email processors, in parallel
 //! 5. Populate threading cache
 
 use crate::sync::import::{
     data_builder, data_structures::ChunkCacheData, database_operations, stats::ImportStats,
 };
 use crate::sync::parser::ParsedEmail;
+use crate::sync::processors::{ProcessorEmail, ProcessorRegistry};
 use crate::threading::{EmailThreadingInfo, MailingListCache};
 use rocket_db_pools::sqlx::PgPool;
 use std::collections::HashMap;
+use std::sync::Arc;
 
 /// Chunk size for streaming imports to avoid overwhelming database connections
 pub const EMAIL_IMPORT_BATCH_SIZE: usize = 25_000;
@@ -25,6 +28,7 @@ pub const EMAIL_IMPORT_BATCH_SIZE: usize = 25_000;
 pub struct BulkImporter {
     pool: PgPool,
     mailing_list_id: i32,
+    processors: Arc<ProcessorRegistry>,
 }
 
 impl BulkImporter {
@@ -37,20 +41,29 @@ impl BulkImporter {
         Self {
             pool,
             mailing_list_id,
+            processors: Arc::new(ProcessorRegistry::default()),
         }
     }
 
+    /// Run the given email processors over every imported chunk.
+    pub fn with_processors(mut self, processors: Arc<ProcessorRegistry>) -> Self {
+        self.processors = processors;
+        self
+    }
+
     /// Import a single chunk of emails with enhanced parallel database operations.
     ///
     /// # Process
     /// 1. Extract and insert authors
     /// 2. Prepare and insert emails
     /// 3. Load email and recipient author IDs in parallel
-    /// 4. Insert recipients and references in parallel
+    /// 4. Insert recipients, references and `Fixes:` trailers, and run the
+    ///    registered email processors, in parallel
     /// 5. Extract cache data for threading
     ///
     /// # Optimizations
-    /// - Uses up to 6 parallel connections from the shared Nexus pool
+    /// - Uses up to 3 parallel connections from the shared Nexus pool, plus
+    ///   one per email processor
     /// - Parallelizes data loading operations where possible
     ///
     /// # Arguments
@@ -148,6 +161,14 @@ impl BulkImporter {
         );
         let references_data =
             data_builder::build_reference_batch_data(self.mailing_list_id, chunk, &email_id_map);
+        let processor_emails: Vec<ProcessorEmail> = chunk
+            .iter()
+            .filter_map(|(_, email, _)| {
+                email_id_map
+                    .get(&email.message_id)
+                    .map(|&email_id| ProcessorEmail::from_parsed(email_id, email))
+            })
+            .collect();
 
         let mut recipient_conn = self.pool.acquire().await?;
         let mut reference_conn = self.pool.acquire().await?;
@@ -155,14 +176,20 @@ impl BulkImporter {
         // Clone references_data before moving it to insert_references_batch
         let references_data_clone = references_data.clone();
 
-        let (recipient_count, reference_count) = tokio::try_join!(
+        let (recipient_count, reference_count, processed) = tokio::try_join!(
             database_operations::insert_recipients_batch(&mut recipient_conn, recipients_data),
             database_operations::insert_references_batch(
                 &mut reference_conn,
                 references_data_clone
             ),
+            self.processors
+                .process(&self.pool, self.mailing_list_id, processor_emails),
         )?;
 
+        for (name, count) in &processed {
+            log::trace!("chunk: email processor {} wrote {} rows", name, count);
+        }
+
         // Phase 5: Extract cache data
         let cache_data = data_builder::extract_cache_data_from_chunk(chunk, &email_id_map);
 
@@ -171,6 +198,7 @@ impl BulkImporter {
             emails: email_count,
             recipients: recipient_count,
             references: reference_count,
+            processed: processed.values().sum(),
             threads: 0,
             thread_memberships: 0,
         };
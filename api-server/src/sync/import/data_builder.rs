@@ -3,7 +3,6 @@
 //! Transforms parsed emails into columnar data structures optimized for
 //! PostgreSQL UNNEST bulk insert operations.
 
-use crate::search::sanitize::strip_patch_payload;
 use crate::sync::import::data_structures::{
     ChunkCacheData, EmailsData, RecipientsData, ReferencesData,
 };
@@ -104,19 +103,15 @@ pub async fn build_email_batch_data(
             data.message_ids.push(email.message_id.clone());
             data.commit_hashes.push(commit_hash.clone());
             data.author_ids.push(author_id);
+            data.author_names.push(email.author_name.clone());
             data.subjects.push(email.subject.clone());
             data.normalized_subjects
                 .push(email.normalized_subject.clone());
             data.dates.push(email.date);
             data.in_reply_tos.push(email.in_reply_to.clone());
             data.bodies.push(email.body.clone());
-            let sanitized = strip_patch_payload(
-                &email.body,
-                email.patch_metadata.as_ref(),
-                email.is_patch_only,
-            )
-            .into_owned();
-            data.search_bodies.push(sanitized);
+            data.authored_bodies.push(email.authored_body.clone());
+            data.search_bodies.push(email.search_body.clone());
 
             if let Some((series_id, series_num, series_total)) = series_info {
                 data.series_ids.push(Some(series_id));
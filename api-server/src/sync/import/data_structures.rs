@@ -16,11 +16,13 @@ pub struct EmailsData {
     pub message_ids: Vec<String>,
     pub commit_hashes: Vec<String>,
     pub author_ids: Vec<i32>,
+    pub author_names: Vec<String>,
     pub subjects: Vec<String>,
     pub normalized_subjects: Vec<String>,
     pub dates: Vec<DateTime<Utc>>,
     pub in_reply_tos: Vec<Option<String>>,
     pub bodies: Vec<String>,
+    pub authored_bodies: Vec<String>,
     pub search_bodies: Vec<String>,
     pub series_ids: Vec<Option<String>>,
     pub series_numbers: Vec<Option<i32>>,
@@ -80,9 +80,9 @@ pub async fn insert_emails_batch(
     let result = sqlx::query(
         r#"INSERT INTO emails (
             mailing_list_id, message_id, git_commit_hash, author_id,
-            subject, normalized_subject, date, in_reply_to, body, search_body,
+            subject, normalized_subject, date, in_reply_to, body, authored_body, search_body,
             series_id, series_number, series_total, epoch,
-            patch_type, is_patch_only, patch_metadata, lex_ts, body_ts
+            patch_type, is_patch_only, patch_metadata, author_name, lex_ts, body_ts
            )
            SELECT
                list_id,
@@ -94,6 +94,7 @@ pub async fn insert_emails_batch(
                mail_date,
                in_reply_to,
                body,
+               authored_body,
                 search_body,
                 series_id,
                 series_number,
@@ -102,6 +103,7 @@ pub async fn insert_emails_batch(
                 patch_type,
                 is_patch_only,
                 patch_metadata,
+                author_name,
                 to_tsvector('english',
                    COALESCE(subject, '') || ' ' || COALESCE(search_body, '')
                 ),
@@ -123,7 +125,9 @@ pub async fn insert_emails_batch(
                $14::int[],
                $15::patch_type[],
                $16::bool[],
-               $17::jsonb[]
+               $17::jsonb[],
+               $18::text[],
+               $19::text[]
            ) AS t (
                list_id,
                message_id,
@@ -141,7 +145,9 @@ pub async fn insert_emails_batch(
                epoch,
                patch_type,
                is_patch_only,
-               patch_metadata
+               patch_metadata,
+               authored_body,
+               author_name
            )
            ON CONFLICT (mailing_list_id, message_id) DO NOTHING"#,
     )
@@ -162,6 +168,8 @@ pub async fn insert_emails_batch(
     .bind(&data.patch_types)
     .bind(&data.is_patch_only)
     .bind(&data.patch_metadata)
+    .bind(&data.authored_bodies)
+    .bind(&data.author_names)
     .execute(&mut **conn)
     .await?;
 
@@ -15,6 +15,8 @@ pub struct ImportStats {
     pub recipients: usize,
     /// Number of reference records inserted
     pub references: usize,
+    /// Number of records written by email processors
+    pub processed: usize,
     /// Number of thread records inserted
     pub threads: usize,
     /// Number of thread membership records inserted
@@ -30,6 +32,7 @@ impl ImportStats {
         self.emails += other.emails;
         self.recipients += other.recipients;
         self.references += other.references;
+        self.processed += other.processed;
         self.threads += other.threads;
         self.thread_memberships += other.thread_memberships;
     }
@@ -0,0 +1,127 @@
+//! Built-in job handlers.
+//!
+//! The sync phases themselves live on [`SyncDispatcher`]; these handlers parse
+//! and validate payloads and hand off to them.
+//!
+//! [`SyncDispatcher`]: crate::sync::dispatcher::SyncDispatcher
+
+use crate::sync::jobs::{JobContext, JobFuture, JobHandler, parse_payload, payload_schema};
+use rocket_okapi::okapi::schemars::{JsonSchema, schema::RootSchema};
+use serde::Deserialize;
+use serde_json::Value;
+
+/// Payload for `import` jobs. The target mailing list is the job's own.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+pub struct ImportJobPayload {}
+
+/// Parses a mailing list's archive, threads it and refreshes everything
+/// derived from it.
+pub struct ImportJobHandler;
+
+impl JobHandler for ImportJobHandler {
+    fn name(&self) -> &'static str {
+        "import"
+    }
+
+    fn requires_mailing_list(&self) -> bool {
+        true
+    }
+
+    fn payload_schema(&self) -> RootSchema {
+        payload_schema::<ImportJobPayload>()
+    }
+
+    fn validate_payload(&self, payload: &Value) -> Result<(), String> {
+        parse_payload::<ImportJobPayload>(payload).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: JobContext<'a>) -> JobFuture<'a> {
+        Box::pin(async move { ctx.dispatcher().run_import_job(&ctx).await })
+    }
+}
+
+#[derive(Debug, Default, Deserialize, JsonSchema, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexJobAction {
+    /// Re-index threads (of one list, or all) and authors.
+    #[default]
+    Refresh,
+    /// Drop and rebuild every search index.
+    Reset,
+}
+
+/// Payload for `index_maintenance` jobs.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct IndexJobPayload {
+    pub action: IndexJobAction,
+    /// Limit a refresh to one mailing list when the job has none.
+    #[serde(rename = "mailingListSlug")]
+    pub mailing_list_slug: Option<String>,
+    /// Accepted for compatibility with older clients; has no effect.
+    pub reindex: bool,
+}
+
+/// Refreshes or rebuilds the Meilisearch indexes.
+pub struct IndexMaintenanceJobHandler;
+
+impl JobHandler for IndexMaintenanceJobHandler {
+    fn name(&self) -> &'static str {
+        "index_maintenance"
+    }
+
+    fn payload_schema(&self) -> RootSchema {
+        payload_schema::<IndexJobPayload>()
+    }
+
+    fn validate_payload(&self, payload: &Value) -> Result<(), String> {
+        parse_payload::<IndexJobPayload>(payload).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: JobContext<'a>) -> JobFuture<'a> {
+        Box::pin(async move {
+            let payload = parse_payload::<IndexJobPayload>(&ctx.job().payload)?;
+            ctx.dispatcher().run_index_job(&ctx, payload).await
+        })
+    }
+}
+
+/// Payload for `upstream_scan` jobs. Missing fields fall back to the
+/// `UPSTREAM_*` environment configuration.
+#[derive(Debug, Default, Deserialize, JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct UpstreamScanPayload {
+    #[serde(rename = "treeName")]
+    pub tree_name: Option<String>,
+    #[serde(rename = "repoPath")]
+    pub repo_path: Option<String>,
+    pub reference: Option<String>,
+    /// Commit or tag whose history is skipped (e.g. `v6.1`) on the first scan.
+    pub since: Option<String>,
+    #[serde(rename = "maxCommits")]
+    pub max_commits: Option<usize>,
+}
+
+/// Scans an upstream git tree for merged patches and pull requests.
+pub struct UpstreamScanJobHandler;
+
+impl JobHandler for UpstreamScanJobHandler {
+    fn name(&self) -> &'static str {
+        "upstream_scan"
+    }
+
+    fn payload_schema(&self) -> RootSchema {
+        payload_schema::<UpstreamScanPayload>()
+    }
+
+    fn validate_payload(&self, payload: &Value) -> Result<(), String> {
+        parse_payload::<UpstreamScanPayload>(payload).map(|_| ())
+    }
+
+    fn run<'a>(&'a self, ctx: JobContext<'a>) -> JobFuture<'a> {
+        Box::pin(async move {
+            let payload = parse_payload::<UpstreamScanPayload>(&ctx.job().payload)?;
+            ctx.dispatcher().run_upstream_scan(&ctx, payload).await
+        })
+    }
+}
@@ -0,0 +1,291 @@
+//! Job handlers run by the sync dispatcher.
+//!
+//! Every job kind is a [`JobHandler`] registered by name in a [`JobRegistry`].
+//! The dispatcher core only claims jobs, looks up their handler, re-validates
+//! the payload, runs the handler and records the outcome, so adding a job kind
+//! means registering a handler. The admin API validates payloads against the
+//! same handler before a job is queued and publishes each handler's payload
+//! schema.
+//!
+//! ## Module Structure
+//!
+//! - `handlers`: Built-in import, index maintenance and upstream scan handlers
+
+mod handlers;
+
+pub use handlers::{
+    ImportJobHandler, ImportJobPayload, IndexJobAction, IndexJobPayload,
+    IndexMaintenanceJobHandler, UpstreamScanJobHandler, UpstreamScanPayload,
+};
+
+use crate::search::SearchService;
+use crate::sync::dispatcher::SyncDispatcher;
+use crate::sync::queue::{Job, JobQueue, JobType};
+use rocket_db_pools::sqlx::PgPool;
+use rocket_okapi::okapi::schemars::{self, JsonSchema, schema::RootSchema};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Future returned by a handler run; errors become the job's error message.
+pub type JobFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + Send + 'a>>;
+
+/// What a running handler can reach: the claimed job and the dispatcher's
+/// database pool, queue and search service.
+pub struct JobContext<'a> {
+    dispatcher: &'a SyncDispatcher,
+    job: &'a Job,
+}
+
+impl<'a> JobContext<'a> {
+    pub(crate) fn new(dispatcher: &'a SyncDispatcher, job: &'a Job) -> Self {
+        Self { dispatcher, job }
+    }
+
+    pub fn job(&self) -> &'a Job {
+        self.job
+    }
+
+    pub fn dispatcher(&self) -> &'a SyncDispatcher {
+        self.dispatcher
+    }
+
+    pub fn pool(&self) -> &'a PgPool {
+        self.dispatcher.pool()
+    }
+
+    pub fn queue(&self) -> &'a JobQueue {
+        self.dispatcher.queue()
+    }
+
+    pub fn search(&self) -> &'a SearchService {
+        self.dispatcher.search()
+    }
+
+    /// Whether the job was cancelled; long-running handlers should check this
+    /// between batches and return early.
+    pub async fn is_cancelled(&self) -> bool {
+        self.queue()
+            .is_job_cancelled(self.job.id)
+            .await
+            .unwrap_or(false)
+    }
+
+    /// Fail once the job was cancelled, so handlers can stop between phases
+    /// with `?`.
+    pub async fn check_cancelled(&self) -> Result<(), String> {
+        if self.is_cancelled().await {
+            log::warn!("job {}: cancelled by user, stopping", self.job.id);
+            return Err("Job cancelled by user".to_string());
+        }
+        Ok(())
+    }
+
+    /// Publish progress on the job record. Failures are logged, not returned,
+    /// since progress is informational.
+    pub async fn report_progress(&self, progress: Value) {
+        if let Err(err) = self.queue().report_progress(self.job.id, &progress).await {
+            log::warn!("job {}: failed to report progress: {}", self.job.id, err);
+        }
+    }
+}
+
+/// Runs one kind of job.
+pub trait JobHandler: Send + Sync {
+    /// Job type name the handler is registered under.
+    fn name(&self) -> &'static str;
+
+    /// Whether jobs of this kind must target a mailing list.
+    fn requires_mailing_list(&self) -> bool {
+        false
+    }
+
+    /// JSON schema of the accepted payload.
+    fn payload_schema(&self) -> RootSchema;
+
+    /// Check a payload before the job is queued and again before it runs.
+    fn validate_payload(&self, payload: &Value) -> Result<(), String>;
+
+    /// Execute a claimed job. The dispatcher marks it succeeded or failed
+    /// afterwards unless it was cancelled meanwhile.
+    fn run<'a>(&'a self, ctx: JobContext<'a>) -> JobFuture<'a>;
+}
+
+/// Parse a payload into a handler's payload type; `null` yields its defaults.
+pub fn parse_payload<T: DeserializeOwned + Default>(payload: &Value) -> Result<T, String> {
+    if payload.is_null() {
+        return Ok(T::default());
+    }
+    serde_json::from_value(payload.clone()).map_err(|e| e.to_string())
+}
+
+/// Schema helper for [`JobHandler::payload_schema`].
+pub fn payload_schema<T: JsonSchema>() -> RootSchema {
+    schemars::schema_for!(T)
+}
+
+/// Description of a registered job type for the admin API.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct JobTypeInfo {
+    pub name: String,
+    pub requires_mailing_list: bool,
+    pub payload_schema: Value,
+}
+
+/// Job handlers by job type name.
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    handlers: BTreeMap<&'static str, Arc<dyn JobHandler>>,
+}
+
+impl JobRegistry {
+    /// Registry with every job handler shipped with the server.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(ImportJobHandler);
+        registry.register(IndexMaintenanceJobHandler);
+        registry.register(UpstreamScanJobHandler);
+        registry
+    }
+
+    /// Register a handler, replacing any handler with the same name.
+    pub fn register<H: JobHandler + 'static>(&mut self, handler: H) {
+        self.handlers.insert(handler.name(), Arc::new(handler));
+    }
+
+    pub fn get(&self, job_type: &JobType) -> Option<&dyn JobHandler> {
+        self.handlers
+            .get(job_type.as_str())
+            .map(|handler| handler.as_ref())
+    }
+
+    /// Look up the handler for a job and validate its target and payload.
+    pub fn validate(
+        &self,
+        job_type: &JobType,
+        mailing_list_id: Option<i32>,
+        payload: &Value,
+    ) -> Result<&dyn JobHandler, String> {
+        let handler = self
+            .get(job_type)
+            .ok_or_else(|| format!("Unknown job type '{job_type}'"))?;
+
+        if handler.requires_mailing_list() && mailing_list_id.is_none() {
+            return Err(format!("Job type '{job_type}' requires a mailing list"));
+        }
+
+        handler
+            .validate_payload(payload)
+            .map_err(|err| format!("Invalid {job_type} payload: {err}"))?;
+
+        Ok(handler)
+    }
+
+    /// Registered job types, ordered by name.
+    pub fn describe(&self) -> Vec<JobTypeInfo> {
+        self.handlers
+            .values()
+            .map(|handler| JobTypeInfo {
+                name: handler.name().to_string(),
+                requires_mailing_list: handler.requires_mailing_list(),
+                payload_schema: serde_json::to_value(handler.payload_schema())
+                    .unwrap_or(Value::Null),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn validates_builtin_payloads() {
+        let registry = JobRegistry::builtin();
+
+        assert!(
+            registry
+                .validate(&JobType::INDEX_MAINTENANCE, None, &Value::Null)
+                .is_ok()
+        );
+        assert!(
+            registry
+                .validate(
+                    &JobType::INDEX_MAINTENANCE,
+                    None,
+                    &json!({"action": "refresh", "mailingListSlug": "lkml"})
+                )
+                .is_ok()
+        );
+        assert!(
+            registry
+                .validate(
+                    &JobType::INDEX_MAINTENANCE,
+                    None,
+                    &json!({"action": "drop"})
+                )
+                .is_err()
+        );
+        assert!(
+            registry
+                .validate(&JobType::UPSTREAM_SCAN, None, &json!({"maxCommit": 10}))
+                .is_err()
+        );
+        assert!(
+            registry
+                .validate(&JobType::IMPORT, None, &json!({}))
+                .is_err()
+        );
+        assert!(
+            registry
+                .validate(&JobType::IMPORT, Some(1), &json!({}))
+                .is_ok()
+        );
+        // Payloads queued by other versions may carry fields this one ignores
+        assert!(
+            registry
+                .validate(&JobType::IMPORT, Some(1), &json!({"epochs": [0]}))
+                .is_ok()
+        );
+        // A misspelt list slug must not widen a refresh to every list
+        assert!(
+            registry
+                .validate(
+                    &JobType::INDEX_MAINTENANCE,
+                    None,
+                    &json!({"action": "refresh", "mailingListslug": "lkml"})
+                )
+                .is_err()
+        );
+        assert!(
+            registry
+                .validate(
+                    &JobType::INDEX_MAINTENANCE,
+                    None,
+                    &json!({"action": "refresh", "reindex": true})
+                )
+                .is_ok()
+        );
+        assert!(
+            registry
+                .validate(&JobType::new("embedding_refresh"), None, &Value::Null)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn describes_registered_types() {
+        let names: Vec<String> = JobRegistry::builtin()
+            .describe()
+            .into_iter()
+            .map(|info| info.name)
+            .collect();
+        assert_eq!(names, vec!["import", "index_maintenance", "upstream_scan"]);
+    }
+}
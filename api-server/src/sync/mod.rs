@@ -18,15 +18,25 @@
 //! - **`parser`**: Parses raw email content from Git blobs into structured data with
 //!   proper header extraction, sanitization, and subject normalization for threading.
 //!
+//! - **`quotes`**: Splits reply bodies into new text, quoted blocks and signature so
+//!   search only indexes what each sender actually wrote.
+//!
 //! - **`import`**: Handles bulk database imports with optimized batch operations,
 //!   author deduplication, and threading cache population.
 //!
+//! - **`processors`**: Registry of versioned email processors that derive enrichment
+//!   tables (pull requests, bot reports, ...) from each imported chunk, and re-run
+//!   over history when a processor's version changes.
+//!
 //! - **`database`**: Provides database utilities for partition management, checkpoints,
 //!   migrations, and cache persistence.
 //!
 //! - **`queue`**: Manages the sync job queue with job claiming, status updates, phase
 //!   tracking, and cancellation support.
 //!
+//! - **`jobs`**: Registry of named job handlers. Each handler validates its payload
+//!   against a JSON schema and runs one kind of job (import, index maintenance, ...).
+//!
 //! ## Data Flow
 //!
 //! The synchronization process follows this pipeline:
@@ -62,10 +72,13 @@ pub mod database;
 pub mod dispatcher;
 pub mod git;
 pub mod import;
+pub mod jobs;
 pub mod manifest;
 pub mod parser;
 pub mod pg_config;
+pub mod processors;
 pub mod queue;
+pub mod quotes;
 
 use crate::sync::git::{GitManager, MailingListSyncConfig};
 use crate::sync::parser::{ParsedEmail, parse_email};
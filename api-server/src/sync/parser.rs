@@ -54,6 +54,7 @@
 //! - No database I/O during parsing
 
 use crate::models::{PatchMetadata, PatchSection, PatchType};
+use crate::sync::quotes::email_texts;
 use chrono::{DateTime, Duration, Utc};
 use mailparse::{MailHeaderMap, ParsedMail, parse_mail};
 use regex::Regex;
@@ -73,6 +74,10 @@ pub struct ParsedEmail {
     pub author_name: String,
     pub author_email: String,
     pub body: String,
+    /// Text written by the sender, without quoted text or signature.
+    pub authored_body: String,
+    /// Authored text without any patch payload, used for full-text search.
+    pub search_body: String,
     pub to_addrs: Vec<(String, String)>, // (name, email)
     pub cc_addrs: Vec<(String, String)>, // (name, email)
     pub in_reply_to: Option<String>,
@@ -397,6 +402,7 @@ pub fn parse_email(blob_data: &[u8]) -> Result<ParsedEmail, ParseEmailError> {
     let body = sanitize_text(&extract_preferred_body(&parsed));
 
     let (patch_type, is_patch_only, patch_metadata) = analyze_patch(&parsed, &body);
+    let texts = email_texts(&body, patch_metadata.as_ref(), is_patch_only);
 
     // Parse recipients
     let to_addrs = parsed
@@ -433,6 +439,8 @@ pub fn parse_email(blob_data: &[u8]) -> Result<ParsedEmail, ParseEmailError> {
         author_name,
         author_email,
         body,
+        authored_body: texts.authored_body,
+        search_body: texts.search_body,
         to_addrs,
         cc_addrs,
         in_reply_to,
@@ -867,6 +875,46 @@ fn is_diff_content_line(line: &str) -> bool {
         || trimmed.starts_with("dissimilarity index")
 }
 
+/// Collect the file paths touched by the inline diffs in an email body.
+///
+/// Paths come from `diff --git a/<old> b/<new>` headers, falling back to the
+/// `---`/`+++` file markers for diffs generated without git. The `a/`/`b/`
+/// prefixes are stripped, `/dev/null` is ignored, and renames contribute both
+/// the old and new path. Order of first appearance is preserved.
+pub fn extract_touched_paths(body: &str) -> Vec<String> {
+    fn push_path(paths: &mut Vec<String>, raw: &str) {
+        let raw = raw.split('\t').next().unwrap_or_default().trim();
+        if raw.is_empty() || raw == "/dev/null" {
+            return;
+        }
+        let path = raw
+            .strip_prefix("a/")
+            .or_else(|| raw.strip_prefix("b/"))
+            .unwrap_or(raw);
+        if !path.is_empty() && !paths.iter().any(|existing| existing == path) {
+            paths.push(path.to_string());
+        }
+    }
+
+    let mut paths = Vec::new();
+    for line in body.lines() {
+        if let Some(rest) = line.strip_prefix("diff --git ") {
+            if let Some((old, new)) = rest.split_once(" b/") {
+                push_path(&mut paths, old);
+                push_path(&mut paths, &format!("b/{new}"));
+            }
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            push_path(&mut paths, rest);
+        } else if let Some(rest) = line.strip_prefix("--- ")
+            && (rest.starts_with("a/") || rest == "/dev/null")
+        {
+            push_path(&mut paths, rest);
+        }
+    }
+
+    paths
+}
+
 fn has_patch_attachment(parsed: &ParsedMail) -> bool {
     fn part_contains_patch(part: &ParsedMail) -> bool {
         if is_patch_attachment(part) {
@@ -1132,4 +1180,42 @@ Cover Letter\n";
         let err = parse_email(raw.as_bytes()).unwrap_err();
         assert!(matches!(err, ParseEmailError::FutureDate { .. }));
     }
+
+    #[test]
+    fn test_extract_touched_paths() {
+        let body = concat!(
+            "Fix the thing.\n",
+            "\n",
+            "Signed-off-by: Dev <dev@example.com>\n",
+            "---\n",
+            " kernel/bpf/verifier.c | 2 +-\n",
+            "\n",
+            "diff --git a/kernel/bpf/verifier.c b/kernel/bpf/verifier.c\n",
+            "index 1111111..2222222 100644\n",
+            "--- a/kernel/bpf/verifier.c\n",
+            "+++ b/kernel/bpf/verifier.c\n",
+            "@@ -1 +1 @@\n",
+            "-old\n",
+            "+new\n",
+            "diff --git a/tools/old.c b/tools/new.c\n",
+            "similarity index 90%\n",
+            "rename from tools/old.c\n",
+            "rename to tools/new.c\n",
+            "diff --git a/drivers/gone.c b/drivers/gone.c\n",
+            "deleted file mode 100644\n",
+            "--- a/drivers/gone.c\n",
+            "+++ /dev/null\n"
+        );
+
+        assert_eq!(
+            extract_touched_paths(body),
+            vec![
+                "kernel/bpf/verifier.c".to_string(),
+                "tools/old.c".to_string(),
+                "tools/new.c".to_string(),
+                "drivers/gone.c".to_string(),
+            ]
+        );
+        assert!(extract_touched_paths("no diff here").is_empty());
+    }
 }
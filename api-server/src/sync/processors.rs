@@ -0,0 +1,309 @@
+//! Pluggable post-import email processors.
+//!
+//! Enrichments that derive their own rows from an email (pull requests, bot
+//! reports, `#regzbot` commands, stable backports, `Fixes:` trailers, ...) or
+//! columns of the email itself (patch-ids, authored text) implement
+//! [`EmailProcessor`] and are registered in a [`ProcessorRegistry`] instead of
+//! being wired into the bulk importer by hand.
+//!
+//! Processors run over every imported chunk, one after another. Each one
+//! carries a version that is recorded per mailing list in
+//! `email_processor_versions`; when a processor's logic changes its version is
+//! bumped, and the next import job resets the processor's rows for the list
+//! and re-runs it over the stored history.
+
+use crate::backports::StableBackportProcessor;
+use crate::bots::BotReportProcessor;
+use crate::models::{PatchMetadata, PatchType};
+use crate::patches::{PatchFixesProcessor, PatchIdProcessor};
+use crate::pull_requests::PullRequestProcessor;
+use crate::regressions::RegzbotCommandProcessor;
+use crate::search::PatchCodeProcessor;
+use crate::sync::parser::ParsedEmail;
+use crate::sync::quotes::AuthoredBodyProcessor;
+use rocket_db_pools::sqlx::{self, PgPool, types::Json};
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+/// Emails read per batch when a processor re-runs over a list's history.
+const HISTORY_BATCH_SIZE: i64 = 5_000;
+
+/// Future returned by processor operations: the number of rows written.
+pub type ProcessorFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<usize, sqlx::Error>> + Send + 'a>>;
+
+/// Email fields available to processors.
+///
+/// Built from parsed emails during import and from the `emails` table when a
+/// processor re-runs over history, so processors only see what is stored.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct ProcessorEmail {
+    pub email_id: i32,
+    pub message_id: String,
+    pub subject: String,
+    pub body: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub in_reply_to: Option<String>,
+    pub patch_type: PatchType,
+    pub is_patch_only: bool,
+    pub patch_metadata: Option<Json<PatchMetadata>>,
+}
+
+impl ProcessorEmail {
+    /// Pair a parsed email with its database ID.
+    pub fn from_parsed(email_id: i32, email: &ParsedEmail) -> Self {
+        Self {
+            email_id,
+            message_id: email.message_id.clone(),
+            subject: email.subject.clone(),
+            body: email.body.clone(),
+            author_name: email.author_name.clone(),
+            author_email: email.author_email.clone(),
+            in_reply_to: email.in_reply_to.clone(),
+            patch_type: email.patch_type,
+            is_patch_only: email.is_patch_only,
+            patch_metadata: email.patch_metadata.clone().map(Json),
+        }
+    }
+}
+
+/// Derives rows in a processor-owned table from imported emails.
+///
+/// Implementations must be idempotent: the same email can be processed more
+/// than once (re-imports, overlapping epochs), so inserts should skip rows
+/// that already exist and column updates should skip unchanged values.
+pub trait EmailProcessor: Send + Sync {
+    /// Stable identifier, recorded alongside the version.
+    fn name(&self) -> &'static str;
+
+    /// Logic version. Bump it when the processor's output changes so that
+    /// already imported history is processed again.
+    fn version(&self) -> i32;
+
+    /// Write rows for a batch of emails from one mailing list.
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a>;
+
+    /// Delete every row the processor wrote for a mailing list. Processors
+    /// that overwrite email columns on every run have nothing to reset.
+    fn reset<'a>(&'a self, pool: &'a PgPool, mailing_list_id: i32) -> ProcessorFuture<'a>;
+}
+
+/// Ordered set of processors run after each import chunk.
+#[derive(Clone, Default)]
+pub struct ProcessorRegistry {
+    processors: Vec<Arc<dyn EmailProcessor>>,
+}
+
+impl ProcessorRegistry {
+    /// Registry with every processor shipped with the server.
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(PullRequestProcessor);
+        registry.register(BotReportProcessor);
+        registry.register(RegzbotCommandProcessor);
+        registry.register(StableBackportProcessor);
+        registry.register(PatchIdProcessor);
+        registry.register(PatchFixesProcessor);
+        registry.register(PatchCodeProcessor);
+        registry.register(AuthoredBodyProcessor);
+        registry
+    }
+
+    /// Add a processor. Names must be unique within a registry.
+    pub fn register<P: EmailProcessor + 'static>(&mut self, processor: P) {
+        debug_assert!(
+            self.processors
+                .iter()
+                .all(|existing| existing.name() != processor.name()),
+            "duplicate email processor {}",
+            processor.name()
+        );
+        self.processors.push(Arc::new(processor));
+    }
+
+    /// Run every processor over a batch of emails, one after another.
+    ///
+    /// Processors are not run concurrently: several of them update the same
+    /// `emails` rows, and concurrent transactions locking those rows in
+    /// different orders can deadlock.
+    ///
+    /// # Returns
+    /// Rows written per processor name
+    pub async fn process(
+        &self,
+        pool: &PgPool,
+        mailing_list_id: i32,
+        emails: Vec<ProcessorEmail>,
+    ) -> Result<HashMap<&'static str, usize>, sqlx::Error> {
+        let mut counts = HashMap::new();
+        if emails.is_empty() {
+            return Ok(counts);
+        }
+
+        for processor in &self.processors {
+            let written = processor.process(pool, mailing_list_id, &emails).await?;
+            counts.insert(processor.name(), written);
+        }
+
+        Ok(counts)
+    }
+
+    /// Record current versions for a list that has no emails yet.
+    ///
+    /// Called before a list's first import so that its processors are not
+    /// re-run over the history they are about to process chunk by chunk.
+    pub async fn initialize_versions(
+        &self,
+        pool: &PgPool,
+        mailing_list_id: i32,
+    ) -> Result<(), sqlx::Error> {
+        let (names, versions) = self.names_and_versions();
+
+        sqlx::query(
+            r#"INSERT INTO email_processor_versions (processor, mailing_list_id, version)
+               SELECT processor, $1, version
+               FROM UNNEST($2::text[], $3::int[]) AS p(processor, version)
+               WHERE NOT EXISTS (SELECT 1 FROM emails WHERE mailing_list_id = $1)
+               ON CONFLICT (processor, mailing_list_id) DO NOTHING"#,
+        )
+        .bind(mailing_list_id)
+        .bind(&names)
+        .bind(&versions)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Re-run processors whose recorded version for the list differs from
+    /// their current one (or was never recorded) over the list's history.
+    ///
+    /// A processor's rows are reset first; its version is only recorded once
+    /// every batch succeeded, so an interrupted run starts over next time.
+    ///
+    /// # Returns
+    /// Names of the processors that were re-run
+    pub async fn rerun_outdated(
+        &self,
+        pool: &PgPool,
+        mailing_list_id: i32,
+    ) -> Result<Vec<&'static str>, sqlx::Error> {
+        let recorded: HashMap<String, i32> = sqlx::query_as(
+            r#"SELECT processor, version
+               FROM email_processor_versions
+               WHERE mailing_list_id = $1"#,
+        )
+        .bind(mailing_list_id)
+        .fetch_all(pool)
+        .await?
+        .into_iter()
+        .collect();
+
+        let mut rerun = Vec::new();
+        for processor in &self.processors {
+            if recorded.get(processor.name()) == Some(&processor.version()) {
+                continue;
+            }
+
+            log::info!(
+                "list {}: re-running email processor {} v{} over history",
+                mailing_list_id,
+                processor.name(),
+                processor.version()
+            );
+            let written = rerun_over_history(pool, processor.as_ref(), mailing_list_id).await?;
+            log::info!(
+                "list {}: email processor {} wrote {} rows",
+                mailing_list_id,
+                processor.name(),
+                written
+            );
+
+            sqlx::query(
+                r#"INSERT INTO email_processor_versions (processor, mailing_list_id, version)
+                   VALUES ($1, $2, $3)
+                   ON CONFLICT (processor, mailing_list_id)
+                   DO UPDATE SET version = EXCLUDED.version, updated_at = NOW()"#,
+            )
+            .bind(processor.name())
+            .bind(mailing_list_id)
+            .bind(processor.version())
+            .execute(pool)
+            .await?;
+
+            rerun.push(processor.name());
+        }
+
+        Ok(rerun)
+    }
+
+    fn names_and_versions(&self) -> (Vec<String>, Vec<i32>) {
+        self.processors
+            .iter()
+            .map(|processor| (processor.name().to_string(), processor.version()))
+            .unzip()
+    }
+}
+
+async fn rerun_over_history(
+    pool: &PgPool,
+    processor: &dyn EmailProcessor,
+    mailing_list_id: i32,
+) -> Result<usize, sqlx::Error> {
+    processor.reset(pool, mailing_list_id).await?;
+
+    let mut last_id = 0;
+    let mut written = 0;
+    loop {
+        let emails: Vec<ProcessorEmail> = sqlx::query_as(
+            r#"SELECT e.id AS email_id, e.message_id, e.subject,
+                      COALESCE(e.body, '') AS body,
+                      COALESCE(e.author_name, a.canonical_name, '') AS author_name,
+                      a.email AS author_email, e.in_reply_to,
+                      e.patch_type, e.is_patch_only, e.patch_metadata
+               FROM emails e
+               JOIN authors a ON a.id = e.author_id
+               WHERE e.mailing_list_id = $1 AND e.id > $2
+               ORDER BY e.id
+               LIMIT $3"#,
+        )
+        .bind(mailing_list_id)
+        .bind(last_id)
+        .bind(HISTORY_BATCH_SIZE)
+        .fetch_all(pool)
+        .await?;
+
+        let Some(last) = emails.last() else {
+            break;
+        };
+        last_id = last.email_id;
+        written += processor.process(pool, mailing_list_id, &emails).await?;
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_processors_have_unique_names() {
+        let registry = ProcessorRegistry::builtin();
+        let (mut names, versions) = registry.names_and_versions();
+        assert!(versions.iter().all(|version| *version > 0));
+
+        let total = names.len();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), total);
+    }
+}
@@ -3,13 +3,58 @@ use rocket_db_pools::sqlx::{self, PgPool, Postgres, QueryBuilder};
 use rocket_okapi::okapi::schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::postgres::{PgArgumentBuffer, PgTypeInfo, PgValueRef};
+use std::borrow::Cow;
+use std::fmt;
+
+/// Name of a job kind, matching the `JobHandler` registered to run it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
+pub struct JobType(Cow<'static, str>);
+
+impl JobType {
+    pub const IMPORT: JobType = JobType(Cow::Borrowed("import"));
+    pub const INDEX_MAINTENANCE: JobType = JobType(Cow::Borrowed("index_maintenance"));
+    pub const UPSTREAM_SCAN: JobType = JobType(Cow::Borrowed("upstream_scan"));
+
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, sqlx::Type, PartialEq, Eq)]
-#[sqlx(type_name = "job_type", rename_all = "snake_case")]
-#[serde(rename_all = "snake_case")]
-pub enum JobType {
-    Import,
-    IndexMaintenance,
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for JobType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl sqlx::Type<Postgres> for JobType {
+    fn type_info() -> PgTypeInfo {
+        <String as sqlx::Type<Postgres>>::type_info()
+    }
+
+    fn compatible(ty: &PgTypeInfo) -> bool {
+        <String as sqlx::Type<Postgres>>::compatible(ty)
+    }
+}
+
+impl sqlx::Encode<'_, Postgres> for JobType {
+    fn encode_by_ref(&self, buf: &mut PgArgumentBuffer) -> IsNull {
+        <&str as sqlx::Encode<Postgres>>::encode(self.as_str(), buf)
+    }
+}
+
+impl<'r> sqlx::Decode<'r, Postgres> for JobType {
+    fn decode(value: PgValueRef<'r>) -> Result<Self, BoxDynError> {
+        let name = <String as sqlx::Decode<Postgres>>::decode(value)?;
+        Ok(Self(Cow::Owned(name)))
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema, sqlx::Type, PartialEq, Eq)]
@@ -31,6 +76,8 @@ pub struct JobRecord {
     pub status: JobStatus,
     pub priority: i32,
     pub payload: Value,
+    /// Last progress reported by the job's handler.
+    pub progress: Option<Value>,
     #[serde(rename = "mailingListId")]
     pub mailing_list_id: Option<i32>,
     #[serde(rename = "mailingListSlug")]
@@ -88,7 +135,7 @@ impl JobQueue {
 
         let mut data_builder = QueryBuilder::new(
             "SELECT \
-                j.id, j.job_type, j.status, j.priority, j.payload, \
+                j.id, j.job_type, j.status, j.priority, j.payload, j.progress, \
                 j.mailing_list_id, ml.slug AS mailing_list_slug, ml.name AS mailing_list_name, \
                 j.created_at, j.started_at, j.completed_at, j.last_heartbeat, j.error_message \
             FROM jobs j \
@@ -112,7 +159,7 @@ impl JobQueue {
     pub async fn get_job(&self, job_id: i32) -> Result<Option<JobRecord>, sqlx::Error> {
         let record = sqlx::query_as::<_, JobRecord>(
             r#"
-            SELECT j.id, j.job_type, j.status, j.priority, j.payload,
+            SELECT j.id, j.job_type, j.status, j.priority, j.payload, j.progress,
                    j.mailing_list_id, ml.slug AS mailing_list_slug, ml.name AS mailing_list_name,
                    j.created_at, j.started_at, j.completed_at, j.last_heartbeat, j.error_message
             FROM jobs j
@@ -177,7 +224,7 @@ impl JobQueue {
         let mut job_ids = Vec::new();
         for (list_id,) in list_ids {
             let job_id = self
-                .enqueue_job(JobType::Import, Some(list_id), Value::Null, 0)
+                .enqueue_job(JobType::IMPORT, Some(list_id), Value::Null, 0)
                 .await?;
             job_ids.push(job_id);
         }
@@ -187,7 +234,7 @@ impl JobQueue {
 
     /// Enqueue a single import job for a mailing list
     pub async fn enqueue_import_job(&self, mailing_list_id: i32) -> Result<i32, sqlx::Error> {
-        self.enqueue_job(JobType::Import, Some(mailing_list_id), Value::Null, 0)
+        self.enqueue_job(JobType::IMPORT, Some(mailing_list_id), Value::Null, 0)
             .await
     }
 
@@ -249,10 +296,10 @@ impl JobQueue {
         }
     }
 
-    /// Mark job complete
+    /// Mark job complete (no-op once the job was cancelled)
     pub async fn complete_job(&self, job_id: i32) -> Result<(), sqlx::Error> {
         sqlx::query(
-            "UPDATE jobs SET status = 'succeeded', completed_at = NOW(), last_heartbeat = NOW() WHERE id = $1",
+            "UPDATE jobs SET status = 'succeeded', completed_at = NOW(), last_heartbeat = NOW() WHERE id = $1 AND status = 'running'",
         )
             .bind(job_id)
             .execute(&self.pool)
@@ -261,10 +308,10 @@ impl JobQueue {
         Ok(())
     }
 
-    /// Mark job failed
+    /// Mark job failed (no-op once the job was cancelled)
     pub async fn fail_job(&self, job_id: i32, error: String) -> Result<(), sqlx::Error> {
         sqlx::query(
-            "UPDATE jobs SET status = 'failed', completed_at = NOW(), error_message = $1, last_heartbeat = NOW() WHERE id = $2",
+            "UPDATE jobs SET status = 'failed', completed_at = NOW(), error_message = $1, last_heartbeat = NOW() WHERE id = $2 AND status = 'running'",
         )
         .bind(error)
         .bind(job_id)
@@ -284,6 +331,17 @@ impl JobQueue {
         Ok(())
     }
 
+    /// Record handler-reported progress, which also counts as a heartbeat.
+    pub async fn report_progress(&self, job_id: i32, progress: &Value) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE jobs SET progress = $1, last_heartbeat = NOW() WHERE id = $2")
+            .bind(progress)
+            .bind(job_id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+
     /// Cancel all queued jobs (waiting only)
     pub async fn cancel_queued_jobs(&self) -> Result<u64, sqlx::Error> {
         let result = sqlx::query(
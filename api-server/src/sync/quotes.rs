@@ -0,0 +1,326 @@
+//! Split reply bodies into new text, quoted blocks and the signature.
+//!
+//! Replies usually quote most of the message they answer, so indexing full
+//! bodies repeats the same text once per reply. The segmenter keeps the
+//! sender's own text apart: quoted lines (`>`) are grouped into blocks together
+//! with the attribution line that introduces them (`On Mon, Jane wrote:`), and
+//! everything below the last `-- ` delimiter is the signature.
+
+use crate::models::{BodySegment, BodySegmentKind, PatchMetadata};
+use crate::search::sanitize::strip_patch_payload;
+use crate::sync::processors::{EmailProcessor, ProcessorEmail, ProcessorFuture};
+use rocket_db_pools::sqlx::{self, PgPool};
+
+/// Split `body` into text, quote and signature segments.
+///
+/// Blank-only text between quotes is dropped, so segments don't necessarily
+/// cover every line of the body.
+pub fn split_body(body: &str) -> Vec<BodySegment> {
+    let lines: Vec<&str> = body.lines().collect();
+    let signature_start = lines.iter().rposition(|line| *line == "-- ");
+    let content_end = signature_start.unwrap_or(lines.len());
+
+    let mut segments = Vec::new();
+    let mut text_start = 0;
+    let mut index = 0;
+
+    while index < content_end {
+        if !is_quoted(lines[index]) {
+            index += 1;
+            continue;
+        }
+
+        let quote_start = index;
+        while index < content_end && is_quoted(lines[index]) {
+            index += 1;
+        }
+
+        let attribution_start =
+            attribution_start(&lines[text_start..quote_start]).map(|offset| text_start + offset);
+        let text_end = attribution_start.unwrap_or(quote_start);
+        push_text(&mut segments, &lines, text_start, text_end);
+
+        let attribution = attribution_start.map(|start| {
+            lines[start..quote_start]
+                .iter()
+                .map(|line| line.trim())
+                .filter(|line| !line.is_empty())
+                .collect::<Vec<_>>()
+                .join(" ")
+        });
+        segments.push(BodySegment {
+            kind: BodySegmentKind::Quote,
+            attribution,
+            start_line: attribution_start.unwrap_or(quote_start),
+            end_line: index - 1,
+            content: lines[quote_start..index].join("\n"),
+        });
+
+        text_start = index;
+    }
+    push_text(&mut segments, &lines, text_start, content_end);
+
+    if let Some(start) = signature_start {
+        let signature = lines[start + 1..].join("\n");
+        if !signature.trim().is_empty() {
+            segments.push(BodySegment {
+                kind: BodySegmentKind::Signature,
+                attribution: None,
+                start_line: start,
+                end_line: lines.len() - 1,
+                content: signature.trim_end().to_string(),
+            });
+        }
+    }
+
+    segments
+}
+
+/// Text written by the sender: all text segments, without quotes or signature.
+pub fn authored_text(segments: &[BodySegment]) -> String {
+    segments
+        .iter()
+        .filter(|segment| segment.kind == BodySegmentKind::Text)
+        .map(|segment| segment.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Derived text columns of an email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmailTexts {
+    /// Text written by the sender, without quoted text or signature.
+    pub authored_body: String,
+    /// Authored text without any patch payload, used for full-text search.
+    pub search_body: String,
+}
+
+/// Compute the authored and search text of an email.
+///
+/// Used both when parsing new emails and when the authored text processor
+/// re-runs over history, so both paths store the same text. The payload is
+/// stripped from the full body first since patch metadata addresses lines of
+/// the full body.
+pub fn email_texts(
+    body: &str,
+    patch_metadata: Option<&PatchMetadata>,
+    is_patch_only: bool,
+) -> EmailTexts {
+    let authored_body = authored_text(&split_body(body));
+    let stripped = strip_patch_payload(body, patch_metadata, is_patch_only);
+    let search_body = authored_text(&split_body(&stripped));
+
+    EmailTexts {
+        authored_body,
+        search_body,
+    }
+}
+
+/// Email processor storing the authored text of emails and rebuilding their
+/// search text from it.
+///
+/// Imports already store both when inserting emails, so only rows whose stored
+/// text differs are updated; the processor mainly exists so that history is
+/// reprocessed when the segmenter changes.
+pub struct AuthoredBodyProcessor;
+
+impl EmailProcessor for AuthoredBodyProcessor {
+    fn name(&self) -> &'static str {
+        "authored_bodies"
+    }
+
+    fn version(&self) -> i32 {
+        1
+    }
+
+    fn process<'a>(
+        &'a self,
+        pool: &'a PgPool,
+        mailing_list_id: i32,
+        emails: &'a [ProcessorEmail],
+    ) -> ProcessorFuture<'a> {
+        Box::pin(async move {
+            if emails.is_empty() {
+                return Ok(0);
+            }
+
+            let mut ids = Vec::with_capacity(emails.len());
+            let mut authored_bodies = Vec::with_capacity(emails.len());
+            let mut search_bodies = Vec::with_capacity(emails.len());
+            for email in emails {
+                let texts = email_texts(
+                    &email.body,
+                    email.patch_metadata.as_ref().map(|json| &json.0),
+                    email.is_patch_only,
+                );
+                ids.push(email.email_id);
+                authored_bodies.push(texts.authored_body);
+                search_bodies.push(texts.search_body);
+            }
+
+            let result = sqlx::query(
+                r#"UPDATE emails e
+                   SET authored_body = b.authored_body,
+                       search_body = b.search_body,
+                       lex_ts = to_tsvector('english',
+                           COALESCE(e.subject, '') || ' ' || COALESCE(b.search_body, '')
+                       ),
+                       body_ts = to_tsvector('english', COALESCE(b.search_body, ''))
+                   FROM UNNEST($2::int[], $3::text[], $4::text[])
+                       AS b(id, authored_body, search_body)
+                   WHERE e.mailing_list_id = $1
+                     AND e.id = b.id
+                     AND (e.authored_body IS DISTINCT FROM b.authored_body
+                          OR e.search_body IS DISTINCT FROM b.search_body)"#,
+            )
+            .bind(mailing_list_id)
+            .bind(&ids)
+            .bind(&authored_bodies)
+            .bind(&search_bodies)
+            .execute(pool)
+            .await?;
+
+            log::trace!("updated authored text of {} emails", result.rows_affected());
+            Ok(result.rows_affected() as usize)
+        })
+    }
+
+    fn reset<'a>(&'a self, _pool: &'a PgPool, _mailing_list_id: i32) -> ProcessorFuture<'a> {
+        // Stale text is overwritten when the processor re-runs
+        Box::pin(async { Ok(0) })
+    }
+}
+
+fn is_quoted(line: &str) -> bool {
+    line.starts_with('>')
+}
+
+/// Offset of the attribution introducing a quote, given the lines between the
+/// previous quote and this one.
+///
+/// Accepts one- and two-line forms (`On ..., Jane <jane@example.org>` wrapped
+/// before `wrote:`) optionally followed by blank lines.
+fn attribution_start(lines: &[&str]) -> Option<usize> {
+    let last = lines.iter().rposition(|line| !line.trim().is_empty())?;
+    let line = lines[last].trim();
+    if !is_attribution_end(line) {
+        return None;
+    }
+
+    if last > 0 {
+        let previous = lines[last - 1].trim();
+        if previous.starts_with("On ") && !is_attribution_end(previous) {
+            return Some(last - 1);
+        }
+    }
+    Some(last)
+}
+
+fn is_attribution_end(line: &str) -> bool {
+    let lower = line.to_ascii_lowercase();
+    lower.ends_with("wrote:")
+        || lower.ends_with("writes:")
+        || (lower.starts_with("quoting ") && lower.ends_with(':'))
+}
+
+fn push_text(segments: &mut Vec<BodySegment>, lines: &[&str], start: usize, end: usize) {
+    let Some(first) = (start..end).find(|&i| !lines[i].trim().is_empty()) else {
+        return;
+    };
+    let last = (start..end)
+        .rfind(|&i| !lines[i].trim().is_empty())
+        .unwrap_or(first);
+
+    segments.push(BodySegment {
+        kind: BodySegmentKind::Text,
+        attribution: None,
+        start_line: first,
+        end_line: last,
+        content: lines[first..=last].join("\n"),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(segments: &[BodySegment]) -> Vec<BodySegmentKind> {
+        segments.iter().map(|segment| segment.kind).collect()
+    }
+
+    #[test]
+    fn splits_interleaved_reply() {
+        let body = "Hi,\n\nOn Mon, 1 Jan 2024, Jane Doe wrote:\n> first point\n> more\n\nAgreed.\n\n> second point\n\nNot sure.\n\n-- \nJohn\n";
+        let segments = split_body(body);
+
+        assert_eq!(
+            kinds(&segments),
+            vec![
+                BodySegmentKind::Text,
+                BodySegmentKind::Quote,
+                BodySegmentKind::Text,
+                BodySegmentKind::Quote,
+                BodySegmentKind::Text,
+                BodySegmentKind::Signature,
+            ]
+        );
+        assert_eq!(
+            segments[1].attribution.as_deref(),
+            Some("On Mon, 1 Jan 2024, Jane Doe wrote:")
+        );
+        assert_eq!(segments[1].start_line, 2);
+        assert_eq!(segments[1].end_line, 4);
+        assert_eq!(segments[1].content, "> first point\n> more");
+        assert_eq!(segments[3].attribution, None);
+        assert_eq!(segments[5].content, "John");
+        assert_eq!(authored_text(&segments), "Hi,\n\nAgreed.\n\nNot sure.");
+    }
+
+    #[test]
+    fn joins_wrapped_attribution() {
+        let body = "On Tue, Jan 2, 2024 at 10:00 AM Jane Doe\n<jane@example.org> wrote:\n>\n> quoted\n\nreply";
+        let segments = split_body(body);
+
+        assert_eq!(
+            kinds(&segments),
+            vec![BodySegmentKind::Quote, BodySegmentKind::Text]
+        );
+        assert_eq!(
+            segments[0].attribution.as_deref(),
+            Some("On Tue, Jan 2, 2024 at 10:00 AM Jane Doe <jane@example.org> wrote:")
+        );
+        assert_eq!(segments[0].start_line, 0);
+        assert_eq!(authored_text(&segments), "reply");
+    }
+
+    #[test]
+    fn plain_message_is_single_text_segment() {
+        let body = "Just a message\nwith two lines\n";
+        let segments = split_body(body);
+
+        assert_eq!(kinds(&segments), vec![BodySegmentKind::Text]);
+        assert_eq!(authored_text(&segments), "Just a message\nwith two lines");
+    }
+
+    #[test]
+    fn only_last_delimiter_starts_signature() {
+        let body = "text\n-- \nnot a signature\n-- \n2.43.0\n";
+        let segments = split_body(body);
+
+        assert_eq!(
+            kinds(&segments),
+            vec![BodySegmentKind::Text, BodySegmentKind::Signature]
+        );
+        assert_eq!(segments[0].content, "text\n-- \nnot a signature");
+        assert_eq!(segments[1].content, "2.43.0");
+    }
+
+    #[test]
+    fn search_text_drops_patch_payload() {
+        let body = "Fix the frobnicator.\n\ndiff --git a/foo.c b/foo.c\nindex 111..222 100644\n--- a/foo.c\n+++ b/foo.c\n@@ -1 +1 @@\n-old\n+new\n";
+        let texts = email_texts(body, None, false);
+
+        assert!(texts.authored_body.contains("+new"));
+        assert_eq!(texts.search_body, "Fix the frobnicator.");
+    }
+}
@@ -33,6 +33,67 @@ fn get_patch_regex() -> &'static Regex {
     })
 }
 
+/// Lazy-initialized regex for the bracketed `[... PATCH ...]` subject prefix
+static PATCH_PREFIX_REGEX: OnceLock<Regex> = OnceLock::new();
+
+/// Lazy-initialized regex for a `vN` token inside the subject prefix
+static VERSION_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn get_patch_prefix_regex() -> &'static Regex {
+    PATCH_PREFIX_REGEX.get_or_init(|| {
+        Regex::new(r"(?i)\[([^\]]*\bPATCH\b[^\]]*)\]").expect("Invalid patch prefix regex")
+    })
+}
+
+fn get_version_regex() -> &'static Regex {
+    VERSION_REGEX.get_or_init(|| Regex::new(r"(?i)\bv(\d+)\b").expect("Invalid version regex"))
+}
+
+/// Extract the revision of a patch or series from its subject prefix
+///
+/// Unlike [`extract_patch_series_info`], this also handles single patches without
+/// an `N/M` counter. Patches without an explicit `vN` tag are version 1.
+///
+/// ## Returns
+///
+/// `Some(version)` if the subject carries a `[PATCH ...]` prefix, `None` otherwise.
+///
+/// ## Examples
+///
+/// ```rust
+/// use api_server::threading::patch_series::extract_patch_version;
+///
+/// assert_eq!(extract_patch_version("[PATCH] Fix memory leak"), Some(1));
+/// assert_eq!(extract_patch_version("[PATCH net-next v3 2/4] Add feature"), Some(3));
+/// assert_eq!(extract_patch_version("Regular email subject"), None);
+/// ```
+pub fn extract_patch_version(subject: &str) -> Option<i32> {
+    let prefix = get_patch_prefix_regex().captures(subject)?.get(1)?.as_str();
+
+    Some(
+        get_version_regex()
+            .captures(prefix)
+            .and_then(|caps| caps.get(1)?.as_str().parse::<i32>().ok())
+            .unwrap_or(1),
+    )
+}
+
+/// Whether the subject prefix marks the patch as a Request For Comments
+///
+/// Matches both `[RFC PATCH ...]` and `[PATCH RFC ...]` forms.
+pub fn is_rfc_subject(subject: &str) -> bool {
+    get_patch_prefix_regex()
+        .captures(subject)
+        .and_then(|caps| caps.get(1))
+        .map(|prefix| {
+            prefix
+                .as_str()
+                .split(|c: char| c.is_whitespace() || c == ',')
+                .any(|token| token.eq_ignore_ascii_case("RFC"))
+        })
+        .unwrap_or(false)
+}
+
 /// Extract patch series information from an email subject
 ///
 /// ## Returns
@@ -116,6 +177,27 @@ mod tests {
         assert_eq!(result, Some(("".to_string(), 1, 3)));
     }
 
+    #[test]
+    fn test_extract_patch_version() {
+        assert_eq!(extract_patch_version("[PATCH] Single fix"), Some(1));
+        assert_eq!(extract_patch_version("[PATCH v2] Single fix"), Some(2));
+        assert_eq!(extract_patch_version("[RFC PATCH v4 0/7] Cover"), Some(4));
+        assert_eq!(
+            extract_patch_version("[PATCH bpf-next V10 3/9] Feature"),
+            Some(10)
+        );
+        assert_eq!(extract_patch_version("Re: [PATCH v2] Single fix"), Some(2));
+        assert_eq!(extract_patch_version("[GIT PULL] net"), None);
+    }
+
+    #[test]
+    fn test_is_rfc_subject() {
+        assert!(is_rfc_subject("[RFC PATCH 1/3] Experimental feature"));
+        assert!(is_rfc_subject("[PATCH RFC v2] Experimental feature"));
+        assert!(!is_rfc_subject("[PATCH v2] RFC: not in the prefix"));
+        assert!(!is_rfc_subject("[RFC] no patch tag"));
+    }
+
     #[test]
     fn test_extract_no_patch() {
         let result = extract_patch_series_info("Regular email subject");
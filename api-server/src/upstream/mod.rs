@@ -0,0 +1,61 @@
+//! Upstream merge detection.
+//!
+//! Scans a local git tree (e.g. a mainline kernel mirror) for commits, matches
+//! them to archived patch emails, marks matched patches as accepted, and
+//! records the first release tag that contains each commit. Pull requests
+//! whose head commit is reachable from the scanned reference are marked merged.
+//!
+//! Commits are matched by, in order of confidence:
+//! 1. an identical stable `git patch-id`
+//! 2. a `Link:`/`Message-ID:` trailer naming the email's Message-ID
+//! 3. the same normalized subject and author email, newest posting only
+//!
+//! ## Module Structure
+//!
+//! - `scanner`: Walks git history and reads release tags (blocking, gix)
+//! - `store`: Matches commits to emails and persists applications
+
+mod scanner;
+mod store;
+
+pub use scanner::{
+    ReleaseVersion, ScanOptions, ScanResult, UpstreamCommit, contained_commits, first_release_tags,
+    link_message_ids, message_id_from_url, scan_commits,
+};
+pub use store::{
+    CommitMatch, finish_scan, match_commits, record_applications, unreleased_commits,
+    update_release_tags, upsert_tree,
+};
+
+use std::env;
+
+/// Upstream tree configured through the environment.
+///
+/// - `UPSTREAM_GIT_PATH`: path to the git repository (required)
+/// - `UPSTREAM_GIT_REF`: branch or ref to scan, default `HEAD`
+/// - `UPSTREAM_TREE_NAME`: name stored with each match, default `mainline`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpstreamTreeConfig {
+    pub name: String,
+    pub repo_path: String,
+    pub reference: String,
+}
+
+impl UpstreamTreeConfig {
+    /// Build the configuration from environment variables, or `None` if not configured.
+    pub fn from_env() -> Option<Self> {
+        let repo_path = non_empty_env("UPSTREAM_GIT_PATH")?;
+        Some(Self {
+            name: non_empty_env("UPSTREAM_TREE_NAME").unwrap_or_else(|| "mainline".to_string()),
+            repo_path,
+            reference: non_empty_env("UPSTREAM_GIT_REF").unwrap_or_else(|| "HEAD".to_string()),
+        })
+    }
+}
+
+fn non_empty_env(key: &str) -> Option<String> {
+    env::var(key)
+        .ok()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
@@ -0,0 +1,709 @@
+//! Read commits and release tags from an upstream git tree with gix.
+//!
+//! Everything here is blocking; call from `spawn_blocking` in async contexts.
+
+use crate::patches::compute_patch_id;
+use chrono::{DateTime, TimeZone, Utc};
+use gix::ObjectId;
+use gix::diff::blob::{Algorithm, UnifiedDiffBuilder, diff, intern::InternedInput};
+use gix::object::tree::diff::{Action, Change};
+use gix::objs::tree::EntryMode;
+use gix::revision::walk::Sorting;
+use gix::traverse::commit::simple::CommitTimeOrder;
+use regex::Regex;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::convert::Infallible;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// Allowed clock skew when pruning history walks by commit time.
+const CLOCK_SKEW_SECS: i64 = 24 * 60 * 60;
+
+/// Object cache for history walks, which look up every commit twice.
+const OBJECT_CACHE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Non-merge commit read from the upstream tree.
+#[derive(Debug, Clone)]
+pub struct UpstreamCommit {
+    pub hash: String,
+    pub subject: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub committed_at: DateTime<Utc>,
+    /// Message-IDs referenced by `Link:` / `Message-ID:` trailers.
+    pub link_message_ids: Vec<String>,
+    /// Stable patch-id of the commit's diff against its parent.
+    pub patch_id: Option<String>,
+}
+
+/// Bounds for a commit scan.
+#[derive(Debug, Clone)]
+pub struct ScanOptions {
+    /// Revision to scan from (branch, tag, or commit).
+    pub reference: String,
+    /// Revisions whose history is excluded (`exclude..reference`), e.g. the
+    /// head of the previous scan.
+    pub exclude: Vec<String>,
+    /// Maximum number of non-merge commits to read. Larger ranges are read
+    /// oldest part first, over several scans.
+    pub max_commits: usize,
+}
+
+/// Commits reachable from the scanned reference, newest first.
+#[derive(Debug, Clone)]
+pub struct ScanResult {
+    /// Commit whose history the scan covered; the next scan resumes from it.
+    /// Older than the reference when the scan was truncated.
+    pub head: String,
+    /// Whether commits newer than `head` are left for the next scan.
+    pub truncated: bool,
+    pub commits: Vec<UpstreamCommit>,
+}
+
+/// Read the non-merge commits in `exclude..reference`.
+///
+/// When the range holds more than `max_commits` non-merge commits, only
+/// `exclude..tip` is read, where `tip` is the commit with `max_commits`
+/// non-merge commits at or below it in commit-time order. Saving `tip` as
+/// the next scan's exclude then continues with newer history instead of
+/// skipping the commits past the limit.
+pub fn scan_commits(repo_path: &Path, options: &ScanOptions) -> Result<ScanResult, String> {
+    let repo = open_repository(repo_path)?;
+
+    let head = resolve_commit_id(&repo, &options.reference)?;
+    let mut exclude = Vec::new();
+    for revision in &options.exclude {
+        match resolve_commit_id(&repo, revision) {
+            Ok(id) => exclude.push(id),
+            Err(e) => log::warn!("upstream scan: ignoring exclude '{}': {}", revision, e),
+        }
+    }
+
+    let mut range = walk_range(&repo, head, &exclude)?;
+    let mut tip = head;
+    if let Some(cutoff) = truncation_tip(&range, options.max_commits) {
+        tip = cutoff;
+        range = walk_range(&repo, tip, &exclude)?;
+    }
+
+    let mut diff_cache = repo
+        .diff_resource_cache_for_tree_diff()
+        .map_err(|e| format!("Failed to prepare tree diffs: {}", e))?;
+    let mut commits = Vec::new();
+    for (id, is_merge) in range {
+        if !is_merge {
+            commits.push(read_commit(
+                &repo,
+                &find_commit(&repo, id)?,
+                &mut diff_cache,
+            )?);
+            diff_cache.clear_resource_cache_keep_allocation();
+        }
+    }
+
+    Ok(ScanResult {
+        head: tip.to_hex().to_string(),
+        truncated: tip != head,
+        commits,
+    })
+}
+
+/// Commits in `exclude..tip`, newest first, with whether each is a merge.
+fn walk_range(
+    repo: &gix::Repository,
+    tip: ObjectId,
+    exclude: &[ObjectId],
+) -> Result<Vec<(ObjectId, bool)>, String> {
+    repo.rev_walk([tip])
+        .with_hidden(exclude.iter().copied())
+        .sorting(Sorting::ByCommitTime(CommitTimeOrder::NewestFirst))
+        .all()
+        .map_err(|e| format!("Failed to walk history of {}: {}", tip, e))?
+        .map(|info| {
+            let info = info.map_err(|e| format!("Failed to walk history of {}: {}", tip, e))?;
+            Ok((info.id, info.parent_ids.len() > 1))
+        })
+        .collect()
+}
+
+/// Commits reachable from `tip` but not from `hidden`, newest first, that
+/// were committed at or after `min_time`.
+fn walk_since<'repo>(
+    repo: &'repo gix::Repository,
+    tip: ObjectId,
+    hidden: &[ObjectId],
+    min_time: i64,
+) -> Result<impl Iterator<Item = Result<ObjectId, String>> + 'repo, String> {
+    let walk = repo
+        .rev_walk([tip])
+        .with_hidden(hidden.iter().copied())
+        .sorting(Sorting::ByCommitTimeCutoff {
+            order: CommitTimeOrder::NewestFirst,
+            seconds: min_time,
+        })
+        .all()
+        .map_err(|e| format!("Failed to walk history: {}", e))?;
+    Ok(walk.map(|info| {
+        info.map(|info| info.id)
+            .map_err(|e| format!("Failed to walk history: {}", e))
+    }))
+}
+
+/// Newest commit of a newest-first `range` to scan up to so that at most
+/// `max_commits` non-merge commits are read, or `None` if all fit.
+fn truncation_tip(range: &[(ObjectId, bool)], max_commits: usize) -> Option<ObjectId> {
+    let non_merges = range.iter().filter(|(_, is_merge)| !is_merge).count();
+    if non_merges <= max_commits {
+        return None;
+    }
+
+    let mut remaining = non_merges - max_commits;
+    for (id, is_merge) in range {
+        if remaining == 0 {
+            return Some(*id);
+        }
+        if !is_merge {
+            remaining -= 1;
+        }
+    }
+    range.last().map(|(id, _)| *id)
+}
+
+/// Find the first release tag (e.g. `v6.8-rc1`) that contains each commit.
+///
+/// Commits not contained in any release tag yet are omitted from the result.
+pub fn first_release_tags(
+    repo_path: &Path,
+    commit_hashes: &[String],
+) -> Result<HashMap<String, String>, String> {
+    let repo = open_repository(repo_path)?;
+
+    let Some((mut remaining, min_time)) = existing_commits(&repo, commit_hashes)? else {
+        return Ok(HashMap::new());
+    };
+
+    // Tags older than every commit can't contain any of them
+    let mut tags = Vec::new();
+    for (version, name, id) in release_tags(&repo)? {
+        if commit_seconds(&find_commit(&repo, id)?)? >= min_time {
+            tags.push((version, name, id));
+        }
+    }
+    tags.sort_by(|a, b| a.0.cmp(&b.0));
+
+    // Each tag is credited with the commits no earlier tag contains
+    let mut tagged = HashMap::new();
+    let mut earlier_tags = Vec::new();
+    for (_, name, tag_commit) in tags {
+        for id in walk_since(&repo, tag_commit, &earlier_tags, min_time)? {
+            let id = id?;
+            if remaining.remove(&id) {
+                tagged.insert(id.to_hex().to_string(), name.clone());
+                if remaining.is_empty() {
+                    return Ok(tagged);
+                }
+            }
+        }
+        earlier_tags.push(tag_commit);
+    }
+
+    Ok(tagged)
+}
+
+/// Find which of `commit_hashes` are reachable from `reference`.
+///
+/// Commits missing from the repository are never contained.
+pub fn contained_commits(
+    repo_path: &Path,
+    reference: &str,
+    commit_hashes: &[String],
+) -> Result<HashSet<String>, String> {
+    let repo = open_repository(repo_path)?;
+
+    let mut contained = HashSet::new();
+    let Some((mut remaining, min_time)) = existing_commits(&repo, commit_hashes)? else {
+        return Ok(contained);
+    };
+
+    let tip = resolve_commit_id(&repo, reference)?;
+    for id in walk_since(&repo, tip, &[], min_time)? {
+        let id = id?;
+        if remaining.remove(&id) {
+            contained.insert(id.to_hex().to_string());
+            if remaining.is_empty() {
+                break;
+            }
+        }
+    }
+
+    Ok(contained)
+}
+
+/// The commits of `commit_hashes` present in the repository, and the commit
+/// time (minus [`CLOCK_SKEW_SECS`]) below which history can't contain them.
+///
+/// Returns `None` when none of them is present.
+fn existing_commits(
+    repo: &gix::Repository,
+    commit_hashes: &[String],
+) -> Result<Option<(HashSet<ObjectId>, i64)>, String> {
+    let mut commits: HashSet<ObjectId> = HashSet::new();
+    let mut min_time = i64::MAX;
+    for hash in commit_hashes {
+        let Ok(id) = ObjectId::from_hex(hash.as_bytes()) else {
+            continue;
+        };
+        let Ok(commit) = find_commit(repo, id) else {
+            continue;
+        };
+        min_time = min_time.min(commit_seconds(&commit)?);
+        commits.insert(id);
+    }
+
+    Ok((!commits.is_empty()).then(|| (commits, min_time - CLOCK_SKEW_SECS)))
+}
+
+/// Extract Message-IDs from `Link:` (lore/patch.msgid.link URLs) and
+/// `Message-ID:` trailers in a commit message.
+pub fn link_message_ids(message: &str) -> Vec<String> {
+    let mut ids: Vec<String> = Vec::new();
+
+    for line in message.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        let id = if key.trim().eq_ignore_ascii_case("Link") {
+            message_id_from_url(value)
+        } else if key.trim().eq_ignore_ascii_case("Message-ID") {
+            Some(value.trim_matches(&['<', '>'][..]).to_string()).filter(|id| id.contains('@'))
+        } else {
+            None
+        };
+
+        if let Some(id) = id
+            && !ids.contains(&id)
+        {
+            ids.push(id);
+        }
+    }
+
+    ids
+}
+
+/// Message-ID named by a lore-style URL (`https://lore.kernel.org/r/<id>`).
+pub fn message_id_from_url(value: &str) -> Option<String> {
+    let url = value.split_whitespace().next()?;
+    let url = url.split(['#', '?']).next()?;
+    url.split('/')
+        .rev()
+        .map(percent_decode)
+        .map(|segment| segment.trim_matches(&['<', '>'][..]).to_string())
+        .find(|segment| segment.contains('@'))
+}
+
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+            if let Some(byte) = hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+/// Sort key for kernel-style release tags (`v6.8`, `v6.8-rc1`, `v6.8.3`, `v2.6.39`).
+///
+/// Release candidates sort before the release they lead up to.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ReleaseVersion {
+    numbers: Vec<u32>,
+    is_final: bool,
+    rc: u32,
+}
+
+impl ReleaseVersion {
+    pub fn parse(tag: &str) -> Option<Self> {
+        static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
+        let re = TAG_REGEX.get_or_init(|| {
+            Regex::new(r"^v(\d+(?:\.\d+)+)(?:-rc(\d+))?$").expect("Invalid release tag regex")
+        });
+
+        let caps = re.captures(tag)?;
+        let numbers = caps
+            .get(1)?
+            .as_str()
+            .split('.')
+            .map(|part| part.parse::<u32>().ok())
+            .collect::<Option<Vec<u32>>>()?;
+        let rc = caps.get(2).and_then(|m| m.as_str().parse::<u32>().ok());
+
+        Some(Self {
+            numbers,
+            is_final: rc.is_none(),
+            rc: rc.unwrap_or(0),
+        })
+    }
+}
+
+fn open_repository(repo_path: &Path) -> Result<gix::Repository, String> {
+    let mut repo = gix::open(repo_path)
+        .map_err(|e| format!("Failed to open git repository {:?}: {}", repo_path, e))?;
+    repo.object_cache_size_if_unset(OBJECT_CACHE_BYTES);
+    Ok(repo)
+}
+
+fn resolve_commit_id(repo: &gix::Repository, revision: &str) -> Result<ObjectId, String> {
+    let commit = repo
+        .rev_parse_single(revision)
+        .map_err(|e| format!("Failed to resolve '{}': {}", revision, e))?
+        .object()
+        .map_err(|e| format!("Failed to find object for '{}': {}", revision, e))?
+        .peel_to_commit()
+        .map_err(|e| format!("'{}' does not point to a commit: {}", revision, e))?;
+    Ok(commit.id)
+}
+
+fn find_commit(repo: &gix::Repository, id: ObjectId) -> Result<gix::Commit<'_>, String> {
+    repo.find_object(id)
+        .map_err(|e| format!("Failed to find commit {}: {}", id, e))?
+        .try_into_commit()
+        .map_err(|e| format!("Object {} is not a commit: {}", id, e))
+}
+
+fn commit_seconds(commit: &gix::Commit<'_>) -> Result<i64, String> {
+    commit
+        .time()
+        .map(|time| time.seconds)
+        .map_err(|e| format!("Failed to read commit time of {}: {}", commit.id, e))
+}
+
+fn release_tags(repo: &gix::Repository) -> Result<Vec<(ReleaseVersion, String, ObjectId)>, String> {
+    let references = repo
+        .references()
+        .map_err(|e| format!("Failed to get references: {}", e))?;
+
+    let mut tags = Vec::new();
+    for reference in references
+        .tags()
+        .map_err(|e| format!("Failed to iterate tags: {}", e))?
+    {
+        let mut reference = reference.map_err(|e| format!("Failed to read tag: {}", e))?;
+        let name = reference.name().shorten().to_string();
+        let Some(version) = ReleaseVersion::parse(&name) else {
+            continue;
+        };
+        let Ok(id) = reference.peel_to_id_in_place().map(|id| id.detach()) else {
+            continue;
+        };
+        // Skip tags of trees or blobs (e.g. `v2.6.11-tree`)
+        if find_commit(repo, id).is_err() {
+            continue;
+        }
+        tags.push((version, name, id));
+    }
+
+    Ok(tags)
+}
+
+fn read_commit(
+    repo: &gix::Repository,
+    commit: &gix::Commit<'_>,
+    diff_cache: &mut gix::diff::blob::Platform,
+) -> Result<UpstreamCommit, String> {
+    let hash = commit.id.to_hex().to_string();
+    let message = commit
+        .message_raw()
+        .map(|raw| String::from_utf8_lossy(raw).into_owned())
+        .map_err(|e| format!("Failed to read message of {}: {}", hash, e))?;
+    let author = commit
+        .author()
+        .map_err(|e| format!("Failed to read author of {}: {}", hash, e))?;
+    let seconds = commit_seconds(commit)?;
+
+    let subject = message
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .unwrap_or_default()
+        .to_string();
+
+    let patch_id = match commit_diff(repo, commit, diff_cache) {
+        Ok(Some(diff)) => compute_patch_id(&diff),
+        Ok(None) => None,
+        Err(e) => {
+            log::debug!("upstream scan: no diff for {}: {}", hash, e);
+            None
+        }
+    };
+
+    Ok(UpstreamCommit {
+        subject,
+        author_name: author.name.to_string(),
+        author_email: author.email.to_string().to_lowercase(),
+        committed_at: Utc
+            .timestamp_opt(seconds, 0)
+            .single()
+            .unwrap_or_else(Utc::now),
+        link_message_ids: link_message_ids(&message),
+        patch_id,
+        hash,
+    })
+}
+
+/// A tree entry: file mode and object id.
+type BlobEntry = (EntryMode, ObjectId);
+
+/// Render the commit's changes against its first parent as a git-style diff.
+///
+/// Returns `None` for binary changes, which can't be matched to mailed patches.
+fn commit_diff(
+    repo: &gix::Repository,
+    commit: &gix::Commit<'_>,
+    diff_cache: &mut gix::diff::blob::Platform,
+) -> Result<Option<String>, String> {
+    let tree = commit
+        .tree()
+        .map_err(|e| format!("Failed to get tree: {}", e))?;
+    let parent_tree = match commit.parent_ids().next() {
+        Some(parent) => find_commit(repo, parent.detach())?
+            .tree()
+            .map_err(|e| format!("Failed to get parent tree: {}", e))?,
+        None => repo.empty_tree(),
+    };
+
+    // Files by path; renames are reported as a deletion and an addition,
+    // like `git diff --no-renames`
+    let mut changes: BTreeMap<String, (Option<BlobEntry>, Option<BlobEntry>)> = BTreeMap::new();
+    parent_tree
+        .changes()
+        .map_err(|e| format!("Failed to prepare tree diff: {}", e))?
+        .options(|options| {
+            options.track_path().track_rewrites(None);
+        })
+        .for_each_to_obtain_tree_with_cache(&tree, diff_cache, |change| {
+            let (location, old, new) = match change {
+                Change::Addition {
+                    location,
+                    entry_mode,
+                    id,
+                    ..
+                } => (location, None, Some((entry_mode, id.detach()))),
+                Change::Deletion {
+                    location,
+                    entry_mode,
+                    id,
+                    ..
+                } => (location, Some((entry_mode, id.detach())), None),
+                Change::Modification {
+                    location,
+                    previous_entry_mode,
+                    previous_id,
+                    entry_mode,
+                    id,
+                } => (
+                    location,
+                    Some((previous_entry_mode, previous_id.detach())),
+                    Some((entry_mode, id.detach())),
+                ),
+                Change::Rewrite { .. } => return Ok::<_, Infallible>(Action::Continue),
+            };
+
+            let old = old.filter(|(mode, _)| is_file(mode));
+            let new = new.filter(|(mode, _)| is_file(mode));
+            if old.is_some() || new.is_some() {
+                let entry = changes.entry(location.to_string()).or_default();
+                entry.0 = entry.0.or(old);
+                entry.1 = entry.1.or(new);
+            }
+            Ok(Action::Continue)
+        })
+        .map_err(|e| format!("Failed to diff trees: {}", e))?;
+
+    let mut text = String::new();
+    for (path, (old, new)) in changes {
+        if !append_file_diff(repo, &mut text, &path, old, new)? {
+            return Ok(None);
+        }
+    }
+
+    Ok(Some(text))
+}
+
+fn is_file(mode: &EntryMode) -> bool {
+    !mode.is_tree() && !mode.is_commit()
+}
+
+fn load_blob(repo: &gix::Repository, entry: Option<BlobEntry>) -> Result<Vec<u8>, String> {
+    match entry {
+        Some((_, id)) => Ok(repo
+            .find_object(id)
+            .map_err(|e| format!("Failed to find blob {}: {}", id, e))?
+            .data
+            .clone()),
+        None => Ok(Vec::new()),
+    }
+}
+
+fn mode_str(mode: &EntryMode) -> &'static str {
+    if mode.is_link() {
+        "120000"
+    } else if mode.is_executable() {
+        "100755"
+    } else {
+        "100644"
+    }
+}
+
+/// Append one file's diff; returns `false` if the file is binary.
+fn append_file_diff(
+    repo: &gix::Repository,
+    diff_text: &mut String,
+    path: &str,
+    old: Option<BlobEntry>,
+    new: Option<BlobEntry>,
+) -> Result<bool, String> {
+    let old_data = load_blob(repo, old)?;
+    let new_data = load_blob(repo, new)?;
+    if is_binary(&old_data) || is_binary(&new_data) {
+        return Ok(false);
+    }
+
+    diff_text.push_str(&format!("diff --git a/{path} b/{path}\n"));
+    match (old, new) {
+        (None, Some((mode, _))) => {
+            diff_text.push_str(&format!("new file mode {}\n", mode_str(&mode)));
+        }
+        (Some((mode, _)), None) => {
+            diff_text.push_str(&format!("deleted file mode {}\n", mode_str(&mode)));
+        }
+        (Some((old_mode, _)), Some((new_mode, _)))
+            if mode_str(&old_mode) != mode_str(&new_mode) =>
+        {
+            diff_text.push_str(&format!(
+                "old mode {}\nnew mode {}\n",
+                mode_str(&old_mode),
+                mode_str(&new_mode)
+            ));
+        }
+        _ => {}
+    }
+
+    if old_data == new_data {
+        return Ok(true);
+    }
+
+    let old_name = if old.is_some() {
+        format!("a/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    let new_name = if new.is_some() {
+        format!("b/{path}")
+    } else {
+        "/dev/null".to_string()
+    };
+    diff_text.push_str(&format!("--- {old_name}\n+++ {new_name}\n"));
+
+    let old_text = String::from_utf8_lossy(&old_data);
+    let new_text = String::from_utf8_lossy(&new_data);
+    let input = InternedInput::new(old_text.as_ref(), new_text.as_ref());
+    let hunks = diff(Algorithm::Myers, &input, UnifiedDiffBuilder::new(&input));
+    diff_text.push_str(&hunks);
+    if !diff_text.ends_with('\n') {
+        diff_text.push('\n');
+    }
+
+    Ok(true)
+}
+
+/// Same heuristic as git: a NUL byte in the first 8000 bytes means binary.
+fn is_binary(data: &[u8]) -> bool {
+    data.iter().take(8000).any(|byte| *byte == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn oid(n: u8) -> ObjectId {
+        ObjectId::from_hex(format!("{:040x}", n).as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn truncates_scans_at_the_oldest_commits() {
+        // Newest first; commit 4 is a merge
+        let range = vec![
+            (oid(6), false),
+            (oid(5), false),
+            (oid(4), true),
+            (oid(3), false),
+            (oid(2), false),
+            (oid(1), false),
+        ];
+
+        assert_eq!(truncation_tip(&range, 5), None);
+        assert_eq!(truncation_tip(&range, 10), None);
+        assert_eq!(truncation_tip(&range, 4), Some(oid(5)));
+        assert_eq!(truncation_tip(&range, 3), Some(oid(4)));
+        assert_eq!(truncation_tip(&range, 1), Some(oid(1)));
+    }
+
+    #[test]
+    fn extracts_link_trailer_message_ids() {
+        let message = "net: fix leak\n\n\
+Body text.\n\n\
+Signed-off-by: Dev <dev@example.com>\n\
+Link: https://lore.kernel.org/r/20240102.123-1-dev@example.com\n\
+Link: https://patch.msgid.link/20240103%40example.org/\n\
+Link: https://lore.kernel.org/netdev/abc@host/T/#u\n\
+Link: https://bugzilla.kernel.org/show_bug.cgi?id=1\n\
+Message-ID: <mid@example.net>\n";
+
+        assert_eq!(
+            link_message_ids(message),
+            vec![
+                "20240102.123-1-dev@example.com".to_string(),
+                "20240103@example.org".to_string(),
+                "abc@host".to_string(),
+                "mid@example.net".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn orders_release_tags() {
+        let mut tags = vec![
+            "v6.2",
+            "v6.1.5",
+            "v6.2-rc1",
+            "v6.2-rc10",
+            "v2.6.39",
+            "v6.2.1",
+        ];
+        tags.sort_by_key(|tag| ReleaseVersion::parse(tag).unwrap());
+        assert_eq!(
+            tags,
+            vec![
+                "v2.6.39",
+                "v6.1.5",
+                "v6.2-rc1",
+                "v6.2-rc10",
+                "v6.2",
+                "v6.2.1"
+            ]
+        );
+
+        assert!(ReleaseVersion::parse("next-20240101").is_none());
+        assert!(ReleaseVersion::parse("v6").is_none());
+    }
+}
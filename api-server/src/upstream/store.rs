@@ -0,0 +1,328 @@
+//! Match upstream commits to archived patch emails and persist the results.
+
+use crate::models::{PatchState, UpstreamMatchMethod};
+use crate::patches::set_patch_states;
+use crate::sync::parser::normalize_subject;
+use crate::upstream::scanner::UpstreamCommit;
+use rocket_db_pools::sqlx::{self, PgPool};
+use std::collections::{HashMap, HashSet};
+
+/// A scanned commit matched to a patch email.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommitMatch {
+    /// Index into the scanned commit list.
+    pub commit: usize,
+    pub mailing_list_id: i32,
+    pub email_id: i32,
+    pub method: UpstreamMatchMethod,
+}
+
+/// Register (or refresh) an upstream tree.
+///
+/// # Returns
+/// Tree id and the head commit recorded by the previous scan, if any
+pub async fn upsert_tree(
+    pool: &PgPool,
+    name: &str,
+    repo_path: &str,
+    reference: &str,
+) -> Result<(i32, Option<String>), sqlx::Error> {
+    sqlx::query_as(
+        r#"INSERT INTO upstream_trees (name, repo_path, reference)
+           VALUES ($1, $2, $3)
+           ON CONFLICT (name) DO UPDATE
+           SET repo_path = EXCLUDED.repo_path,
+               reference = EXCLUDED.reference
+           RETURNING id, last_scanned_commit"#,
+    )
+    .bind(name)
+    .bind(repo_path)
+    .bind(reference)
+    .fetch_one(pool)
+    .await
+}
+
+/// Remember the head commit so the next scan only reads newer history.
+pub async fn finish_scan(pool: &PgPool, tree_id: i32, head: &str) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "UPDATE upstream_trees SET last_scanned_commit = $2, last_scanned_at = NOW() WHERE id = $1",
+    )
+    .bind(tree_id)
+    .bind(head)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Match commits to patch emails.
+///
+/// Strategies are tried in order of confidence and a commit matched by one is
+/// not considered by the next:
+/// 1. identical `git patch-id`
+/// 2. `Link:`/`Message-ID:` trailer naming the email's Message-ID
+/// 3. same normalized subject and author email, against the newest posting
+///    only so that earlier revisions of the patch aren't marked accepted
+pub async fn match_commits(
+    pool: &PgPool,
+    commits: &[UpstreamCommit],
+) -> Result<Vec<CommitMatch>, sqlx::Error> {
+    let mut matches = Vec::new();
+    let mut matched: HashSet<usize> = HashSet::new();
+
+    // 1. Patch-id
+    let mut commits_by_patch_id: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, commit) in commits.iter().enumerate() {
+        if let Some(patch_id) = commit.patch_id.as_deref() {
+            commits_by_patch_id.entry(patch_id).or_default().push(index);
+        }
+    }
+    if !commits_by_patch_id.is_empty() {
+        let patch_ids: Vec<&str> = commits_by_patch_id.keys().copied().collect();
+        let rows: Vec<(i32, i32, String)> = sqlx::query_as(
+            "SELECT mailing_list_id, id, patch_id FROM emails WHERE patch_id = ANY($1)",
+        )
+        .bind(&patch_ids)
+        .fetch_all(pool)
+        .await?;
+
+        for (mailing_list_id, email_id, patch_id) in rows {
+            for &commit in commits_by_patch_id
+                .get(patch_id.as_str())
+                .into_iter()
+                .flatten()
+            {
+                matched.insert(commit);
+                matches.push(CommitMatch {
+                    commit,
+                    mailing_list_id,
+                    email_id,
+                    method: UpstreamMatchMethod::PatchId,
+                });
+            }
+        }
+    }
+
+    // 2. Link trailers
+    let mut commits_by_message_id: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, commit) in commits.iter().enumerate() {
+        if matched.contains(&index) {
+            continue;
+        }
+        for message_id in &commit.link_message_ids {
+            commits_by_message_id
+                .entry(message_id.as_str())
+                .or_default()
+                .push(index);
+        }
+    }
+    if !commits_by_message_id.is_empty() {
+        let message_ids: Vec<&str> = commits_by_message_id.keys().copied().collect();
+        let rows: Vec<(i32, i32, String)> = sqlx::query_as(
+            "SELECT mailing_list_id, id, message_id FROM emails WHERE message_id = ANY($1)",
+        )
+        .bind(&message_ids)
+        .fetch_all(pool)
+        .await?;
+
+        for (mailing_list_id, email_id, message_id) in rows {
+            for &commit in commits_by_message_id
+                .get(message_id.as_str())
+                .into_iter()
+                .flatten()
+            {
+                matched.insert(commit);
+                matches.push(CommitMatch {
+                    commit,
+                    mailing_list_id,
+                    email_id,
+                    method: UpstreamMatchMethod::Link,
+                });
+            }
+        }
+    }
+
+    // 3. Subject + author, newest posting (and its cross-posted copies) only
+    let mut indexes = Vec::new();
+    let mut subjects = Vec::new();
+    let mut emails = Vec::new();
+    for (index, commit) in commits.iter().enumerate() {
+        if matched.contains(&index) || commit.subject.is_empty() {
+            continue;
+        }
+        indexes.push(index as i32);
+        subjects.push(normalize_subject(&commit.subject));
+        emails.push(commit.author_email.clone());
+    }
+    if !indexes.is_empty() {
+        let rows: Vec<(i32, i32, i32)> = sqlx::query_as(
+            r#"WITH candidates AS (
+                   SELECT c.idx, e.mailing_list_id, e.id, e.message_id, e.date
+                   FROM UNNEST($1::int[], $2::text[], $3::text[]) AS c(idx, subject, email)
+                   JOIN authors a ON lower(a.email) = c.email
+                   JOIN emails e ON e.author_id = a.id AND e.normalized_subject = c.subject
+                   WHERE e.patch_type <> 'none'
+                     AND e.subject !~* '^\s*(re|fwd?)\s*:'
+               ),
+               newest AS (
+                   SELECT DISTINCT ON (idx) idx, message_id
+                   FROM candidates
+                   ORDER BY idx, date DESC, id DESC
+               )
+               SELECT c.idx, c.mailing_list_id, c.id
+               FROM candidates c
+               JOIN newest n ON n.idx = c.idx AND n.message_id = c.message_id"#,
+        )
+        .bind(&indexes)
+        .bind(&subjects)
+        .bind(&emails)
+        .fetch_all(pool)
+        .await?;
+
+        for (index, mailing_list_id, email_id) in rows {
+            matches.push(CommitMatch {
+                commit: index as usize,
+                mailing_list_id,
+                email_id,
+                method: UpstreamMatchMethod::SubjectAuthor,
+            });
+        }
+    }
+
+    Ok(matches)
+}
+
+/// Store matches and mark the matched patches as accepted.
+///
+/// Patches that already reached a final state (accepted, rejected, superseded)
+/// keep it.
+///
+/// # Returns
+/// Emails whose state changed to accepted, keyed by mailing list id
+pub async fn record_applications(
+    pool: &PgPool,
+    tree_id: i32,
+    tree_name: &str,
+    commits: &[UpstreamCommit],
+    matches: &[CommitMatch],
+) -> Result<HashMap<i32, Vec<i32>>, sqlx::Error> {
+    let mut accepted: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut by_list_and_commit: HashMap<(i32, usize), Vec<i32>> = HashMap::new();
+
+    for m in matches {
+        let commit = &commits[m.commit];
+        sqlx::query(
+            r#"INSERT INTO upstream_applications (
+                   mailing_list_id, email_id, tree_id, commit_hash, commit_subject,
+                   committed_at, match_method
+               )
+               VALUES ($1, $2, $3, $4, $5, $6, $7)
+               ON CONFLICT (mailing_list_id, email_id, tree_id, commit_hash) DO UPDATE
+               SET match_method = EXCLUDED.match_method"#,
+        )
+        .bind(m.mailing_list_id)
+        .bind(m.email_id)
+        .bind(tree_id)
+        .bind(&commit.hash)
+        .bind(&commit.subject)
+        .bind(commit.committed_at)
+        .bind(m.method)
+        .execute(pool)
+        .await?;
+
+        by_list_and_commit
+            .entry((m.mailing_list_id, m.commit))
+            .or_default()
+            .push(m.email_id);
+    }
+
+    let open_states: Vec<PatchState> = PatchState::ALL
+        .into_iter()
+        .filter(PatchState::is_open)
+        .collect();
+
+    for ((mailing_list_id, commit), email_ids) in by_list_and_commit {
+        let to_accept: Vec<i32> = sqlx::query_scalar(
+            r#"SELECT ids.email_id
+               FROM UNNEST($2::int[]) AS ids(email_id)
+               LEFT JOIN patch_states ps ON ps.mailing_list_id = $1
+                   AND ps.email_id = ids.email_id
+               WHERE ps.state IS NULL OR ps.state = ANY($3)"#,
+        )
+        .bind(mailing_list_id)
+        .bind(&email_ids)
+        .bind(&open_states)
+        .fetch_all(pool)
+        .await?;
+
+        if to_accept.is_empty() {
+            continue;
+        }
+
+        let hash = &commits[commit].hash;
+        let comment = format!(
+            "Applied to {} as {}",
+            tree_name,
+            &hash[..hash.len().min(12)]
+        );
+        set_patch_states(
+            pool,
+            mailing_list_id,
+            &to_accept,
+            PatchState::Accepted,
+            None,
+            Some(&comment),
+        )
+        .await?;
+
+        accepted
+            .entry(mailing_list_id)
+            .or_default()
+            .extend(to_accept);
+    }
+
+    Ok(accepted)
+}
+
+/// Commits of a tree whose applications have no release tag yet.
+pub async fn unreleased_commits(pool: &PgPool, tree_id: i32) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        r#"SELECT DISTINCT commit_hash
+           FROM upstream_applications
+           WHERE tree_id = $1 AND release_tag IS NULL"#,
+    )
+    .bind(tree_id)
+    .fetch_all(pool)
+    .await
+}
+
+/// Store the first release tag for each commit.
+pub async fn update_release_tags(
+    pool: &PgPool,
+    tree_id: i32,
+    tags: &HashMap<String, String>,
+) -> Result<u64, sqlx::Error> {
+    if tags.is_empty() {
+        return Ok(0);
+    }
+
+    let (hashes, names): (Vec<&str>, Vec<&str>) = tags
+        .iter()
+        .map(|(hash, tag)| (hash.as_str(), tag.as_str()))
+        .unzip();
+
+    let result = sqlx::query(
+        r#"UPDATE upstream_applications ua
+           SET release_tag = t.tag
+           FROM UNNEST($2::text[], $3::text[]) AS t(commit_hash, tag)
+           WHERE ua.tree_id = $1
+             AND ua.commit_hash = t.commit_hash
+             AND ua.release_tag IS NULL"#,
+    )
+    .bind(tree_id)
+    .bind(&hashes)
+    .bind(&names)
+    .execute(pool)
+    .await?;
+
+    Ok(result.rows_affected())
+}
@@ -104,7 +104,7 @@ async fn create_index_maintenance_job_enqueues() {
     assert_eq!(response.status(), Status::Ok);
 
     let body: ApiResponse<JobRecord> = response.into_json().await.expect("JSON response");
-    assert_eq!(body.data.job_type, JobType::IndexMaintenance);
+    assert_eq!(body.data.job_type, JobType::INDEX_MAINTENANCE);
     assert_eq!(body.data.mailing_list_slug.as_deref(), Some("lkml"));
 
     let count: (i64,) =
@@ -0,0 +1,161 @@
+use api_server::search::{
+    EmbeddingProviderKind, EmbeddingsClient, EmbeddingsConfig, SearchBackendKind, SearchService,
+    SimilarThreadsPayload, reindex_changed_threads,
+};
+use api_server::test_support::{TestDatabase, TestDatabaseError};
+use sqlx::PgPool;
+
+async fn insert_thread(
+    pool: &PgPool,
+    mailing_list_id: i32,
+    author_id: i32,
+    message_id: &str,
+    subject: &str,
+    body: &str,
+) -> i32 {
+    let email_id: i32 = sqlx::query_scalar(
+        "INSERT INTO emails (mailing_list_id, message_id, git_commit_hash, author_id, subject, normalized_subject, date, body, authored_body, search_body, epoch) \
+         VALUES ($1, $2, $2, $3, $4, lower($4), NOW(), $5, $5, $5, 0) RETURNING id",
+    )
+    .bind(mailing_list_id)
+    .bind(message_id)
+    .bind(author_id)
+    .bind(subject)
+    .bind(body)
+    .fetch_one(pool)
+    .await
+    .expect("failed to insert email");
+
+    let thread_id: i32 = sqlx::query_scalar(
+        "INSERT INTO threads (mailing_list_id, root_message_id, subject, start_date, last_date, message_count) \
+         VALUES ($1, $2, $3, NOW(), NOW(), 1) RETURNING id",
+    )
+    .bind(mailing_list_id)
+    .bind(message_id)
+    .bind(subject)
+    .fetch_one(pool)
+    .await
+    .expect("failed to insert thread");
+
+    sqlx::query(
+        "INSERT INTO thread_memberships (mailing_list_id, thread_id, email_id, depth) VALUES ($1, $2, $3, 0)",
+    )
+    .bind(mailing_list_id)
+    .bind(thread_id)
+    .bind(email_id)
+    .execute(pool)
+    .await
+    .expect("failed to insert membership");
+
+    thread_id
+}
+
+#[tokio::test]
+async fn postgres_backend_returns_nearest_indexed_threads() {
+    let test_db = match TestDatabase::new_from_env().await {
+        Ok(db) => db,
+        Err(TestDatabaseError::MissingUrl) => {
+            eprintln!("skipping similar threads test: TEST_DATABASE_URL not set");
+            return;
+        }
+        Err(err) => panic!("failed to provision test database: {err:?}"),
+    };
+
+    let pool = test_db.pool_clone();
+
+    let mailing_list_id: i32 = sqlx::query_scalar(
+        "INSERT INTO mailing_lists (name, slug, description, enabled, sync_priority) VALUES ($1, $2, $3, $4, $5) RETURNING id",
+    )
+    .bind("Memory management")
+    .bind("linux-mm")
+    .bind::<Option<String>>(None)
+    .bind(true)
+    .bind(0)
+    .fetch_one(&pool)
+    .await
+    .expect("failed to insert mailing list");
+
+    let author_id: i32 = sqlx::query_scalar(
+        "INSERT INTO authors (email, canonical_name) VALUES ($1, $2) RETURNING id",
+    )
+    .bind("dev@example.org")
+    .bind("Dev")
+    .fetch_one(&pool)
+    .await
+    .expect("failed to insert author");
+
+    let source = insert_thread(
+        &pool,
+        mailing_list_id,
+        author_id,
+        "leak-1@example.org",
+        "mm/slab: fix kmem_cache leak on allocation failure",
+        "The slab allocator leaks the kmem_cache when the allocation of the cpu cache fails.",
+    )
+    .await;
+    let neighbour = insert_thread(
+        &pool,
+        mailing_list_id,
+        author_id,
+        "leak-2@example.org",
+        "mm/slab: kmem_cache leak when allocation fails",
+        "Another report of the slab allocator leaking the kmem_cache after a failed allocation.",
+    )
+    .await;
+    let unrelated = insert_thread(
+        &pool,
+        mailing_list_id,
+        author_id,
+        "usb-1@example.org",
+        "usb: gadget: rename endpoint descriptors",
+        "Rename the gadget endpoint descriptor helpers for consistency with the host side.",
+    )
+    .await;
+
+    let search = SearchService::new(
+        "http://localhost:7700",
+        None,
+        EmbeddingsClient::new(EmbeddingsConfig::new(EmbeddingProviderKind::Hash, 1024)),
+        0.35,
+        false,
+    )
+    .with_backend(SearchBackendKind::Postgres);
+
+    let (indexed, _) = reindex_changed_threads(
+        &pool,
+        &search,
+        mailing_list_id,
+        &[source, neighbour, unrelated],
+        &[],
+        None,
+    )
+    .await
+    .expect("threads are embedded");
+    assert_eq!(indexed, 3);
+
+    let stored: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM thread_embeddings")
+        .fetch_one(&pool)
+        .await
+        .expect("lookup succeeded");
+    assert_eq!(stored, 3);
+
+    let hits = SearchBackendKind::Postgres
+        .build(&search, pool.clone())
+        .similar_threads(SimilarThreadsPayload {
+            thread_id: source,
+            mailing_list_id,
+            mailing_lists: Vec::new(),
+            limit: 5,
+            exclude_series: None,
+            has_patches: None,
+        })
+        .await
+        .expect("similar threads query succeeds");
+
+    let thread_ids: Vec<i32> = hits.iter().map(|hit| hit.document.thread_id).collect();
+    assert_eq!(thread_ids.first(), Some(&neighbour));
+    assert!(!thread_ids.contains(&source));
+    assert!(hits[0].ranking_score.is_some());
+
+    test_db.close().await.expect("failed to drop test database");
+}